@@ -5,6 +5,8 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+use indexer::utils::logging;
+
 // Define valid operations
 #[derive(Debug, Clone, ValueEnum)]
 enum Operation {
@@ -19,6 +21,8 @@ enum DexType {
     Orca,
     Raydium,
     All,
+    /// Cross-DEX views over the per-DEX tables (opt-in)
+    Unified,
 }
 
 #[derive(Parser, Debug)]
@@ -28,7 +32,7 @@ struct Args {
     #[arg(value_enum)]
     operation: Operation,
 
-    /// DEX type to target (common, orca, raydium, or all)
+    /// DEX type to target (common, orca, raydium, all, or unified)
     #[arg(value_enum)]
     dex: DexType,
 
@@ -53,17 +57,18 @@ async fn main() -> Result<()> {
     // Parse command line arguments
     let args = Args::parse();
 
+    // Initialize logging (--verbose enables debug output, RUST_LOG overrides both)
+    logging::init_logger(if args.verbose { "debug,sqlx=warn" } else { "info,sqlx=warn" });
+
     // Get database URL
     let database_url = match &args.database_url {
         Some(url) => url.clone(),
         None => env::var("DATABASE_URL").context("DATABASE_URL environment variable not set")?,
     };
 
-    if args.verbose {
-        println!("Database URL: {}", database_url);
-        println!("Operation: {:?}", args.operation);
-        println!("DEX: {:?}", args.dex);
-    }
+    log::debug!("Database URL: {}", database_url);
+    log::debug!("Operation: {:?}", args.operation);
+    log::debug!("DEX: {:?}", args.dex);
 
     // Determine which DEXes to process
     let mut dexes = match args.dex {
@@ -71,15 +76,19 @@ async fn main() -> Result<()> {
         DexType::Common => vec!["common"],
         DexType::Orca => vec!["common", "orca"], // Always include common for individual DEXes
         DexType::Raydium => vec!["common", "raydium"], // Always include common for individual DEXes
+        // The unified views depend on every DEX schema, but deleting them leaves the tables intact
+        DexType::Unified =>
+            match args.operation {
+                Operation::Create => vec!["common", "orca", "raydium", "unified"],
+                Operation::Delete => vec!["unified"],
+            }
     };
 
     // For delete operations, reverse the order to handle common schema last
     // This ensures dependencies are deleted in the correct order
     if matches!(args.operation, Operation::Delete) {
         dexes.reverse();
-        if args.verbose {
-            println!("Delete operation: Processing schemas in reverse order");
-        }
+        log::debug!("Delete operation: Processing schemas in reverse order");
     }
 
     // Connect to the database
@@ -88,7 +97,7 @@ async fn main() -> Result<()> {
         .connect(&database_url).await
         .context("Failed to connect to database")?;
 
-    println!("Connected to database.");
+    log::info!("Connected to database.");
 
     // Process each DEX
     for dex in dexes {
@@ -97,10 +106,10 @@ async fn main() -> Result<()> {
 
         match args.operation {
             Operation::Create => {
-                println!("Creating schema for {}...", dex);
+                log::info!("Creating schema for {}...", dex);
 
                 if !Path::new(&schema_path).exists() {
-                    println!("Warning: Schema file not found at {}", schema_path);
+                    log::warn!("Warning: Schema file not found at {}", schema_path);
                     continue;
                 }
 
@@ -110,7 +119,7 @@ async fn main() -> Result<()> {
                     .context(format!("Failed to read schema file: {}", schema_path))?;
 
                 execute_sql_statements(&pool, &schema_sql, args.verbose).await?;
-                println!("Successfully created schema for {}", dex);
+                log::info!("Successfully created schema for {}", dex);
             }
             Operation::Delete => {
                 if !args.yes {
@@ -121,17 +130,17 @@ async fn main() -> Result<()> {
                     let mut input = String::new();
                     std::io::stdin().read_line(&mut input).context("Failed to read input")?;
                     if input.trim().to_lowercase() != "yes" {
-                        println!("Skipping deletion of {} schema.", dex);
+                        log::info!("Skipping deletion of {} schema.", dex);
                         continue;
                     }
                 }
 
                 if !Path::new(&delete_path).exists() {
-                    println!("Warning: Delete schema file not found at {}", delete_path);
+                    log::warn!("Warning: Delete schema file not found at {}", delete_path);
                     continue;
                 }
 
-                println!("Deleting schema for {}...", dex);
+                log::info!("Deleting schema for {}...", dex);
 
                 // Read and execute the delete SQL file
                 let delete_sql = fs
@@ -139,12 +148,12 @@ async fn main() -> Result<()> {
                     .context(format!("Failed to read delete schema file: {}", delete_path))?;
 
                 execute_sql_statements(&pool, &delete_sql, args.verbose).await?;
-                println!("Successfully deleted schema for {}", dex);
+                log::info!("Successfully deleted schema for {}", dex);
             }
         }
     }
 
-    println!("Database operation completed successfully.");
+    log::info!("Database operation completed successfully.");
     Ok(())
 }
 
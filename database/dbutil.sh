@@ -50,6 +50,7 @@ if [[ "$1" == "--help" || "$1" == "-h" || $# -eq 0 ]]; then
     echo "  orca                  Orca schema (includes common schema)"
     echo "  raydium               Raydium schema (includes common schema)"
     echo "  all                   All schemas (common, orca, raydium)"
+    echo "  unified               Cross-DEX views (creates all schemas first)"
     echo
     echo "OPTIONS:"
     echo "  --database-url URL    Override database URL from .env"
@@ -1,21 +1,28 @@
 use anyhow::{ Context, Result };
-use clap::Parser;
+use clap::{ Parser, Subcommand };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use sqlx::postgres::PgPoolOptions;
-use sqlx::Row;
 use std::str::FromStr;
 use std::env;
 use std::fs;
 use std::path::Path;
-use std::collections::{ HashMap, HashSet };
+use std::sync::Arc;
+use std::time::Duration;
+use std::collections::HashMap;
 use dotenv::dotenv;
 
-// Import our models
-mod models;
-use models::{ TokenInfo, PoolRecord, DexProcessor };
-use models::orca::OrcaProcessor;
+use indexer::db::TokenMetadataStore;
+use indexer::models::orca::WhirlpoolData;
+use indexer::pool_loader::{ TokenInfo, PoolRecord, DexProcessor };
+use indexer::pool_loader::orca::{ discover_whirlpools, OrcaProcessor };
+use indexer::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+use indexer::rpc::build_rpc_client;
+use indexer::utils::logging;
+
+// Mainnet Metaplex Token Metadata program
+const METAPLEX_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 
 // Command line arguments
 #[derive(Parser, Debug)]
@@ -33,9 +40,51 @@ struct Args {
     #[arg(long, env = "SOLANA_RPC_URL")]
     solana_rpc_url: Option<String>,
 
+    /// Metaplex Token Metadata program ID (override for local validators)
+    #[arg(long, env = "METAPLEX_PROGRAM_ID", default_value = METAPLEX_METADATA_PROGRAM_ID)]
+    metadata_program_id: String,
+
+    /// Orca Whirlpool program ID that must own Orca pool accounts
+    #[arg(long, env = "ORCA_WHIRLPOOL_PROGRAM_ID", default_value = ORCA_WHIRLPOOL_PROGRAM_ID)]
+    orca_program_id: String,
+
+    /// Timeout in seconds for each RPC request
+    #[arg(long, env = "RPC_TIMEOUT_SECS", default_value_t = 30)]
+    rpc_timeout_secs: u64,
+
+    /// Fail a pool instead of storing placeholder names when its token metadata can't be resolved
+    #[arg(long, env = "REQUIRE_TOKEN_METADATA")]
+    require_token_metadata: bool,
+
+    /// Hours before stored token metadata is considered stale and fetched from chain again
+    #[arg(long, env = "TOKEN_METADATA_TTL_HOURS", default_value_t = 24)]
+    token_metadata_ttl_hours: u64,
+
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Command to run instead of loading the pools files
+    #[command(subcommand)]
+    command: Option<LoadCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum LoadCommand {
+    /// Find Orca Whirlpools on chain with getProgramAccounts and print or save them
+    Discover {
+        /// Confirm the getProgramAccounts scan, which is slow and often rate limited on public RPC nodes
+        #[arg(long)]
+        confirm: bool,
+
+        /// Only discover pools trading this token mint
+        #[arg(long)]
+        token_mint: Option<String>,
+
+        /// Save discovered pools and their tokens to the database instead of printing them
+        #[arg(long)]
+        save: bool,
+    },
 }
 
 #[tokio::main]
@@ -46,15 +95,12 @@ async fn main() -> Result<()> {
     // Parse command line arguments
     let args = Args::parse();
 
-    // Get database URL from arguments or environment
-    let database_url = match args.database_url {
-        Some(url) => url,
-        None => env::var("DATABASE_URL").context("DATABASE_URL not set")?,
-    };
+    // Initialize logging (--verbose enables debug output, RUST_LOG overrides both)
+    logging::init_logger(if args.verbose { "debug,sqlx=warn" } else { "info,sqlx=warn" });
 
     // Get Solana RPC URL from arguments or environment
-    let solana_rpc_url = match args.solana_rpc_url {
-        Some(url) => url,
+    let solana_rpc_url = match &args.solana_rpc_url {
+        Some(url) => url.clone(),
         None =>
             env
                 ::var("SOLANA_RPC_URL")
@@ -64,9 +110,8 @@ async fn main() -> Result<()> {
     };
 
     if args.verbose {
-        println!("Database URL: {}", database_url);
-        println!("Solana RPC URL: {}", solana_rpc_url);
-        println!("DEX: {}", args.dex);
+        log::debug!("Solana RPC URL: {}", solana_rpc_url);
+        log::debug!("DEX: {}", args.dex);
     }
 
     // Determine which DEXes to process
@@ -81,7 +126,41 @@ async fn main() -> Result<()> {
     };
 
     // Connect to Solana RPC
-    let rpc_client = RpcClient::new_with_commitment(solana_rpc_url, CommitmentConfig::confirmed());
+    let rpc_client = Arc::new(
+        build_rpc_client(
+            &solana_rpc_url,
+            CommitmentConfig::confirmed(),
+            Duration::from_secs(args.rpc_timeout_secs)
+        )
+    );
+
+    let orca_program_id = Pubkey::from_str(&args.orca_program_id).context(
+        format!("Invalid Orca Whirlpool program ID: {}", args.orca_program_id)
+    )?;
+
+    // Discovered pools are only printed, so the database isn't needed
+    if let Some(LoadCommand::Discover { save: false, .. }) = &args.command {
+        let pools = discover_pools(&args, &rpc_client, &orca_program_id).await?;
+        for (pool_address, pool_data) in &pools {
+            // Printed in the subscribed_pools.txt format
+            println!(
+                "# {} / {} (tick spacing {}, fee rate {})",
+                pool_data.token_mint_a,
+                pool_data.token_mint_b,
+                pool_data.tick_spacing,
+                pool_data.fee_rate
+            );
+            println!("{}", pool_address);
+        }
+        return Ok(());
+    }
+
+    // Get database URL from arguments or environment
+    let database_url = match &args.database_url {
+        Some(url) => url.clone(),
+        None => env::var("DATABASE_URL").context("DATABASE_URL not set")?,
+    };
+    log::debug!("Database URL: {}", database_url);
 
     // Connect to the database
     let db_pool = PgPoolOptions::new()
@@ -89,29 +168,68 @@ async fn main() -> Result<()> {
         .connect(&database_url).await
         .context("Failed to connect to the database")?;
 
-    // Preload token cache from database
-    let mut token_cache: HashMap<Pubkey, TokenInfo> = load_token_cache_from_db(&db_pool).await?;
+    // Tokens already resolved in this run, shared by pools with a common token
+    let mut token_cache: HashMap<Pubkey, TokenInfo> = HashMap::new();
 
-    // Keep track of which tokens are already in the database to avoid redundant writes
-    let mut saved_tokens: HashSet<Pubkey> = token_cache.keys().cloned().collect();
+    // Get Metaplex program ID
+    let metadata_program_id = Pubkey::from_str(&args.metadata_program_id).context(
+        format!("Invalid metadata program ID: {}", args.metadata_program_id)
+    )?;
+
+    // Stored tokens are reused until they are older than the TTL
+    let tokens = TokenMetadataStore::new(
+        db_pool.clone(),
+        rpc_client.clone(),
+        metadata_program_id,
+        args.require_token_metadata
+    );
+    let token_metadata_ttl = Duration::from_secs(args.token_metadata_ttl_hours * 60 * 60);
+
+    // Save discovered pools the same way pools from the pools files are saved
+    if let Some(LoadCommand::Discover { save: true, .. }) = &args.command {
+        let processor = OrcaProcessor {
+            program_id: orca_program_id,
+            tokens,
+            token_metadata_ttl,
+        };
+        let pools = discover_pools(&args, &rpc_client, &orca_program_id).await?;
+        let mut failed_pools = 0;
+        for (pool_address, pool_data) in &pools {
+            match
+                processor.pool_record(pool_address, pool_data, &mut token_cache, args.verbose).await
+            {
+                Ok(pool_record) => {
+                    save_pool_to_database(&db_pool, &pool_record).await.context(
+                        "Failed to save discovered orca pool data to database"
+                    )?;
+                }
+                Err(e) => {
+                    log::error!("Error processing discovered orca pool {}: {:#}", pool_address, e);
+                    failed_pools += 1;
+                }
+            }
+        }
 
-    if args.verbose {
-        println!("Preloaded {} tokens from database", token_cache.len());
+        if args.require_token_metadata && failed_pools > 0 {
+            anyhow::bail!("{} pools failed to load with --require-token-metadata", failed_pools);
+        }
+        log::info!("Saved {} of {} discovered orca pools", pools.len() - failed_pools, pools.len());
+        return Ok(());
     }
 
-    // Get Metaplex program ID
-    let metadata_program_id = Pubkey::from_str("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s")?;
+    // Pools that failed to load, reported as an error in strict mode
+    let mut failed_pools = 0;
 
     // Process each DEX
     for dex in dexes {
-        println!("Processing {} pools...", dex);
+        log::info!("Processing {} pools...", dex);
 
         // Define the path to the subscribed pools file
         let pools_file_path = format!("database/schema/{}/subscribed_pools.txt", dex);
         let path = Path::new(&pools_file_path);
 
         if !path.exists() {
-            println!("Warning: Pools file not found at {}", pools_file_path);
+            log::warn!("Warning: Pools file not found at {}", pools_file_path);
             continue;
         }
 
@@ -122,9 +240,14 @@ async fn main() -> Result<()> {
 
         // Get the appropriate processor for this DEX
         let processor: Box<dyn DexProcessor> = match dex {
-            "orca" => Box::new(OrcaProcessor {}),
+            "orca" =>
+                Box::new(OrcaProcessor {
+                    program_id: orca_program_id,
+                    tokens: tokens.clone(),
+                    token_metadata_ttl,
+                }),
             "raydium" => {
-                println!("Raydium processing not yet implemented, skipping...");
+                log::info!("Raydium processing not yet implemented, skipping...");
                 continue;
                 // TODO: When implemented, return Box::new(RaydiumProcessor {})
             }
@@ -142,107 +265,78 @@ async fn main() -> Result<()> {
             let pool_pubkey = match Pubkey::from_str(trimmed) {
                 Ok(pubkey) => pubkey,
                 Err(e) => {
-                    println!("Warning: Invalid pool address format '{}': {}", trimmed, e);
+                    log::warn!("Warning: Invalid pool address format '{}': {}", trimmed, e);
                     continue;
                 }
             };
 
-            println!("Fetching data for {} pool: {}", dex, pool_pubkey);
+            log::info!("Fetching data for {} pool: {}", dex, pool_pubkey);
 
             // Process the pool using the appropriate processor
             match
                 processor.process_pool(
                     &rpc_client,
                     &pool_pubkey,
-                    &metadata_program_id,
                     &mut token_cache,
                     args.verbose
                 ).await
             {
                 Ok(pool_record) => {
                     // Save the pool data to the database
-                    save_pool_to_database(&db_pool, &pool_record, &mut saved_tokens).await.context(
+                    save_pool_to_database(&db_pool, &pool_record).await.context(
                         format!("Failed to save {} pool data to database", dex)
                     )?;
-                    println!("Successfully processed {} pool: {}", dex, pool_pubkey);
+                    log::info!("Successfully processed {} pool: {}", dex, pool_pubkey);
                 }
                 Err(e) => {
-                    println!("Error processing {} pool {}: {}", dex, pool_pubkey, e);
+                    log::error!("Error processing {} pool {}: {:#}", dex, pool_pubkey, e);
+                    failed_pools += 1;
                 }
             }
         }
     }
 
-    println!("Successfully loaded pools data!");
+    // Strict pipelines shouldn't treat a partial load as success
+    if args.require_token_metadata && failed_pools > 0 {
+        anyhow::bail!("{} pools failed to load with --require-token-metadata", failed_pools);
+    }
+
+    log::info!("Successfully loaded pools data!");
     Ok(())
 }
 
-// Load token metadata from database into cache
-async fn load_token_cache_from_db(db_pool: &sqlx::PgPool) -> Result<HashMap<Pubkey, TokenInfo>> {
-    let mut token_cache = HashMap::new();
-
-    // Query all tokens from the database
-    let rows = sqlx
-        ::query("SELECT mint, token_name, symbol, decimals FROM apestrong.token_metadata")
-        .fetch_all(db_pool).await
-        .context("Failed to load tokens from database")?;
-
-    // Convert database records to TokenInfo and add to cache
-    for row in rows {
-        let mint: String = row.get(0);
-        match Pubkey::from_str(&mint) {
-            Ok(pubkey) => {
-                let token_info = TokenInfo {
-                    mint: pubkey,
-                    name: row.get::<Option<String>, _>(1).unwrap_or_default(),
-                    symbol: row.get::<Option<String>, _>(2).unwrap_or_default(),
-                    decimals: row.get::<i32, _>(3) as u8,
-                };
-                token_cache.insert(pubkey, token_info);
-            }
-            Err(e) => {
-                println!("Warning: Invalid pubkey in database: {}: {}", mint, e);
-                continue;
-            }
-        }
-    }
+// Discover Orca Whirlpools for the discover command, once the scan is confirmed
+async fn discover_pools(
+    args: &Args,
+    rpc_client: &RpcClient,
+    orca_program_id: &Pubkey
+) -> Result<Vec<(Pubkey, WhirlpoolData)>> {
+    let Some(LoadCommand::Discover { confirm, token_mint, .. }) = &args.command else {
+        unreachable!("discover_pools called without the discover command");
+    };
 
-    Ok(token_cache)
-}
+    let token_mint = token_mint
+        .as_deref()
+        .map(|mint| Pubkey::from_str(mint).context(format!("Invalid token mint: {}", mint)))
+        .transpose()?;
 
-// Save token metadata and pool to database
-async fn save_pool_to_database(
-    db_pool: &sqlx::PgPool,
-    pool_record: &PoolRecord,
-    saved_tokens: &mut HashSet<Pubkey>
-) -> Result<()> {
-    // Start a transaction
-    let mut transaction = db_pool.begin().await?;
-
-    // First, save token metadata if it's not already in the database
-    for token in [&pool_record.token_a, &pool_record.token_b].iter() {
-        // Only insert tokens that we haven't already saved or loaded from the database
-        if !saved_tokens.contains(&token.mint) {
-            sqlx
-                ::query(
-                    "INSERT INTO apestrong.token_metadata (mint, token_name, symbol, decimals, last_updated)
-                 VALUES ($1, $2, $3, $4, NOW())
-                 ON CONFLICT (mint) DO UPDATE 
-                 SET token_name = $2, symbol = $3, decimals = $4, last_updated = NOW()"
-                )
-                .bind(token.mint.to_string())
-                .bind(&token.name)
-                .bind(&token.symbol)
-                .bind(token.decimals as i32)
-                .execute(&mut *transaction).await
-                .context("Failed to save token metadata")?;
-
-            // Add to saved tokens set to avoid duplicate inserts in future
-            saved_tokens.insert(token.mint);
-        }
+    if !confirm {
+        anyhow::bail!(
+            "Discovery scans every Whirlpool with getProgramAccounts, which is slow and often rate limited on public RPC nodes; pass --confirm to run it"
+        );
+    }
+
+    match &token_mint {
+        Some(mint) => log::info!("Discovering orca pools trading {}...", mint),
+        None => log::info!("Discovering all orca pools..."),
     }
+    let pools = discover_whirlpools(rpc_client, orca_program_id, token_mint.as_ref()).await?;
+    log::info!("Discovered {} orca pools", pools.len());
+    Ok(pools)
+}
 
-    // Then, save the pool record
+// Save a pool to the database; its tokens were stored when they were resolved
+async fn save_pool_to_database(db_pool: &sqlx::PgPool, pool_record: &PoolRecord) -> Result<()> {
     sqlx
         ::query(
             "INSERT INTO apestrong.subscribed_pools 
@@ -256,12 +350,9 @@ async fn save_pool_to_database(
         .bind(&pool_record.dex)
         .bind(pool_record.token_a.mint.to_string())
         .bind(pool_record.token_b.mint.to_string())
-        .execute(&mut *transaction).await
+        .execute(db_pool).await
         .context("Failed to save pool record")?;
 
-    // Commit the transaction
-    transaction.commit().await?;
-
-    println!("Saved/updated pool: {}", pool_record.pool_name);
+    log::info!("Saved/updated pool: {}", pool_record.pool_name);
     Ok(())
 }
@@ -4,6 +4,11 @@ DROP TABLE IF EXISTS apestrong.subscribed_pools;
 DROP INDEX IF EXISTS idx_token_metadata_last_updated;
 DROP INDEX IF EXISTS idx_subscribed_pools_last_updated;
 DROP TABLE IF EXISTS apestrong.token_metadata;
+DROP TABLE IF EXISTS apestrong.backfill_progress;
+DROP TABLE IF EXISTS apestrong.transaction_logs;
+DROP INDEX IF EXISTS idx_quarantine_events_dex;
+DROP INDEX IF EXISTS idx_quarantine_events_signature_event_index;
+DROP TABLE IF EXISTS apestrong.quarantine_events;
 DROP INDEX IF EXISTS idx_last_signatures_dex;
 DROP INDEX IF EXISTS idx_last_signatures_last_updated;
 DROP TABLE IF EXISTS apestrong.last_signatures;
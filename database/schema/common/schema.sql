@@ -21,13 +21,36 @@ CREATE TABLE IF NOT EXISTS apestrong.last_signatures (
     pool_address VARCHAR(44) PRIMARY KEY,
     signature VARCHAR(88) NOT NULL,
     dex apestrong.dex_type NOT NULL,
+    slot BIGINT,
     last_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
 );
 
+-- Add the slot of the last signature, used to roll back reorgs, to tables created before it existed
+ALTER TABLE apestrong.last_signatures
+    ADD COLUMN IF NOT EXISTS slot BIGINT;
+
 -- Add indexes for performance on last_signatures
 CREATE INDEX IF NOT EXISTS idx_last_signatures_dex ON apestrong.last_signatures(dex);
 CREATE INDEX IF NOT EXISTS idx_last_signatures_last_updated ON apestrong.last_signatures(last_updated);
 
+-- Create the backfill_progress table so an interrupted initial backfill resumes on restart
+CREATE TABLE IF NOT EXISTS apestrong.backfill_progress (
+    pool_address VARCHAR(44) PRIMARY KEY,
+    dex apestrong.dex_type NOT NULL,
+    oldest_backfilled_signature VARCHAR(88),
+    backfill_complete BOOLEAN NOT NULL DEFAULT FALSE,
+    last_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Create the transaction_logs table holding the raw logs of indexed transactions (optional, see --store-transaction-logs)
+CREATE TABLE IF NOT EXISTS apestrong.transaction_logs (
+    signature VARCHAR(88) PRIMARY KEY,
+    dex apestrong.dex_type NOT NULL,
+    slot BIGINT,
+    logs JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
 -- Create the token_metadata table for pool-related token info
 CREATE TABLE IF NOT EXISTS apestrong.token_metadata (
     mint VARCHAR(44) PRIMARY KEY,
@@ -53,4 +76,45 @@ CREATE TABLE IF NOT EXISTS apestrong.subscribed_pools (
 -- Add indexes for performance on subscribed_pools
 CREATE INDEX IF NOT EXISTS idx_subscribed_pools_token_a ON apestrong.subscribed_pools(token_a_mint);
 CREATE INDEX IF NOT EXISTS idx_subscribed_pools_token_b ON apestrong.subscribed_pools(token_b_mint);
-CREATE INDEX IF NOT EXISTS idx_subscribed_pools_last_updated ON apestrong.subscribed_pools(last_updated);
\ No newline at end of file
+CREATE INDEX IF NOT EXISTS idx_subscribed_pools_last_updated ON apestrong.subscribed_pools(last_updated);
+
+-- Raydium pools are either 'amm' or 'clmm'; NULL for other DEXes, and read as CLMM for Raydium
+ALTER TABLE apestrong.subscribed_pools ADD COLUMN IF NOT EXISTS pool_type VARCHAR(8);
+
+-- Create the quarantine_events table holding events rejected by validation (optional, see --event-validation)
+CREATE TABLE IF NOT EXISTS apestrong.quarantine_events (
+    id SERIAL PRIMARY KEY,
+    dex apestrong.dex_type NOT NULL,
+    signature VARCHAR(88) NOT NULL,
+    event_index INT,
+    event_type VARCHAR(32) NOT NULL,
+    pool VARCHAR(44) NOT NULL,
+    reason TEXT NOT NULL,
+    raw_data TEXT NOT NULL,
+    slot BIGINT,
+    block_time TIMESTAMPTZ,
+    quarantined_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantine_events_dex ON apestrong.quarantine_events (dex, id);
+
+-- Position of the event in its transaction; NULL for rows quarantined before it was recorded
+ALTER TABLE apestrong.quarantine_events ADD COLUMN IF NOT EXISTS event_index INT;
+
+-- An event is quarantined once however often its transaction is replayed, keyed like
+-- the event tables. Rows without a position can't be told apart by it, so only drop
+-- those that repeat an earlier row exactly
+DROP INDEX IF EXISTS apestrong.idx_quarantine_events_signature_event_type_pool;
+DELETE FROM apestrong.quarantine_events later
+    USING apestrong.quarantine_events earlier
+    WHERE later.id > earlier.id
+        AND later.event_index IS NULL
+        AND earlier.event_index IS NULL
+        AND later.dex = earlier.dex
+        AND later.signature = earlier.signature
+        AND later.event_type = earlier.event_type
+        AND later.pool = earlier.pool
+        AND later.reason = earlier.reason
+        AND later.raw_data = earlier.raw_data;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_quarantine_events_signature_event_index
+    ON apestrong.quarantine_events (signature, event_index);
@@ -1,10 +1,12 @@
 -- Drop statements for Orca tables and views
+DROP VIEW IF EXISTS apestrong.v_dex_events;
 DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_traded;
 DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_liquidity_increased;
 DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_liquidity_decreased;
 DROP TABLE IF EXISTS apestrong.orca_traded_events;
 DROP TABLE IF EXISTS apestrong.orca_liquidity_increased_events;
 DROP TABLE IF EXISTS apestrong.orca_liquidity_decreased_events;
+DROP TABLE IF EXISTS apestrong.orca_pool_initialized_events;
 DROP TABLE IF EXISTS apestrong.orca_whirlpool_events;
 
 
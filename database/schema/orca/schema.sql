@@ -5,42 +5,88 @@
 -- Base table for common fields of Orca Whirlpool events
 CREATE TABLE IF NOT EXISTS apestrong.orca_whirlpool_events (
     id SERIAL PRIMARY KEY,
-    signature VARCHAR(88) NOT NULL UNIQUE,
+    signature VARCHAR(88) NOT NULL,
+    event_index INT NOT NULL DEFAULT 0,
     whirlpool VARCHAR(44) NOT NULL,
     event_type VARCHAR(32) NOT NULL,
+    dex VARCHAR(32) NOT NULL DEFAULT 'orca',
+    slot BIGINT,
+    block_time TIMESTAMPTZ,
+    fee_payer VARCHAR(44),
     version INT NOT NULL DEFAULT 1,
-    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    enrichment JSONB
 );
 
+-- Add the dex column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS dex VARCHAR(32) NOT NULL DEFAULT 'orca';
+
+-- Add the slot column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS slot BIGINT;
+
+-- Add the block_time column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS block_time TIMESTAMPTZ;
+
+-- Add the fee_payer column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS fee_payer VARCHAR(44);
+
+-- Add the enrichment column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS enrichment JSONB;
+
+-- Add the event_index column to tables created before it existed
+ALTER TABLE apestrong.orca_whirlpool_events
+    ADD COLUMN IF NOT EXISTS event_index INT NOT NULL DEFAULT 0;
+
+-- Events are keyed by their transaction and position in it, since one transaction
+-- can emit several events; tables created before that were unique on signature alone
+ALTER TABLE apestrong.orca_whirlpool_events
+    DROP CONSTRAINT IF EXISTS orca_whirlpool_events_signature_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_orca_whirlpool_events_signature_event_index
+    ON apestrong.orca_whirlpool_events (signature, event_index);
+
 -- Index for performance on whirlpool and timestamp
 CREATE INDEX IF NOT EXISTS idx_orca_whirlpool_events_whirlpool_timestamp 
     ON apestrong.orca_whirlpool_events (whirlpool, timestamp);
 
+-- Index for per-slot aggregates and reorg rollbacks
+CREATE INDEX IF NOT EXISTS idx_orca_whirlpool_events_whirlpool_slot
+    ON apestrong.orca_whirlpool_events (whirlpool, slot);
+
 -- Table for Traded events, inheriting from base events
 CREATE TABLE IF NOT EXISTS apestrong.orca_traded_events (
     event_id INT PRIMARY KEY REFERENCES apestrong.orca_whirlpool_events(id) ON DELETE CASCADE,
     a_to_b BOOLEAN NOT NULL,
-    pre_sqrt_price BIGINT NOT NULL,
-    post_sqrt_price BIGINT NOT NULL,
-    input_amount BIGINT NOT NULL,
-    output_amount BIGINT NOT NULL,
-    input_transfer_fee BIGINT NOT NULL,
-    output_transfer_fee BIGINT NOT NULL,
-    lp_fee BIGINT NOT NULL,
-    protocol_fee BIGINT NOT NULL
+    pre_sqrt_price NUMERIC(39, 0) NOT NULL,
+    post_sqrt_price NUMERIC(39, 0) NOT NULL,
+    input_amount NUMERIC(20, 0) NOT NULL,
+    output_amount NUMERIC(20, 0) NOT NULL,
+    input_transfer_fee NUMERIC(20, 0) NOT NULL,
+    output_transfer_fee NUMERIC(20, 0) NOT NULL,
+    lp_fee NUMERIC(20, 0) NOT NULL,
+    protocol_fee NUMERIC(20, 0) NOT NULL,
+    price DOUBLE PRECISION
 );
 
+-- Add the post-trade price column to tables created before it existed
+ALTER TABLE apestrong.orca_traded_events
+    ADD COLUMN IF NOT EXISTS price DOUBLE PRECISION;
+
 -- Table for Liquidity Increased events, inheriting from base events
 CREATE TABLE IF NOT EXISTS apestrong.orca_liquidity_increased_events (
     event_id INT PRIMARY KEY REFERENCES apestrong.orca_whirlpool_events(id) ON DELETE CASCADE,
     position VARCHAR(44) NOT NULL,
     tick_lower_index INT NOT NULL,
     tick_upper_index INT NOT NULL,
-    liquidity BIGINT NOT NULL,
-    token_a_amount BIGINT NOT NULL,
-    token_b_amount BIGINT NOT NULL,
-    token_a_transfer_fee BIGINT NOT NULL,
-    token_b_transfer_fee BIGINT NOT NULL
+    liquidity NUMERIC(39, 0) NOT NULL,
+    token_a_amount NUMERIC(20, 0) NOT NULL,
+    token_b_amount NUMERIC(20, 0) NOT NULL,
+    token_a_transfer_fee NUMERIC(20, 0) NOT NULL,
+    token_b_transfer_fee NUMERIC(20, 0) NOT NULL
 );
 
 -- Table for Liquidity Decreased events, inheriting from base events
@@ -49,19 +95,64 @@ CREATE TABLE IF NOT EXISTS apestrong.orca_liquidity_decreased_events (
     position VARCHAR(44) NOT NULL,
     tick_lower_index INT NOT NULL,
     tick_upper_index INT NOT NULL,
-    liquidity BIGINT NOT NULL,
-    token_a_amount BIGINT NOT NULL,
-    token_b_amount BIGINT NOT NULL,
-    token_a_transfer_fee BIGINT NOT NULL,
-    token_b_transfer_fee BIGINT NOT NULL
+    liquidity NUMERIC(39, 0) NOT NULL,
+    token_a_amount NUMERIC(20, 0) NOT NULL,
+    token_b_amount NUMERIC(20, 0) NOT NULL,
+    token_a_transfer_fee NUMERIC(20, 0) NOT NULL,
+    token_b_transfer_fee NUMERIC(20, 0) NOT NULL
 );
 
+-- Table for Pool Initialized events, inheriting from base events
+CREATE TABLE IF NOT EXISTS apestrong.orca_pool_initialized_events (
+    event_id INT PRIMARY KEY REFERENCES apestrong.orca_whirlpool_events(id) ON DELETE CASCADE,
+    whirlpools_config VARCHAR(44) NOT NULL,
+    token_mint_a VARCHAR(44) NOT NULL,
+    token_mint_b VARCHAR(44) NOT NULL,
+    tick_spacing INT NOT NULL,
+    decimals_a INT NOT NULL,
+    decimals_b INT NOT NULL,
+    initial_sqrt_price NUMERIC(39, 0) NOT NULL
+);
+
+-- The Whirlpool program emits no reward emission event, so this table never got rows
+DROP TABLE IF EXISTS apestrong.orca_reward_emission_events;
+
+-- Widen the u64 and u128 columns of tables created when they were BIGINT. The indexer
+-- cast the on-chain values to i64, keeping only their low 64 bits: values below 2^64 that
+-- came out negative are restored, larger u128 values were lost and stay truncated. The
+-- views depend on these columns, so they are dropped here and recreated below
+DO $$
+DECLARE
+    col RECORD;
+BEGIN
+    FOR col IN
+        SELECT table_name, column_name
+        FROM information_schema.columns
+        WHERE table_schema = 'apestrong' AND data_type = 'bigint'
+            AND table_name IN (
+                'orca_traded_events',
+                'orca_liquidity_increased_events',
+                'orca_liquidity_decreased_events'
+            )
+    LOOP
+        DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_traded;
+        DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_liquidity_increased;
+        DROP VIEW IF EXISTS apestrong.v_orca_whirlpool_liquidity_decreased;
+        EXECUTE format(
+            'ALTER TABLE apestrong.%1$I ALTER COLUMN %2$I TYPE NUMERIC(%3$s, 0) USING CASE WHEN %2$I < 0 THEN %2$I + 18446744073709551616 ELSE %2$I END',
+            col.table_name,
+            col.column_name,
+            CASE WHEN col.column_name IN ('pre_sqrt_price', 'post_sqrt_price', 'liquidity') THEN 39 ELSE 20 END
+        );
+    END LOOP;
+END $$;
+
 -- View for Traded events
 CREATE OR REPLACE VIEW apestrong.v_orca_whirlpool_traded AS
 SELECT
     e.id, e.signature, e.whirlpool, e.timestamp,
     t.a_to_b, t.pre_sqrt_price, t.post_sqrt_price, t.input_amount, t.output_amount,
-    t.input_transfer_fee, t.output_transfer_fee, t.lp_fee, t.protocol_fee
+    t.input_transfer_fee, t.output_transfer_fee, t.lp_fee, t.protocol_fee, t.price
 FROM
     apestrong.orca_whirlpool_events e
 JOIN
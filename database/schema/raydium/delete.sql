@@ -1 +1,10 @@
--- placeholder
\ No newline at end of file
+-- Drop statements for Raydium tables and views
+DROP VIEW IF EXISTS apestrong.v_dex_events;
+DROP VIEW IF EXISTS apestrong.v_raydium_clmm_create_position;
+DROP VIEW IF EXISTS apestrong.v_raydium_clmm_liquidity_increased;
+DROP VIEW IF EXISTS apestrong.v_raydium_clmm_liquidity_decreased;
+DROP TABLE IF EXISTS apestrong.raydium_clmm_create_position_events;
+DROP TABLE IF EXISTS apestrong.raydium_clmm_liquidity_increased_events;
+DROP TABLE IF EXISTS apestrong.raydium_clmm_liquidity_decreased_events;
+DROP TABLE IF EXISTS apestrong.raydium_clmm_events;
+DROP TABLE IF EXISTS apestrong.raydium_clmm_positions;
@@ -1,4 +1,12 @@
+-- Ensure the schema exists (assuming apestrong schema is already created from previous script)
+-- If not, uncomment the following line:
+-- CREATE SCHEMA IF NOT EXISTS apestrong;
 
+-- NOTE ON NUMERIC ENCODING:
+-- Raydium CLMM events carry u64 amounts and u128 liquidity values. Postgres has no unsigned
+-- or 128-bit integer types, so these are stored as NUMERIC (NUMERIC(39, 0) for u128), which
+-- holds the full range without loss. Reward amounts ([u64; 3]) are stored as NUMERIC[].
+-- The indexer binds these values as base-10 text and casts them in SQL.
 
 -- Base table for common fields of Raydium CLMM events
 CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_events (
@@ -6,38 +14,66 @@ CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_events (
     signature VARCHAR(88) NOT NULL UNIQUE,
     pool VARCHAR(44) NOT NULL,
     event_type VARCHAR(32) NOT NULL,
+    dex VARCHAR(32) NOT NULL DEFAULT 'raydium',
+    slot BIGINT,
+    block_time TIMESTAMPTZ,
+    is_backfill BOOLEAN NOT NULL DEFAULT FALSE,
     version INT NOT NULL DEFAULT 1,
     timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
 );
 
--- Index for performance on whirlpool and timestamp
+-- Add the dex column to tables created before it existed
+ALTER TABLE apestrong.raydium_clmm_events
+    ADD COLUMN IF NOT EXISTS dex VARCHAR(32) NOT NULL DEFAULT 'raydium';
+
+-- Add the slot column to tables created before it existed
+ALTER TABLE apestrong.raydium_clmm_events
+    ADD COLUMN IF NOT EXISTS slot BIGINT;
+
+-- Add the block_time column to tables created before it existed
+ALTER TABLE apestrong.raydium_clmm_events
+    ADD COLUMN IF NOT EXISTS block_time TIMESTAMPTZ;
+
+-- Add the is_backfill column to tables created before it existed
+ALTER TABLE apestrong.raydium_clmm_events
+    ADD COLUMN IF NOT EXISTS is_backfill BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Index for performance on pool and timestamp
 CREATE INDEX IF NOT EXISTS idx_raydium_clmm_events_pool_timestamp 
     ON apestrong.raydium_clmm_events (pool, timestamp);
 
+-- Index for reorg rollbacks, which delete a pool's events after a slot
+CREATE INDEX IF NOT EXISTS idx_raydium_clmm_events_pool_slot
+    ON apestrong.raydium_clmm_events (pool, slot);
+
 -- Table for CreatePersonalPosition events, inheriting from base events
 CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_create_position_events (
     event_id INT PRIMARY KEY REFERENCES apestrong.raydium_clmm_events(id) ON DELETE CASCADE,
     minter VARCHAR(44) NOT NULL,
     nft_owner VARCHAR(44) NOT NULL,
-    output_amount BIGINT NOT NULL,
     tick_lower_index INTEGER NOT NULL,
     tick_upper_index INTEGER NOT NULL,
     liquidity NUMERIC(39, 0) NOT NULL,
-    deposit_amount_0 NUMERIC NOT NULL,
-    deposit_amount_1 NUMERIC NOT NULL,
-    deposit_amount_0_transfer_fee NUMERIC NOT NULL,
-    deposit_amount_1_transfer_fee NUMERIC NOT NULL
+    deposit_amount_0 NUMERIC(20, 0) NOT NULL,
+    deposit_amount_1 NUMERIC(20, 0) NOT NULL,
+    deposit_amount_0_transfer_fee NUMERIC(20, 0) NOT NULL,
+    deposit_amount_1_transfer_fee NUMERIC(20, 0) NOT NULL
 );
 
+-- Drop the output_amount column from tables created before it was removed. The
+-- CreatePersonalPosition event has no output amount, so it was always 0
+ALTER TABLE apestrong.raydium_clmm_create_position_events
+    DROP COLUMN IF EXISTS output_amount;
+
 -- Table for Liquidity Increased events, inheriting from base events
 CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_liquidity_increased_events (
     event_id INT PRIMARY KEY REFERENCES apestrong.raydium_clmm_events(id) ON DELETE CASCADE,
     position_nft_mint VARCHAR(44) NOT NULL,
     liquidity NUMERIC(39, 0) NOT NULL,
-    amount_0 NUMERIC NOT NULL,
-    amount_1 NUMERIC NOT NULL,
-    amount_0_transfer_fee NUMERIC NOT NULL,
-    amount_1_transfer_fee NUMERIC NOT NULL,
+    amount_0 NUMERIC(20, 0) NOT NULL,
+    amount_1 NUMERIC(20, 0) NOT NULL,
+    amount_0_transfer_fee NUMERIC(20, 0) NOT NULL,
+    amount_1_transfer_fee NUMERIC(20, 0) NOT NULL
 );
 
 -- Table for Liquidity Decreased events, inheriting from base events
@@ -45,51 +81,66 @@ CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_liquidity_decreased_events (
     event_id INT PRIMARY KEY REFERENCES apestrong.raydium_clmm_events(id) ON DELETE CASCADE,
     position_nft_mint VARCHAR(44) NOT NULL,
     liquidity NUMERIC(39, 0) NOT NULL,
-    decrease_amount_0 NUMERIC NOT NULL,
-    decrease_amount_1 NUMERIC NOT NULL,
-    fee_amount_0 NUMERIC NOT NULL,
-    fee_amount_1 NUMERIC NOT NULL,
-    transfer_fee_0 NUMERIC NOT NULL,
-    transfer_fee_1 NUMERIC NOT NULL,
+    decrease_amount_0 NUMERIC(20, 0) NOT NULL,
+    decrease_amount_1 NUMERIC(20, 0) NOT NULL,
+    fee_amount_0 NUMERIC(20, 0) NOT NULL,
+    fee_amount_1 NUMERIC(20, 0) NOT NULL,
+    reward_amounts NUMERIC(20, 0)[] NOT NULL,
+    transfer_fee_0 NUMERIC(20, 0) NOT NULL,
+    transfer_fee_1 NUMERIC(20, 0) NOT NULL
 );
 
--- View for Traded events
+-- Mapping of CLMM position NFT mints to their pool. Increase/decrease liquidity events only
+-- carry the position NFT mint, so the pool is resolved through this table when it cannot be
+-- found in the transaction's own logs.
+CREATE TABLE IF NOT EXISTS apestrong.raydium_clmm_positions (
+    position_nft_mint VARCHAR(44) PRIMARY KEY,
+    pool VARCHAR(44) NOT NULL,
+    signature VARCHAR(88) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for looking up positions by pool
+CREATE INDEX IF NOT EXISTS idx_raydium_clmm_positions_pool
+    ON apestrong.raydium_clmm_positions (pool);
+
+-- View for CreatePosition events
 CREATE OR REPLACE VIEW apestrong.v_raydium_clmm_create_position AS
 SELECT
-    e.id, e.signature, e.whirlpool, e.timestamp,
-    t.a_to_b, t.pre_sqrt_price, t.post_sqrt_price, t.input_amount, t.output_amount,
-    t.input_transfer_fee, t.output_transfer_fee, t.lp_fee, t.protocol_fee
+    e.id, e.signature, e.pool, e.timestamp,
+    c.minter, c.nft_owner, c.tick_lower_index, c.tick_upper_index, c.liquidity,
+    c.deposit_amount_0, c.deposit_amount_1,
+    c.deposit_amount_0_transfer_fee, c.deposit_amount_1_transfer_fee
 FROM
     apestrong.raydium_clmm_events e
 JOIN
-    apestrong.raydium_clmm_create_position_events t ON e.id = t.event_id
+    apestrong.raydium_clmm_create_position_events c ON e.id = c.event_id
 WHERE
-    e.event_type = 'CreatePersonalPosition';
+    e.event_type = 'CreatePosition';
 
 -- View for Liquidity Increased events
 CREATE OR REPLACE VIEW apestrong.v_raydium_clmm_liquidity_increased AS
 SELECT 
-    e.id, e.signature, e.whirlpool, e.timestamp, 
-    l.position, l.tick_lower_index, l.tick_upper_index, 
-    l.liquidity, l.token_a_amount, l.token_b_amount,
-    l.token_a_transfer_fee, l.token_b_transfer_fee
+    e.id, e.signature, e.pool, e.timestamp, 
+    l.position_nft_mint, l.liquidity, l.amount_0, l.amount_1,
+    l.amount_0_transfer_fee, l.amount_1_transfer_fee
 FROM 
     apestrong.raydium_clmm_events e
 JOIN 
-    apestrong.orca_liquidity_increased_events l ON e.id = l.event_id
+    apestrong.raydium_clmm_liquidity_increased_events l ON e.id = l.event_id
 WHERE 
-    e.event_type = 'liquidity_increased';
+    e.event_type = 'IncreaseLiquidity';
 
 -- View for Liquidity Decreased events
 CREATE OR REPLACE VIEW apestrong.v_raydium_clmm_liquidity_decreased AS
 SELECT 
-    e.id, e.signature, e.whirlpool, e.timestamp, 
-    l.position, l.tick_lower_index, l.tick_upper_index, 
-    l.liquidity, l.token_a_amount, l.token_b_amount,
-    l.token_a_transfer_fee, l.token_b_transfer_fee
+    e.id, e.signature, e.pool, e.timestamp, 
+    l.position_nft_mint, l.liquidity, l.decrease_amount_0, l.decrease_amount_1,
+    l.fee_amount_0, l.fee_amount_1, l.reward_amounts,
+    l.transfer_fee_0, l.transfer_fee_1
 FROM 
     apestrong.raydium_clmm_events e
 JOIN 
-    apestrong.orca_liquidity_decreased_events l ON e.id = l.event_id
+    apestrong.raydium_clmm_liquidity_decreased_events l ON e.id = l.event_id
 WHERE 
-    e.event_type = 'liquidity_decreased';
\ No newline at end of file
+    e.event_type = 'DecreaseLiquidity';
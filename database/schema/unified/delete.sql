@@ -0,0 +1,2 @@
+-- Drop statements for the unified (cross-DEX) views
+DROP VIEW IF EXISTS apestrong.v_dex_events;
@@ -0,0 +1,15 @@
+-- Unified (cross-DEX) schema mode
+-- Opt-in view over the per-DEX base event tables. The per-DEX tables remain the storage
+-- of record; this view requires both the Orca and Raydium schemas to exist.
+
+-- View of base events from all DEXes, distinguished by the dex column
+CREATE OR REPLACE VIEW apestrong.v_dex_events AS
+SELECT
+    e.dex, e.id, e.signature, e.whirlpool AS pool, e.event_type, e.version, e.timestamp
+FROM
+    apestrong.orca_whirlpool_events e
+UNION ALL
+SELECT
+    e.dex, e.id, e.signature, e.pool, e.event_type, e.version, e.timestamp
+FROM
+    apestrong.raydium_clmm_events e;
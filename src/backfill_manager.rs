@@ -3,33 +3,372 @@ use solana_client::{
     nonblocking::rpc_client::RpcClient,
     rpc_client::GetConfirmedSignaturesForAddress2Config,
     rpc_config::RpcTransactionConfig,
+    rpc_request::RpcRequest,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
 };
 use solana_sdk::{ commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature };
-use solana_transaction_status::UiTransactionEncoding;
+use futures::stream::{ self, StreamExt };
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta,
+    TransactionStatus,
+    UiTransactionEncoding,
+};
+use rand::Rng;
+use std::collections::{ HashMap, HashSet };
+use std::future::Future;
 use std::str::FromStr;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::Duration;
 
-use crate::db::signature_store::SignatureStore;
+use crate::db::signature_store::{ BackfillProgress, SignatureStore, TrackedPoolOrder };
+use crate::error::IndexerError;
+use crate::metrics;
+use crate::models::common::Protocol;
+use crate::rate_limiter::RateLimiter;
+use crate::rpc::{ build_rpc_client_with_headers, DEFAULT_RPC_TIMEOUT };
 use crate::utils::logging;
 
+/// Default number of backfill transactions fetched at once, low enough to sit
+/// comfortably alongside a request rate limit
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 5;
+
+/// Default number of pools backfilled at once during the initial backfill
+pub const DEFAULT_POOL_CONCURRENCY: usize = 4;
+
+/// Default number of pools whose last signatures are read with one query
+pub const DEFAULT_SIGNATURE_READ_BATCH_SIZE: usize = 500;
+
+/// Slots before a dropped last signature that a reorg rollback also re-indexes,
+/// since the dropped fork may have started a few slots earlier
+pub const REORG_ROLLBACK_SLOTS: u64 = 32;
+
 /// Configuration for backfill operations
+#[derive(Clone)]
 pub struct BackfillConfig {
     /// Solana RPC URL
     pub rpc_url: String,
+    /// Extra HTTP headers sent with every RPC request (e.g. an API key)
+    pub rpc_headers: HashMap<String, String>,
     /// Maximum number of signatures to fetch per request
     pub max_signatures_per_request: usize,
     /// How far back to look for transactions on initial backfill
     pub initial_backfill_slots: u64,
     /// DEX type identifier (e.g., "orca", "raydium")
     pub dex_type: String,
+    /// Commitment level for RPC calls
+    pub commitment: CommitmentConfig,
+    /// Timeout for each RPC request
+    pub rpc_timeout: Duration,
+    /// Retry settings for signature and transaction fetches
+    pub retry: RetryConfig,
+    /// Order in which initial backfill signatures are processed
+    pub order: BackfillOrder,
+    /// Maximum transactions backfilled per pool in one cycle, so one busy pool can't starve the rest
+    pub pool_cap: Option<usize>,
+    /// Maximum RPC requests per second, to stay under public RPC rate limits (None for no limit)
+    pub max_requests_per_second: Option<u32>,
+    /// Maximum getTransaction requests in flight at once during backfill
+    pub fetch_concurrency: usize,
+    /// Maximum pools backfilled at once during the initial backfill
+    pub pool_concurrency: usize,
+    /// Pools whose last signatures are read per signature store query in a scheduled backfill
+    pub signature_read_batch_size: usize,
 }
 
 impl Default for BackfillConfig {
     fn default() -> Self {
         Self {
             rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            rpc_headers: HashMap::new(),
             max_signatures_per_request: 100,
             initial_backfill_slots: 10_000, // Approx 4 hours of slots
-            dex_type: "orca".to_string(),
+            dex_type: Protocol::OrcaWhirlpool.dex().to_string(),
+            commitment: CommitmentConfig::confirmed(),
+            rpc_timeout: DEFAULT_RPC_TIMEOUT,
+            retry: RetryConfig::default(),
+            order: BackfillOrder::default(),
+            pool_cap: None,
+            max_requests_per_second: None,
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            pool_concurrency: DEFAULT_POOL_CONCURRENCY,
+            signature_read_batch_size: DEFAULT_SIGNATURE_READ_BATCH_SIZE,
+        }
+    }
+}
+
+/// Order in which backfilled signatures are processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackfillOrder {
+    /// Most recent transactions first, as returned by the RPC node
+    #[default]
+    NewestFirst,
+    /// Oldest transactions first, so events are emitted chronologically
+    OldestFirst,
+}
+
+impl BackfillOrder {
+    /// Put signatures that arrived newest first into this order
+    pub fn apply(&self, signatures: &mut [Signature]) {
+        if *self == BackfillOrder::OldestFirst {
+            signatures.reverse();
+        }
+    }
+}
+
+impl FromStr for BackfillOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "newest-first" => Ok(BackfillOrder::NewestFirst),
+            "oldest-first" => Ok(BackfillOrder::OldestFirst),
+            other =>
+                Err(
+                    format!(
+                        "Unknown backfill order '{}', expected 'newest-first' or 'oldest-first'",
+                        other
+                    )
+                ),
+        }
+    }
+}
+
+/// Retry settings for RPC calls that fail with transient errors
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry in milliseconds, doubled on each further retry
+    pub base_delay_ms: u64,
+    /// Maximum delay between retries in milliseconds
+    pub max_delay_ms: u64,
+    /// Fraction of each delay that may be randomly shaved off (0.0 disables jitter)
+    pub jitter_ratio: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            jitter_ratio: 0.2,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before the given retry (1 for the first retry)
+    pub fn delay_for_retry(&self, retry: u32) -> Duration {
+        let delay_ms = std::cmp::min(
+            self.base_delay_ms.saturating_mul(1u64 << (retry - 1).min(32)),
+            self.max_delay_ms
+        );
+        let max_jitter_ms = ((delay_ms as f64) * self.jitter_ratio.clamp(0.0, 1.0)) as u64;
+        let jitter_ms = if max_jitter_ms > 0 {
+            rand::thread_rng().gen_range(0..=max_jitter_ms)
+        } else {
+            0
+        };
+
+        Duration::from_millis(delay_ms - jitter_ms)
+    }
+}
+
+/// Categorize errors as transient (rate limits, timeouts, dropped connections) or permanent
+///
+/// Only an `IndexerError` saying so is transient; errors of any other type are
+/// treated as permanent.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    IndexerError::find(err).is_some_and(IndexerError::is_transient)
+}
+
+/// Run an operation, retrying transient errors with jittered exponential backoff
+///
+/// Permanent errors and the error from the last attempt are returned as is.
+pub async fn retry_with_backoff<T, F, Fut>(
+    config: &RetryConfig,
+    operation_name: &str,
+    mut operation: F
+) -> Result<T>
+    where F: FnMut() -> Fut, Fut: Future<Output = Result<T>>
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Ok(value) => {
+                return Ok(value);
+            }
+            Err(e) if attempt < config.max_attempts && is_transient_error(&e) => {
+                let delay = config.delay_for_retry(attempt);
+                logging::log_activity(
+                    "backfill",
+                    "Retrying RPC call",
+                    Some(
+                        &format!(
+                            "{} failed (attempt {}/{}), retrying in {:?}: {}",
+                            operation_name,
+                            attempt,
+                            config.max_attempts,
+                            delay,
+                            e
+                        )
+                    )
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Parse a list of transaction signatures, one per line
+///
+/// Blank lines and lines starting with `#` are skipped, and surrounding
+/// whitespace is ignored. Errors name the offending line.
+pub fn parse_signature_list(contents: &str) -> Result<Vec<Signature>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            Signature::from_str(line).with_context(||
+                format!("Invalid signature '{}' on line {}", line, line_number)
+            )
+        })
+        .collect()
+}
+
+/// Walks a pool's signature history page by page using the `before` cursor
+///
+/// Pages come back newest first. Paging stops when a page is shorter than the
+/// page size (history or the `until` signature was reached) or, if `min_slot`
+/// is set, once signatures older than that slot show up.
+#[derive(Debug, Clone)]
+pub struct SignaturePaginator {
+    page_size: usize,
+    before: Option<Signature>,
+    until: Option<Signature>,
+    min_slot: Option<u64>,
+    done: bool,
+}
+
+impl SignaturePaginator {
+    pub fn new(page_size: usize, until: Option<Signature>, min_slot: Option<u64>) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            before: None,
+            until,
+            min_slot,
+            done: false,
+        }
+    }
+
+    /// Cursor for the next page: the oldest signature seen so far
+    pub fn before(&self) -> Option<Signature> {
+        self.before
+    }
+
+    /// Signature the walk stops at (exclusive)
+    pub fn until(&self) -> Option<Signature> {
+        self.until
+    }
+
+    /// Whether there are no more pages to fetch
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Continue from previously stored progress instead of the newest signature
+    pub fn resume(mut self, progress: &BackfillProgress) -> Result<Self> {
+        if let Some(oldest) = &progress.oldest_backfilled_signature {
+            self.before = Some(Signature::from_str(oldest)?);
+        }
+        self.done = progress.backfill_complete;
+        Ok(self)
+    }
+
+    /// Progress to store once every signature returned so far has been processed
+    pub fn progress(&self) -> BackfillProgress {
+        BackfillProgress {
+            oldest_backfilled_signature: self.before.map(|signature| signature.to_string()),
+            backfill_complete: self.done,
+        }
+    }
+
+    /// Consume a page, returning its signatures within the slot bound and moving the cursor
+    pub fn advance(
+        &mut self,
+        page: &[RpcConfirmedTransactionStatusWithSignature]
+    ) -> Result<Vec<Signature>> {
+        if page.len() < self.page_size {
+            self.done = true;
+        }
+
+        let mut signatures = Vec::with_capacity(page.len());
+        for info in page {
+            if self.min_slot.is_some_and(|min_slot| info.slot < min_slot) {
+                self.done = true;
+                break;
+            }
+            signatures.push(Signature::from_str(&info.signature)?);
+        }
+
+        if let Some(oldest) = page.last() {
+            self.before = Some(Signature::from_str(&oldest.signature)?);
+        }
+
+        Ok(signatures)
+    }
+}
+
+/// Slot or block time range to re-index, with both ends inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillWindow {
+    Slots {
+        from: u64,
+        to: u64,
+    },
+    /// Unix timestamps in seconds
+    BlockTime {
+        from: i64,
+        to: i64,
+    },
+}
+
+impl BackfillWindow {
+    /// Check that the window is not empty
+    pub fn validate(&self) -> Result<()> {
+        let valid = match self {
+            BackfillWindow::Slots { from, to } => from <= to,
+            BackfillWindow::BlockTime { from, to } => from <= to,
+        };
+        if !valid {
+            anyhow::bail!("Backfill window start must not be after its end: {:?}", self);
+        }
+        Ok(())
+    }
+
+    /// Whether a signature falls inside the window
+    ///
+    /// Signatures without a block time never match a time window.
+    pub fn contains(&self, info: &RpcConfirmedTransactionStatusWithSignature) -> bool {
+        match self {
+            BackfillWindow::Slots { from, to } => (*from..=*to).contains(&info.slot),
+            BackfillWindow::BlockTime { from, to } =>
+                info.block_time.is_some_and(|time| (*from..=*to).contains(&time)),
+        }
+    }
+
+    /// Whether a signature is older than the window, so paging can stop
+    pub fn is_past(&self, info: &RpcConfirmedTransactionStatusWithSignature) -> bool {
+        match self {
+            BackfillWindow::Slots { from, .. } => info.slot < *from,
+            BackfillWindow::BlockTime { from, .. } => info.block_time.is_some_and(|time| time < *from),
         }
     }
 }
@@ -39,142 +378,393 @@ pub struct BackfillManager {
     config: BackfillConfig,
     signature_store: SignatureStore,
     rpc_client: RpcClient,
+    rate_limiter: Option<RateLimiter>,
+    pool_logging: AtomicBool,
+    // Last (slot, block time) looked up, shared by the live events of one block
+    last_block_time: std::sync::Mutex<Option<(u64, i64)>>,
 }
 
 impl BackfillManager {
     /// Create a new BackfillManager
     pub fn new(config: BackfillConfig, signature_store: SignatureStore) -> Self {
-        let rpc_client = RpcClient::new_with_commitment(
-            config.rpc_url.clone(),
-            CommitmentConfig::confirmed()
+        let rpc_client = build_rpc_client_with_headers(
+            &config.rpc_url,
+            config.commitment,
+            config.rpc_timeout,
+            &config.rpc_headers
         );
 
+        Self::with_rpc_client(config, signature_store, rpc_client)
+    }
+
+    /// Create a BackfillManager that uses the given RPC client instead of `config.rpc_url`
+    pub fn with_rpc_client(
+        config: BackfillConfig,
+        signature_store: SignatureStore,
+        rpc_client: RpcClient
+    ) -> Self {
+        let rate_limiter = config.max_requests_per_second.map(RateLimiter::new);
         Self {
             config,
             signature_store,
             rpc_client,
+            rate_limiter,
+            pool_logging: AtomicBool::new(true),
+            last_block_time: std::sync::Mutex::new(None),
         }
     }
 
-    /// Perform initial backfill for a pool to establish baseline data
-    pub async fn initial_backfill_for_pool(&self, pool: &Pubkey) -> Result<Vec<Signature>> {
-        logging::log_dex_activity(
-            "backfill",
-            &self.config.dex_type,
-            "Initial backfill",
-            Some(&format!("for pool {}", pool))
+    /// Log per-pool backfill steps at info level (the default), or at debug level
+    ///
+    /// Indexers monitoring thousands of pools turn this off to keep logs readable.
+    pub fn set_pool_logging(&self, enabled: bool) {
+        self.pool_logging.store(enabled, Ordering::Relaxed);
+    }
+
+    // Log a backfill step for a single pool
+    fn log_pool_activity(&self, message: &str, details: &str) {
+        if self.pool_logging.load(Ordering::Relaxed) {
+            logging::log_dex_activity("backfill", &self.config.dex_type, message, Some(details));
+        } else {
+            log::debug!("[{}] {}: {}", self.config.dex_type, message, details);
+        }
+    }
+
+    /// Backfill configuration
+    pub fn config(&self) -> &BackfillConfig {
+        &self.config
+    }
+
+    // Wait for the rate limiter, if any, before an RPC request
+    async fn throttle(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+
+    /// Commitment for transaction history calls
+    ///
+    /// getSignaturesForAddress and getTransaction reject `processed`, so it is
+    /// raised to `confirmed` for those calls.
+    fn history_commitment(&self) -> CommitmentConfig {
+        if self.config.commitment.is_at_least_confirmed() {
+            self.config.commitment
+        } else {
+            CommitmentConfig::confirmed()
+        }
+    }
+
+    /// Fetch one page of signatures for a pool (newest first), older than `before`
+    /// and stopping at `until` if given
+    async fn get_signatures_for_address(
+        &self,
+        pool: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        retry_with_backoff(&self.config.retry, "getSignaturesForAddress", || async {
+            self.throttle().await;
+            self.rpc_client
+                .get_signatures_for_address_with_config(pool, GetConfirmedSignaturesForAddress2Config {
+                    limit: Some(self.config.max_signatures_per_request),
+                    before,
+                    until,
+                    commitment: Some(self.history_commitment()),
+                }).await
+                .map_err(IndexerError::from)
+                .with_context(|| format!("Failed to fetch signatures for pool {}", pool))
+        }).await
+    }
+
+    /// Start an initial backfill going back `initial_backfill_slots` from the current slot
+    ///
+    /// If an earlier run stored progress for the pool, paging resumes from the
+    /// oldest signature it processed, and a completed backfill yields no pages.
+    pub async fn initial_backfill_paginator(&self, pool: &Pubkey) -> Result<SignaturePaginator> {
+        let current_slot = self.get_current_slot().await?;
+        let min_slot = current_slot.saturating_sub(self.config.initial_backfill_slots);
+        let paginator = SignaturePaginator::new(
+            self.config.max_signatures_per_request,
+            None,
+            Some(min_slot)
         );
 
-        let signatures = self.rpc_client.get_signatures_for_address_with_config(
-            pool,
-            GetConfirmedSignaturesForAddress2Config {
-                limit: Some(self.config.max_signatures_per_request),
-                before: None,
-                until: None,
-                commitment: Some(CommitmentConfig::confirmed()),
+        match self.backfill_progress(pool).await? {
+            Some(progress) => {
+                self.log_pool_activity(
+                    "Resuming initial backfill",
+                    &format!(
+                        "for pool {} from signature {} (complete: {})",
+                        pool,
+                        progress.oldest_backfilled_signature.as_deref().unwrap_or("none"),
+                        progress.backfill_complete
+                    )
+                );
+                paginator.resume(&progress)
+            }
+            None => {
+                self.log_pool_activity(
+                    "Initial backfill",
+                    &format!("for pool {} from slot {} to {}", pool, min_slot, current_slot)
+                );
+                Ok(paginator)
             }
+        }
+    }
+
+    /// Initial backfill progress stored for a pool, if a backfill was started
+    pub async fn backfill_progress(&self, pool: &Pubkey) -> Result<Option<BackfillProgress>> {
+        self.signature_store.get_backfill_progress(pool, &self.config.dex_type).await
+    }
+
+    /// Store a pool's initial backfill progress so a restart resumes from it
+    pub async fn record_backfill_progress(
+        &self,
+        pool: &Pubkey,
+        progress: BackfillProgress
+    ) -> Result<()> {
+        self.signature_store.update_backfill_progress(pool, progress, &self.config.dex_type).await
+    }
+
+    /// Fetch the next page of signatures (newest first) and advance the paginator
+    ///
+    /// The newest signature of the first page is stored as the pool's checkpoint
+    /// for future backfills.
+    pub async fn next_signature_page(
+        &self,
+        pool: &Pubkey,
+        paginator: &mut SignaturePaginator
+    ) -> Result<Vec<Signature>> {
+        if paginator.is_done() {
+            return Ok(Vec::new());
+        }
+
+        let is_first_page = paginator.before().is_none();
+        let page = self.get_signatures_for_address(
+            pool,
+            paginator.before(),
+            paginator.until()
         ).await?;
 
-        let mut result = Vec::new();
+        if is_first_page {
+            if let Some(newest) = page.first() {
+                self.signature_store.update_signature_at_slot(
+                    pool,
+                    newest.signature.clone(),
+                    Some(newest.slot),
+                    &self.config.dex_type
+                ).await?;
+            }
+        }
 
-        if let Some(last_info) = signatures.last() {
-            // Store the oldest signature as our start point
-            self.signature_store.update_signature(
-                pool,
-                last_info.signature.clone(),
-                &self.config.dex_type
-            ).await?;
+        paginator.advance(&page)
+    }
+
+    /// Perform initial backfill for a pool, collecting every signature within the backfill depth
+    ///
+    /// Signatures are returned in the configured `order`. Prefer paging with
+    /// `initial_backfill_paginator`/`next_signature_page` when newest-first
+    /// signatures are processed as they arrive.
+    pub async fn initial_backfill_for_pool(&self, pool: &Pubkey) -> Result<Vec<Signature>> {
+        let mut paginator = self.initial_backfill_paginator(pool).await?;
+
+        let mut result = Vec::new();
+        while !paginator.is_done() {
+            result.extend(self.next_signature_page(pool, &mut paginator).await?);
         }
 
-        if let Some(first_info) = signatures.first() {
-            // Process from newest to oldest
-            for info in &signatures {
-                let signature = Signature::from_str(&info.signature)?;
-                result.push(signature);
+        self.log_pool_activity(
+            "Initial backfill complete",
+            &format!("for pool {}, fetched {} signatures", pool, result.len())
+        );
+
+        self.config.order.apply(&mut result);
+        Ok(result)
+    }
+
+    /// Collect a pool's signatures inside a slot or time window, oldest first
+    ///
+    /// History is paged back from the newest signature with the `before` cursor,
+    /// skipping signatures newer than the window and stopping at the first one
+    /// older than it. The stored checkpoint is left untouched.
+    pub async fn signatures_in_window(
+        &self,
+        pool: &Pubkey,
+        window: &BackfillWindow
+    ) -> Result<Vec<Signature>> {
+        window.validate()?;
+        logging::log_dex_activity(
+            "backfill",
+            &self.config.dex_type,
+            "Window backfill",
+            Some(&format!("for pool {} in {:?}", pool, window))
+        );
+
+        let mut before = None;
+        let mut result = Vec::new();
+        loop {
+            let page = self.get_signatures_for_address(pool, before, None).await?;
+
+            let mut reached_start = false;
+            for info in &page {
+                if window.is_past(info) {
+                    reached_start = true;
+                    break;
+                }
+                if window.contains(info) {
+                    result.push(Signature::from_str(&info.signature)?);
+                }
             }
 
-            // Store the newest signature for future backfills
-            self.signature_store.update_signature(
-                pool,
-                first_info.signature.clone(),
-                &self.config.dex_type
-            ).await?;
+            if reached_start || page.len() < self.config.max_signatures_per_request.max(1) {
+                break;
+            }
+            match page.last() {
+                Some(oldest) => {
+                    before = Some(Signature::from_str(&oldest.signature)?);
+                }
+                None => {
+                    break;
+                }
+            }
         }
 
         logging::log_dex_activity(
             "backfill",
             &self.config.dex_type,
-            "Initial backfill complete",
-            Some(&format!("for pool {}, fetched {} signatures", pool, result.len()))
+            "Window backfill signatures",
+            Some(&format!("for pool {}, found {} signatures", pool, result.len()))
         );
+
+        result.reverse();
         Ok(result)
     }
 
     /// Backfill missed transactions for a pool since the last processed signature
+    ///
+    /// Signatures are returned oldest first, at most `pool_cap` of them.
     pub async fn backfill_since_last_signature(&self, pool: &Pubkey) -> Result<Vec<Signature>> {
-        let last_signature = match
-            self.signature_store.get_signature(pool, &self.config.dex_type).await?
-        {
+        let last_signature = self.last_signature(pool).await?;
+        self.backfill_since_signature(pool, last_signature).await
+    }
+
+    /// Backfill missed transactions for a pool since an already read last signature
+    ///
+    /// Lets a caller backfilling many pools read their signatures in bulk with
+    /// `last_signatures`. Without a signature, the pool gets its initial backfill.
+    pub async fn backfill_since_signature(
+        &self,
+        pool: &Pubkey,
+        last_signature: Option<String>
+    ) -> Result<Vec<Signature>> {
+        let last_signature = match last_signature {
             Some(sig) => sig,
             None => {
-                logging::log_dex_activity(
-                    "backfill",
-                    &self.config.dex_type,
+                self.log_pool_activity(
                     "No last signature",
-                    Some(&format!("for pool {}, performing initial backfill", pool))
+                    &format!("for pool {}, performing initial backfill", pool)
                 );
                 return self.initial_backfill_for_pool(pool).await;
             }
         };
-        logging::log_dex_activity(
-            "backfill",
-            &self.config.dex_type,
+        self.log_pool_activity(
             "Incremental backfill",
-            Some(&format!("for pool {} since signature {}", pool, last_signature))
+            &format!("for pool {} since signature {}", pool, last_signature)
         );
 
         // Convert the last_signature string to a Signature
         let until_signature = Signature::from_str(&last_signature)?;
 
-        let signatures = self.rpc_client.get_signatures_for_address_with_config(
-            pool,
-            GetConfirmedSignaturesForAddress2Config {
-                limit: Some(self.config.max_signatures_per_request),
-                before: None,
-                until: Some(until_signature),
-                commitment: Some(CommitmentConfig::confirmed()),
-            }
-        ).await?;
-
+        // Page back until the stored signature so nothing between it and the
+        // newest page is skipped when more than a page accrued since the last run
+        let mut paginator = SignaturePaginator::new(
+            self.config.max_signatures_per_request,
+            Some(until_signature),
+            None
+        );
+        let mut newest_signature = None;
+        let mut slots = HashMap::new();
         let mut result = Vec::new();
+        while !paginator.is_done() {
+            let page = self.get_signatures_for_address(
+                pool,
+                paginator.before(),
+                paginator.until()
+            ).await?;
+            if newest_signature.is_none() {
+                newest_signature = page.first().map(|info| info.signature.clone());
+            }
+            slots.extend(page.iter().map(|info| (info.signature.clone(), info.slot)));
+            result.extend(paginator.advance(&page)?);
+        }
 
-        if signatures.is_empty() {
-            logging::log_dex_activity(
-                "backfill",
-                &self.config.dex_type,
-                "No updates",
-                Some("No new transactions since last signature")
-            );
+        if result.is_empty() {
+            self.log_pool_activity("No updates", "No new transactions since last signature");
             return Ok(result);
         }
-        logging::log_dex_activity(
-            "backfill",
-            &self.config.dex_type,
+        self.log_pool_activity(
             "New transactions found",
-            Some(&format!("{} new transactions since last signature", signatures.len()))
+            &format!("{} new transactions since last signature", result.len())
         );
 
-        // Process from newest to oldest
-        for info in &signatures {
-            let signature = Signature::from_str(&info.signature)?;
-            result.push(signature);
+        // Everything after the checkpoint should have landed in later slots, so a
+        // transaction in an earlier slot was confirmed late and missed by the live stream
+        if let Some(checkpoint_slot) = self.last_signature_slot(pool).await? {
+            let missed = result
+                .iter()
+                .filter(|signature| {
+                    slots
+                        .get(&signature.to_string())
+                        .is_some_and(|slot| *slot < checkpoint_slot)
+                })
+                .count();
+            if missed > 0 {
+                logging::log_dex_activity(
+                    "backfill",
+                    &self.config.dex_type,
+                    "Missed live events",
+                    Some(
+                        &format!(
+                            "for pool {}, {} transactions in slots before checkpoint slot {}",
+                            pool,
+                            missed,
+                            checkpoint_slot
+                        )
+                    )
+                );
+                metrics::record_missed_live_events(&self.config.dex_type, missed);
+            }
+        }
+
+        // Process from oldest to newest
+        result.reverse();
+
+        // Defer anything over the per-pool cap to the next cycle by only
+        // checkpointing the newest signature that will be processed now
+        if let Some(cap) = self.config.pool_cap.filter(|cap| result.len() > *cap) {
+            logging::log_dex_activity(
+                "backfill",
+                &self.config.dex_type,
+                "Pool cap reached",
+                Some(
+                    &format!(
+                        "for pool {}, deferring {} transactions to the next cycle",
+                        pool,
+                        result.len() - cap
+                    )
+                )
+            );
+            result.truncate(cap);
+            newest_signature = result.last().map(|signature| signature.to_string());
         }
 
-        // Update the newest signature
-        if let Some(first_info) = signatures.first() {
-            self.signature_store.update_signature(
+        // Update the newest signature once the whole range has been fetched
+        if let Some(newest_signature) = newest_signature {
+            let slot = slots.get(&newest_signature).copied();
+            self.signature_store.update_signature_at_slot(
                 pool,
-                first_info.signature.clone(),
+                newest_signature,
+                slot,
                 &self.config.dex_type
             ).await?;
         }
@@ -182,9 +772,109 @@ impl BackfillManager {
         Ok(result)
     }
 
-    /// Get all pools this DEX is tracking
-    pub async fn get_tracked_pools(&self) -> Result<Vec<Pubkey>> {
-        self.signature_store.get_tracked_pools(&self.config.dex_type).await
+    /// Collect a pool's signatures in slots after `slot`, oldest first, after a reorg rollback
+    ///
+    /// History is paged back from the newest signature, which becomes the pool's
+    /// checkpoint, until a signature at or before `slot` shows up.
+    pub async fn signatures_after_slot(&self, pool: &Pubkey, slot: u64) -> Result<Vec<Signature>> {
+        let mut paginator = SignaturePaginator::new(
+            self.config.max_signatures_per_request,
+            None,
+            Some(slot.saturating_add(1))
+        );
+
+        let mut result = Vec::new();
+        while !paginator.is_done() {
+            result.extend(self.next_signature_page(pool, &mut paginator).await?);
+        }
+
+        result.reverse();
+
+        Ok(result)
+    }
+
+    /// Get pools this DEX is tracking, optionally ordered and limited
+    pub async fn get_tracked_pools(
+        &self,
+        order: TrackedPoolOrder,
+        limit: Option<usize>
+    ) -> Result<Vec<Pubkey>> {
+        self.signature_store.get_tracked_pools(&self.config.dex_type, order, limit).await
+    }
+
+    /// Read the last processed signatures of many pools, in batches of `signature_read_batch_size`
+    ///
+    /// Pools without a stored signature are left out of the map.
+    pub async fn last_signatures(&self, pools: &[Pubkey]) -> Result<HashMap<Pubkey, String>> {
+        let mut signatures = HashMap::with_capacity(pools.len());
+        for batch in pools.chunks(self.config.signature_read_batch_size.max(1)) {
+            signatures.extend(
+                self.signature_store.get_signatures_for_pools(batch, &self.config.dex_type).await?
+            );
+        }
+        Ok(signatures)
+    }
+
+    /// Last processed signature of a pool
+    pub async fn last_signature(&self, pool: &Pubkey) -> Result<Option<String>> {
+        self.signature_store.get_signature(pool, &self.config.dex_type).await
+    }
+
+    /// Slot of a pool's last processed signature, when it was stored with one
+    pub async fn last_signature_slot(&self, pool: &Pubkey) -> Result<Option<u64>> {
+        self.signature_store.get_signature_slot(pool, &self.config.dex_type).await
+    }
+
+    /// Pools whose last signature the cluster no longer knows
+    ///
+    /// A signature seen at `confirmed` that later disappears was in a block dropped
+    /// by a reorg. The cluster's whole transaction history is searched, so old
+    /// signatures aren't mistaken for dropped ones.
+    pub async fn pools_with_dropped_signatures(
+        &self,
+        last_signatures: &HashMap<Pubkey, String>
+    ) -> Result<HashSet<Pubkey>> {
+        let mut pools = Vec::with_capacity(last_signatures.len());
+        let mut signatures = Vec::with_capacity(last_signatures.len());
+        for (pool, signature) in last_signatures {
+            pools.push(*pool);
+            signatures.push(Signature::from_str(signature)?);
+        }
+
+        let mut dropped = HashSet::new();
+        // The RPC accepts at most 256 signatures per request
+        for (pools, signatures) in pools.chunks(256).zip(signatures.chunks(256)) {
+            let statuses = retry_with_backoff(&self.config.retry, "getSignatureStatuses", || async {
+                self.throttle().await;
+                self.rpc_client
+                    .get_signature_statuses_with_history(signatures).await
+                    .map_err(IndexerError::from)
+                    .context("Failed to fetch signature statuses")
+            }).await?;
+            for (pool, status) in pools.iter().zip(statuses.value) {
+                if status.is_none() {
+                    dropped.insert(*pool);
+                }
+            }
+        }
+
+        Ok(dropped)
+    }
+
+    /// Order pools so the most recently active ones come first
+    ///
+    /// Pools without a stored signature are appended at the end.
+    pub async fn prioritize_pools(&self, pools: &HashSet<Pubkey>) -> Result<Vec<Pubkey>> {
+        let recent = self.get_tracked_pools(TrackedPoolOrder::RecentFirst, None).await?;
+
+        let mut ordered: Vec<Pubkey> = recent
+            .into_iter()
+            .filter(|pool| pools.contains(pool))
+            .collect();
+        let seen: HashSet<Pubkey> = ordered.iter().copied().collect();
+        ordered.extend(pools.iter().filter(|pool| !seen.contains(pool)));
+
+        Ok(ordered)
     }
 
     /// Check if we have a signature for this pool
@@ -192,17 +882,106 @@ impl BackfillManager {
         self.signature_store.has_signature(pool, &self.config.dex_type).await
     }
 
+    /// Get the current slot at the configured commitment
+    pub async fn get_current_slot(&self) -> Result<u64> {
+        self.throttle().await;
+        self.rpc_client
+            .get_slot().await
+            .map_err(IndexerError::from)
+            .context("Failed to fetch current slot")
+    }
+
+    /// Get the Unix time of a slot's block
+    ///
+    /// The last slot looked up is cached, so events from the same block cost one request.
+    pub async fn get_block_time(&self, slot: u64) -> Result<i64> {
+        if let Some((cached_slot, block_time)) = *self.last_block_time.lock().unwrap() {
+            if cached_slot == slot {
+                return Ok(block_time);
+            }
+        }
+
+        self.throttle().await;
+        let block_time = self.rpc_client
+            .get_block_time(slot).await
+            .map_err(IndexerError::from)
+            .with_context(|| format!("Failed to fetch block time for slot {}", slot))?;
+        *self.last_block_time.lock().unwrap() = Some((slot, block_time));
+        Ok(block_time)
+    }
+
+    /// Get the status of each signature, or None for signatures the cluster does not know
+    pub async fn get_signature_statuses(
+        &self,
+        signatures: &[Signature]
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        let mut statuses = Vec::with_capacity(signatures.len());
+
+        // The RPC accepts at most 256 signatures per request
+        for chunk in signatures.chunks(256) {
+            self.throttle().await;
+            let response = self.rpc_client
+                .get_signature_statuses(chunk).await
+                .map_err(IndexerError::from)
+                .context("Failed to fetch signature statuses")?;
+            statuses.extend(response.value);
+        }
+
+        Ok(statuses)
+    }
+
     /// Fetch transaction details for a signature
+    ///
+    /// A transaction the RPC node doesn't know fails with `IndexerError::TransactionNotFound`.
     pub async fn fetch_transaction(
         &self,
         signature: &Signature
-    ) -> Result<solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta> {
-        self.rpc_client
-            .get_transaction_with_config(signature, RpcTransactionConfig {
-                encoding: Some(UiTransactionEncoding::JsonParsed),
-                commitment: Some(CommitmentConfig::confirmed()),
-                max_supported_transaction_version: Some(0),
-            }).await
-            .with_context(|| format!("Failed to fetch transaction for signature {}", signature))
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: Some(self.history_commitment()),
+            max_supported_transaction_version: Some(0),
+        };
+        retry_with_backoff(&self.config.retry, "getTransaction", || async {
+            self.throttle().await;
+            // Sent directly, since the typed call reports an unknown transaction as a
+            // deserialization error
+            let transaction: Option<EncodedConfirmedTransactionWithStatusMeta> = self.rpc_client
+                .send(RpcRequest::GetTransaction, serde_json::json!([signature.to_string(), config])).await
+                .map_err(IndexerError::from)
+                .with_context(|| format!("Failed to fetch transaction for signature {}", signature))?;
+            transaction.ok_or_else(|| IndexerError::TransactionNotFound(signature.to_string()).into())
+        }).await
+    }
+
+    /// Fetch transaction details for several signatures, up to `fetch_concurrency` at once
+    ///
+    /// Fetches finish in any order, so the results are sorted by slot, keeping the
+    /// given order within a slot, with failed fetches last.
+    pub async fn fetch_transactions<'a>(
+        &self,
+        signatures: &'a [Signature]
+    ) -> Vec<(&'a Signature, Result<EncodedConfirmedTransactionWithStatusMeta>)> {
+        // Build the futures up front; mapping inside the stream trips up the Send
+        // check on callers' async_trait futures
+        let fetches: Vec<_> = signatures
+            .iter()
+            .enumerate()
+            .map(|(position, signature)| async move {
+                (position, signature, self.fetch_transaction(signature).await)
+            })
+            .collect();
+        let mut fetched: Vec<_> = stream
+            ::iter(fetches)
+            .buffer_unordered(self.config.fetch_concurrency.max(1))
+            .collect().await;
+
+        fetched.sort_by_key(|(position, _, result)| {
+            (result.as_ref().map_or(u64::MAX, |tx| tx.slot), *position)
+        });
+        fetched
+            .into_iter()
+            .map(|(_, signature, result)| (signature, result))
+            .collect()
     }
 }
@@ -0,0 +1,231 @@
+use anyhow::{ bail, Context, Result };
+use serde::de::Visitor;
+use serde::{ Deserialize, Deserializer };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::backfill_manager::BackfillOrder;
+use crate::indexers::{ parse_commitment, StartupStrategy };
+use crate::rpc::Cluster;
+use crate::utils::logging::LogFormat;
+use crate::validation::ValidationMode;
+use crate::websocket_manager::ChannelOverflowPolicy;
+
+/// Indexer settings read from a TOML file with `--config`
+///
+/// Keys are the command line flags with underscores, e.g. `rpc_url` for `--rpc-url`,
+/// plus `orca_pools`, `raydium_pools` and `database_url`. Every key is optional: a
+/// flag given on the command line overrides the file, and the file overrides
+/// environment variables and defaults. Unknown keys are rejected, so a typo
+/// doesn't silently fall back to a default.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Postgres connection string, used instead of `DATABASE_URL`
+    pub database_url: Option<String>,
+    /// Cluster whose public endpoints are used unless `rpc_url` or `ws_url` is set
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub cluster: Option<Cluster>,
+    pub rpc_url: Option<String>,
+    /// Extra HTTP headers for RPC requests, as a table of names to values
+    pub rpc_headers: Option<HashMap<String, String>>,
+    pub ws_url: Option<String>,
+    pub ws_fallback_urls: Option<Vec<String>>,
+    pub ws_channel_capacity: Option<usize>,
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub ws_overflow_policy: Option<ChannelOverflowPolicy>,
+    pub subscribe_pool_accounts: Option<bool>,
+    pub confirmation_delay_slots: Option<u64>,
+    pub orca_program_id: Option<String>,
+    pub orca_default_pool: Option<String>,
+    pub raydium_amm_program_id: Option<String>,
+    pub raydium_clmm_program_id: Option<String>,
+    #[serde(deserialize_with = "deserialize_commitment")]
+    pub commitment: Option<CommitmentConfig>,
+    pub rpc_timeout_secs: Option<u64>,
+    pub dead_letter_file: Option<String>,
+    pub outage_buffer_size: Option<usize>,
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub backfill_order: Option<BackfillOrder>,
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub startup_strategy: Option<StartupStrategy>,
+    /// Seconds between scheduled backfills
+    pub backfill_interval_secs: Option<u64>,
+    /// Seconds between reconciliation passes
+    pub reconcile_interval_secs: Option<u64>,
+    pub reconcile_window_secs: Option<u64>,
+    pub backfill_pool_cap: Option<usize>,
+    pub backfill_max_rps: Option<u32>,
+    pub backfill_concurrency: Option<u32>,
+    pub backfill_pool_concurrency: Option<u32>,
+    pub signature_read_batch_size: Option<u32>,
+    pub max_plausible_trade_amount: Option<f64>,
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub event_validation: Option<ValidationMode>,
+    pub min_input_amount: Option<u64>,
+    /// Minimum trade input amounts of single pools, as a table of pool addresses to amounts
+    pub pool_min_input_amounts: Option<HashMap<String, u64>>,
+    pub webhook_url: Option<String>,
+    pub webhook_queue_size: Option<usize>,
+    pub stale_max_secs: Option<u64>,
+    pub stale_gap_multiplier: Option<f64>,
+    pub also_stdout: Option<bool>,
+    pub strict_pool_addresses: Option<bool>,
+    pub store_transaction_logs: Option<bool>,
+    #[serde(deserialize_with = "deserialize_parsed")]
+    pub log_format: Option<LogFormat>,
+    pub metrics_port: Option<u16>,
+    pub health_port: Option<u16>,
+    pub health_stale_secs: Option<u64>,
+    /// Orca pools to index, for the `orca` and `all` commands
+    pub orca_pools: Option<Vec<String>>,
+    /// Token mint whose subscribed Orca pools are indexed, for the `orca` command
+    pub token_mint: Option<String>,
+    /// Raydium pools to index, for the `all` command
+    pub raydium_pools: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Read and validate a TOML config file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs
+            ::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parse and validate the contents of a TOML config file
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Config = toml::from_str(contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Keys a config file accepts, in the order `Config` declares them
+    pub fn keys() -> &'static [&'static str] {
+        let mut keys: &'static [&'static str] = &[];
+        // Only records the field names, so its error is expected
+        let _ = Config::deserialize(StructFields(&mut keys));
+        keys
+    }
+
+    /// A config file listing every key commented out, so it parses to the defaults
+    ///
+    /// `describe` gives a key's description and default value, either of which can
+    /// be missing; defaults that aren't numbers or booleans are quoted as strings.
+    pub fn template(describe: impl Fn(&str) -> (Option<String>, Option<String>)) -> String {
+        let mut template = String::from(
+            "# Indexer config file, for --config. Every key is optional: uncomment the ones to set.\n"
+        );
+        for key in Self::keys() {
+            let (description, default) = describe(key);
+            template.push('\n');
+            for line in description.iter().flat_map(|description| description.lines()) {
+                template.push_str(&format!("# {}\n", line));
+            }
+            match default {
+                Some(default) => template.push_str(&format!("# {} = {}\n", key, toml_value(&default))),
+                None => template.push_str(&format!("# No default\n# {} =\n", key)),
+            }
+        }
+        template
+    }
+
+    /// Reject values the command line flags don't accept either
+    fn validate(&self) -> Result<()> {
+        let positive = [
+            ("backfill_interval_secs", self.backfill_interval_secs),
+            ("reconcile_interval_secs", self.reconcile_interval_secs),
+            ("reconcile_window_secs", self.reconcile_window_secs),
+            ("backfill_max_rps", self.backfill_max_rps.map(u64::from)),
+            ("backfill_concurrency", self.backfill_concurrency.map(u64::from)),
+            ("backfill_pool_concurrency", self.backfill_pool_concurrency.map(u64::from)),
+            ("signature_read_batch_size", self.signature_read_batch_size.map(u64::from)),
+        ];
+        for (key, value) in positive {
+            if value == Some(0) {
+                bail!("{} must be at least 1", key);
+            }
+        }
+        let pubkeys = [
+            ("orca_program_id", &self.orca_program_id),
+            ("orca_default_pool", &self.orca_default_pool),
+            ("raydium_amm_program_id", &self.raydium_amm_program_id),
+            ("raydium_clmm_program_id", &self.raydium_clmm_program_id),
+        ];
+        for (key, value) in pubkeys {
+            if let Some(value) = value {
+                Pubkey::from_str(value).with_context(|| format!("Invalid {} '{}'", key, value))?;
+            }
+        }
+        if let Some(mint) = &self.token_mint {
+            Pubkey::from_str(mint).with_context(|| format!("Invalid token_mint '{}'", mint))?;
+        }
+        for pool in self.pool_min_input_amounts.iter().flat_map(HashMap::keys) {
+            Pubkey::from_str(pool).with_context(||
+                format!("Invalid pool address '{}' in pool_min_input_amounts", pool)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Write a default value as TOML, quoting anything that isn't a number or boolean
+fn toml_value(value: &str) -> String {
+    if value.parse::<i64>().is_ok() || value.parse::<f64>().is_ok() || value.parse::<bool>().is_ok() {
+        value.to_string()
+    } else {
+        toml::Value::String(value.to_string()).to_string()
+    }
+}
+
+// A deserializer that records the field names of the struct asked for, then fails
+struct StructFields<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for StructFields<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("expected a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(serde::de::Error::custom("only the field names are read"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+// Deserialize a string through the type's FromStr impl, as the command line flags are parsed
+fn deserialize_parsed<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>, T: FromStr, T::Err: Display
+{
+    Option::<String>
+        ::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_commitment<'de, D>(deserializer: D) -> Result<Option<CommitmentConfig>, D::Error>
+    where D: Deserializer<'de>
+{
+    Option::<String>
+        ::deserialize(deserializer)?
+        .map(|value| parse_commitment(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
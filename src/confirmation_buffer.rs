@@ -0,0 +1,104 @@
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_transaction_status::TransactionStatus;
+use std::collections::VecDeque;
+
+/// A live event waiting for enough confirmations before being persisted
+#[derive(Debug, Clone)]
+pub struct PendingEvent {
+    /// Slot the log notification was received in
+    pub slot: u64,
+    /// The log notification itself
+    pub log: RpcLogsResponse,
+}
+
+/// Holds live events until they are a configured number of slots old
+///
+/// Events at `confirmed` can still be rolled back by a reorg. Buffering them
+/// until the chain has advanced `delay_slots` past them, then checking that the
+/// transaction still exists, keeps that churn out of the database.
+#[derive(Debug)]
+pub struct ConfirmationBuffer {
+    delay_slots: u64,
+    current_slot: u64,
+    pending: VecDeque<PendingEvent>,
+}
+
+impl ConfirmationBuffer {
+    /// Create a buffer that holds events for `delay_slots` slots (0 disables buffering)
+    pub fn new(delay_slots: u64) -> Self {
+        Self {
+            delay_slots,
+            current_slot: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Whether the buffer is bypassed and events should be persisted immediately
+    pub fn is_disabled(&self) -> bool {
+        self.delay_slots == 0
+    }
+
+    /// Number of slots an event is held for
+    pub fn delay_slots(&self) -> u64 {
+        self.delay_slots
+    }
+
+    /// Highest slot observed so far
+    pub fn current_slot(&self) -> u64 {
+        self.current_slot
+    }
+
+    /// Add an event received at the given slot
+    pub fn push(&mut self, slot: u64, log: RpcLogsResponse) {
+        self.observe_slot(slot);
+        self.pending.push_back(PendingEvent { slot, log });
+    }
+
+    /// Record the latest known slot (older slots are ignored)
+    pub fn observe_slot(&mut self, slot: u64) {
+        self.current_slot = self.current_slot.max(slot);
+    }
+
+    /// Remove and return every event that is at least `delay_slots` old
+    pub fn drain_ready(&mut self) -> Vec<PendingEvent> {
+        let current_slot = self.current_slot;
+        let delay_slots = self.delay_slots;
+        let is_ready = |event: &PendingEvent| current_slot.saturating_sub(event.slot) >= delay_slots;
+
+        let (ready, waiting): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(is_ready);
+        self.pending = waiting.into();
+        ready
+    }
+
+    /// Number of events still waiting
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether no events are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Split ready events into those whose transaction still exists and succeeded,
+/// and those that vanished (unknown to the cluster or failed)
+///
+/// `statuses` must be in the same order as `events`, as returned by
+/// `getSignatureStatuses`.
+pub fn split_vanished(
+    events: Vec<PendingEvent>,
+    statuses: &[Option<TransactionStatus>]
+) -> (Vec<PendingEvent>, Vec<PendingEvent>) {
+    let mut landed = Vec::new();
+    let mut vanished = Vec::new();
+
+    for (index, event) in events.into_iter().enumerate() {
+        match statuses.get(index) {
+            Some(Some(status)) if status.err.is_none() => landed.push(event),
+            _ => vanished.push(event),
+        }
+    }
+
+    (landed, vanished)
+}
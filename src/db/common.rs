@@ -1,7 +1,113 @@
+use anyhow::{ Context, Result };
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use sqlx::PgPool;
+use std::fmt::Display;
+use std::str::FromStr;
 
 // Protocol-agnostic repository trait that all specific repositories can implement
 pub trait Repository {
     /// Get the connection pool
     fn pool(&self) -> &PgPool;
 }
+
+//
+// NUMERIC ENCODING HELPERS
+//
+// Postgres has no unsigned or 128-bit integer types, so on-chain `u64`/`u128` values
+// are stored in NUMERIC columns. sqlx is built without a decimal feature, so values are
+// bound as their base-10 string form and cast in SQL (`$1::NUMERIC`), and read back
+// with a `::TEXT` cast. This keeps the full range of the value without precision loss.
+//
+
+/// Encode an unsigned integer for binding to a NUMERIC column
+pub fn to_numeric<T: Display>(value: T) -> String {
+    value.to_string()
+}
+
+/// Encode an array of unsigned integers for binding to a NUMERIC[] column
+pub fn to_numeric_array<T: Display>(values: &[T]) -> Vec<String> {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
+/// Decode a NUMERIC column (selected as TEXT) back into an unsigned integer
+pub fn from_numeric<T>(value: &str) -> Result<T> where T: FromStr, T::Err: Display {
+    value
+        .parse::<T>()
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .with_context(|| format!("Invalid NUMERIC value: {}", value))
+}
+
+/// Decode a fixed-size NUMERIC[] column (selected as TEXT[]) back into an array
+pub fn from_numeric_array<T, const N: usize>(values: &[String]) -> Result<[T; N]>
+    where T: FromStr + Copy + Default, T::Err: Display
+{
+    if values.len() != N {
+        return Err(
+            anyhow::anyhow!("Expected {} NUMERIC values, found {}", N, values.len())
+        );
+    }
+
+    let mut result = [T::default(); N];
+    for (slot, value) in result.iter_mut().zip(values) {
+        *slot = from_numeric(value)?;
+    }
+    Ok(result)
+}
+
+//
+// SIGNATURE ENCODING HELPERS
+//
+// Signatures arrive as base58 strings from both WebSocket logs and backfill RPC calls.
+// They are parsed and re-encoded before every write or lookup so the same transaction
+// always maps to the same stored key, and malformed values are rejected up front.
+//
+
+/// Canonical base58 form of a transaction signature
+pub fn normalize_signature(signature: &str) -> Result<String> {
+    let parsed = Signature::from_str(signature.trim()).map_err(|e|
+        anyhow::anyhow!("Invalid transaction signature '{}': {}", signature, e)
+    )?;
+    Ok(parsed.to_string())
+}
+
+//
+// POOL ADDRESS HELPERS
+//
+// Pool lists given on the command line or in a config file are often long and
+// copied by hand. By default one bad entry is skipped with a warning rather than
+// aborting startup; strict mode restores the fail-fast behavior.
+//
+
+/// Parse a list of pool addresses
+///
+/// Invalid addresses are logged with their position in the list and skipped, and
+/// an error is only returned when none are valid. With `strict`, the first invalid
+/// address is an error.
+pub fn parse_pool_addresses(addresses: &[String], strict: bool) -> Result<Vec<Pubkey>> {
+    let mut pubkeys = Vec::with_capacity(addresses.len());
+    for (index, address) in addresses.iter().enumerate() {
+        match Pubkey::from_str(address.trim()) {
+            Ok(pubkey) => pubkeys.push(pubkey),
+            Err(e) if strict => {
+                anyhow::bail!("Invalid pool address '{}' at position {}: {}", address, index + 1, e);
+            }
+            Err(e) => {
+                log::warn!(
+                    "Skipping invalid pool address '{}' at position {}: {}",
+                    address,
+                    index + 1,
+                    e
+                );
+            }
+        }
+    }
+
+    if pubkeys.is_empty() && !addresses.is_empty() {
+        anyhow::bail!("None of the {} pool addresses are valid", addresses.len());
+    }
+    Ok(pubkeys)
+}
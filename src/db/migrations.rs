@@ -0,0 +1,33 @@
+use anyhow::{ bail, Context, Result };
+use sqlx::{ Executor, PgPool };
+
+/// Schema files embedded at build time, in the order they must be applied
+const SCHEMAS: &[(&str, &str)] = &[
+    ("common", include_str!("../../database/schema/common/schema.sql")),
+    ("orca", include_str!("../../database/schema/orca/schema.sql")),
+    ("raydium", include_str!("../../database/schema/raydium/schema.sql")),
+    ("unified", include_str!("../../database/schema/unified/schema.sql")),
+];
+
+/// Apply the schema for the given DEXes.
+///
+/// The common schema is always applied first. The schema files are idempotent,
+/// so running this against an existing database brings it up to date.
+pub async fn run_migrations(pool: &PgPool, dexes: &[&str]) -> Result<()> {
+    for dex in dexes {
+        if !SCHEMAS.iter().any(|(name, _)| name == dex) {
+            bail!("Unknown schema: {}", dex);
+        }
+    }
+
+    for (name, sql) in SCHEMAS {
+        if *name != "common" && !dexes.contains(name) {
+            continue;
+        }
+
+        log::debug!("Applying {} schema", name);
+        pool.execute(*sql).await.with_context(|| format!("Failed to apply {} schema", name))?;
+    }
+
+    Ok(())
+}
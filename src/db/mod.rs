@@ -1,9 +1,17 @@
 pub mod common;
+pub mod migrations;
 pub mod pool;
+pub mod quarantine;
 pub mod repositories;
 pub mod signature_store;
+pub mod token_metadata;
+pub mod transaction_logs;
 
 pub use common::*;
+pub use migrations::run_migrations;
 pub use pool::*;
+pub use quarantine::*;
 pub use repositories::*;
 pub use signature_store::*;
+pub use token_metadata::*;
+pub use transaction_logs::*;
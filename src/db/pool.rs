@@ -18,20 +18,25 @@ pub struct DbConfig {
 }
 
 impl DbConfig {
-    /// Create a configuration from environment variables
-    pub fn from_env() -> Result<Self> {
-        let connection_string = env
-            ::var("DATABASE_URL")
-            .context("DATABASE_URL environment variable not set")?;
-
-        Ok(Self {
+    /// Create a configuration for a connection string, with the default pool settings
+    pub fn new(connection_string: String) -> Self {
+        Self {
             connection_string,
             max_connections: 10,
             min_connections: 1,
             max_lifetime: Duration::from_secs(30 * 60), // 30 minutes
             idle_timeout: Duration::from_secs(10 * 60), // 10 minutes
             connect_timeout: Duration::from_secs(30), // 30 seconds
-        })
+        }
+    }
+
+    /// Create a configuration from environment variables
+    pub fn from_env() -> Result<Self> {
+        let connection_string = env
+            ::var("DATABASE_URL")
+            .context("DATABASE_URL environment variable not set")?;
+
+        Ok(Self::new(connection_string))
     }
 }
 
@@ -68,3 +73,38 @@ impl Database {
         &self.pool
     }
 }
+
+/// Whether an error means the database couldn't be reached, rather than a query failing
+///
+/// Covers pool timeouts, I/O errors and the errors Postgres returns for dropped
+/// connections or while restarting, all of which clear up once it is back.
+pub fn is_unreachable_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        let Some(error) = cause.downcast_ref::<sqlx::Error>() else {
+            return false;
+        };
+        match error {
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::WorkerCrashed => true,
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => true,
+            // Class 08 is connection exceptions, 57P shutdowns and startups
+            sqlx::Error::Database(e) =>
+                e.code().is_some_and(|code| code.starts_with("08") || code.starts_with("57P")),
+            _ => false,
+        }
+    })
+}
+
+/// Whether an error is a unique constraint violation, i.e. the row was already stored
+pub fn is_duplicate_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505")
+        )
+    })
+}
+
+/// Check that the database answers a trivial query
+pub async fn is_reachable(pool: &PgPool) -> bool {
+    sqlx::query("SELECT 1").execute(pool).await.is_ok()
+}
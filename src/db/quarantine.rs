@@ -0,0 +1,96 @@
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use sqlx::{ FromRow, PgPool };
+
+/// An event that failed validation, kept for inspection instead of being indexed
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedEvent {
+    pub dex: String,
+    pub signature: String,
+    /// Position of the event in its transaction, see `DexIndexer::parse_indexed_log_events`
+    pub event_index: usize,
+    pub event_type: String,
+    pub pool: String,
+    /// The check that failed and why
+    pub reason: String,
+    /// Event data as base64, as it appeared after `Program data:` in the logs
+    pub raw_data: String,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+}
+
+/// A row of the `quarantine_events` table
+#[derive(Debug, Clone, FromRow)]
+pub struct QuarantinedEventRecord {
+    pub id: i32,
+    pub dex: String,
+    pub signature: String,
+    /// NULL for events quarantined before positions were recorded
+    pub event_index: Option<i32>,
+    pub event_type: String,
+    pub pool: String,
+    pub reason: String,
+    pub raw_data: String,
+    pub slot: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// Stores events rejected by `--event-validation`
+#[derive(Clone)]
+pub struct QuarantineStore {
+    db_pool: PgPool,
+}
+
+impl QuarantineStore {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Save a rejected event, returning its ID, or None if the event at that
+    /// position of the transaction is already quarantined
+    pub async fn insert(&self, event: &QuarantinedEvent) -> Result<Option<i32>> {
+        let id: Option<i32> = sqlx
+            ::query_scalar(
+                r#"
+            INSERT INTO apestrong.quarantine_events
+                (dex, signature, event_index, event_type, pool, reason, raw_data, slot, block_time)
+            VALUES ($1::apestrong.dex_type, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (signature, event_index) DO NOTHING
+            RETURNING id
+            "#
+            )
+            .bind(&event.dex)
+            .bind(&event.signature)
+            .bind(event.event_index as i32)
+            .bind(&event.event_type)
+            .bind(&event.pool)
+            .bind(&event.reason)
+            .bind(&event.raw_data)
+            .bind(event.slot.map(|slot| slot as i64))
+            .bind(event.block_time.and_then(|time| DateTime::from_timestamp(time, 0)))
+            .fetch_optional(&self.db_pool).await
+            .with_context(|| format!("Failed to quarantine event of transaction {}", event.signature))?;
+
+        Ok(id)
+    }
+
+    /// Most recently quarantined events of a DEX, newest first
+    pub async fn recent(&self, dex: &str, limit: i64) -> Result<Vec<QuarantinedEventRecord>> {
+        sqlx
+            ::query_as::<_, QuarantinedEventRecord>(
+                r#"
+            SELECT id, dex::TEXT AS dex, signature, event_index, event_type, pool, reason, raw_data, slot,
+                block_time, quarantined_at
+            FROM apestrong.quarantine_events
+            WHERE dex = $1::apestrong.dex_type
+            ORDER BY id DESC
+            LIMIT $2
+            "#
+            )
+            .bind(dex)
+            .bind(limit)
+            .fetch_all(&self.db_pool).await
+            .with_context(|| format!("Failed to get quarantined {} events", dex))
+    }
+}
@@ -1,4 +1,20 @@
 mod orca;
+mod orca_event_writer;
+pub mod raydium;
 
-pub use orca::OrcaWhirlpoolRepository;
+pub use orca::{
+    decimal_amount,
+    decode_liquidity_amounts,
+    LiquidityEvent,
+    OrcaWhirlpoolRepository,
+    PoolDeletion,
+    PoolStats,
+    SlotGap,
+    SlotGapReport,
+    TradedEventDecoded,
+    MIN_SLOT_GAP,
+    SLOT_GAP_MEDIAN_MULTIPLIER,
+};
+pub use orca_event_writer::{ InMemoryOrcaEventWriter, OrcaEventRecord, OrcaEventWriter };
+pub use raydium::RaydiumRepository;
 pub use crate::models::orca::whirlpool::OrcaWhirlpoolPoolRecord;
@@ -1,18 +1,197 @@
 use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use rust_decimal::Decimal;
 use sqlx::{ PgPool, Postgres, Transaction, Row };
 use std::collections::HashSet;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
-use crate::db::common::Repository;
+use crate::db::common::{
+    from_numeric,
+    normalize_signature,
+    parse_pool_addresses,
+    to_numeric,
+    Repository,
+};
+use crate::error::IndexerError;
 use crate::models::orca::whirlpool::{
     OrcaWhirlpoolEvent,
     OrcaWhirlpoolTradedEventRecord,
     OrcaWhirlpoolLiquidityIncreasedEventRecord,
     OrcaWhirlpoolLiquidityDecreasedEventRecord,
+    OrcaWhirlpoolLiquidityRecord,
+    OrcaWhirlpoolPoolInitializedEventRecord,
     OrcaWhirlpoolPoolRecord,
+    OrcaWhirlpoolTradedRecord,
 };
 
+/// Activity summary of a pool over a period, as returned by `get_pool_stats`
+///
+/// Amounts are in base units. Volumes add up trades in both directions, so the
+/// input volume mixes token A and token B amounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Number of trades
+    pub total_trades: i64,
+    /// Sum of trade input amounts
+    pub input_volume: u128,
+    /// Sum of trade output amounts
+    pub output_volume: u128,
+    /// Liquidity added minus liquidity removed
+    pub net_liquidity_change: i128,
+    /// Number of distinct positions that added or removed liquidity
+    pub positions_touched: i64,
+}
+
+/// What `delete_pool` removes besides the pool's subscription
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolDeletion {
+    /// Remove the pool's last signature and backfill progress, so it is
+    /// backfilled from scratch if it is added again
+    pub forget_signatures: bool,
+    /// Delete the pool's indexed events
+    pub delete_events: bool,
+}
+
+/// Smallest slot distance between two events `find_slot_gaps` reports, about a minute
+pub const MIN_SLOT_GAP: i64 = 150;
+
+/// How many times a pool's median distance between event slots a quiet stretch
+/// must span before `find_slot_gaps` reports it
+pub const SLOT_GAP_MEDIAN_MULTIPLIER: f64 = 50.0;
+
+/// A stretch of slots without events between two indexed events of a pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotGap {
+    /// Slot of the last event before the gap
+    pub after_slot: i64,
+    /// Slot of the first event after the gap
+    pub before_slot: i64,
+}
+
+impl SlotGap {
+    /// Number of slots in the gap, excluding the two event slots around it
+    pub fn missing_slots(&self) -> i64 {
+        self.before_slot - self.after_slot - 1
+    }
+}
+
+/// Slot coverage of a pool's indexed events, as returned by `find_slot_gaps`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotGapReport {
+    /// Slot of the oldest event with a recorded slot
+    pub oldest_slot: Option<i64>,
+    /// Slot of the newest event with a recorded slot
+    pub newest_slot: Option<i64>,
+    /// Suspiciously long stretches without events, oldest first
+    pub gaps: Vec<SlotGap>,
+}
+
+impl SlotGapReport {
+    /// Oldest slot from which events reach the newest one without a gap
+    pub fn contiguous_from(&self) -> Option<i64> {
+        self.gaps
+            .last()
+            .map(|gap| gap.before_slot)
+            .or(self.oldest_slot)
+    }
+}
+
+/// One change to a position's liquidity, as returned by `get_position_history`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityEvent {
+    pub event_id: i32,
+    pub signature: String,
+    pub whirlpool: String,
+    /// `LiquidityIncreased` or `LiquidityDecreased`
+    pub event_type: String,
+    pub slot: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+    pub timestamp: DateTime<Utc>,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    /// Liquidity added, or removed when negative
+    pub delta_liquidity: i128,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    /// The position's liquidity after this change, summed over the indexed changes
+    pub balance: i128,
+}
+
+/// A trade's amounts scaled into whole tokens, as decoded by `TradedEventDecoded::from_record`
+///
+/// The input side, including the LP and protocol fees, uses the decimals of the
+/// token sold (token A when `a_to_b`), and the output side those of the token
+/// bought. As in the raw record, `input_amount` and `output_amount` include the
+/// Token-2022 transfer fees; use `net_input_amount` and `net_output_amount` for
+/// what actually reached the pool and the trader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradedEventDecoded {
+    pub a_to_b: bool,
+    pub input_amount: Decimal,
+    pub output_amount: Decimal,
+    pub input_transfer_fee: Decimal,
+    pub output_transfer_fee: Decimal,
+    pub lp_fee: Decimal,
+    pub protocol_fee: Decimal,
+}
+
+impl TradedEventDecoded {
+    /// Scale a stored trade by its pool's token decimals
+    pub fn from_record(
+        record: &OrcaWhirlpoolTradedRecord,
+        decimals_a: u8,
+        decimals_b: u8
+    ) -> Result<Self> {
+        let (input_decimals, output_decimals) = if record.a_to_b {
+            (decimals_a, decimals_b)
+        } else {
+            (decimals_b, decimals_a)
+        };
+
+        Ok(Self {
+            a_to_b: record.a_to_b,
+            input_amount: decimal_amount(record.input_amount, input_decimals)?,
+            output_amount: decimal_amount(record.output_amount, output_decimals)?,
+            input_transfer_fee: decimal_amount(record.input_transfer_fee, input_decimals)?,
+            output_transfer_fee: decimal_amount(record.output_transfer_fee, output_decimals)?,
+            lp_fee: decimal_amount(record.lp_fee, input_decimals)?,
+            protocol_fee: decimal_amount(record.protocol_fee, input_decimals)?,
+        })
+    }
+
+    /// Input amount that reached the pool, after the input token's transfer fee
+    pub fn net_input_amount(&self) -> Decimal {
+        self.input_amount - self.input_transfer_fee
+    }
+
+    /// Output amount the trader received, after the output token's transfer fee
+    pub fn net_output_amount(&self) -> Decimal {
+        self.output_amount - self.output_transfer_fee
+    }
+}
+
+/// Scale the token A and token B amounts of a stored liquidity change into whole tokens
+pub fn decode_liquidity_amounts(
+    record: &OrcaWhirlpoolLiquidityRecord,
+    decimals_a: u8,
+    decimals_b: u8
+) -> Result<(Decimal, Decimal)> {
+    Ok((
+        decimal_amount(record.token_a_amount, decimals_a)?,
+        decimal_amount(record.token_b_amount, decimals_b)?,
+    ))
+}
+
+/// Scale a base unit amount into whole tokens
+///
+/// Fails for more than 28 decimals, which `Decimal` can't hold.
+pub fn decimal_amount(amount: u64, decimals: u8) -> Result<Decimal> {
+    Decimal::try_from_i128_with_scale(amount as i128, decimals as u32).with_context(||
+        format!("Cannot scale amount {} by {} decimals", amount, decimals)
+    )
+}
+
 /// Repository for Orca Whirlpool event database operations
 pub struct OrcaWhirlpoolRepository {
     pool: PgPool,
@@ -24,131 +203,465 @@ impl OrcaWhirlpoolRepository {
         Self { pool }
     }
 
-    /// Insert a base Orca Whirlpool event
+    /// Insert a base Orca Whirlpool event, returning None if its key is already stored
+    ///
+    /// Events are keyed by (signature, event_index), so re-indexing a transaction
+    /// only adds its missing events.
     async fn insert_base_event<'a>(
         &self,
         tx: &mut Transaction<'a, Postgres>,
         event: &OrcaWhirlpoolEvent
-    ) -> Result<i32> {
-        let row = sqlx
-            ::query(
-                "INSERT INTO apestrong.orca_whirlpool_events (signature, whirlpool, event_type, version) VALUES ($1, $2, $3, $4) RETURNING id"
+    ) -> Result<Option<i32>> {
+        let signature = normalize_signature(&event.signature)?;
+        let id: Option<i32> = sqlx
+            ::query_scalar(
+                "INSERT INTO apestrong.orca_whirlpool_events (signature, event_index, whirlpool, event_type, dex, slot, block_time, fee_payer, version, enrichment) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) ON CONFLICT (signature, event_index) DO NOTHING RETURNING id"
             )
-            .bind(&event.signature)
+            .bind(&signature)
+            .bind(event.event_index)
             .bind(&event.whirlpool)
             .bind(&event.event_type)
+            .bind(&event.dex)
+            .bind(event.slot)
+            .bind(event.block_time)
+            .bind(&event.fee_payer)
             .bind(event.version)
-            .fetch_one(&mut **tx).await
+            .bind(&event.enrichment)
+            .fetch_optional(&mut **tx).await
             .context("Failed to insert base Orca Whirlpool event")?;
 
-        let id: i32 = row.get("id");
         Ok(id)
     }
 
     /// Insert a traded event into the database
-    pub async fn insert_traded_event(&self, event: OrcaWhirlpoolTradedEventRecord) -> Result<i32> {
+    pub async fn insert_traded_event(
+        &self,
+        event: OrcaWhirlpoolTradedEventRecord
+    ) -> Result<Option<i32>> {
         let mut tx = self.pool.begin().await?;
 
-        // Insert the base event
-        let event_id = self.insert_base_event(&mut tx, &event.base).await?;
+        // Insert the base event, unless it is already stored
+        let Some(event_id) = self.insert_base_event(&mut tx, &event.base).await? else {
+            return Ok(None);
+        };
 
         // Insert the traded-specific data
         sqlx
             ::query(
-                "INSERT INTO apestrong.orca_traded_events (event_id, a_to_b, pre_sqrt_price, post_sqrt_price, input_amount, output_amount, input_transfer_fee, output_transfer_fee, lp_fee, protocol_fee) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
+                "INSERT INTO apestrong.orca_traded_events (event_id, a_to_b, pre_sqrt_price, post_sqrt_price, input_amount, output_amount, input_transfer_fee, output_transfer_fee, lp_fee, protocol_fee, price) VALUES ($1, $2, $3::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9::NUMERIC, $10::NUMERIC, $11)"
             )
             .bind(event_id)
             .bind(event.data.a_to_b)
-            .bind(event.data.pre_sqrt_price)
-            .bind(event.data.post_sqrt_price)
-            .bind(event.data.input_amount)
-            .bind(event.data.output_amount)
-            .bind(event.data.input_transfer_fee)
-            .bind(event.data.output_transfer_fee)
-            .bind(event.data.lp_fee)
-            .bind(event.data.protocol_fee)
+            .bind(to_numeric(event.data.pre_sqrt_price))
+            .bind(to_numeric(event.data.post_sqrt_price))
+            .bind(to_numeric(event.data.input_amount))
+            .bind(to_numeric(event.data.output_amount))
+            .bind(to_numeric(event.data.input_transfer_fee))
+            .bind(to_numeric(event.data.output_transfer_fee))
+            .bind(to_numeric(event.data.lp_fee))
+            .bind(to_numeric(event.data.protocol_fee))
+            .bind(event.data.price)
             .execute(&mut *tx).await
             .context("Failed to insert Orca Whirlpool traded event")?;
 
         tx.commit().await?;
-        Ok(event_id)
+        Ok(Some(event_id))
     }
 
     /// Insert a liquidity increased event into the database
     pub async fn insert_liquidity_increased_event(
         &self,
         event: OrcaWhirlpoolLiquidityIncreasedEventRecord
-    ) -> Result<i32> {
+    ) -> Result<Option<i32>> {
         let mut tx = self.pool.begin().await?;
 
-        // Insert the base event
-        let event_id = self.insert_base_event(&mut tx, &event.base).await?;
+        // Insert the base event, unless it is already stored
+        let Some(event_id) = self.insert_base_event(&mut tx, &event.base).await? else {
+            return Ok(None);
+        };
 
         // Insert the liquidity data
         sqlx
             ::query(
-                "INSERT INTO apestrong.orca_liquidity_increased_events (event_id, position, tick_lower_index, tick_upper_index, liquidity, token_a_amount, token_b_amount, token_a_transfer_fee, token_b_transfer_fee) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+                "INSERT INTO apestrong.orca_liquidity_increased_events (event_id, position, tick_lower_index, tick_upper_index, liquidity, token_a_amount, token_b_amount, token_a_transfer_fee, token_b_transfer_fee) VALUES ($1, $2, $3, $4, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9::NUMERIC)"
             )
             .bind(event_id)
             .bind(&event.data.position)
             .bind(event.data.tick_lower_index)
             .bind(event.data.tick_upper_index)
-            .bind(event.data.liquidity)
-            .bind(event.data.token_a_amount)
-            .bind(event.data.token_b_amount)
-            .bind(event.data.token_a_transfer_fee)
-            .bind(event.data.token_b_transfer_fee)
+            .bind(to_numeric(event.data.liquidity))
+            .bind(to_numeric(event.data.token_a_amount))
+            .bind(to_numeric(event.data.token_b_amount))
+            .bind(to_numeric(event.data.token_a_transfer_fee))
+            .bind(to_numeric(event.data.token_b_transfer_fee))
             .execute(&mut *tx).await
             .context("Failed to insert Orca Whirlpool liquidity increased event")?;
 
         tx.commit().await?;
-        Ok(event_id)
+        Ok(Some(event_id))
     }
 
     /// Insert a liquidity decreased event into the database
     pub async fn insert_liquidity_decreased_event(
         &self,
         event: OrcaWhirlpoolLiquidityDecreasedEventRecord
-    ) -> Result<i32> {
+    ) -> Result<Option<i32>> {
         let mut tx = self.pool.begin().await?;
 
-        // Insert the base event
-        let event_id = self.insert_base_event(&mut tx, &event.base).await?;
+        // Insert the base event, unless it is already stored
+        let Some(event_id) = self.insert_base_event(&mut tx, &event.base).await? else {
+            return Ok(None);
+        };
 
         // Insert the liquidity data
         sqlx
             ::query(
-                "INSERT INTO apestrong.orca_liquidity_decreased_events (event_id, position, tick_lower_index, tick_upper_index, liquidity, token_a_amount, token_b_amount, token_a_transfer_fee, token_b_transfer_fee) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+                "INSERT INTO apestrong.orca_liquidity_decreased_events (event_id, position, tick_lower_index, tick_upper_index, liquidity, token_a_amount, token_b_amount, token_a_transfer_fee, token_b_transfer_fee) VALUES ($1, $2, $3, $4, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9::NUMERIC)"
             )
             .bind(event_id)
             .bind(&event.data.position)
             .bind(event.data.tick_lower_index)
             .bind(event.data.tick_upper_index)
-            .bind(event.data.liquidity)
-            .bind(event.data.token_a_amount)
-            .bind(event.data.token_b_amount)
-            .bind(event.data.token_a_transfer_fee)
-            .bind(event.data.token_b_transfer_fee)
+            .bind(to_numeric(event.data.liquidity))
+            .bind(to_numeric(event.data.token_a_amount))
+            .bind(to_numeric(event.data.token_b_amount))
+            .bind(to_numeric(event.data.token_a_transfer_fee))
+            .bind(to_numeric(event.data.token_b_transfer_fee))
             .execute(&mut *tx).await
             .context("Failed to insert Orca Whirlpool liquidity decreased event")?;
 
         tx.commit().await?;
-        Ok(event_id)
+        Ok(Some(event_id))
+    }
+
+    /// Insert a pool initialized event into the database
+    pub async fn insert_pool_initialized_event(
+        &self,
+        event: OrcaWhirlpoolPoolInitializedEventRecord
+    ) -> Result<Option<i32>> {
+        let mut tx = self.pool.begin().await?;
+
+        // Insert the base event, unless it is already stored
+        let Some(event_id) = self.insert_base_event(&mut tx, &event.base).await? else {
+            return Ok(None);
+        };
+
+        // Insert the pool creation data
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.orca_pool_initialized_events (event_id, whirlpools_config, token_mint_a, token_mint_b, tick_spacing, decimals_a, decimals_b, initial_sqrt_price) VALUES ($1, $2, $3, $4, $5, $6, $7, $8::NUMERIC)"
+            )
+            .bind(event_id)
+            .bind(&event.data.whirlpools_config)
+            .bind(&event.data.token_mint_a)
+            .bind(&event.data.token_mint_b)
+            .bind(event.data.tick_spacing)
+            .bind(event.data.decimals_a)
+            .bind(event.data.decimals_b)
+            .bind(to_numeric(event.data.initial_sqrt_price))
+            .execute(&mut *tx).await
+            .context("Failed to insert Orca Whirlpool pool initialized event")?;
+
+        tx.commit().await?;
+        Ok(Some(event_id))
     }
 
     /// Get recent trade volume for a specific pool
-    pub async fn get_recent_trade_volume(&self, pool_address: &str, hours: i64) -> Result<i64> {
+    pub async fn get_recent_trade_volume(&self, pool_address: &str, hours: i64) -> Result<u128> {
         let row = sqlx
             ::query(
-                "SELECT COALESCE(SUM(t.input_amount), 0) as volume FROM apestrong.orca_whirlpool_events e JOIN apestrong.orca_traded_events t ON e.id = t.event_id WHERE e.whirlpool = $1 AND e.event_type = 'traded' AND e.timestamp > NOW() - INTERVAL '1 hour' * $2"
+                "SELECT COALESCE(SUM(t.input_amount), 0)::TEXT as volume FROM apestrong.orca_whirlpool_events e JOIN apestrong.orca_traded_events t ON e.id = t.event_id WHERE e.whirlpool = $1 AND e.event_type = 'traded' AND e.timestamp > NOW() - INTERVAL '1 hour' * $2"
             )
             .bind(pool_address)
             .bind(hours)
             .fetch_one(&self.pool).await
             .context("Failed to get recent trade volume")?;
 
-        let volume: Option<i64> = row.get("volume");
-        Ok(volume.unwrap_or(0))
+        from_numeric(row.get("volume"))
+    }
+
+    /// Count the distinct fee payers that traded in a pool within `[from, to)`
+    ///
+    /// Trades are dated by their block time when known, otherwise by when they were
+    /// indexed. Trades without a recorded fee payer are not counted, and only
+    /// backfilled trades have one, since live logs don't include the accounts.
+    pub async fn unique_traders(
+        &self,
+        pool_address: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<i64> {
+        let (count,): (i64,) = sqlx
+            ::query_as(
+                "SELECT COUNT(DISTINCT e.fee_payer) FROM apestrong.orca_whirlpool_events e JOIN apestrong.orca_traded_events t ON e.id = t.event_id WHERE e.whirlpool = $1 AND COALESCE(e.block_time, e.timestamp) >= $2 AND COALESCE(e.block_time, e.timestamp) < $3"
+            )
+            .bind(pool_address)
+            .bind(from)
+            .bind(to)
+            .fetch_one(&self.pool).await
+            .context("Failed to count unique Orca Whirlpool traders")?;
+
+        Ok(count)
+    }
+
+    /// Signatures among the given ones that already have a stored event
+    pub async fn indexed_signatures(&self, signatures: &[String]) -> Result<HashSet<String>> {
+        let rows: Vec<(String,)> = sqlx
+            ::query_as(
+                "SELECT signature FROM apestrong.orca_whirlpool_events WHERE signature = ANY($1)"
+            )
+            .bind(signatures)
+            .fetch_all(&self.pool).await
+            .map_err(IndexerError::from)
+            .context("Failed to look up indexed Orca Whirlpool signatures")?;
+
+        Ok(
+            rows
+                .into_iter()
+                .map(|(signature,)| signature)
+                .collect()
+        )
+    }
+
+    /// Events among the given (signature, event_index) keys that are already stored
+    pub async fn indexed_events(
+        &self,
+        keys: &[(String, i32)]
+    ) -> Result<HashSet<(String, i32)>> {
+        let (signatures, indexes): (Vec<String>, Vec<i32>) = keys.iter().cloned().unzip();
+        let rows: Vec<(String, i32)> = sqlx
+            ::query_as(
+                "SELECT e.signature, e.event_index FROM apestrong.orca_whirlpool_events e JOIN UNNEST($1::VARCHAR[], $2::INT[]) AS k(signature, event_index) ON e.signature = k.signature AND e.event_index = k.event_index"
+            )
+            .bind(&signatures)
+            .bind(&indexes)
+            .fetch_all(&self.pool).await
+            .map_err(IndexerError::from)
+            .context("Failed to look up indexed Orca Whirlpool events")?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Count events per slot for a pool within an inclusive slot range
+    ///
+    /// Returns (slot, event count) pairs ordered by slot. Only slots with at least
+    /// one event are included, and events without a recorded slot are ignored.
+    pub async fn events_by_slot(
+        &self,
+        pool_address: &str,
+        from_slot: i64,
+        to_slot: i64
+    ) -> Result<Vec<(i64, i64)>> {
+        let rows: Vec<(i64, i64)> = sqlx
+            ::query_as(
+                "SELECT slot, COUNT(*) as event_count FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 AND slot BETWEEN $2 AND $3 GROUP BY slot ORDER BY slot"
+            )
+            .bind(pool_address)
+            .bind(from_slot)
+            .bind(to_slot)
+            .fetch_all(&self.pool).await
+            .context("Failed to get Orca Whirlpool events by slot")?;
+
+        Ok(rows)
+    }
+
+    /// Find stretches of slots with suspiciously few events for a pool
+    ///
+    /// This is a heuristic: a distance between consecutive event slots counts as a
+    /// gap when it is at least `MIN_SLOT_GAP` and `SLOT_GAP_MEDIAN_MULTIPLIER` times
+    /// the pool's median distance, so quiet pools aren't flagged for being quiet.
+    /// A gap may be a pool nobody traded, or events the indexer missed; backfilling
+    /// the range tells them apart. Events without a recorded slot are ignored.
+    pub async fn find_slot_gaps(&self, pool_address: &str) -> Result<SlotGapReport> {
+        let (oldest_slot, newest_slot): (Option<i64>, Option<i64>) = sqlx
+            ::query_as(
+                "SELECT MIN(slot), MAX(slot) FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1"
+            )
+            .bind(pool_address)
+            .fetch_one(&self.pool).await
+            .context("Failed to get Orca Whirlpool event slot range")?;
+
+        let rows: Vec<(i64, i64)> = sqlx
+            ::query_as(
+                r#"
+            WITH slots AS (
+                SELECT DISTINCT slot FROM apestrong.orca_whirlpool_events
+                WHERE whirlpool = $1 AND slot IS NOT NULL
+            ),
+            distances AS (
+                SELECT LAG(slot) OVER (ORDER BY slot) AS after_slot, slot AS before_slot
+                FROM slots
+            ),
+            threshold AS (
+                SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY before_slot - after_slot) AS median
+                FROM distances
+                WHERE after_slot IS NOT NULL
+            )
+            SELECT d.after_slot, d.before_slot
+            FROM distances d, threshold t
+            WHERE d.after_slot IS NOT NULL
+                AND d.before_slot - d.after_slot >= $2
+                AND d.before_slot - d.after_slot >= t.median * $3
+            ORDER BY d.after_slot
+            "#
+            )
+            .bind(pool_address)
+            .bind(MIN_SLOT_GAP)
+            .bind(SLOT_GAP_MEDIAN_MULTIPLIER)
+            .fetch_all(&self.pool).await
+            .with_context(|| format!("Failed to find slot gaps of pool {}", pool_address))?;
+
+        Ok(SlotGapReport {
+            oldest_slot,
+            newest_slot,
+            gaps: rows
+                .into_iter()
+                .map(|(after_slot, before_slot)| SlotGap { after_slot, before_slot })
+                .collect(),
+        })
+    }
+
+    /// Base events of a pool stored from one event layout version, oldest first
+    ///
+    /// Event details have the same columns for every version stored so far; see the
+    /// schema docs for handling a table holding several versions.
+    pub async fn get_events_by_version(
+        &self,
+        pool_address: &str,
+        version: i32
+    ) -> Result<Vec<OrcaWhirlpoolEvent>> {
+        let events = sqlx
+            ::query_as::<_, OrcaWhirlpoolEvent>(
+                "SELECT id, signature, event_index, whirlpool, event_type, dex, slot, block_time, fee_payer, version, timestamp, enrichment FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 AND version = $2 ORDER BY id"
+            )
+            .bind(pool_address)
+            .bind(version)
+            .fetch_all(&self.pool).await
+            .with_context(|| format!("Failed to get version {} Orca Whirlpool events", version))?;
+
+        Ok(events)
+    }
+
+    /// Delete a pool's events in slots after `slot`, returning how many were deleted
+    ///
+    /// Rolls back events from blocks dropped by a reorg. Event details are deleted
+    /// with their base event, and events without a recorded slot are kept.
+    pub async fn delete_events_after_slot(&self, pool_address: &str, slot: i64) -> Result<u64> {
+        let result = sqlx
+            ::query("DELETE FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 AND slot > $2")
+            .bind(pool_address)
+            .bind(slot)
+            .execute(&self.pool).await
+            .with_context(|| format!("Failed to delete Orca Whirlpool events after slot {}", slot))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Summarize a pool's trades and liquidity changes since the given time
+    ///
+    /// Events are dated by their block time when known, otherwise by when they were
+    /// indexed. Everything is computed in one query, so the figures are consistent.
+    pub async fn get_pool_stats(&self, pool: &str, since: DateTime<Utc>) -> Result<PoolStats> {
+        let row = sqlx
+            ::query(
+                "WITH pool_events AS (
+                     SELECT id FROM apestrong.orca_whirlpool_events
+                     WHERE whirlpool = $1 AND COALESCE(block_time, timestamp) >= $2
+                 ),
+                 trades AS (
+                     SELECT COUNT(*) as total_trades,
+                            COALESCE(SUM(t.input_amount), 0)::TEXT as input_volume,
+                            COALESCE(SUM(t.output_amount), 0)::TEXT as output_volume
+                     FROM pool_events e
+                     JOIN apestrong.orca_traded_events t ON e.id = t.event_id
+                 ),
+                 liquidity_changes AS (
+                     SELECT i.position, i.liquidity as change
+                     FROM pool_events e
+                     JOIN apestrong.orca_liquidity_increased_events i ON e.id = i.event_id
+                     UNION ALL
+                     SELECT d.position, -d.liquidity as change
+                     FROM pool_events e
+                     JOIN apestrong.orca_liquidity_decreased_events d ON e.id = d.event_id
+                 ),
+                 liquidity AS (
+                     SELECT COALESCE(SUM(change), 0)::TEXT as net_liquidity_change,
+                            COUNT(DISTINCT position) as positions_touched
+                     FROM liquidity_changes
+                 )
+                 SELECT trades.*, liquidity.* FROM trades, liquidity"
+            )
+            .bind(pool)
+            .bind(since)
+            .fetch_one(&self.pool).await
+            .context("Failed to get Orca Whirlpool pool stats")?;
+
+        Ok(PoolStats {
+            total_trades: row.get("total_trades"),
+            input_volume: from_numeric(row.get("input_volume"))?,
+            output_volume: from_numeric(row.get("output_volume"))?,
+            net_liquidity_change: from_numeric(row.get("net_liquidity_change"))?,
+            positions_touched: row.get("positions_touched"),
+        })
+    }
+
+    /// Liquidity changes of a position, oldest first, with its running balance
+    ///
+    /// Increases and decreases are stored in separate tables and merged here, with
+    /// decreases as negative deltas. Events are ordered like `get_pool_stats` dates
+    /// them, by block time when known, otherwise by when they were indexed. The
+    /// balance only sums indexed changes, so it starts from zero at the first one;
+    /// for a position opened before indexing started, it is off by the liquidity
+    /// it had by then.
+    pub async fn get_position_history(&self, position: &str) -> Result<Vec<LiquidityEvent>> {
+        let rows = sqlx
+            ::query(
+                "WITH changes AS (
+                     SELECT i.event_id, i.tick_lower_index, i.tick_upper_index,
+                            i.liquidity as delta_liquidity, i.token_a_amount, i.token_b_amount
+                     FROM apestrong.orca_liquidity_increased_events i
+                     WHERE i.position = $1
+                     UNION ALL
+                     SELECT d.event_id, d.tick_lower_index, d.tick_upper_index,
+                            -d.liquidity as delta_liquidity, d.token_a_amount, d.token_b_amount
+                     FROM apestrong.orca_liquidity_decreased_events d
+                     WHERE d.position = $1
+                 )
+                 SELECT e.id, e.signature, e.whirlpool, e.event_type, e.slot, e.block_time, e.timestamp,
+                        c.tick_lower_index, c.tick_upper_index,
+                        c.delta_liquidity::TEXT as delta_liquidity,
+                        c.token_a_amount::TEXT as token_a_amount,
+                        c.token_b_amount::TEXT as token_b_amount
+                 FROM changes c
+                 JOIN apestrong.orca_whirlpool_events e ON e.id = c.event_id
+                 ORDER BY COALESCE(e.block_time, e.timestamp), e.slot, e.id"
+            )
+            .bind(position)
+            .fetch_all(&self.pool).await
+            .with_context(|| format!("Failed to get the history of position {}", position))?;
+
+        let mut balance: i128 = 0;
+        rows.iter()
+            .map(|row| {
+                let delta_liquidity: i128 = from_numeric(row.get("delta_liquidity"))?;
+                balance += delta_liquidity;
+                Ok(LiquidityEvent {
+                    event_id: row.get("id"),
+                    signature: row.get("signature"),
+                    whirlpool: row.get("whirlpool"),
+                    event_type: row.get("event_type"),
+                    slot: row.get("slot"),
+                    block_time: row.get("block_time"),
+                    timestamp: row.get("timestamp"),
+                    tick_lower_index: row.get("tick_lower_index"),
+                    tick_upper_index: row.get("tick_upper_index"),
+                    delta_liquidity,
+                    token_a_amount: from_numeric(row.get("token_a_amount"))?,
+                    token_b_amount: from_numeric(row.get("token_b_amount"))?,
+                    balance,
+                })
+            })
+            .collect()
     }
 
     //
@@ -156,17 +669,20 @@ impl OrcaWhirlpoolRepository {
     //
 
     /// Get all pools from the database
+    ///
+    /// Pools added while indexing every pool have no tokens yet; their mints are
+    /// empty and their decimals zero.
     pub async fn get_all_pools(&self) -> Result<Vec<OrcaWhirlpoolPoolRecord>> {
         let rows = sqlx
             ::query(
                 "SELECT p.pool_mint as whirlpool, 
-                        p.token_a_mint as token_mint_a, 
-                        p.token_b_mint as token_mint_b, 
+                        COALESCE(p.token_a_mint, '') as token_mint_a, 
+                        COALESCE(p.token_b_mint, '') as token_mint_b, 
                         p.pool_name,
                         ta.token_name as token_name_a, 
                         tb.token_name as token_name_b,
-                        ta.decimals as decimals_a, 
-                        tb.decimals as decimals_b
+                        COALESCE(ta.decimals, 0) as decimals_a, 
+                        COALESCE(tb.decimals, 0) as decimals_b
                  FROM apestrong.subscribed_pools p
                  LEFT JOIN apestrong.token_metadata ta ON p.token_a_mint = ta.mint
                  LEFT JOIN apestrong.token_metadata tb ON p.token_b_mint = tb.mint
@@ -200,13 +716,13 @@ impl OrcaWhirlpoolRepository {
         let row = sqlx
             ::query(
                 "SELECT p.pool_mint as whirlpool, 
-                        p.token_a_mint as token_mint_a, 
-                        p.token_b_mint as token_mint_b, 
+                        COALESCE(p.token_a_mint, '') as token_mint_a, 
+                        COALESCE(p.token_b_mint, '') as token_mint_b, 
                         p.pool_name,
                         ta.token_name as token_name_a, 
                         tb.token_name as token_name_b,
-                        ta.decimals as decimals_a, 
-                        tb.decimals as decimals_b
+                        COALESCE(ta.decimals, 0) as decimals_a, 
+                        COALESCE(tb.decimals, 0) as decimals_b
                  FROM apestrong.subscribed_pools p
                  LEFT JOIN apestrong.token_metadata ta ON p.token_a_mint = ta.mint
                  LEFT JOIN apestrong.token_metadata tb ON p.token_b_mint = tb.mint
@@ -234,7 +750,34 @@ impl OrcaWhirlpoolRepository {
         }
     }
 
+    /// Get the token A and token B decimals of a pool
+    ///
+    /// Returns None if the pool isn't subscribed or either token has no metadata.
+    pub async fn get_token_decimals(&self, whirlpool_address: &str) -> Result<Option<(u8, u8)>> {
+        let row: Option<(Option<i32>, Option<i32>)> = sqlx
+            ::query_as(
+                "SELECT ta.decimals, tb.decimals
+                 FROM apestrong.subscribed_pools p
+                 LEFT JOIN apestrong.token_metadata ta ON p.token_a_mint = ta.mint
+                 LEFT JOIN apestrong.token_metadata tb ON p.token_b_mint = tb.mint
+                 WHERE p.pool_mint = $1 AND p.dex = 'orca'"
+            )
+            .bind(whirlpool_address)
+            .fetch_optional(&self.pool).await
+            .context("Failed to fetch Orca Whirlpool token decimals")?;
+
+        Ok(match row {
+            Some((Some(decimals_a), Some(decimals_b))) =>
+                u8::try_from(decimals_a).ok().zip(u8::try_from(decimals_b).ok()),
+            _ => None,
+        })
+    }
+
     /// Add or update a pool
+    ///
+    /// Names that are None keep their stored values, so pools discovered on-chain
+    /// don't erase names loaded from a pool list. Token metadata is only rewritten
+    /// when it changed, so `last_updated` keeps saying when it was last fetched.
     pub async fn upsert_pool(&self, pool: &OrcaWhirlpoolPoolRecord) -> Result<()> {
         // Start a transaction
         let mut tx = self.pool.begin().await?;
@@ -249,9 +792,12 @@ impl OrcaWhirlpoolRepository {
                     "INSERT INTO apestrong.token_metadata (mint, token_name, decimals, last_updated)
                  VALUES ($1, $2, $3, NOW())
                  ON CONFLICT (mint) DO UPDATE SET
-                 token_name = EXCLUDED.token_name,
+                 token_name = COALESCE(EXCLUDED.token_name, apestrong.token_metadata.token_name),
                  decimals = EXCLUDED.decimals,
-                 last_updated = NOW()"
+                 last_updated = NOW()
+                 WHERE (apestrong.token_metadata.token_name, apestrong.token_metadata.decimals)
+                     IS DISTINCT FROM
+                     (COALESCE(EXCLUDED.token_name, apestrong.token_metadata.token_name), EXCLUDED.decimals)"
                 )
                 .bind(mint)
                 .bind(name)
@@ -273,7 +819,7 @@ impl OrcaWhirlpoolRepository {
              (pool_mint, pool_name, dex, token_a_mint, token_b_mint, last_updated)
              VALUES ($1, $2, 'orca', $3, $4, NOW())
              ON CONFLICT (pool_mint) DO UPDATE SET
-             pool_name = EXCLUDED.pool_name,
+             pool_name = COALESCE(EXCLUDED.pool_name, apestrong.subscribed_pools.pool_name),
              dex = EXCLUDED.dex,
              token_a_mint = EXCLUDED.token_a_mint,
              token_b_mint = EXCLUDED.token_b_mint,
@@ -292,6 +838,68 @@ impl OrcaWhirlpoolRepository {
         Ok(())
     }
 
+    /// Add a pool known only by its address, returning false if it was already stored
+    ///
+    /// Used when indexing every pool: the pool's tokens aren't known from its events,
+    /// so they stay NULL until the pool is loaded with `load_pools`.
+    pub async fn insert_pool_if_missing(&self, whirlpool_address: &str) -> Result<bool> {
+        let result = sqlx
+            ::query(
+                "INSERT INTO apestrong.subscribed_pools (pool_mint, dex, last_updated)
+             VALUES ($1, 'orca', NOW())
+             ON CONFLICT (pool_mint) DO NOTHING"
+            )
+            .bind(whirlpool_address)
+            .execute(&self.pool).await
+            .context("Failed to insert pool")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a pool from the subscribed pools, returning false if it wasn't stored
+    ///
+    /// By default only the subscription is removed: the pool's indexed events are
+    /// kept for history, and its last signature lets a re-added pool resume where
+    /// it stopped. `deletion` also removes those, all in one transaction.
+    pub async fn delete_pool(&self, whirlpool_address: &str, deletion: PoolDeletion) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx
+            ::query("DELETE FROM apestrong.subscribed_pools WHERE pool_mint = $1 AND dex = 'orca'")
+            .bind(whirlpool_address)
+            .execute(&mut *tx).await
+            .context("Failed to delete pool")?;
+
+        if deletion.forget_signatures {
+            // Backfill progress is only meaningful alongside the last signature
+            for table in ["last_signatures", "backfill_progress"] {
+                sqlx
+                    ::query(
+                        &format!(
+                            "DELETE FROM apestrong.{} WHERE pool_address = $1 AND dex = 'orca'",
+                            table
+                        )
+                    )
+                    .bind(whirlpool_address)
+                    .execute(&mut *tx).await
+                    .with_context(|| format!("Failed to delete pool from {}", table))?;
+            }
+        }
+
+        if deletion.delete_events {
+            // The event type tables cascade from the base events
+            sqlx
+                ::query("DELETE FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1")
+                .bind(whirlpool_address)
+                .execute(&mut *tx).await
+                .context("Failed to delete pool events")?;
+        }
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Check if a pool exists
     pub async fn pool_exists(&self, whirlpool_address: &str) -> Result<bool> {
         let exists: (bool,) = sqlx
@@ -325,38 +933,68 @@ impl OrcaWhirlpoolRepository {
         Ok(pool_set)
     }
 
-    /// Get pool addresses with priority fallback: Provided list > Database > Default
+    /// Get the pubkeys of the subscribed pools trading a token on either side
+    pub async fn get_pools_by_token(&self, mint: &str) -> Result<HashSet<Pubkey>> {
+        let rows: Vec<(String,)> = sqlx
+            ::query_as(
+                "SELECT pool_mint FROM apestrong.subscribed_pools
+                 WHERE dex = 'orca' AND (token_a_mint = $1 OR token_b_mint = $1)"
+            )
+            .bind(mint)
+            .fetch_all(&self.pool).await
+            .with_context(|| format!("Failed to fetch pools trading {}", mint))?;
+
+        Ok(
+            rows
+                .into_iter()
+                .filter_map(|(address,)| Pubkey::from_str(&address).ok())
+                .collect()
+        )
+    }
+
+    /// Get pool addresses with priority fallback: Provided list > Token filter > Database > Default
     ///
     /// This function fetches pool addresses based on the following priority:
     /// 1. The provided list of addresses (if any)
-    /// 2. Pool addresses stored in the database
-    /// 3. A default pool address as a fallback
+    /// 2. Pool addresses stored in the database that trade `token_mint`, if given
+    /// 3. Pool addresses stored in the database
+    /// 4. A default pool address as a fallback
+    ///
+    /// Invalid provided addresses are skipped with a warning, unless
+    /// `strict_pool_addresses` is set (see `parse_pool_addresses`). A token
+    /// filter matching no stored pool is an error rather than a fallback, so a
+    /// mistyped mint doesn't index every pool.
     pub async fn get_pools_with_fallback(
         &self,
         provided_pools: Option<&Vec<String>>,
-        default_pool: &str
+        default_pool: &str,
+        strict_pool_addresses: bool,
+        token_mint: Option<&Pubkey>
     ) -> Result<HashSet<Pubkey>> {
-        // 1. If provided addresses exist and are not empty, use them
+        // 1. If provided addresses exist and are not empty, use the valid ones
         if let Some(addresses) = provided_pools {
             if !addresses.is_empty() {
-                let mut pubkeys = HashSet::new();
-                for addr in addresses {
-                    let pubkey = Pubkey::from_str(addr).context(
-                        format!("Invalid Solana address: {}", addr)
-                    )?;
-                    pubkeys.insert(pubkey);
-                }
-                return Ok(pubkeys);
+                let pubkeys = parse_pool_addresses(addresses, strict_pool_addresses)?;
+                return Ok(pubkeys.into_iter().collect());
+            }
+        }
+
+        // 2. Select the stored pools trading the token
+        if let Some(mint) = token_mint {
+            let token_pools = self.get_pools_by_token(&mint.to_string()).await?;
+            if token_pools.is_empty() {
+                anyhow::bail!("No subscribed Orca pools trade token {}", mint);
             }
+            return Ok(token_pools);
         }
 
-        // 2. Try to get pools from the database
+        // 3. Try to get pools from the database
         let db_pools = self.get_pool_pubkeys().await?;
         if !db_pools.is_empty() {
             return Ok(db_pools);
         }
 
-        // 3. Use the default pool as fallback
+        // 4. Use the default pool as fallback
         let mut pubkeys = HashSet::new();
         pubkeys.insert(
             Pubkey::from_str(default_pool).context("Failed to parse default Orca pool address")?
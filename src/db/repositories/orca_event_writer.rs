@@ -0,0 +1,160 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::{ Arc, Mutex };
+
+use crate::db::common::Repository;
+use crate::db::quarantine::{ QuarantineStore, QuarantinedEvent };
+use crate::models::orca::whirlpool::{
+    OrcaWhirlpoolLiquidityDecreasedEventRecord,
+    OrcaWhirlpoolLiquidityIncreasedEventRecord,
+    OrcaWhirlpoolPoolInitializedEventRecord,
+    OrcaWhirlpoolTradedEventRecord,
+};
+
+use super::OrcaWhirlpoolRepository;
+
+/// Writes the parsed events of the Orca indexer
+///
+/// `OrcaWhirlpoolRepository` stores them in Postgres and is what the indexer
+/// uses by default. Each insert returns the stored event's ID, or None if an
+/// event with the same signature and event index is already stored; quarantined
+/// events are numbered separately from indexed ones, and likewise stored once.
+#[async_trait]
+pub trait OrcaEventWriter: Send + Sync {
+    async fn insert_traded_event(
+        &self,
+        event: OrcaWhirlpoolTradedEventRecord
+    ) -> Result<Option<i32>>;
+
+    async fn insert_liquidity_increased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityIncreasedEventRecord
+    ) -> Result<Option<i32>>;
+
+    async fn insert_liquidity_decreased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityDecreasedEventRecord
+    ) -> Result<Option<i32>>;
+
+    async fn insert_pool_initialized_event(
+        &self,
+        event: OrcaWhirlpoolPoolInitializedEventRecord
+    ) -> Result<Option<i32>>;
+
+    /// Store an event that failed validation instead of indexing it
+    async fn insert_quarantined_event(&self, event: QuarantinedEvent) -> Result<Option<i32>>;
+}
+
+#[async_trait]
+impl OrcaEventWriter for OrcaWhirlpoolRepository {
+    async fn insert_traded_event(
+        &self,
+        event: OrcaWhirlpoolTradedEventRecord
+    ) -> Result<Option<i32>> {
+        OrcaWhirlpoolRepository::insert_traded_event(self, event).await
+    }
+
+    async fn insert_liquidity_increased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityIncreasedEventRecord
+    ) -> Result<Option<i32>> {
+        OrcaWhirlpoolRepository::insert_liquidity_increased_event(self, event).await
+    }
+
+    async fn insert_liquidity_decreased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityDecreasedEventRecord
+    ) -> Result<Option<i32>> {
+        OrcaWhirlpoolRepository::insert_liquidity_decreased_event(self, event).await
+    }
+
+    async fn insert_pool_initialized_event(
+        &self,
+        event: OrcaWhirlpoolPoolInitializedEventRecord
+    ) -> Result<Option<i32>> {
+        OrcaWhirlpoolRepository::insert_pool_initialized_event(self, event).await
+    }
+
+    async fn insert_quarantined_event(&self, event: QuarantinedEvent) -> Result<Option<i32>> {
+        QuarantineStore::new(self.pool().clone()).insert(&event).await
+    }
+}
+
+/// An Orca event written to an `InMemoryOrcaEventWriter`
+#[derive(Debug, Clone)]
+pub enum OrcaEventRecord {
+    Traded(OrcaWhirlpoolTradedEventRecord),
+    LiquidityIncreased(OrcaWhirlpoolLiquidityIncreasedEventRecord),
+    LiquidityDecreased(OrcaWhirlpoolLiquidityDecreasedEventRecord),
+    PoolInitialized(OrcaWhirlpoolPoolInitializedEventRecord),
+}
+
+/// Keeps written events in memory, for tests that don't need a database
+///
+/// Clones share the same events, so a test can hand one clone to the indexer
+/// and inspect what it wrote through another.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryOrcaEventWriter {
+    events: Arc<Mutex<Vec<OrcaEventRecord>>>,
+    quarantined: Arc<Mutex<Vec<QuarantinedEvent>>>,
+}
+
+impl InMemoryOrcaEventWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events written so far, in order
+    pub fn events(&self) -> Vec<OrcaEventRecord> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Events quarantined so far, in order
+    pub fn quarantined(&self) -> Vec<QuarantinedEvent> {
+        self.quarantined.lock().unwrap().clone()
+    }
+
+    // Store an event, numbering events from 1 like the database does
+    fn push(&self, event: OrcaEventRecord) -> i32 {
+        let mut events = self.events.lock().unwrap();
+        events.push(event);
+        events.len() as i32
+    }
+}
+
+#[async_trait]
+impl OrcaEventWriter for InMemoryOrcaEventWriter {
+    async fn insert_traded_event(
+        &self,
+        event: OrcaWhirlpoolTradedEventRecord
+    ) -> Result<Option<i32>> {
+        Ok(Some(self.push(OrcaEventRecord::Traded(event))))
+    }
+
+    async fn insert_liquidity_increased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityIncreasedEventRecord
+    ) -> Result<Option<i32>> {
+        Ok(Some(self.push(OrcaEventRecord::LiquidityIncreased(event))))
+    }
+
+    async fn insert_liquidity_decreased_event(
+        &self,
+        event: OrcaWhirlpoolLiquidityDecreasedEventRecord
+    ) -> Result<Option<i32>> {
+        Ok(Some(self.push(OrcaEventRecord::LiquidityDecreased(event))))
+    }
+
+    async fn insert_pool_initialized_event(
+        &self,
+        event: OrcaWhirlpoolPoolInitializedEventRecord
+    ) -> Result<Option<i32>> {
+        Ok(Some(self.push(OrcaEventRecord::PoolInitialized(event))))
+    }
+
+    async fn insert_quarantined_event(&self, event: QuarantinedEvent) -> Result<Option<i32>> {
+        let mut quarantined = self.quarantined.lock().unwrap();
+        quarantined.push(event);
+        Ok(Some(quarantined.len() as i32))
+    }
+}
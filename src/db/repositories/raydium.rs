@@ -1,12 +1,21 @@
 use anyhow::{ Context, Result };
 use solana_sdk::pubkey::Pubkey;
-use sqlx::PgPool;
-use std::collections::HashSet;
+use sqlx::{ PgPool, Postgres, Row, Transaction };
+use std::collections::{ HashMap, HashSet };
 use std::str::FromStr;
+use std::sync::RwLock;
 use async_trait::async_trait;
 
-use crate::db::common::Repository;
+use crate::db::common::{
+    normalize_signature,
+    parse_pool_addresses,
+    to_numeric,
+    to_numeric_array,
+    Repository,
+};
+use crate::error::IndexerError;
 use crate::models::raydium::clmm::{
+    RaydiumCLMMEvent,
     RaydiumCLMMCreatePostionEventRecord,
     RaydiumCLMMIncreaseLiquidityEventRecord,
     RaydiumCLMMDecreaseLiquidityEventRecord,
@@ -29,6 +38,8 @@ pub enum RaydiumPoolType {
 /// Repository for Raydium data access
 pub struct RaydiumRepository {
     pool: PgPool,
+    /// Cache of position NFT mint -> pool mappings to avoid repeated DB lookups
+    position_pools: RwLock<HashMap<Pubkey, Pubkey>>,
 }
 
 #[async_trait]
@@ -41,7 +52,10 @@ impl Repository for RaydiumRepository {
 impl RaydiumRepository {
     /// Create a new repository instance
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            position_pools: RwLock::new(HashMap::new()),
+        }
     }
 
     /// Get pools from database or CLI args, with fallbacks to defaults
@@ -49,12 +63,14 @@ impl RaydiumRepository {
         &self,
         provided_pools: Option<&Vec<String>>,
         default_amm_pool: &str,
-        default_clmm_pool: &str
+        default_clmm_pool: &str,
+        strict_pool_addresses: bool
     ) -> Result<(HashSet<Pubkey>, HashSet<Pubkey>)> {
-        // If pools are provided via CLI, use those
+        // If pools are provided via CLI, use the valid ones
         if let Some(pools) = provided_pools {
             if !pools.is_empty() {
-                let (amm_pools, clmm_pools) = self.classify_pools(pools).await?;
+                let pools = parse_pool_addresses(pools, strict_pool_addresses)?;
+                let (amm_pools, clmm_pools) = self.classify_pools(&pools).await?;
                 return Ok((amm_pools, clmm_pools));
             }
         }
@@ -62,11 +78,6 @@ impl RaydiumRepository {
         // Try to get pools from database
         let db_pools = self.get_subscribed_pools().await?;
         if !db_pools.is_empty() {
-            let pool_strs: Vec<String> = db_pools
-                .iter()
-                .map(|p| p.pool_address.clone())
-                .collect();
-
             // Classify pools from database
             let mut amm_pools = HashSet::new();
             let mut clmm_pools = HashSet::new();
@@ -114,24 +125,41 @@ impl RaydiumRepository {
 
     /// Get all subscribed pools from the database
     async fn get_subscribed_pools(&self) -> Result<Vec<RaydiumPool>> {
-        // Query would look something like:
-        // SELECT pool_address, pool_type FROM raydium_pools WHERE is_subscribed = true
+        let rows = sqlx
+            ::query(
+                r#"
+            SELECT pool_mint, pool_type
+            FROM apestrong.subscribed_pools
+            WHERE dex = 'raydium'
+            "#
+            )
+            .fetch_all(&self.pool).await
+            .context("Failed to fetch subscribed Raydium pools")?;
 
-        // For now, this is a placeholder returning an empty vector
-        // In a real implementation, you would query the database
-        Ok(Vec::new())
+        // Pools subscribed without a type are assumed to be CLMM, like CLI pools
+        Ok(
+            rows
+                .into_iter()
+                .map(|row| {
+                    let pool_type = match row.get::<Option<String>, _>("pool_type").as_deref() {
+                        Some("amm") => RaydiumPoolType::AMM,
+                        _ => RaydiumPoolType::CLMM,
+                    };
+                    RaydiumPool {
+                        pool_address: row.get("pool_mint"),
+                        pool_type,
+                    }
+                })
+                .collect()
+        )
     }
 
     /// Classify provided pool addresses into AMM and CLMM types
-    async fn classify_pools(&self, pools: &[String]) -> Result<(HashSet<Pubkey>, HashSet<Pubkey>)> {
+    async fn classify_pools(&self, pools: &[Pubkey]) -> Result<(HashSet<Pubkey>, HashSet<Pubkey>)> {
         let mut amm_pools = HashSet::new();
         let mut clmm_pools = HashSet::new();
 
-        for pool_str in pools {
-            let pool_pubkey = Pubkey::from_str(pool_str).context(
-                format!("Failed to parse pool address: {}", pool_str)
-            )?;
-
+        for &pool_pubkey in pools {
             // Determine if this is an AMM or CLMM pool
             // This could be based on database lookup, on-chain data, or naming convention
             // For now, use a simple placeholder approach
@@ -151,42 +179,224 @@ impl RaydiumRepository {
     }
 
     /// Determine the type of a pool (AMM or CLMM)
-    async fn determine_pool_type(&self, pool: Pubkey) -> Result<RaydiumPoolType> {
+    async fn determine_pool_type(&self, _pool: Pubkey) -> Result<RaydiumPoolType> {
         // This would typically query the database or check on-chain data
         // For now, this is a placeholder that assumes all pools are CLMM
         // In a real implementation, you would need logic to distinguish pool types
         Ok(RaydiumPoolType::CLMM)
     }
 
+    //
+    // POSITION -> POOL MAPPING
+    //
+
+    /// Get a cached pool for a position NFT mint without touching the database
+    pub fn cached_position_pool(&self, position_nft_mint: &Pubkey) -> Option<Pubkey> {
+        self.position_pools.read().unwrap().get(position_nft_mint).copied()
+    }
+
+    /// Cache the pool for a position NFT mint
+    pub fn cache_position_pool(&self, position_nft_mint: Pubkey, pool: Pubkey) {
+        self.position_pools.write().unwrap().insert(position_nft_mint, pool);
+    }
+
+    /// Look up the pool for a position NFT mint, checking the cache before the database
+    pub async fn get_position_pool(&self, position_nft_mint: &Pubkey) -> Result<Option<Pubkey>> {
+        if let Some(pool) = self.cached_position_pool(position_nft_mint) {
+            return Ok(Some(pool));
+        }
+
+        let row = sqlx
+            ::query("SELECT pool FROM apestrong.raydium_clmm_positions WHERE position_nft_mint = $1")
+            .bind(position_nft_mint.to_string())
+            .fetch_optional(&self.pool).await
+            .context("Failed to query Raydium CLMM position mapping")?;
+
+        match row {
+            Some(row) => {
+                let pool_str: String = row.get("pool");
+                let pool = Pubkey::from_str(&pool_str).context(
+                    format!("Invalid pool address in position mapping: {}", pool_str)
+                )?;
+                self.cache_position_pool(*position_nft_mint, pool);
+                Ok(Some(pool))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Persist the pool for a position NFT mint (the first mapping recorded wins)
+    pub async fn save_position_pool(
+        &self,
+        position_nft_mint: &Pubkey,
+        pool: &Pubkey,
+        signature: &str
+    ) -> Result<()> {
+        let signature = normalize_signature(signature)?;
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.raydium_clmm_positions (position_nft_mint, pool, signature) VALUES ($1, $2, $3) ON CONFLICT (position_nft_mint) DO NOTHING"
+            )
+            .bind(position_nft_mint.to_string())
+            .bind(pool.to_string())
+            .bind(&signature)
+            .execute(&self.pool).await
+            .context("Failed to save Raydium CLMM position mapping")?;
+
+        self.cache_position_pool(*position_nft_mint, *pool);
+        Ok(())
+    }
+
+    /// Insert a base Raydium CLMM event
+    async fn insert_clmm_base_event<'a>(
+        &self,
+        tx: &mut Transaction<'a, Postgres>,
+        event: &RaydiumCLMMEvent
+    ) -> Result<i32> {
+        let signature = normalize_signature(&event.signature)?;
+        let row = sqlx
+            ::query(
+                "INSERT INTO apestrong.raydium_clmm_events (signature, pool, event_type, dex, slot, block_time, is_backfill, version) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id"
+            )
+            .bind(&signature)
+            .bind(&event.pool)
+            .bind(&event.event_type)
+            .bind(&event.dex)
+            .bind(event.slot)
+            .bind(event.block_time)
+            .bind(event.is_backfill)
+            .bind(event.version)
+            .fetch_one(&mut **tx).await
+            .context("Failed to insert base Raydium CLMM event")?;
+
+        let id: i32 = row.get("id");
+        Ok(id)
+    }
+
     /// Insert a CLMM create position event
     pub async fn insert_clmm_create_position_event(
         &self,
         event: RaydiumCLMMCreatePostionEventRecord
-    ) -> Result<()> {
-        // Implementation would insert event into database
-        // For now, just log that we would save the event
-        log::info!("Would insert CLMM create position event for pool: {}", event.base.pool);
-        Ok(())
+    ) -> Result<i32> {
+        let mut tx = self.pool.begin().await?;
+
+        // Insert the base event
+        let event_id = self.insert_clmm_base_event(&mut tx, &event.base).await?;
+
+        // Insert the create position data (u64/u128 values are bound as NUMERIC text)
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.raydium_clmm_create_position_events (event_id, minter, nft_owner, tick_lower_index, tick_upper_index, liquidity, deposit_amount_0, deposit_amount_1, deposit_amount_0_transfer_fee, deposit_amount_1_transfer_fee) VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9::NUMERIC, $10::NUMERIC)"
+            )
+            .bind(event_id)
+            .bind(&event.data.minter)
+            .bind(&event.data.nft_owner)
+            .bind(event.data.tick_lower_index)
+            .bind(event.data.tick_upper_index)
+            .bind(to_numeric(event.data.liquidity))
+            .bind(to_numeric(event.data.deposit_amount_0))
+            .bind(to_numeric(event.data.deposit_amount_1))
+            .bind(to_numeric(event.data.deposit_amount_0_transfer_fee))
+            .bind(to_numeric(event.data.deposit_amount_1_transfer_fee))
+            .execute(&mut *tx).await
+            .context("Failed to insert Raydium CLMM create position event")?;
+
+        tx.commit().await?;
+        Ok(event_id)
     }
 
     /// Insert a CLMM increase liquidity event
     pub async fn insert_clmm_increase_liquidity_event(
         &self,
         event: RaydiumCLMMIncreaseLiquidityEventRecord
-    ) -> Result<()> {
-        // Implementation would insert event into database
-        log::info!("Would insert CLMM increase liquidity event for pool: {}", event.base.pool);
-        Ok(())
+    ) -> Result<i32> {
+        let mut tx = self.pool.begin().await?;
+
+        // Insert the base event
+        let event_id = self.insert_clmm_base_event(&mut tx, &event.base).await?;
+
+        // Insert the increase liquidity data
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.raydium_clmm_liquidity_increased_events (event_id, position_nft_mint, liquidity, amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee) VALUES ($1, $2, $3::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC)"
+            )
+            .bind(event_id)
+            .bind(event.data.position_nft_mint.to_string())
+            .bind(to_numeric(event.data.liquidity))
+            .bind(to_numeric(event.data.amount_0))
+            .bind(to_numeric(event.data.amount_1))
+            .bind(to_numeric(event.data.amount_0_transfer_fee))
+            .bind(to_numeric(event.data.amount_1_transfer_fee))
+            .execute(&mut *tx).await
+            .context("Failed to insert Raydium CLMM increase liquidity event")?;
+
+        tx.commit().await?;
+        Ok(event_id)
     }
 
     /// Insert a CLMM decrease liquidity event
     pub async fn insert_clmm_decrease_liquidity_event(
         &self,
         event: RaydiumCLMMDecreaseLiquidityEventRecord
-    ) -> Result<()> {
-        // Implementation would insert event into database
-        log::info!("Would insert CLMM decrease liquidity event for pool: {}", event.base.pool);
-        Ok(())
+    ) -> Result<i32> {
+        let mut tx = self.pool.begin().await?;
+
+        // Insert the base event
+        let event_id = self.insert_clmm_base_event(&mut tx, &event.base).await?;
+
+        // Insert the decrease liquidity data (reward amounts are stored as NUMERIC[])
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.raydium_clmm_liquidity_decreased_events (event_id, position_nft_mint, liquidity, decrease_amount_0, decrease_amount_1, fee_amount_0, fee_amount_1, reward_amounts, transfer_fee_0, transfer_fee_1) VALUES ($1, $2, $3::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC[], $9::NUMERIC, $10::NUMERIC)"
+            )
+            .bind(event_id)
+            .bind(event.data.position_nft_mint.to_string())
+            .bind(to_numeric(event.data.liquidity))
+            .bind(to_numeric(event.data.decrease_amount_0))
+            .bind(to_numeric(event.data.decrease_amount_1))
+            .bind(to_numeric(event.data.fee_amount_0))
+            .bind(to_numeric(event.data.fee_amount_1))
+            .bind(to_numeric_array(&event.data.reward_amounts))
+            .bind(to_numeric(event.data.transfer_fee_0))
+            .bind(to_numeric(event.data.transfer_fee_1))
+            .execute(&mut *tx).await
+            .context("Failed to insert Raydium CLMM decrease liquidity event")?;
+
+        tx.commit().await?;
+        Ok(event_id)
+    }
+
+    /// Signatures among the given ones that already have a stored CLMM event
+    pub async fn indexed_signatures(&self, signatures: &[String]) -> Result<HashSet<String>> {
+        let rows: Vec<(String,)> = sqlx
+            ::query_as("SELECT signature FROM apestrong.raydium_clmm_events WHERE signature = ANY($1)")
+            .bind(signatures)
+            .fetch_all(&self.pool).await
+            .map_err(IndexerError::from)
+            .context("Failed to look up indexed Raydium CLMM signatures")?;
+
+        Ok(
+            rows
+                .into_iter()
+                .map(|(signature,)| signature)
+                .collect()
+        )
+    }
+
+    /// Delete a pool's CLMM events in slots after `slot`, returning how many were deleted
+    ///
+    /// Rolls back events from blocks dropped by a reorg. Event details are deleted
+    /// with their base event, events without a recorded slot are kept, and position
+    /// to pool mappings are left in place.
+    pub async fn delete_events_after_slot(&self, pool: &str, slot: i64) -> Result<u64> {
+        let result = sqlx
+            ::query("DELETE FROM apestrong.raydium_clmm_events WHERE pool = $1 AND slot > $2")
+            .bind(pool)
+            .bind(slot)
+            .execute(&self.pool).await
+            .with_context(|| format!("Failed to delete Raydium CLMM events after slot {}", slot))?;
+
+        Ok(result.rows_affected())
     }
 
     // AMM event insertion methods would be added here
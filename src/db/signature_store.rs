@@ -3,8 +3,31 @@ use solana_sdk::pubkey::Pubkey;
 use sqlx::{ PgPool, Row };
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{ AtomicU64, Ordering };
 use std::sync::{ Arc, Mutex };
 
+use crate::error::IndexerError;
+use crate::db::common::normalize_signature;
+use crate::metrics;
+
+/// Order in which tracked pools are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackedPoolOrder {
+    /// No particular order
+    Unordered,
+    /// Most recently updated first (by last_updated)
+    RecentFirst,
+}
+
+/// How far a pool's initial backfill has paged back through history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillProgress {
+    /// Oldest signature processed so far, where paging resumes
+    pub oldest_backfilled_signature: Option<String>,
+    /// Whether the initial backfill reached its configured depth
+    pub backfill_complete: bool,
+}
+
 /// Enum-based store to contain both memory and database implementations
 #[derive(Clone)]
 pub enum SignatureStore {
@@ -13,30 +36,69 @@ pub enum SignatureStore {
 }
 
 impl SignatureStore {
-    /// Store the last processed signature for a pool
+    /// Store the last processed signature for a pool, without its slot
     pub async fn update_signature(
         &self,
         pool: &Pubkey,
         signature: String,
         dex_type: &str
     ) -> Result<()> {
+        self.update_signature_at_slot(pool, signature, None, dex_type).await
+    }
+
+    /// Store the last processed signature for a pool along with the slot it landed in
+    ///
+    /// The slot tells how far to roll back if a reorg drops the signature.
+    pub async fn update_signature_at_slot(
+        &self,
+        pool: &Pubkey,
+        signature: String,
+        slot: Option<u64>,
+        dex_type: &str
+    ) -> Result<()> {
+        let signature = normalize_signature(&signature)?;
         match self {
             Self::InMemory(store) => {
-                store.update_signature(pool, signature, dex_type);
+                store.update_signature(pool, signature, slot, dex_type);
                 Ok(())
             }
-            Self::Database(store) => store.update_signature_async(pool, signature, dex_type).await,
+            Self::Database(store) =>
+                store.update_signature_async(pool, signature, slot, dex_type).await,
+        }
+    }
+
+    /// Retrieve the slot of a pool's last processed signature, if it was stored with one
+    pub async fn get_signature_slot(&self, pool: &Pubkey, dex_type: &str) -> Result<Option<u64>> {
+        match self {
+            Self::InMemory(store) => Ok(store.get_signature_slot(pool, dex_type)),
+            Self::Database(store) => store.get_signature_slot_async(pool, dex_type).await,
         }
     }
 
     /// Retrieve the last processed signature for a pool
     pub async fn get_signature(&self, pool: &Pubkey, dex_type: &str) -> Result<Option<String>> {
+        metrics::record_signature_store_read(dex_type, "single");
         match self {
             Self::InMemory(store) => Ok(store.get_signature(pool, dex_type)),
             Self::Database(store) => store.get_signature_async(pool, dex_type).await,
         }
     }
 
+    /// Retrieve the last processed signatures of several pools in one read
+    ///
+    /// Pools without a stored signature are left out of the map.
+    pub async fn get_signatures_for_pools(
+        &self,
+        pools: &[Pubkey],
+        dex_type: &str
+    ) -> Result<HashMap<Pubkey, String>> {
+        metrics::record_signature_store_read(dex_type, "bulk");
+        match self {
+            Self::InMemory(store) => Ok(store.get_signatures_for_pools(pools, dex_type)),
+            Self::Database(store) => store.get_signatures_for_pools_async(pools, dex_type).await,
+        }
+    }
+
     /// Check if we have a stored signature for this pool
     pub async fn has_signature(&self, pool: &Pubkey, dex_type: &str) -> Result<bool> {
         match self {
@@ -45,42 +107,125 @@ impl SignatureStore {
         }
     }
 
-    /// Get all tracked pools for a specific DEX
-    pub async fn get_tracked_pools(&self, dex_type: &str) -> Result<Vec<Pubkey>> {
+    /// Get tracked pools for a specific DEX, optionally ordered and limited
+    pub async fn get_tracked_pools(
+        &self,
+        dex_type: &str,
+        order: TrackedPoolOrder,
+        limit: Option<usize>
+    ) -> Result<Vec<Pubkey>> {
         match self {
-            Self::InMemory(store) => Ok(store.get_tracked_pools(dex_type)),
-            Self::Database(store) => store.get_tracked_pools_async(dex_type).await,
+            Self::InMemory(store) => Ok(store.get_tracked_pools(dex_type, order, limit)),
+            Self::Database(store) => store.get_tracked_pools_async(dex_type, order, limit).await,
+        }
+    }
+
+    /// Store the initial backfill progress for a pool
+    pub async fn update_backfill_progress(
+        &self,
+        pool: &Pubkey,
+        progress: BackfillProgress,
+        dex_type: &str
+    ) -> Result<()> {
+        let progress = BackfillProgress {
+            oldest_backfilled_signature: progress.oldest_backfilled_signature
+                .as_deref()
+                .map(normalize_signature)
+                .transpose()?,
+            ..progress
+        };
+        match self {
+            Self::InMemory(store) => {
+                store.update_backfill_progress(pool, progress, dex_type);
+                Ok(())
+            }
+            Self::Database(store) =>
+                store.update_backfill_progress_async(pool, progress, dex_type).await,
+        }
+    }
+
+    /// Retrieve the initial backfill progress for a pool, if a backfill was started
+    pub async fn get_backfill_progress(
+        &self,
+        pool: &Pubkey,
+        dex_type: &str
+    ) -> Result<Option<BackfillProgress>> {
+        match self {
+            Self::InMemory(store) => Ok(store.get_backfill_progress(pool, dex_type)),
+            Self::Database(store) => store.get_backfill_progress_async(pool, dex_type).await,
         }
     }
 }
 
+// Key: (pool_pubkey, dex_type), value: (signature, slot, update sequence number)
+type SignatureMap = HashMap<(Pubkey, String), (String, Option<u64>, u64)>;
+
+// Key: (pool_pubkey, dex_type)
+type BackfillProgressMap = HashMap<(Pubkey, String), BackfillProgress>;
+
 /// In-memory implementation of signature storage
 #[derive(Clone)]
 pub struct InMemorySignatureStore {
-    // Key: (pool_pubkey, dex_type)
-    signatures: Arc<Mutex<HashMap<(Pubkey, String), String>>>,
+    signatures: Arc<Mutex<SignatureMap>>,
+    // Increases with every update, standing in for last_updated
+    update_seq: Arc<AtomicU64>,
+    backfill_progress: Arc<Mutex<BackfillProgressMap>>,
 }
 
 impl InMemorySignatureStore {
     pub fn new() -> Self {
         Self {
             signatures: Arc::new(Mutex::new(HashMap::new())),
+            update_seq: Arc::new(AtomicU64::new(0)),
+            backfill_progress: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    pub fn update_signature(&self, pool: &Pubkey, signature: String, dex_type: &str) {
+    pub fn update_signature(
+        &self,
+        pool: &Pubkey,
+        signature: String,
+        slot: Option<u64>,
+        dex_type: &str
+    ) {
         if let Ok(mut store) = self.signatures.lock() {
-            store.insert((*pool, dex_type.to_string()), signature);
+            let seq = self.update_seq.fetch_add(1, Ordering::Relaxed);
+            store.insert((*pool, dex_type.to_string()), (signature, slot, seq));
         }
     }
 
     pub fn get_signature(&self, pool: &Pubkey, dex_type: &str) -> Option<String> {
         if let Ok(store) = self.signatures.lock() {
-            return store.get(&(*pool, dex_type.to_string())).cloned();
+            return store
+                .get(&(*pool, dex_type.to_string()))
+                .map(|(signature, _, _)| signature.clone());
+        }
+        None
+    }
+
+    pub fn get_signature_slot(&self, pool: &Pubkey, dex_type: &str) -> Option<u64> {
+        if let Ok(store) = self.signatures.lock() {
+            return store.get(&(*pool, dex_type.to_string())).and_then(|(_, slot, _)| *slot);
         }
         None
     }
 
+    pub fn get_signatures_for_pools(
+        &self,
+        pools: &[Pubkey],
+        dex_type: &str
+    ) -> HashMap<Pubkey, String> {
+        let mut signatures = HashMap::new();
+        if let Ok(store) = self.signatures.lock() {
+            for pool in pools {
+                if let Some((signature, _, _)) = store.get(&(*pool, dex_type.to_string())) {
+                    signatures.insert(*pool, signature.clone());
+                }
+            }
+        }
+        signatures
+    }
+
     pub fn has_signature(&self, pool: &Pubkey, dex_type: &str) -> bool {
         if let Ok(store) = self.signatures.lock() {
             return store.contains_key(&(*pool, dex_type.to_string()));
@@ -88,16 +233,50 @@ impl InMemorySignatureStore {
         false
     }
 
-    pub fn get_tracked_pools(&self, dex_type: &str) -> Vec<Pubkey> {
+    pub fn get_tracked_pools(
+        &self,
+        dex_type: &str,
+        order: TrackedPoolOrder,
+        limit: Option<usize>
+    ) -> Vec<Pubkey> {
         let mut pools = Vec::new();
         if let Ok(store) = self.signatures.lock() {
-            for ((pool, stored_dex), _) in store.iter() {
+            for ((pool, stored_dex), (_, _, seq)) in store.iter() {
                 if stored_dex == dex_type {
-                    pools.push(*pool);
+                    pools.push((*pool, *seq));
                 }
             }
         }
+
+        if order == TrackedPoolOrder::RecentFirst {
+            pools.sort_by_key(|(_, seq)| std::cmp::Reverse(*seq));
+        }
+        if let Some(limit) = limit {
+            pools.truncate(limit);
+        }
+
         pools
+            .into_iter()
+            .map(|(pool, _)| pool)
+            .collect()
+    }
+
+    pub fn update_backfill_progress(
+        &self,
+        pool: &Pubkey,
+        progress: BackfillProgress,
+        dex_type: &str
+    ) {
+        if let Ok(mut store) = self.backfill_progress.lock() {
+            store.insert((*pool, dex_type.to_string()), progress);
+        }
+    }
+
+    pub fn get_backfill_progress(&self, pool: &Pubkey, dex_type: &str) -> Option<BackfillProgress> {
+        if let Ok(store) = self.backfill_progress.lock() {
+            return store.get(&(*pool, dex_type.to_string())).cloned();
+        }
+        None
     }
 }
 
@@ -117,24 +296,28 @@ impl DbSignatureStore {
         &self,
         pool: &Pubkey,
         signature: String,
+        slot: Option<u64>,
         dex_type: &str
     ) -> Result<()> {
         sqlx
             ::query(
                 r#"
-            INSERT INTO apestrong.last_signatures (pool_address, signature, dex, last_updated)
-            VALUES ($1, $2, $3::apestrong.dex_type, NOW())
+            INSERT INTO apestrong.last_signatures (pool_address, signature, dex, slot, last_updated)
+            VALUES ($1, $2, $3::apestrong.dex_type, $4, NOW())
             ON CONFLICT (pool_address) 
             DO UPDATE SET 
                 signature = $2,
                 dex = $3::apestrong.dex_type,
+                slot = $4,
                 last_updated = NOW()
             "#
             )
             .bind(pool.to_string())
             .bind(&signature)
             .bind(dex_type)
+            .bind(slot.map(|slot| slot as i64))
             .execute(&self.db_pool).await
+            .map_err(IndexerError::from)
             .with_context(|| format!("Failed to update signature in database for pool {}", pool))?;
 
         Ok(())
@@ -157,6 +340,7 @@ impl DbSignatureStore {
             .bind(pool.to_string())
             .bind(dex_type)
             .fetch_optional(&self.db_pool).await
+            .map_err(IndexerError::from)
             .with_context(|| format!("Failed to query signature from database for pool {}", pool))?;
 
         // Extract the signature value
@@ -175,6 +359,79 @@ impl DbSignatureStore {
         }
     }
 
+    /// Asynchronous wrapper to get the slot of a pool's signature from the database
+    pub async fn get_signature_slot_async(
+        &self,
+        pool: &Pubkey,
+        dex_type: &str
+    ) -> Result<Option<u64>> {
+        let slot: Option<(Option<i64>,)> = sqlx
+            ::query_as(
+                r#"
+            SELECT slot
+            FROM apestrong.last_signatures
+            WHERE pool_address = $1 AND dex = $2::apestrong.dex_type
+            "#
+            )
+            .bind(pool.to_string())
+            .bind(dex_type)
+            .fetch_optional(&self.db_pool).await
+            .map_err(IndexerError::from)
+            .with_context(|| format!("Failed to query signature slot from database for pool {}", pool))?;
+
+        Ok(slot.and_then(|(slot,)| slot).map(|slot| slot as u64))
+    }
+
+    /// Asynchronous wrapper to get the signatures of several pools with one query
+    pub async fn get_signatures_for_pools_async(
+        &self,
+        pools: &[Pubkey],
+        dex_type: &str
+    ) -> Result<HashMap<Pubkey, String>> {
+        let addresses: Vec<String> = pools
+            .iter()
+            .map(|pool| pool.to_string())
+            .collect();
+        let rows = sqlx
+            ::query(
+                r#"
+            SELECT pool_address, signature
+            FROM apestrong.last_signatures
+            WHERE pool_address = ANY($1) AND dex = $2::apestrong.dex_type
+            "#
+            )
+            .bind(&addresses)
+            .bind(dex_type)
+            .fetch_all(&self.db_pool).await
+            .map_err(IndexerError::from)
+            .with_context(||
+                format!("Failed to query signatures from database for {} pools", pools.len())
+            )?;
+
+        let mut signatures = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let address: String = row
+                .try_get("pool_address")
+                .context("Failed to extract pool_address field from result")?;
+            let signature: String = row
+                .try_get("signature")
+                .with_context(||
+                    format!("Failed to extract signature field from result for pool {}", address)
+                )?;
+
+            match Pubkey::from_str(&address) {
+                Ok(pubkey) => {
+                    signatures.insert(pubkey, signature);
+                }
+                Err(e) => {
+                    log::warn!("Failed to parse pool address {}: {}", address, e);
+                }
+            }
+        }
+
+        Ok(signatures)
+    }
+
     /// Asynchronous wrapper to check if a signature exists in the database
     pub async fn has_signature_async(&self, pool: &Pubkey, dex_type: &str) -> Result<bool> {
         let result = sqlx
@@ -188,6 +445,7 @@ impl DbSignatureStore {
             .bind(pool.to_string())
             .bind(dex_type)
             .fetch_optional(&self.db_pool).await
+            .map_err(IndexerError::from)
             .with_context(||
                 format!("Failed to check signature existence in database for pool {}", pool)
             )?;
@@ -195,18 +453,35 @@ impl DbSignatureStore {
         Ok(result.is_some())
     }
 
-    /// Asynchronous wrapper to get all tracked pools for a specific DEX
-    pub async fn get_tracked_pools_async(&self, dex_type: &str) -> Result<Vec<Pubkey>> {
-        let rows = sqlx
-            ::query(
-                r#"
+    /// Asynchronous wrapper to get tracked pools for a specific DEX
+    pub async fn get_tracked_pools_async(
+        &self,
+        dex_type: &str,
+        order: TrackedPoolOrder,
+        limit: Option<usize>
+    ) -> Result<Vec<Pubkey>> {
+        let order_clause = match order {
+            TrackedPoolOrder::Unordered => "",
+            TrackedPoolOrder::RecentFirst => "ORDER BY last_updated DESC, pool_address",
+        };
+        let query = format!(
+            r#"
             SELECT pool_address 
             FROM apestrong.last_signatures 
             WHERE dex = $1::apestrong.dex_type
-            "#
-            )
+            {}
+            LIMIT $2
+            "#,
+            order_clause
+        );
+
+        // LIMIT NULL returns every row
+        let rows = sqlx
+            ::query(&query)
             .bind(dex_type)
+            .bind(limit.map(|limit| limit as i64))
             .fetch_all(&self.db_pool).await
+            .map_err(IndexerError::from)
             .with_context(||
                 format!("Failed to query tracked pools from database for DEX type {}", dex_type)
             )?;
@@ -220,7 +495,7 @@ impl DbSignatureStore {
             match Pubkey::from_str(&address) {
                 Ok(pubkey) => pools.push(pubkey),
                 Err(e) => {
-                    eprintln!("Failed to parse pool address {}: {}", address, e);
+                    log::warn!("Failed to parse pool address {}: {}", address, e);
                     // Continue with next row
                 }
             }
@@ -229,6 +504,77 @@ impl DbSignatureStore {
         Ok(pools)
     }
 
+    /// Asynchronous wrapper to update backfill progress in the database
+    pub async fn update_backfill_progress_async(
+        &self,
+        pool: &Pubkey,
+        progress: BackfillProgress,
+        dex_type: &str
+    ) -> Result<()> {
+        sqlx
+            ::query(
+                r#"
+            INSERT INTO apestrong.backfill_progress (pool_address, dex, oldest_backfilled_signature, backfill_complete, last_updated)
+            VALUES ($1, $2::apestrong.dex_type, $3, $4, NOW())
+            ON CONFLICT (pool_address)
+            DO UPDATE SET
+                dex = $2::apestrong.dex_type,
+                oldest_backfilled_signature = $3,
+                backfill_complete = $4,
+                last_updated = NOW()
+            "#
+            )
+            .bind(pool.to_string())
+            .bind(dex_type)
+            .bind(progress.oldest_backfilled_signature)
+            .bind(progress.backfill_complete)
+            .execute(&self.db_pool).await
+            .map_err(IndexerError::from)
+            .with_context(||
+                format!("Failed to update backfill progress in database for pool {}", pool)
+            )?;
+
+        Ok(())
+    }
+
+    /// Asynchronous wrapper to get backfill progress from the database
+    pub async fn get_backfill_progress_async(
+        &self,
+        pool: &Pubkey,
+        dex_type: &str
+    ) -> Result<Option<BackfillProgress>> {
+        let result = sqlx
+            ::query(
+                r#"
+            SELECT oldest_backfilled_signature, backfill_complete
+            FROM apestrong.backfill_progress
+            WHERE pool_address = $1 AND dex = $2::apestrong.dex_type
+            "#
+            )
+            .bind(pool.to_string())
+            .bind(dex_type)
+            .fetch_optional(&self.db_pool).await
+            .map_err(IndexerError::from)
+            .with_context(||
+                format!("Failed to query backfill progress from database for pool {}", pool)
+            )?;
+
+        match result {
+            Some(row) =>
+                Ok(
+                    Some(BackfillProgress {
+                        oldest_backfilled_signature: row
+                            .try_get("oldest_backfilled_signature")
+                            .context("Failed to extract oldest_backfilled_signature field")?,
+                        backfill_complete: row
+                            .try_get("backfill_complete")
+                            .context("Failed to extract backfill_complete field")?,
+                    })
+                ),
+            None => Ok(None),
+        }
+    }
+
     // Removed synchronous methods that created new Tokio runtimes
     // These were causing the "Cannot start a runtime from within a runtime" error
     // We now call the async methods directly from SignatureStore
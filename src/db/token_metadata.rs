@@ -0,0 +1,108 @@
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::token::{ fetch_token_info, TokenInfo };
+
+/// A stored token's name, symbol, decimals and when it was last fetched
+type TokenMetadataRow = (Option<String>, Option<String>, i32, DateTime<Utc>);
+
+/// Stores token mint details and metadata, re-fetching them from chain once stale
+///
+/// `last_updated` records when a token was last fetched, so re-running the pool
+/// loader only costs RPC calls for tokens older than the TTL.
+#[derive(Clone)]
+pub struct TokenMetadataStore {
+    db_pool: PgPool,
+    rpc_client: Arc<RpcClient>,
+    metadata_program_id: Pubkey,
+    require_metadata: bool,
+}
+
+impl TokenMetadataStore {
+    /// Create a store that fetches missing or stale tokens through `rpc_client`
+    ///
+    /// `require_metadata` is passed on to `fetch_token_info`, failing tokens whose
+    /// Metaplex metadata can't be resolved instead of storing a placeholder symbol.
+    pub fn new(
+        db_pool: PgPool,
+        rpc_client: Arc<RpcClient>,
+        metadata_program_id: Pubkey,
+        require_metadata: bool
+    ) -> Self {
+        Self {
+            db_pool,
+            rpc_client,
+            metadata_program_id,
+            require_metadata,
+        }
+    }
+
+    /// Get a stored token and when it was last fetched
+    pub async fn get_token(&self, mint: &Pubkey) -> Result<Option<(TokenInfo, DateTime<Utc>)>> {
+        let row: Option<TokenMetadataRow> = sqlx
+            ::query_as(
+                "SELECT token_name, symbol, decimals, last_updated
+                 FROM apestrong.token_metadata WHERE mint = $1"
+            )
+            .bind(mint.to_string())
+            .fetch_optional(&self.db_pool).await
+            .with_context(|| format!("Failed to get token metadata for {}", mint))?;
+
+        Ok(
+            row.map(|(name, symbol, decimals, last_updated)| {
+                let info = TokenInfo {
+                    mint: *mint,
+                    decimals: decimals as u8,
+                    symbol: symbol.unwrap_or_default(),
+                    name: name.unwrap_or_default(),
+                };
+                (info, last_updated)
+            })
+        )
+    }
+
+    /// Store a token fetched just now, marking it fresh
+    pub async fn save_token(&self, info: &TokenInfo) -> Result<()> {
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.token_metadata (mint, token_name, symbol, decimals, last_updated)
+                 VALUES ($1, $2, $3, $4, NOW())
+                 ON CONFLICT (mint) DO UPDATE
+                 SET token_name = $2, symbol = $3, decimals = $4, last_updated = NOW()"
+            )
+            .bind(info.mint.to_string())
+            .bind(&info.name)
+            .bind(&info.symbol)
+            .bind(info.decimals as i32)
+            .execute(&self.db_pool).await
+            .with_context(|| format!("Failed to save token metadata for {}", info.mint))?;
+
+        Ok(())
+    }
+
+    /// Get a token, fetching it from chain only if it isn't stored or was fetched more than `ttl` ago
+    pub async fn refresh_token_metadata(&self, mint: &Pubkey, ttl: Duration) -> Result<TokenInfo> {
+        if let Some((info, last_updated)) = self.get_token(mint).await? {
+            let age = (Utc::now() - last_updated).to_std().unwrap_or_default();
+            if age < ttl {
+                log::debug!("Token metadata for {} is fresh ({}s old)", mint, age.as_secs());
+                return Ok(info);
+            }
+            log::debug!("Token metadata for {} is stale ({}s old), re-fetching", mint, age.as_secs());
+        }
+
+        let info = fetch_token_info(
+            &self.rpc_client,
+            mint,
+            &self.metadata_program_id,
+            self.require_metadata
+        ).await?;
+        self.save_token(&info).await?;
+        Ok(info)
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::{ Context, Result };
+use sqlx::types::Json;
+use sqlx::{ PgPool, Row };
+
+use crate::db::common::normalize_signature;
+
+/// Stores the raw log messages of transactions that produced indexed events
+///
+/// Logs are keyed by signature, so a transaction with several events is stored once.
+#[derive(Clone)]
+pub struct TransactionLogStore {
+    db_pool: PgPool,
+}
+
+impl TransactionLogStore {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Save a transaction's log messages
+    ///
+    /// Returns false when the transaction's logs were already stored.
+    pub async fn save_logs(
+        &self,
+        signature: &str,
+        dex_type: &str,
+        slot: Option<u64>,
+        logs: &[String]
+    ) -> Result<bool> {
+        let signature = normalize_signature(signature)?;
+        let result = sqlx
+            ::query(
+                r#"
+            INSERT INTO apestrong.transaction_logs (signature, dex, slot, logs)
+            VALUES ($1, $2::apestrong.dex_type, $3, $4)
+            ON CONFLICT (signature) DO NOTHING
+            "#
+            )
+            .bind(&signature)
+            .bind(dex_type)
+            .bind(slot.map(|slot| slot as i64))
+            .bind(Json(logs))
+            .execute(&self.db_pool).await
+            .with_context(|| format!("Failed to save logs for transaction {}", signature))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a transaction's stored log messages
+    pub async fn get_logs(&self, signature: &str) -> Result<Option<Vec<String>>> {
+        let row = sqlx
+            ::query("SELECT logs FROM apestrong.transaction_logs WHERE signature = $1")
+            .bind(signature)
+            .fetch_optional(&self.db_pool).await
+            .with_context(|| format!("Failed to get logs for transaction {}", signature))?;
+
+        match row {
+            Some(row) => {
+                let Json(logs): Json<Vec<String>> = row.try_get("logs")?;
+                Ok(Some(logs))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use std::path::{ Path, PathBuf };
+use tokio::io::AsyncWriteExt;
+
+/// Default file that events are dead-lettered to
+pub const DEFAULT_DEAD_LETTER_PATH: &str = "dead_letter_events.jsonl";
+
+/// An event that could not be persisted after all retries
+///
+/// The transaction logs and the event's index within them are kept so the
+/// event can be reprocessed by parsing the logs again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetterEntry {
+    pub failed_at: DateTime<Utc>,
+    pub dex: String,
+    pub signature: String,
+    pub slot: Option<u64>,
+    /// Unix time of the transaction's block, when known
+    pub block_time: Option<i64>,
+    /// Position of the event among the events of `logs`, see `DexIndexer::parse_indexed_log_events`
+    pub event_index: usize,
+    pub is_backfill: bool,
+    /// Account that paid the transaction's fees, when known
+    #[serde(default)]
+    pub fee_payer: Option<String>,
+    pub attempts: u32,
+    pub error: String,
+    /// Debug representation of the parsed event, for inspection
+    pub event: String,
+    pub logs: Vec<String>,
+}
+
+/// Append-only dead-letter queue stored as one JSON entry per line
+///
+/// A file is used rather than a table so events are still kept when the
+/// database itself is the reason inserts fail.
+#[derive(Debug, Clone)]
+pub struct DeadLetterQueue {
+    path: PathBuf,
+}
+
+impl DeadLetterQueue {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the dead-letter file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry to the queue
+    pub async fn push(&self, entry: &DeadLetterEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry).context("Failed to serialize dead-letter entry")?;
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions
+            ::new()
+            .create(true)
+            .append(true)
+            .open(&self.path).await
+            .with_context(|| format!("Failed to open dead-letter file {}", self.path.display()))?;
+        file
+            .write_all(line.as_bytes()).await
+            .with_context(|| format!("Failed to write dead-letter file {}", self.path.display()))?;
+
+        Ok(())
+    }
+
+    /// Read all entries, e.g. to reprocess them
+    pub async fn read_entries(&self) -> Result<Vec<DeadLetterEntry>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Vec::new());
+            }
+            Err(e) => {
+                return Err(e).with_context(||
+                    format!("Failed to read dead-letter file {}", self.path.display())
+                );
+            }
+        };
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).context("Invalid dead-letter entry"))
+            .collect()
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{ Map, Value };
+use std::str::FromStr;
+
+use crate::events::IndexedEvent;
+use crate::metrics;
+
+/// Derived fields attached to an event, stored with it as a JSON object
+pub type Enrichment = Map<String, Value>;
+
+/// Hook that derives extra fields for an event before it is persisted
+///
+/// Used for values that aren't part of the on-chain event, such as a USD value
+/// looked up from an external price source. The fields are stored in the event's
+/// `enrichment` column; an empty result stores nothing.
+#[async_trait]
+pub trait EventEnricher: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    /// Derive the extra fields for an event
+    async fn enrich(&self, event: &IndexedEvent) -> Result<Enrichment>;
+}
+
+/// Enricher that adds no fields, used when none is configured
+pub struct IdentityEnricher;
+
+#[async_trait]
+impl EventEnricher for IdentityEnricher {
+    fn name(&self) -> &str {
+        "identity"
+    }
+
+    async fn enrich(&self, _event: &IndexedEvent) -> Result<Enrichment> {
+        Ok(Enrichment::new())
+    }
+}
+
+/// What to do with an event when its enricher fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnrichmentFailurePolicy {
+    /// Drop the event: it is neither stored nor published
+    Skip,
+    /// Store and publish the event without the derived fields
+    #[default]
+    StoreRaw,
+}
+
+impl FromStr for EnrichmentFailurePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(EnrichmentFailurePolicy::Skip),
+            "store-raw" | "store_raw" => Ok(EnrichmentFailurePolicy::StoreRaw),
+            _ =>
+                Err(
+                    format!("Unknown enrichment failure policy: {}. Supported values: skip, store-raw", s)
+                ),
+        }
+    }
+}
+
+/// Run an enricher over an event, applying the failure policy
+///
+/// Returns `None` when the event should be skipped, otherwise the fields to
+/// store (`Some(None)` when there are none).
+pub async fn enrich_event(
+    enricher: &dyn EventEnricher,
+    policy: EnrichmentFailurePolicy,
+    dex: &str,
+    event: &IndexedEvent
+) -> Option<Option<Value>> {
+    match enricher.enrich(event).await {
+        Ok(fields) if fields.is_empty() => Some(None),
+        Ok(fields) => Some(Some(Value::Object(fields))),
+        Err(e) => {
+            metrics::record_enrichment_failure(dex);
+            match policy {
+                EnrichmentFailurePolicy::Skip => {
+                    log::warn!(
+                        "[{}] Enricher {} failed for {}, skipping event: {:#}",
+                        dex,
+                        enricher.name(),
+                        event.source().signature,
+                        e
+                    );
+                    None
+                }
+                EnrichmentFailurePolicy::StoreRaw => {
+                    log::warn!(
+                        "[{}] Enricher {} failed for {}, storing event without enrichment: {:#}",
+                        dex,
+                        enricher.name(),
+                        event.source().signature,
+                        e
+                    );
+                    Some(None)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use reqwest::StatusCode;
+use solana_client::client_error::{ ClientError, ClientErrorKind };
+use solana_client::rpc_request::RpcError;
+use thiserror::Error;
+
+use crate::rpc::is_transient_client_error;
+
+/// JSON-RPC error code some providers answer with instead of an HTTP 429
+const RPC_RATE_LIMIT_CODE: i64 = 429;
+
+/// Errors from the indexer's RPC, parsing and storage paths
+///
+/// The variant says how a caller should react, so retry and skip decisions match
+/// on it rather than on error messages. These errors usually travel inside an
+/// `anyhow::Error` with added context; `IndexerError::find` looks through it.
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    /// The RPC node rejected the request for exceeding its rate limit
+    #[error("Rate limited by the RPC node: {0}")]
+    RateLimited(String),
+    /// The RPC node doesn't know the transaction
+    #[error("Transaction {0} not found")]
+    TransactionNotFound(String),
+    /// A timeout, dropped connection or server error that may clear up on retry
+    #[error("{0}")]
+    Transient(String),
+    /// A failure that retrying won't fix, such as a rejected request
+    #[error("{0}")]
+    Permanent(String),
+    /// Data that doesn't decode as expected
+    #[error("Failed to deserialize {0}")]
+    Deserialize(String),
+    /// A database query failed
+    #[error("Database query failed")]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<ClientError> for IndexerError {
+    fn from(err: ClientError) -> Self {
+        let message = err.to_string();
+        match err.kind() {
+            ClientErrorKind::Reqwest(e) if e.status() == Some(StatusCode::TOO_MANY_REQUESTS) => {
+                IndexerError::RateLimited(message)
+            }
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) if
+                *code == RPC_RATE_LIMIT_CODE
+            => {
+                IndexerError::RateLimited(message)
+            }
+            ClientErrorKind::SerdeJson(_) => IndexerError::Deserialize(message),
+            _ if is_transient_client_error(&err) => IndexerError::Transient(message),
+            _ => IndexerError::Permanent(message),
+        }
+    }
+}
+
+impl IndexerError {
+    /// Find the `IndexerError` behind an error, looking through added context
+    pub fn find(err: &anyhow::Error) -> Option<&IndexerError> {
+        err.chain().find_map(|cause| cause.downcast_ref::<IndexerError>())
+    }
+
+    /// Whether the operation may succeed if retried
+    pub fn is_transient(&self) -> bool {
+        matches!(self, IndexerError::RateLimited(_) | IndexerError::Transient(_))
+    }
+
+    /// Short name of the variant, used as the `kind` label of the RPC error metric
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IndexerError::RateLimited(_) => "rate_limit",
+            IndexerError::TransactionNotFound(_) => "not_found",
+            IndexerError::Transient(_) => "transient",
+            IndexerError::Permanent(_) => "permanent",
+            IndexerError::Deserialize(_) => "deserialize",
+            IndexerError::Database(_) => "database",
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{ Serialize, Serializer };
+use solana_sdk::pubkey::Pubkey;
+
+use crate::models::common::Protocol;
+
+/// Default number of events buffered for each event stream subscriber
+pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Where an event was found and whether it came from backfill
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventSource {
+    #[serde(serialize_with = "serialize_display")]
+    pub pool: Pubkey,
+    pub signature: String,
+    pub slot: Option<u64>,
+    /// Unix time of the transaction's block, when known
+    pub block_time: Option<i64>,
+    pub is_backfill: bool,
+}
+
+/// A swap in an Orca Whirlpool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrcaTrade {
+    #[serde(flatten)]
+    pub source: EventSource,
+    pub a_to_b: bool,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    #[serde(serialize_with = "serialize_display")]
+    pub pre_sqrt_price: u128,
+    #[serde(serialize_with = "serialize_display")]
+    pub post_sqrt_price: u128,
+}
+
+/// Liquidity added to or removed from an Orca Whirlpool position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrcaLiquidityChange {
+    #[serde(flatten)]
+    pub source: EventSource,
+    #[serde(serialize_with = "serialize_display")]
+    pub position: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    #[serde(serialize_with = "serialize_display")]
+    pub liquidity: u128,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub token_a_transfer_fee: u64,
+    pub token_b_transfer_fee: u64,
+}
+
+/// Creation of an Orca Whirlpool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrcaPoolInitialization {
+    #[serde(flatten)]
+    pub source: EventSource,
+    #[serde(serialize_with = "serialize_display")]
+    pub whirlpools_config: Pubkey,
+    #[serde(serialize_with = "serialize_display")]
+    pub token_mint_a: Pubkey,
+    #[serde(serialize_with = "serialize_display")]
+    pub token_mint_b: Pubkey,
+    pub tick_spacing: u16,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    #[serde(serialize_with = "serialize_display")]
+    pub initial_sqrt_price: u128,
+}
+
+/// A parsed event published to event stream subscribers
+///
+/// Published once the event has been persisted (or, in dry-run mode, instead
+/// of persisting it), so embedders can consume events without reading them
+/// back from Postgres.
+///
+/// Serializes to a flat JSON object tagged by `event` (e.g. `"orca_traded"`).
+/// Addresses are base58 strings and u128 values decimal strings, so they
+/// survive JSON parsers that read numbers as doubles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum IndexedEvent {
+    OrcaTraded(OrcaTrade),
+    OrcaLiquidityIncreased(OrcaLiquidityChange),
+    OrcaLiquidityDecreased(OrcaLiquidityChange),
+    OrcaPoolInitialized(OrcaPoolInitialization),
+}
+
+impl IndexedEvent {
+    /// Pool, transaction and slot the event came from
+    pub fn source(&self) -> &EventSource {
+        match self {
+            IndexedEvent::OrcaTraded(trade) => &trade.source,
+            IndexedEvent::OrcaLiquidityIncreased(change) => &change.source,
+            IndexedEvent::OrcaLiquidityDecreased(change) => &change.source,
+            IndexedEvent::OrcaPoolInitialized(initialization) => &initialization.source,
+        }
+    }
+
+    /// DEX identifier, matching the dex column
+    pub fn dex(&self) -> &'static str {
+        match self {
+            IndexedEvent::OrcaTraded(_) |
+            IndexedEvent::OrcaLiquidityIncreased(_) |
+            IndexedEvent::OrcaLiquidityDecreased(_) |
+            IndexedEvent::OrcaPoolInitialized(_) => Protocol::OrcaWhirlpool.dex(),
+        }
+    }
+
+    /// Event name, matching the event_type column
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            IndexedEvent::OrcaTraded(_) => "Traded",
+            IndexedEvent::OrcaLiquidityIncreased(_) => "LiquidityIncreased",
+            IndexedEvent::OrcaLiquidityDecreased(_) => "LiquidityDecreased",
+            IndexedEvent::OrcaPoolInitialized(_) => "PoolInitialized",
+        }
+    }
+}
+
+/// A Whirlpool's price and liquidity, read from its account after it changed
+///
+/// Published by pool state tracking rather than parsed from logs, so it has no
+/// transaction; `slot` is the slot the account change was observed in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PoolStateUpdate {
+    #[serde(serialize_with = "serialize_display")]
+    pub pool: Pubkey,
+    pub slot: u64,
+    #[serde(serialize_with = "serialize_display")]
+    pub sqrt_price: u128,
+    #[serde(serialize_with = "serialize_display")]
+    pub liquidity: u128,
+    pub tick_current_index: i32,
+    pub fee_rate: u16,
+    /// Emission rates of the pool's initialized rewards, for APRs
+    pub reward_emissions: Vec<RewardEmission>,
+}
+
+/// Emission rate of one of a Whirlpool's rewards, from its account's `reward_infos`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RewardEmission {
+    /// Position of the reward in the pool (0 to 2)
+    pub reward_index: u8,
+    #[serde(serialize_with = "serialize_display")]
+    pub reward_mint: Pubkey,
+    /// Reward tokens emitted per second, as a Q64.64 fixed-point number of base units
+    #[serde(serialize_with = "serialize_display")]
+    pub emissions_per_second_x64: u128,
+}
+
+// Serialize a value through its Display impl (base58 addresses, decimal u128s)
+fn serialize_display<T: std::fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
@@ -0,0 +1,265 @@
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, SecondsFormat, Utc };
+use flate2::write::GzEncoder;
+use sqlx::{ FromRow, PgPool };
+use std::io::{ self, Write };
+use std::str::FromStr;
+
+use crate::db::common::from_numeric;
+use crate::db::repositories::OrcaWhirlpoolRepository;
+use crate::utils::price::ui_amount;
+
+/// Rows fetched from the database per query during an export
+pub const DEFAULT_EXPORT_BATCH_SIZE: i64 = 1_000;
+
+/// Columns of the trade export, in order
+pub const TRADE_EXPORT_COLUMNS: [&str; 15] = [
+    "signature",
+    "slot",
+    "block_time",
+    "indexed_at",
+    "whirlpool",
+    "a_to_b",
+    "input_amount",
+    "output_amount",
+    "input_amount_ui",
+    "output_amount_ui",
+    "input_transfer_fee",
+    "output_transfer_fee",
+    "lp_fee",
+    "protocol_fee",
+    "price",
+];
+
+/// Compression applied to export output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportCompression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl FromStr for ExportCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(ExportCompression::None),
+            "gzip" => Ok(ExportCompression::Gzip),
+            "zstd" => Ok(ExportCompression::Zstd),
+            other =>
+                Err(format!("Unknown compression '{}', expected 'gzip', 'zstd' or 'none'", other)),
+        }
+    }
+}
+
+/// Writer compressing everything written to it before passing it on
+///
+/// Data is compressed as it is written, so only the encoder's small internal
+/// buffer is held in memory. Call `finish` once done; dropping the writer
+/// instead can leave the compressed stream truncated.
+pub enum CompressedWriter<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    pub fn new(writer: W, compression: ExportCompression) -> io::Result<Self> {
+        Ok(match compression {
+            ExportCompression::None => CompressedWriter::Plain(writer),
+            ExportCompression::Gzip =>
+                CompressedWriter::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+            ExportCompression::Zstd =>
+                CompressedWriter::Zstd(zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?),
+        })
+    }
+
+    /// Write the end of the compressed stream and return the inner writer
+    pub fn finish(self) -> io::Result<W> {
+        let mut writer = match self {
+            CompressedWriter::Plain(writer) => writer,
+            CompressedWriter::Gzip(encoder) => encoder.finish()?,
+            CompressedWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(writer) => writer.write(buf),
+            CompressedWriter::Gzip(encoder) => encoder.write(buf),
+            CompressedWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedWriter::Plain(writer) => writer.flush(),
+            CompressedWriter::Gzip(encoder) => encoder.flush(),
+            CompressedWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Which Orca trades to export
+///
+/// Trades are selected by block time when known, otherwise by when they were
+/// indexed, from `from` (inclusive) to `to` (exclusive).
+#[derive(Debug, Clone)]
+pub struct TradeExport {
+    pub pool: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub batch_size: i64,
+}
+
+impl TradeExport {
+    /// Export all trades of a pool
+    pub fn new(pool: String) -> Self {
+        Self {
+            pool,
+            from: None,
+            to: None,
+            batch_size: DEFAULT_EXPORT_BATCH_SIZE,
+        }
+    }
+
+    /// Only export trades in the given time range
+    pub fn with_range(mut self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Set how many rows are fetched per query
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct TradeRow {
+    id: i32,
+    signature: String,
+    slot: Option<i64>,
+    block_time: Option<DateTime<Utc>>,
+    timestamp: DateTime<Utc>,
+    a_to_b: bool,
+    // u64 columns, selected as text since sqlx can't decode NUMERIC here
+    input_amount: String,
+    output_amount: String,
+    input_transfer_fee: String,
+    output_transfer_fee: String,
+    lp_fee: String,
+    protocol_fee: String,
+    price: Option<f64>,
+}
+
+/// Write a pool's Orca trades as CSV, returning the number of rows written
+///
+/// Rows are read in batches ordered by event id, each batch resuming after the
+/// last id of the previous one, so memory use doesn't grow with the export size.
+/// Trades come out in the order they were indexed. The `_ui` amounts are scaled
+/// by the pool's token decimals from `token_metadata`, and left empty when the
+/// decimals aren't known.
+pub async fn export_orca_trades_csv<W: Write>(
+    db_pool: &PgPool,
+    export: &TradeExport,
+    writer: &mut W
+) -> Result<u64> {
+    let decimals = OrcaWhirlpoolRepository::new(db_pool.clone()).get_token_decimals(
+        &export.pool
+    ).await?;
+
+    writeln!(writer, "{}", TRADE_EXPORT_COLUMNS.join(",")).context("Failed to write CSV header")?;
+
+    let mut last_id = 0;
+    let mut rows_written = 0;
+    loop {
+        let rows: Vec<TradeRow> = sqlx
+            ::query_as(
+                "SELECT e.id, e.signature, e.slot, e.block_time, e.timestamp,
+                        t.a_to_b, t.input_amount::TEXT, t.output_amount::TEXT,
+                        t.input_transfer_fee::TEXT, t.output_transfer_fee::TEXT,
+                        t.lp_fee::TEXT, t.protocol_fee::TEXT, t.price
+                 FROM apestrong.orca_whirlpool_events e
+                 JOIN apestrong.orca_traded_events t ON e.id = t.event_id
+                 WHERE e.whirlpool = $1 AND e.id > $2
+                   AND ($3::TIMESTAMPTZ IS NULL OR COALESCE(e.block_time, e.timestamp) >= $3)
+                   AND ($4::TIMESTAMPTZ IS NULL OR COALESCE(e.block_time, e.timestamp) < $4)
+                 ORDER BY e.id
+                 LIMIT $5"
+            )
+            .bind(&export.pool)
+            .bind(last_id)
+            .bind(export.from)
+            .bind(export.to)
+            .bind(export.batch_size)
+            .fetch_all(db_pool).await
+            .context("Failed to fetch Orca trades for export")?;
+
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        last_id = last_row.id;
+
+        for row in &rows {
+            write_trade_row(writer, &export.pool, row, decimals)?;
+        }
+        rows_written += rows.len() as u64;
+
+        if (rows.len() as i64) < export.batch_size {
+            break;
+        }
+    }
+
+    writer.flush().context("Failed to flush export output")?;
+    Ok(rows_written)
+}
+
+// Every field is a number, timestamp, boolean or base58 string, so none need quoting
+fn write_trade_row<W: Write>(
+    writer: &mut W,
+    pool: &str,
+    row: &TradeRow,
+    decimals: Option<(u8, u8)>
+) -> Result<()> {
+    let (input_decimals, output_decimals) = match decimals {
+        Some((decimals_a, decimals_b)) if row.a_to_b => (Some(decimals_a), Some(decimals_b)),
+        Some((decimals_a, decimals_b)) => (Some(decimals_b), Some(decimals_a)),
+        None => (None, None),
+    };
+    let scaled = |amount: &str, decimals: Option<u8>| -> Result<String> {
+        match decimals {
+            Some(decimals) => Ok(ui_amount(from_numeric(amount)?, decimals).to_string()),
+            None => Ok(String::new()),
+        }
+    };
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    writeln!(
+        writer,
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        row.signature,
+        optional(row.slot.map(|slot| slot.to_string())),
+        optional(row.block_time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))),
+        row.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        pool,
+        row.a_to_b,
+        row.input_amount,
+        row.output_amount,
+        scaled(&row.input_amount, input_decimals)?,
+        scaled(&row.output_amount, output_decimals)?,
+        row.input_transfer_fee,
+        row.output_transfer_fee,
+        row.lp_fee,
+        row.protocol_fee,
+        optional(row.price.map(|price| price.to_string()))
+    ).context("Failed to write CSV row")
+}
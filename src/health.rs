@@ -0,0 +1,143 @@
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use hyper::service::{ make_service_fn, service_fn };
+use hyper::{ Body, Method, Request, Response, Server, StatusCode };
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{ AtomicBool, AtomicU64, Ordering };
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+use crate::utils::logging;
+
+/// Default time without WebSocket messages after which the connection is considered dead
+pub const DEFAULT_HEALTH_STALE_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// Snapshot of a running indexer's liveness
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexerHealth {
+    /// When the last event was handled
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// When the last initial or scheduled backfill finished
+    pub last_backfill_at: Option<DateTime<Utc>>,
+    /// Whether the WebSocket subscription is currently connected
+    pub websocket_connected: bool,
+    /// Seconds since the last WebSocket message, if one was ever received
+    pub seconds_since_last_message: Option<u64>,
+    /// Events handled since the indexer started, live and backfilled
+    pub events_processed_total: u64,
+}
+
+/// Liveness state shared between an indexer, its WebSocket manager and the health server
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    last_event_at: Mutex<Option<DateTime<Utc>>>,
+    last_backfill_at: Mutex<Option<DateTime<Utc>>>,
+    last_message_at: Mutex<Option<Instant>>,
+    websocket_connected: AtomicBool,
+    events_processed: AtomicU64,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an event handled by the indexer
+    pub fn record_event(&self) {
+        self.events_processed.fetch_add(1, Ordering::Relaxed);
+        *self.last_event_at.lock().unwrap() = Some(Utc::now());
+    }
+
+    /// Record a finished backfill
+    pub fn record_backfill(&self) {
+        *self.last_backfill_at.lock().unwrap() = Some(Utc::now());
+    }
+
+    /// Record a message received on the WebSocket subscription
+    pub fn record_message(&self) {
+        *self.last_message_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Record the WebSocket connection state
+    pub fn set_websocket_connected(&self, connected: bool) {
+        self.websocket_connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// Time since the last WebSocket message
+    pub fn time_since_last_message(&self) -> Option<Duration> {
+        self.last_message_at.lock().unwrap().map(|instant| instant.elapsed())
+    }
+
+    /// Whether the connection is likely dead (never, before the first message)
+    pub fn is_connection_dead(&self, threshold: Duration) -> bool {
+        match self.time_since_last_message() {
+            Some(elapsed) => elapsed > threshold,
+            None => false,
+        }
+    }
+
+    /// Current liveness snapshot
+    pub fn snapshot(&self) -> IndexerHealth {
+        IndexerHealth {
+            last_event_at: *self.last_event_at.lock().unwrap(),
+            last_backfill_at: *self.last_backfill_at.lock().unwrap(),
+            websocket_connected: self.websocket_connected.load(Ordering::Relaxed),
+            seconds_since_last_message: self.time_since_last_message().map(|elapsed| elapsed.as_secs()),
+            events_processed_total: self.events_processed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+//
+// HTTP SERVER
+//
+
+// Answer /healthz with the health snapshot, as 503 once the connection is dead
+fn health_response(tracker: &HealthTracker, threshold: Duration) -> Response<Body> {
+    let status = if tracker.is_connection_dead(threshold) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    let body = serde_json::to_string(&tracker.snapshot()).unwrap_or_default();
+
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .unwrap_or_else(|_| Response::new(Body::empty()))
+}
+
+/// Serve `/healthz` for an indexer on the given address until the process exits
+///
+/// Responds 200 with the health snapshot as JSON, or 503 when no WebSocket
+/// message has been received for longer than `threshold`.
+pub async fn serve(addr: SocketAddr, tracker: Arc<HealthTracker>, threshold: Duration) -> Result<()> {
+    let make_service = make_service_fn(move |_conn| {
+        let tracker = tracker.clone();
+        async move {
+            Ok::<_, Infallible>(
+                service_fn(move |req: Request<Body>| {
+                    let response = match (req.method(), req.uri().path()) {
+                        (&Method::GET, "/healthz") => health_response(&tracker, threshold),
+                        _ =>
+                            Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::from("Not Found"))
+                                .unwrap_or_else(|_| Response::new(Body::empty())),
+                    };
+                    async move { Ok::<_, Infallible>(response) }
+                })
+            )
+        }
+    });
+
+    let server = Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind health server to {}", addr))?
+        .serve(make_service);
+
+    logging::log_activity("health", "Server started", Some(&format!("listening on {}", addr)));
+    server.await.context("Health server failed")
+}
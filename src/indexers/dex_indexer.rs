@@ -1,46 +1,506 @@
-use anyhow::Result;
+use anyhow::{ Context, Result };
 use solana_client::rpc_config::RpcTransactionLogsFilter;
-use solana_client::rpc_response::RpcLogsResponse;
+use solana_client::rpc_response::{ Response, RpcLogsResponse };
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
 use sqlx::PgPool;
-use std::collections::HashSet;
+use std::collections::{ HashMap, HashSet };
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{ AtomicBool, Ordering };
 use std::time::Duration;
 use tokio::sync::{ Mutex, mpsc::Receiver };
 use tokio::task::JoinHandle;
-use tokio::time::interval;
+use tokio::time::{ interval, interval_at, Instant };
 use tokio::select;
 use base64::engine::general_purpose;
 use base64::Engine;
 use async_trait::async_trait;
+use futures::stream::{ self, StreamExt };
+use futures::FutureExt;
+
+use crate::backfill_manager::{
+    BackfillConfig,
+    BackfillManager,
+    BackfillOrder,
+    BackfillWindow,
+    RetryConfig,
+    DEFAULT_FETCH_CONCURRENCY,
+    DEFAULT_POOL_CONCURRENCY,
+    DEFAULT_SIGNATURE_READ_BATCH_SIZE,
+    REORG_ROLLBACK_SLOTS,
+};
+use crate::confirmation_buffer::{ self, ConfirmationBuffer };
+use crate::dead_letter::{ DeadLetterEntry, DeadLetterQueue, DEFAULT_DEAD_LETTER_PATH };
+use crate::events::DEFAULT_EVENT_CHANNEL_CAPACITY;
+use crate::health::{ HealthTracker, IndexerHealth };
+use crate::db::signature_store::{ BackfillProgress, SignatureStore, SignatureStoreType };
+use crate::db::transaction_logs::TransactionLogStore;
+use crate::db::{ is_duplicate_error, is_reachable, is_unreachable_error, Repository };
+use crate::error::IndexerError;
+use crate::metrics;
+use crate::outage_buffer::{ HeldEvent, OutageBuffer, DEFAULT_OUTAGE_BUFFER_CAPACITY };
+use crate::program_ids::{ ProgramIds, DEFAULT_ORCA_POOL };
+use crate::rpc::DEFAULT_RPC_TIMEOUT;
+use crate::staleness::{ StalenessConfig, StalenessDetector };
+use crate::utils::cpi_events::{ cpi_event_data, fee_payer, with_cpi_event_lines };
+use crate::validation::ValidationMode;
+use crate::websocket_manager::{
+    ChannelOverflowPolicy,
+    LogSubscription,
+    WebSocketManager,
+    WebSocketConfig,
+    DEFAULT_WS_CHANNEL_CAPACITY,
+};
+
+/// Context of the fetched transaction an event was parsed from
+#[derive(Debug, Clone)]
+pub struct EventContext {
+    /// Slot the transaction landed in
+    pub slot: u64,
+    /// Unix time of the transaction's block, when the RPC node knows it
+    pub block_time: Option<i64>,
+    /// Whether the transaction succeeded on-chain
+    pub success: bool,
+    /// The transaction's logs, with its CPI events, that the event was parsed from
+    pub log: Arc<RpcLogsResponse>,
+    /// Position of the event among the transaction's events, see `parse_indexed_log_events`
+    pub event_index: usize,
+    /// Account that paid the transaction's fees
+    pub fee_payer: Option<Pubkey>,
+}
+
+/// Order in which the indexer subscribes to live events and runs its initial backfill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartupStrategy {
+    /// Subscribe first and buffer live events, backfill, then process the buffer
+    #[default]
+    Buffer,
+    /// Subscribe and process live events right away while the backfill runs alongside
+    Background,
+    /// Finish the backfill before subscribing, which can miss events that land in between
+    BackfillFirst,
+}
 
-use crate::backfill_manager::{ BackfillConfig, BackfillManager };
-use crate::db::signature_store::{ SignatureStore, SignatureStoreType };
-use crate::db::Repository;
-use crate::websocket_manager::{ WebSocketManager, WebSocketConfig };
+impl FromStr for StartupStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "buffer" => Ok(StartupStrategy::Buffer),
+            "background" => Ok(StartupStrategy::Background),
+            "backfill-first" => Ok(StartupStrategy::BackfillFirst),
+            other =>
+                Err(
+                    format!(
+                        "Unknown startup strategy '{}', expected 'buffer', 'background' or 'backfill-first'",
+                        other
+                    )
+                ),
+        }
+    }
+}
 
 // Connection configuration for RPC and WebSocket URLs
 #[derive(Clone)]
 pub struct ConnectionConfig {
     pub rpc_url: String,
+    pub rpc_headers: HashMap<String, String>,
     pub ws_url: String,
+    pub ws_fallback_urls: Vec<String>,
+    pub ws_channel_capacity: usize,
+    pub ws_overflow_policy: ChannelOverflowPolicy,
+    pub ws_subscription_per_program: bool,
+    pub program_commitments: HashMap<String, CommitmentConfig>,
+    pub pool_state_updates: bool,
+    pub confirmation_delay_slots: u64,
+    pub program_ids: ProgramIds,
+    pub default_orca_pool: String,
+    pub dry_run: bool,
+    pub strict_pool_addresses: bool,
+    pub pool_token_mint: Option<Pubkey>,
+    pub commitment: CommitmentConfig,
+    pub rpc_timeout: Duration,
+    pub persist_retry: RetryConfig,
+    pub dead_letter_path: PathBuf,
+    pub outage_buffer_capacity: usize,
+    pub backfill_order: BackfillOrder,
+    pub startup_strategy: StartupStrategy,
+    pub backfill_pool_cap: Option<usize>,
+    pub backfill_max_requests_per_second: Option<u32>,
+    pub backfill_fetch_concurrency: usize,
+    pub backfill_pool_concurrency: usize,
+    pub signature_read_batch_size: usize,
+    pub event_channel_capacity: usize,
+    pub store_transaction_logs: bool,
+    pub staleness: StalenessConfig,
+    pub max_plausible_trade_amount: Option<f64>,
+    pub event_validation: ValidationMode,
+    pub min_input_amount: Option<u64>,
+    pub pool_min_input_amounts: HashMap<String, u64>,
+    pub backfill_interval: Duration,
+    pub reconciliation_interval: Option<Duration>,
+    pub reconciliation_window: Duration,
 }
 
 impl ConnectionConfig {
     pub fn new(rpc_url: String, ws_url: String) -> Self {
-        Self { rpc_url, ws_url }
+        Self {
+            rpc_url,
+            rpc_headers: HashMap::new(),
+            ws_url,
+            ws_fallback_urls: Vec::new(),
+            ws_channel_capacity: DEFAULT_WS_CHANNEL_CAPACITY,
+            ws_overflow_policy: ChannelOverflowPolicy::default(),
+            ws_subscription_per_program: false,
+            program_commitments: HashMap::new(),
+            pool_state_updates: false,
+            confirmation_delay_slots: 0,
+            program_ids: ProgramIds::default(),
+            default_orca_pool: DEFAULT_ORCA_POOL.to_string(),
+            dry_run: false,
+            strict_pool_addresses: false,
+            pool_token_mint: None,
+            commitment: CommitmentConfig::confirmed(),
+            rpc_timeout: DEFAULT_RPC_TIMEOUT,
+            persist_retry: RetryConfig {
+                max_attempts: 3,
+                base_delay_ms: 200,
+                max_delay_ms: 2_000,
+                jitter_ratio: 0.2,
+            },
+            dead_letter_path: PathBuf::from(DEFAULT_DEAD_LETTER_PATH),
+            outage_buffer_capacity: DEFAULT_OUTAGE_BUFFER_CAPACITY,
+            backfill_order: BackfillOrder::default(),
+            startup_strategy: StartupStrategy::default(),
+            backfill_pool_cap: None,
+            backfill_max_requests_per_second: None,
+            backfill_fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            backfill_pool_concurrency: DEFAULT_POOL_CONCURRENCY,
+            signature_read_batch_size: DEFAULT_SIGNATURE_READ_BATCH_SIZE,
+            event_channel_capacity: DEFAULT_EVENT_CHANNEL_CAPACITY,
+            store_transaction_logs: false,
+            staleness: StalenessConfig::default(),
+            max_plausible_trade_amount: None,
+            event_validation: ValidationMode::default(),
+            min_input_amount: None,
+            pool_min_input_amounts: HashMap::new(),
+            backfill_interval: DEFAULT_BACKFILL_INTERVAL,
+            reconciliation_interval: None,
+            reconciliation_window: DEFAULT_RECONCILIATION_WINDOW,
+        }
+    }
+
+    /// Set extra HTTP headers sent with every RPC request, e.g. an `Authorization` API key
+    ///
+    /// These only apply to RPC calls. The WebSocket client takes just a URL, so
+    /// providers that need a key for subscriptions must accept it in the URL.
+    pub fn with_rpc_headers(mut self, rpc_headers: HashMap<String, String>) -> Self {
+        self.rpc_headers = rpc_headers;
+        self
+    }
+
+    /// Set the WebSocket URLs to fail over to when the primary connection fails
+    pub fn with_ws_fallback_urls(mut self, ws_fallback_urls: Vec<String>) -> Self {
+        self.ws_fallback_urls = ws_fallback_urls;
+        self
+    }
+
+    /// Set how many log notifications are buffered while earlier ones are processed,
+    /// and what happens to notifications once the buffer is full
+    pub fn with_ws_channel(
+        mut self,
+        ws_channel_capacity: usize,
+        ws_overflow_policy: ChannelOverflowPolicy
+    ) -> Self {
+        self.ws_channel_capacity = ws_channel_capacity;
+        self.ws_overflow_policy = ws_overflow_policy;
+        self
+    }
+
+    /// Subscribe to each monitored program's logs separately instead of in one subscription
+    ///
+    /// Each subscription has its own connection and reconnects on its own, so a flaky
+    /// one doesn't interrupt the others. `program_commitments` overrides the commitment
+    /// level of the programs it lists.
+    pub fn with_ws_subscription_per_program(
+        mut self,
+        ws_subscription_per_program: bool,
+        program_commitments: HashMap<String, CommitmentConfig>
+    ) -> Self {
+        self.ws_subscription_per_program = ws_subscription_per_program;
+        self.program_commitments = program_commitments;
+        self
+    }
+
+    /// Also subscribe to the monitored pools' accounts, publishing their price and
+    /// liquidity whenever they change
+    pub fn with_pool_state_updates(mut self, pool_state_updates: bool) -> Self {
+        self.pool_state_updates = pool_state_updates;
+        self
+    }
+
+    /// Hold live events until they are this many slots old before persisting them
+    pub fn with_confirmation_delay_slots(mut self, confirmation_delay_slots: u64) -> Self {
+        self.confirmation_delay_slots = confirmation_delay_slots;
+        self
+    }
+
+    /// Set the program IDs to subscribe to (e.g. local validator deployments)
+    pub fn with_program_ids(mut self, program_ids: ProgramIds) -> Self {
+        self.program_ids = program_ids;
+        self
+    }
+
+    /// Set the Orca pool indexed when no pools are given or subscribed
+    pub fn with_default_orca_pool(mut self, pool: impl Into<String>) -> Self {
+        self.default_orca_pool = pool.into();
+        self
+    }
+
+    /// Check that the program IDs and the default Orca pool are valid pubkeys
+    pub fn validate(&self) -> Result<()> {
+        self.program_ids.validate()?;
+        Pubkey::from_str(&self.default_orca_pool).with_context(||
+            format!("Invalid default Orca pool: {}", self.default_orca_pool)
+        )?;
+        Ok(())
+    }
+
+    /// Parse and log events without writing anything to the database
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Fail startup on any invalid provided pool address, instead of skipping it with a warning
+    pub fn with_strict_pool_addresses(mut self, strict_pool_addresses: bool) -> Self {
+        self.strict_pool_addresses = strict_pool_addresses;
+        self
+    }
+
+    /// Index only the subscribed pools trading this token, when no pools are given
+    pub fn with_pool_token_mint(mut self, pool_token_mint: Option<Pubkey>) -> Self {
+        self.pool_token_mint = pool_token_mint;
+        self
+    }
+
+    /// Set the commitment level for the WebSocket subscription and backfill RPC calls
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Set the timeout for each RPC request
+    pub fn with_rpc_timeout(mut self, rpc_timeout: Duration) -> Self {
+        self.rpc_timeout = rpc_timeout;
+        self
+    }
+
+    /// Set how often a failed event insert is retried before it is dead-lettered
+    pub fn with_persist_retry(mut self, persist_retry: RetryConfig) -> Self {
+        self.persist_retry = persist_retry;
+        self
+    }
+
+    /// Set the file that events are dead-lettered to
+    pub fn with_dead_letter_path(mut self, dead_letter_path: impl Into<PathBuf>) -> Self {
+        self.dead_letter_path = dead_letter_path.into();
+        self
+    }
+
+    /// Set how many events are held while the database is unreachable (0 dead-letters them)
+    pub fn with_outage_buffer_capacity(mut self, outage_buffer_capacity: usize) -> Self {
+        self.outage_buffer_capacity = outage_buffer_capacity;
+        self
+    }
+
+    /// Set whether the initial backfill processes the newest or oldest transactions first
+    pub fn with_backfill_order(mut self, backfill_order: BackfillOrder) -> Self {
+        self.backfill_order = backfill_order;
+        self
+    }
+
+    /// Set the order in which the indexer subscribes and runs its initial backfill
+    pub fn with_startup_strategy(mut self, startup_strategy: StartupStrategy) -> Self {
+        self.startup_strategy = startup_strategy;
+        self
+    }
+
+    /// Cap the transactions backfilled per pool in one cycle, deferring the rest to the next
+    pub fn with_backfill_pool_cap(mut self, backfill_pool_cap: Option<usize>) -> Self {
+        self.backfill_pool_cap = backfill_pool_cap;
+        self
+    }
+
+    /// Limit backfill RPC requests per second, e.g. to stay within a public RPC node's rate limit
+    pub fn with_backfill_max_requests_per_second(
+        mut self,
+        backfill_max_requests_per_second: Option<u32>
+    ) -> Self {
+        self.backfill_max_requests_per_second = backfill_max_requests_per_second;
+        self
+    }
+
+    /// Set how many backfill transactions are fetched at once
+    pub fn with_backfill_fetch_concurrency(mut self, backfill_fetch_concurrency: usize) -> Self {
+        self.backfill_fetch_concurrency = backfill_fetch_concurrency;
+        self
+    }
+
+    /// Set how many pools are backfilled at once during the initial backfill
+    pub fn with_backfill_pool_concurrency(mut self, backfill_pool_concurrency: usize) -> Self {
+        self.backfill_pool_concurrency = backfill_pool_concurrency;
+        self
+    }
+
+    /// Set how many pools' last signatures are read per query in a scheduled backfill
+    pub fn with_signature_read_batch_size(mut self, signature_read_batch_size: usize) -> Self {
+        self.signature_read_batch_size = signature_read_batch_size;
+        self
+    }
+
+    /// Warn about trades moving more than this many whole tokens, which usually means
+    /// the pool's token decimals are wrong
+    pub fn with_max_plausible_trade_amount(mut self, max_plausible_trade_amount: Option<f64>) -> Self {
+        self.max_plausible_trade_amount = max_plausible_trade_amount;
+        self
+    }
+
+    /// Check parsed events against the program's invariants before storing them,
+    /// quarantining the ones that fail; off by default
+    pub fn with_event_validation(mut self, event_validation: ValidationMode) -> Self {
+        self.event_validation = event_validation;
+        self
+    }
+
+    /// Drop trades whose input amount, in the input token's smallest units, is below
+    /// this; off by default
+    ///
+    /// Dropped trades are counted but never stored, so this loses data by design.
+    pub fn with_min_input_amount(mut self, min_input_amount: Option<u64>) -> Self {
+        self.min_input_amount = min_input_amount;
+        self
+    }
+
+    /// Set per-pool minimum trade input amounts, keyed by pool address, overriding
+    /// `min_input_amount` for those pools
+    pub fn with_pool_min_input_amounts(mut self, pool_min_input_amounts: HashMap<String, u64>) -> Self {
+        self.pool_min_input_amounts = pool_min_input_amounts;
+        self
+    }
+
+    /// Minimum trade input amount for a pool, if trades of it are filtered
+    pub fn min_input_amount_for(&self, pool: &str) -> Option<u64> {
+        self.pool_min_input_amounts.get(pool).copied().or(self.min_input_amount)
+    }
+
+    /// Set how many events each event stream subscriber can fall behind before skipping events
+    pub fn with_event_channel_capacity(mut self, event_channel_capacity: usize) -> Self {
+        self.event_channel_capacity = event_channel_capacity;
+        self
+    }
+
+    /// Store the raw log messages of every transaction that produced an indexed event
+    pub fn with_store_transaction_logs(mut self, store_transaction_logs: bool) -> Self {
+        self.store_transaction_logs = store_transaction_logs;
+        self
+    }
+
+    /// Set the bounds of the adaptive threshold after which a quiet stream triggers a backfill
+    pub fn with_staleness(mut self, staleness: StalenessConfig) -> Self {
+        self.staleness = staleness;
+        self
+    }
+
+    /// Set how often the scheduled backfill catches up on missed transactions
+    pub fn with_backfill_interval(mut self, backfill_interval: Duration) -> Self {
+        self.backfill_interval = backfill_interval;
+        self
+    }
+
+    /// Set how often the reconciliation pass re-checks recent history (disabled if not set)
+    pub fn with_reconciliation_interval(mut self, reconciliation_interval: Option<Duration>) -> Self {
+        self.reconciliation_interval = reconciliation_interval;
+        self
+    }
+
+    /// Set how far back the reconciliation pass looks
+    pub fn with_reconciliation_window(mut self, reconciliation_window: Duration) -> Self {
+        self.reconciliation_window = reconciliation_window;
+        self
+    }
+}
+
+/// Default time between scheduled backfills
+pub const DEFAULT_BACKFILL_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default time span re-checked by each reconciliation pass
+pub const DEFAULT_RECONCILIATION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Pools listed individually in startup and backfill logs; larger sets are summarized
+pub const MAX_LOGGED_POOLS: usize = 20;
+
+/// Pools between progress lines while backfilling a set too large to log per pool
+pub const BACKFILL_PROGRESS_INTERVAL: usize = 500;
+
+/// Parse a commitment level: processed, confirmed or finalized
+pub fn parse_commitment(value: &str) -> Result<CommitmentConfig> {
+    match value.to_ascii_lowercase().as_str() {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        other =>
+            Err(
+                anyhow::anyhow!(
+                    "Unknown commitment level '{}', expected 'processed', 'confirmed' or 'finalized'",
+                    other
+                )
+            ),
     }
 }
 
+/// Parse a program's commitment level override as 'PROGRAM_ID=LEVEL'
+pub fn parse_program_commitment(value: &str) -> Result<(String, CommitmentConfig)> {
+    let (program_id, level) = value
+        .split_once('=')
+        .ok_or_else(||
+            anyhow::anyhow!("Invalid program commitment '{}', expected 'PROGRAM_ID=LEVEL'", value)
+        )?;
+    let program_id = program_id.trim();
+    Pubkey::from_str(program_id).with_context(|| format!("Invalid program ID '{}'", program_id))?;
+
+    Ok((program_id.to_string(), parse_commitment(level.trim())?))
+}
+
+/// Parse a pool's minimum trade input amount as 'POOL=AMOUNT'
+pub fn parse_pool_min_input_amount(value: &str) -> Result<(String, u64)> {
+    let (pool, amount) = value
+        .split_once('=')
+        .ok_or_else(||
+            anyhow::anyhow!("Invalid pool minimum input amount '{}', expected 'POOL=AMOUNT'", value)
+        )?;
+    let pool = pool.trim();
+    Pubkey::from_str(pool).with_context(|| format!("Invalid pool address '{}'", pool))?;
+    let amount = amount
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid minimum input amount '{}'", amount.trim()))?;
+
+    Ok((pool.to_string(), amount))
+}
+
 /// Core trait for all DEX indexers
 #[async_trait]
 pub trait DexIndexer {
     // Associated types for DEX-specific structures
     type Repository: crate::db::common::Repository;
-    type ParsedEvent: Send;
+    type ParsedEvent: Send + std::fmt::Debug;
 
     //
     // REQUIRED CONSTRUCTOR METHOD (unified instantiation pattern)
@@ -71,8 +531,30 @@ pub trait DexIndexer {
     /// Return program IDs to monitor
     fn program_ids(&self) -> Vec<&str>;
 
-    /// Return pools to monitor
-    fn pool_pubkeys(&self) -> &HashSet<Pubkey>;
+    /// Return a snapshot of the pools to monitor
+    ///
+    /// The copy isn't affected by pools added or removed afterwards. Prefer
+    /// `contains_pool` and `pool_count` for lookups, which don't copy the set.
+    fn pool_pubkeys(&self) -> HashSet<Pubkey>;
+
+    /// Whether a pool is currently monitored, without copying the pool set
+    fn contains_pool(&self, pool: &Pubkey) -> bool {
+        self.pool_pubkeys().contains(pool)
+    }
+
+    /// Number of pools monitored, without copying the pool set
+    fn pool_count(&self) -> usize {
+        self.pool_pubkeys().len()
+    }
+
+    /// Start monitoring a pool, returning false if it was already monitored
+    ///
+    /// The WebSocket subscription covers the whole program, so this takes effect
+    /// with the next log. Use `add_pool_with_backfill` to also fetch its history.
+    fn add_pool(&self, pool: Pubkey) -> bool;
+
+    /// Stop monitoring a pool, returning false if it wasn't monitored
+    fn remove_pool(&self, pool: &Pubkey) -> bool;
 
     /// Access to repository
     fn repository(&self) -> &Self::Repository;
@@ -80,6 +562,9 @@ pub trait DexIndexer {
     /// Name of the DEX (for logs and config)
     fn dex_name(&self) -> &str;
 
+    /// Event discriminators this indexer parses, each with its event name
+    fn event_discriminators(&self) -> Vec<([u8; 8], &'static str)>;
+
     /// Access to signature store
     fn signature_store(&self) -> &SignatureStore;
 
@@ -89,41 +574,491 @@ pub trait DexIndexer {
     /// Access to connection configuration
     fn connection_config(&self) -> &ConnectionConfig;
 
+    /// Access to the liveness state shared with the WebSocket manager
+    fn health_tracker(&self) -> &Arc<HealthTracker>;
+
+    /// Access to the events held while the database is unreachable
+    fn outage_buffer(&self) -> &OutageBuffer;
+
+    /// Current liveness of the indexer
+    fn health(&self) -> IndexerHealth {
+        self.health_tracker().snapshot()
+    }
+
+    /// Whether events of every pool of the program are indexed, not just the monitored set
+    ///
+    /// Such an indexer can't enumerate its pools up front, so backfills are skipped
+    /// and only live events are indexed.
+    fn indexes_all_pools(&self) -> bool {
+        false
+    }
+
+    /// Whether events are only parsed and logged, never written to the database
+    fn is_dry_run(&self) -> bool {
+        self.connection_config().dry_run
+    }
+
     /// Parse events from a log, returning any found events without persisting them
     async fn parse_log_events(&self, log: &RpcLogsResponse) -> Result<Vec<Self::ParsedEvent>>;
 
+    /// Parse events from a log, each with its position among the events of the transaction
+    ///
+    /// With the signature, the position identifies a stored event, so it must not change
+    /// with the monitored pools: indexers that drop events of unmonitored pools while
+    /// parsing count them anyway. Defaults to the position among the returned events.
+    async fn parse_indexed_log_events(
+        &self,
+        log: &RpcLogsResponse
+    ) -> Result<Vec<(usize, Self::ParsedEvent)>> {
+        Ok(self.parse_log_events(log).await?.into_iter().enumerate().collect())
+    }
+
+    /// Parse the event at a position of a log again, None if it is no longer parsed
+    async fn parse_event_at(
+        &self,
+        log: &RpcLogsResponse,
+        event_index: usize
+    ) -> Result<Option<Self::ParsedEvent>> {
+        let events = self.parse_indexed_log_events(log).await?;
+        Ok(
+            events
+                .into_iter()
+                .find(|(index, _)| *index == event_index)
+                .map(|(_, event)| event)
+        )
+    }
+
+    /// Parse events from a fetched transaction, each with the context of its transaction
+    ///
+    /// Events emitted through CPI are recovered from the inner instructions, since the
+    /// logs can truncate them. Events of failed transactions are returned too, marked
+    /// as unsuccessful. Transactions without log messages have no events.
+    async fn parse_transaction_events(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+        signature: &str
+    ) -> Result<Vec<(Self::ParsedEvent, EventContext)>> {
+        let Some(meta) = &tx.transaction.meta else {
+            return Ok(Vec::new());
+        };
+        let Some(log_messages) = Into::<Option<Vec<String>>>::into(meta.log_messages.clone()) else {
+            log::debug!("[{}] Transaction {} has no log messages", self.dex_name(), signature);
+            return Ok(Vec::new());
+        };
+
+        // Recover events emitted through CPI that the logs don't show
+        let cpi_events = cpi_event_data(&tx.transaction, &self.program_ids());
+        if !cpi_events.is_empty() {
+            log::debug!(
+                "[{}] Found {} CPI event(s) in transaction {}",
+                self.dex_name(),
+                cpi_events.len(),
+                signature
+            );
+        }
+        let log_messages = with_cpi_event_lines(log_messages, &cpi_events);
+        let log = Arc::new(self.tx_to_logs_response(signature, &log_messages, meta.err.clone()));
+        let fee_payer = fee_payer(&tx.transaction);
+
+        let events = self.parse_indexed_log_events(&log).await?;
+        Ok(
+            events
+                .into_iter()
+                .map(|(event_index, event)| {
+                    let context = EventContext {
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        success: meta.err.is_none(),
+                        log: log.clone(),
+                        event_index,
+                        fee_payer,
+                    };
+                    (event, context)
+                })
+                .collect()
+        )
+    }
+
     /// Handle a single event (for both real-time and backfill processing)
     ///
     /// Parameters:
     /// - event: The parsed event to handle
+    /// - event_index: Position of the event among its transaction's events, which
+    ///   together with the signature identifies it
     /// - is_backfill: Flag indicating if this event comes from backfill (true) or live streaming (false)
-    async fn handle_event(&self, event: Self::ParsedEvent, is_backfill: bool) -> Result<()>;
+    /// - slot: Slot the transaction landed in, when known
+    /// - block_time: Unix time of the transaction's block, when known
+    /// - fee_payer: Account that paid the transaction's fees, only known for fetched
+    ///   transactions since live logs don't include the accounts
+    ///
+    /// Returns whether the event was stored, or in a dry run would have been; false
+    /// when it was already stored, quarantined or skipped.
+    async fn handle_event(
+        &self,
+        event: Self::ParsedEvent,
+        event_index: usize,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+        fee_payer: Option<Pubkey>
+    ) -> Result<bool>;
 
     //
     // CORE PROCESSING METHODS (default implementations)
     //
 
-    /// Process a single log (for real-time events)
-    async fn process_log(&self, log: &RpcLogsResponse) -> Result<()> {
+    /// Process a single log (for real-time events) observed at the given slot
+    async fn process_log(&self, log: &RpcLogsResponse, slot: Option<u64>) -> Result<()> {
         // Check if log contains relevant program IDs
         if !self.contains_program_mentions(log) {
             return Ok(());
         }
 
+        if self.skip_failed_transaction(log) {
+            return Ok(());
+        }
+
         // Parse and process events
-        let events = self.parse_log_events(log).await?;
+        let events = self.parse_indexed_log_events(log).await?;
+        metrics::record_events_parsed(self.dex_name(), events.len());
+
+        let block_time = if events.is_empty() { None } else { self.live_block_time(slot).await };
 
-        for event in events {
+        let mut any_persisted = false;
+        for (index, event) in events {
             // Real-time events from WebSocket/process_log are not backfill
-            if let Err(e) = self.handle_event(event, false).await {
-                self.log_error("Failed to handle event", &e);
-                // Continue processing other events
+            match self.persist_event(event, log, index, false, slot, block_time, None).await {
+                Err(e) => {
+                    self.log_error("Failed to handle event", &e);
+                    // Continue processing other events
+                }
+                Ok(true) => {
+                    any_persisted = true;
+                    self.health_tracker().record_event();
+                    if !self.is_dry_run() {
+                        metrics::record_event_persisted(self.dex_name());
+                    }
+                }
+                // Quarantined, skipped or already stored
+                Ok(false) => {}
             }
         }
 
+        if any_persisted {
+            self.save_transaction_logs(log, slot).await;
+        }
+
         Ok(())
     }
 
+    /// Look up the block time of a live event's slot
+    ///
+    /// Returns None when the slot is unknown or the RPC node has no block time for it
+    /// (yet), in which case events are stored without one and dated by their indexing time.
+    async fn live_block_time(&self, slot: Option<u64>) -> Option<i64> {
+        let slot = slot?;
+        match self.backfill_manager().get_block_time(slot).await {
+            Ok(block_time) => Some(block_time),
+            Err(e) => {
+                log::debug!("[{}] No block time for live slot {}: {:#}", self.dex_name(), slot, e);
+                None
+            }
+        }
+    }
+
+    /// Whether a transaction failed on-chain, in which case its events are skipped
+    fn skip_failed_transaction(&self, log: &RpcLogsResponse) -> bool {
+        let Some(err) = &log.err else {
+            return false;
+        };
+
+        log::debug!(
+            "[{}] Skipping events from failed transaction {}: {}",
+            self.dex_name(),
+            log.signature,
+            err
+        );
+        metrics::record_failed_transaction_skipped(self.dex_name());
+        true
+    }
+
+    /// Store a transaction's raw log messages when enabled, once per transaction
+    ///
+    /// Failures are logged rather than returned, since the events are already stored.
+    async fn save_transaction_logs(&self, log: &RpcLogsResponse, slot: Option<u64>) {
+        if !self.connection_config().store_transaction_logs || self.is_dry_run() {
+            return;
+        }
+
+        let store = TransactionLogStore::new(self.repository().pool().clone());
+        if let Err(e) = store.save_logs(&log.signature, self.dex_name(), slot, &log.logs).await {
+            self.log_error("Failed to store transaction logs", &e);
+        }
+    }
+
+    /// Signatures among `signatures` with a stored event
+    ///
+    /// Lets transactions that were already indexed be skipped before they are
+    /// fetched. Indexers that can't tell return an empty set, so every signature
+    /// is processed.
+    async fn indexed_signatures(&self, _signatures: &[Signature]) -> Result<HashSet<Signature>> {
+        Ok(HashSet::new())
+    }
+
+    /// Events among the (signature, event index) keys that are already stored
+    ///
+    /// Defaults to every key whose signature has a stored event, for indexers that
+    /// store one event per transaction.
+    async fn indexed_events(
+        &self,
+        keys: &[(Signature, usize)]
+    ) -> Result<HashSet<(Signature, usize)>> {
+        let signatures: Vec<Signature> = keys
+            .iter()
+            .map(|(signature, _)| *signature)
+            .collect();
+        let indexed = self.indexed_signatures(&signatures).await?;
+        Ok(
+            keys
+                .iter()
+                .filter(|(signature, _)| indexed.contains(signature))
+                .copied()
+                .collect()
+        )
+    }
+
+    /// Delete a pool's stored events in slots after `slot`, returning how many were deleted
+    ///
+    /// Used to roll back a reorg. Indexers that don't store event slots delete nothing.
+    async fn delete_events_after_slot(&self, _pool: &Pubkey, _slot: u64) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Handle an event, retrying failures and dead-lettering the event once retries run out
+    ///
+    /// Only transient errors and an unreachable database are retried; others are
+    /// dead-lettered right away, and a duplicate key means the event was already
+    /// stored, so it counts as persisted. Retries re-parse the event from its log, so
+    /// parsed events don't need to be cloneable. Events that fail because the database
+    /// is unreachable are held in the outage buffer instead, as are any events that
+    /// arrive while others are held. Returns whether the event was stored, like
+    /// `handle_event`, or the last error if the event was held or dead-lettered.
+    ///
+    /// Parameters:
+    /// - log: The log the event was parsed from
+    /// - event_index: Position of the event among the log's events, see `parse_indexed_log_events`
+    /// - fee_payer: Account that paid the transaction's fees, when known
+    #[allow(clippy::too_many_arguments)] // The event's context, as it is held and dead-lettered
+    async fn persist_event(
+        &self,
+        event: Self::ParsedEvent,
+        log: &RpcLogsResponse,
+        event_index: usize,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+        fee_payer: Option<Pubkey>
+    ) -> Result<bool> {
+        let retry = &self.connection_config().persist_retry;
+        let event_debug = format!("{:?}", event);
+
+        // While the database is known to be down, queue up behind the events already held
+        if
+            !self.outage_buffer().is_empty() &&
+            self.hold_event(log, event_index, is_backfill, slot, block_time, fee_payer)
+        {
+            return Err(
+                anyhow::anyhow!("Database unreachable, event from {} held for replay", log.signature)
+            );
+        }
+
+        let mut attempt = 1;
+        let mut result = self.handle_event(event, event_index, is_backfill, slot, block_time, fee_payer).await;
+        while let Err(e) = &result {
+            let retryable = self.is_transient_error(e) || is_unreachable_error(e);
+            if !retryable || is_duplicate_error(e) || attempt >= retry.max_attempts {
+                break;
+            }
+
+            let delay = retry.delay_for_retry(attempt);
+            log::warn!(
+                "[{}] Failed to persist event from {} (attempt {}/{}), retrying in {:?}: {:#}",
+                self.dex_name(),
+                log.signature,
+                attempt,
+                retry.max_attempts,
+                delay,
+                e
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+
+            let event = match self.parse_event_at(log, event_index).await {
+                Ok(event) => event,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+            result = match event {
+                Some(event) => self.handle_event(event, event_index, is_backfill, slot, block_time, fee_payer).await,
+                None =>
+                    Err(
+                        IndexerError::Deserialize(
+                            format!("event {} of {}, no longer parsed from its log", event_index, log.signature)
+                        ).into()
+                    ),
+            };
+        }
+
+        let e = match result {
+            Ok(stored) => {
+                return Ok(stored);
+            }
+            Err(e) => e,
+        };
+        if is_duplicate_error(&e) {
+            log::debug!("[{}] Event {} of {} already stored", self.dex_name(), event_index, log.signature);
+            return Ok(false);
+        }
+
+        if
+            is_unreachable_error(&e) &&
+            self.hold_event(log, event_index, is_backfill, slot, block_time, fee_payer)
+        {
+            log::warn!(
+                "[{}] Database unreachable, holding event from {} until it recovers ({} held)",
+                self.dex_name(),
+                log.signature,
+                self.outage_buffer().len()
+            );
+            return Err(e.context(format!("Event from {} held until the database recovers", log.signature)));
+        }
+
+        let entry = DeadLetterEntry {
+            failed_at: chrono::Utc::now(),
+            dex: self.dex_name().to_string(),
+            signature: log.signature.clone(),
+            slot,
+            block_time,
+            event_index,
+            is_backfill,
+            fee_payer: fee_payer.map(|fee_payer| fee_payer.to_string()),
+            attempts: attempt,
+            error: format!("{:#}", e),
+            event: event_debug,
+            logs: log.logs.clone(),
+        };
+        let queue = DeadLetterQueue::new(&self.connection_config().dead_letter_path);
+        queue
+            .push(&entry).await
+            .with_context(|| format!("Failed to dead-letter event from {}: {:#}", log.signature, e))?;
+        metrics::record_event_dead_lettered(self.dex_name());
+
+        Err(
+            e.context(
+                format!(
+                    "Event from {} dead-lettered to {} after {} attempts",
+                    log.signature,
+                    queue.path().display(),
+                    attempt
+                )
+            )
+        )
+    }
+
+    /// Hold an event in the outage buffer, returning false if the buffer is full
+    fn hold_event(
+        &self,
+        log: &RpcLogsResponse,
+        event_index: usize,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+        fee_payer: Option<Pubkey>
+    ) -> bool {
+        let event = HeldEvent {
+            log: log.clone(),
+            event_index,
+            is_backfill,
+            slot,
+            block_time,
+            fee_payer,
+        };
+        let held = self.outage_buffer().hold(event);
+        metrics::set_outage_buffer_events(self.dex_name(), self.outage_buffer().len());
+        held
+    }
+
+    /// Persist the events held while the database was unreachable, once it answers again
+    ///
+    /// Events are replayed in the order they were held. If the database goes away
+    /// again, the rest are held once more. Returns how many events were stored.
+    async fn replay_held_events(&self) -> usize {
+        let buffer = self.outage_buffer();
+        if buffer.is_empty() || !is_reachable(self.repository().pool()).await {
+            return 0;
+        }
+
+        let held = buffer.take_all();
+        self.log_activity(
+            "Database reachable again, replaying held events",
+            Some(&format!("{} events", held.len()))
+        );
+
+        let mut replayed = 0;
+        let mut logged_signatures = HashSet::new();
+        for held_event in held {
+            let HeldEvent { log, event_index, is_backfill, slot, block_time, fee_payer } = held_event;
+            let event = match self.parse_event_at(&log, event_index).await {
+                Ok(event) => event,
+                Err(e) => {
+                    self.log_error("Failed to parse held event", &e);
+                    continue;
+                }
+            };
+            let Some(event) = event else {
+                log::error!(
+                    "[{}] Held event {} no longer parsed from {}",
+                    self.dex_name(),
+                    event_index,
+                    log.signature
+                );
+                continue;
+            };
+
+            match
+                self.persist_event(
+                    event,
+                    &log,
+                    event_index,
+                    is_backfill,
+                    slot,
+                    block_time,
+                    fee_payer
+                ).await
+            {
+                Err(e) => {
+                    self.log_error("Failed to replay held event", &e);
+                    continue;
+                }
+                Ok(true) => {}
+                Ok(false) => {
+                    continue;
+                }
+            }
+            replayed += 1;
+            if logged_signatures.insert(log.signature.clone()) {
+                self.save_transaction_logs(&log, slot).await;
+            }
+            self.health_tracker().record_event();
+            metrics::record_event_persisted(self.dex_name());
+        }
+
+        metrics::set_outage_buffer_events(self.dex_name(), buffer.len());
+        replayed
+    }
+
     /// Start the indexer
     async fn start(&self) -> Result<()> {
         // Modified to use pre-initialized components and config
@@ -133,21 +1068,43 @@ pub trait DexIndexer {
         // Log all pools being monitored
         self.log_monitored_pools();
 
-        // Setup WebSocket manager
-        let (ws_manager, rx_buffer) = self.setup_websocket_manager().await?;
+        let strategy = self.connection_config().startup_strategy;
+        self.log_activity("Startup strategy", Some(&format!("{:?}", strategy)));
+        match strategy {
+            StartupStrategy::Buffer => {
+                // Setup WebSocket manager
+                let (ws_manager, rx_buffer) = self.setup_websocket_manager().await?;
 
-        // Setup event buffering during backfill
-        let (event_buffer, is_backfilling, buffer_task) =
-            self.setup_event_buffering(rx_buffer).await;
+                // Setup event buffering during backfill
+                let (event_buffer, is_backfilling, buffer_task) =
+                    self.setup_event_buffering(rx_buffer).await;
 
-        // Perform initial backfill
-        self.perform_backfill().await?;
+                // Perform initial backfill
+                self.perform_backfill().await?;
 
-        // Signal backfill completion and process buffered events
-        self.process_buffered_events(event_buffer, is_backfilling, buffer_task).await?;
+                // Signal backfill completion and process buffered events
+                self.process_buffered_events(event_buffer, is_backfilling, buffer_task).await?;
 
-        // Main event processing loop with periodic backfill
-        self.run_main_event_loop(ws_manager).await
+                // Main event processing loop with periodic backfill
+                self.run_main_event_loop(ws_manager).await
+            }
+            StartupStrategy::Background => {
+                let backfill = async {
+                    if let Err(e) = self.perform_backfill().await {
+                        self.log_error("Error during background backfill", &e);
+                    }
+                };
+                let (_, result) = tokio::join!(
+                    backfill,
+                    self.run_main_event_loop(self.websocket_manager())
+                );
+                result
+            }
+            StartupStrategy::BackfillFirst => {
+                self.perform_backfill().await?;
+                self.run_main_event_loop(self.websocket_manager()).await
+            }
+        }
     }
 
     //
@@ -191,23 +1148,40 @@ pub trait DexIndexer {
         data.len() >= 8 && &data[0..8] == discriminator
     }
 
-    /// Check if a pubkey is in the monitored pool set
-    fn is_monitored_pool(&self, pool: &Pubkey, pool_set: &HashSet<Pubkey>) -> bool {
-        let is_monitored = pool_set.contains(pool);
+    /// Check if a pubkey is in the monitored pool set (always true when indexing all pools)
+    ///
+    /// Safe to call while pools are added or removed; it sees the set either
+    /// before or after each change.
+    fn is_monitored_pool(&self, pool: &Pubkey) -> bool {
+        let is_monitored = self.indexes_all_pools() || self.contains_pool(pool);
         log::debug!(
-            "[{}] Pool check: {} is{} in monitored set of {} pools",
+            "[{}] Pool check: {} is{} in monitored set",
             self.dex_name(),
             pool,
             if is_monitored {
                 ""
             } else {
                 " not"
-            },
-            pool_set.len()
+            }
         );
         is_monitored
     }
 
+    /// Record a decoded event that was skipped because its pool isn't monitored
+    ///
+    /// Logged at debug level and counted in `unmonitored_pool_events_total`, so
+    /// operators can discover active pools they aren't indexing yet.
+    fn log_unmonitored_pool_event(&self, pool: &Pubkey, event_type: &str, signature: &str) {
+        log::debug!(
+            "[{}] Skipping {} event for unmonitored pool {} in {}",
+            self.dex_name(),
+            event_type,
+            pool,
+            signature
+        );
+        metrics::record_unmonitored_pool_event(self.dex_name(), event_type);
+    }
+
     /// Check if a log contains events from any of the monitored programs
     fn contains_program_mentions(&self, log: &RpcLogsResponse) -> bool {
         let program_ids = self.program_ids();
@@ -222,10 +1196,17 @@ pub trait DexIndexer {
     }
 
     /// Helper to convert transaction & metadata into RpcLogsResponse for processing
-    fn tx_to_logs_response(&self, signature: &str, logs: &[String]) -> RpcLogsResponse {
+    ///
+    /// `err` is the transaction's on-chain error from its metadata, if it failed.
+    fn tx_to_logs_response(
+        &self,
+        signature: &str,
+        logs: &[String],
+        err: Option<TransactionError>
+    ) -> RpcLogsResponse {
         RpcLogsResponse {
             signature: signature.to_string(),
-            err: None,
+            err,
             logs: logs
                 .iter()
                 .map(|s| s.to_string())
@@ -238,64 +1219,87 @@ pub trait DexIndexer {
     //
 
     /// Handle common error cases with standardized recovery strategies
-    fn handle_rpc_error(&self, err: &anyhow::Error, context: &str) -> Result<()> {
+    ///
+    /// Returns `IndexerError::RateLimited` when the caller should back off, and
+    /// `IndexerError::Permanent` otherwise.
+    fn handle_rpc_error(&self, err: &anyhow::Error, context: &str) -> Result<(), IndexerError> {
         // Log the error with context
         self.log_error(context, err);
 
-        // Check if it's a rate limit error
-        if err.to_string().contains("429") || err.to_string().contains("rate limit") {
-            // Implement exponential backoff
-            self.log_activity("Rate limit hit, implementing backoff...", None);
-            // Return special error type that signals backoff needed
-            return Err(anyhow::anyhow!("RateLimit"));
+        match IndexerError::find(err) {
+            Some(IndexerError::RateLimited(message)) => {
+                self.log_activity("Rate limit hit, implementing backoff...", None);
+                Err(IndexerError::RateLimited(message.clone()))
+            }
+            _ => Err(IndexerError::Permanent(format!("Non-recoverable error: {:#}", err))),
         }
-
-        // For other errors, log and continue
-        Err(anyhow::anyhow!("Non-recoverable error: {}", err))
     }
 
-    /// Helper to handle transaction parsing errors
+    /// Helper to handle a backfill transaction that couldn't be fetched
+    ///
+    /// RPC failures, which are already retried, skip the transaction. Database
+    /// errors and errors that aren't an `IndexerError` are propagated.
     fn handle_tx_parse_error(&self, signature: &str, err: &anyhow::Error) -> Result<()> {
         self.log_error(&format!("Error parsing transaction {}", signature), err);
 
-        // Decide if we should retry or skip based on error type
-        if
-            err.to_string().contains("not found") ||
-            err.to_string().contains("Failed to fetch transaction")
-        {
-            self.log_activity("Transaction not found or unavailable, skipping", None);
-            Ok(())
-        } else {
-            // For other errors, propagate
-            Err(anyhow::anyhow!("Transaction parse error: {}", err))
+        match IndexerError::find(err) {
+            Some(IndexerError::TransactionNotFound(_)) => {
+                self.log_activity("Transaction not found, skipping", Some(signature));
+                Ok(())
+            }
+            Some(
+                | IndexerError::RateLimited(_)
+                | IndexerError::Transient(_)
+                | IndexerError::Permanent(_)
+                | IndexerError::Deserialize(_),
+            ) => {
+                self.log_activity("Transaction unavailable, skipping", Some(signature));
+                Ok(())
+            }
+            Some(IndexerError::Database(_)) | None => {
+                Err(anyhow::anyhow!("Transaction parse error: {:#}", err))
+            }
         }
     }
 
     /// Helper to categorize errors as transient or permanent
     fn is_transient_error(&self, err: &anyhow::Error) -> bool {
-        let err_str = err.to_string();
-        err_str.contains("429") ||
-            err_str.contains("rate limit") ||
-            err_str.contains("timeout") ||
-            err_str.contains("connection")
+        crate::backfill_manager::is_transient_error(err)
     }
 
     //
     // LOGGING METHODS
     //
 
-    /// Log all pools being monitored
+    /// Log the pools being monitored, listing at most `MAX_LOGGED_POOLS` of them
     fn log_monitored_pools(&self) {
-        let pool_addresses: Vec<String> = self
+        let mut pool_addresses: Vec<String> = self
             .pool_pubkeys()
             .iter()
             .map(|p| p.to_string())
             .collect();
+        pool_addresses.sort();
+
         // Log using the standard format
         self.log_activity("Monitoring pools", Some(&format!("{} pools", pool_addresses.len())));
-        for pool in &pool_addresses {
+        for pool in pool_addresses.iter().take(MAX_LOGGED_POOLS) {
             self.log_activity("Pool", Some(pool));
         }
+        if pool_addresses.len() > MAX_LOGGED_POOLS {
+            self.log_activity(
+                "Pool",
+                Some(&format!("... and {} more", pool_addresses.len() - MAX_LOGGED_POOLS))
+            );
+        }
+    }
+
+    /// Log activity for a single pool, at debug level when too many pools are monitored to list
+    fn log_pool_activity(&self, activity: &str, details: &str) {
+        if self.pool_count() > MAX_LOGGED_POOLS {
+            log::debug!("[{}] {}: {}", self.dex_name(), activity, details);
+        } else {
+            self.log_activity(activity, Some(details));
+        }
     }
 
     /// Log event processing with standard format
@@ -325,6 +1329,16 @@ pub trait DexIndexer {
         }
     }
 
+    /// Log a fully-parsed event record that dry-run mode skipped writing
+    fn log_dry_run_event<T: std::fmt::Debug>(&self, source_label: &str, record: &T) {
+        crate::utils::logging::log_dex_activity(
+            "dry-run",
+            self.dex_name(),
+            &format!("[{}] Would insert", source_label),
+            Some(&format!("{:?}", record))
+        );
+    }
+
     /// Log processing statistics
     fn log_processing_stats(&self, context: &str, total: usize, success: usize) {
         let percent = if total > 0 { ((success as f64) / (total as f64)) * 100.0 } else { 0.0 };
@@ -380,46 +1394,90 @@ pub trait DexIndexer {
     ) -> BackfillManager {
         let backfill_config = BackfillConfig {
             rpc_url: rpc_url.to_string(),
+            rpc_headers: self.connection_config().rpc_headers.clone(),
             max_signatures_per_request: 100,
             initial_backfill_slots: 10_000,
             dex_type: self.dex_name().to_string(),
+            commitment: self.connection_config().commitment,
+            rpc_timeout: self.connection_config().rpc_timeout,
+            retry: RetryConfig::default(),
+            order: self.connection_config().backfill_order,
+            pool_cap: self.connection_config().backfill_pool_cap,
+            max_requests_per_second: self.connection_config().backfill_max_requests_per_second,
+            fetch_concurrency: self.connection_config().backfill_fetch_concurrency,
+            pool_concurrency: self.connection_config().backfill_pool_concurrency,
+            signature_read_batch_size: self.connection_config().signature_read_batch_size,
         };
 
         BackfillManager::new(backfill_config, signature_store)
     }
 
+    /// Log subscription filter mentioning each monitored program
+    fn logs_filter(&self) -> RpcTransactionLogsFilter {
+        RpcTransactionLogsFilter::Mentions(
+            self
+                .program_ids()
+                .iter()
+                .map(|&s| s.to_string())
+                .collect()
+        )
+    }
+
+    /// Log subscriptions to open: one for all programs, or one per program
+    fn log_subscriptions(&self) -> Vec<LogSubscription> {
+        let config = self.connection_config();
+        if !config.ws_subscription_per_program {
+            return Vec::new();
+        }
+
+        self.program_ids()
+            .into_iter()
+            .map(|program_id| LogSubscription {
+                filter: RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+                commitment: config.program_commitments
+                    .get(program_id)
+                    .copied()
+                    .unwrap_or(config.commitment),
+            })
+            .collect()
+    }
+
     /// Setup WebSocket manager
     async fn setup_websocket_manager(
         &self
-    ) -> Result<(WebSocketManager, Receiver<RpcLogsResponse>)> {
+    ) -> Result<(WebSocketManager, Receiver<Response<RpcLogsResponse>>)> {
+        let ws_manager = self.websocket_manager();
+        self.log_activity("Starting WebSocket subscription for real-time events", None);
+        let rx_buffer = ws_manager.start_subscription().await?;
+
+        Ok((ws_manager, rx_buffer))
+    }
+
+    /// Create the WebSocket manager for this indexer's log subscriptions
+    fn websocket_manager(&self) -> WebSocketManager {
         let ws_config = WebSocketConfig {
             ws_url: self.connection_config().ws_url.clone(),
-            filter: RpcTransactionLogsFilter::Mentions(
-                self
-                    .program_ids()
-                    .iter()
-                    .map(|&s| s.to_string())
-                    .collect()
-            ),
+            fallback_ws_urls: self.connection_config().ws_fallback_urls.clone(),
+            filter: self.logs_filter(),
+            subscriptions: self.log_subscriptions(),
             max_reconnect_attempts: 0, // Unlimited reconnection attempts
             reconnect_base_delay_ms: 500,
             reconnect_max_delay_ms: 30_000,
-            commitment: CommitmentConfig::confirmed(),
+            reconnect_jitter_ratio: 0.2,
+            commitment: self.connection_config().commitment,
+            channel_capacity: self.connection_config().ws_channel_capacity,
+            overflow_policy: self.connection_config().ws_overflow_policy,
         };
 
-        self.log_activity("Starting WebSocket subscription for real-time events", None);
-        let ws_manager = WebSocketManager::new(ws_config);
-        let rx_buffer = ws_manager.start_subscription().await?;
-
-        Ok((ws_manager, rx_buffer))
+        WebSocketManager::new(ws_config).with_health_tracker(self.health_tracker().clone())
     }
 
     /// Setup event buffering during backfill
     async fn setup_event_buffering(
         &self,
-        rx_buffer: Receiver<RpcLogsResponse>
-    ) -> (Arc<Mutex<Vec<RpcLogsResponse>>>, Arc<AtomicBool>, JoinHandle<()>) {
-        let event_buffer = Arc::new(Mutex::new(Vec::<RpcLogsResponse>::new()));
+        rx_buffer: Receiver<Response<RpcLogsResponse>>
+    ) -> (Arc<Mutex<Vec<Response<RpcLogsResponse>>>>, Arc<AtomicBool>, JoinHandle<()>) {
+        let event_buffer = Arc::new(Mutex::new(Vec::<Response<RpcLogsResponse>>::new()));
         let is_backfilling = Arc::new(AtomicBool::new(true));
 
         // Create clones for the buffer collection task
@@ -434,7 +1492,7 @@ pub trait DexIndexer {
                     Ok(Some(log_response)) => {
                         // Store the event in our buffer
                         let mut guard = buffer_clone.lock().await;
-                        guard.push(log_response.clone());
+                        guard.push(log_response);
                     }
                     _ => {} // Either timeout or None result, just continue
                 }
@@ -448,17 +1506,56 @@ pub trait DexIndexer {
     // BACKFILL OPERATIONS
     //
 
+    /// Start monitoring a pool while the indexer runs and run its initial backfill
+    ///
+    /// Returns (processed, success) counts from the backfill, or (0, 0) if the
+    /// pool was already monitored.
+    async fn add_pool_with_backfill(&self, pool: Pubkey) -> Result<(usize, usize)> {
+        if !self.add_pool(pool) {
+            return Ok((0, 0));
+        }
+
+        self.log_activity("Added pool", Some(&pool.to_string()));
+        self.backfill_pool(&pool).await
+    }
+
     /// Main backfill coordinator - orchestrates the entire backfill process
+    ///
+    /// Up to `pool_concurrency` pools are backfilled at once. Their RPC requests
+    /// all go through the backfill manager, so they share one rate limit.
     async fn perform_backfill(&self) -> Result<()> {
+        if self.indexes_all_pools() {
+            self.log_activity("Skipping initial backfill", Some("indexing all pools"));
+            return Ok(());
+        }
         self.log_activity("Starting initial backfill", None);
 
         // Track overall statistics
         let mut total_processed = 0;
         let mut total_success = 0;
 
-        for pool in self.pool_pubkeys() {
-            let result = self.backfill_pool(pool).await;
+        let pools = self.pool_pubkeys();
+        self.backfill_manager().set_pool_logging(pools.len() <= MAX_LOGGED_POOLS);
+
+        // Pools are interleaved on this task rather than spawned, since spawned tasks
+        // can't borrow the indexer. A panicking pool is caught so the others finish.
+        // The futures are built up front; mapping inside the stream trips up the Send
+        // check on async_trait futures
+        let backfills: Vec<_> = pools
+            .iter()
+            .map(|pool| async move {
+                let result = AssertUnwindSafe(self.backfill_pool(pool))
+                    .catch_unwind().await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Backfill panicked")));
+                (pool, result)
+            })
+            .collect();
+        let mut backfills = stream
+            ::iter(backfills)
+            .buffer_unordered(self.backfill_manager().config().pool_concurrency.max(1));
 
+        let mut done = 0;
+        while let Some((pool, result)) = backfills.next().await {
             match result {
                 Ok((processed, success)) => {
                     total_processed += processed;
@@ -469,116 +1566,354 @@ pub trait DexIndexer {
                     // Continue with next pool
                 }
             }
+
+            // Per-pool lines are at debug level for large pool sets, so report progress instead
+            done += 1;
+            if pools.len() > MAX_LOGGED_POOLS && done % BACKFILL_PROGRESS_INTERVAL == 0 {
+                self.log_activity(
+                    "Backfill progress",
+                    Some(&format!("{}/{} pools", done, pools.len()))
+                );
+            }
         }
 
         self.log_processing_stats("Initial backfill complete", total_processed, total_success);
+        self.health_tracker().record_backfill();
         Ok(())
     }
 
     /// Process backfill for a single pool
+    ///
+    /// Newest-first backfills fetch and process signatures one page at a time,
+    /// so they never hold more than a page of transactions in memory. Oldest-first
+    /// backfills have to collect every signature before reversing them, but still
+    /// fetch the transactions a page at a time.
+    ///
+    /// Progress is stored as pages are processed. After a restart the pool first
+    /// catches up on transactions since its checkpoint, then resumes paging older
+    /// history only if the initial backfill hadn't completed.
     async fn backfill_pool(&self, pool: &Pubkey) -> Result<(usize, usize)> {
-        self.log_activity("Backfilling pool", Some(&pool.to_string()));
+        self.log_pool_activity("Backfilling pool", &pool.to_string());
 
         let backfill_manager = self.backfill_manager();
-        // Get signatures for this pool
-        let signatures = backfill_manager.initial_backfill_for_pool(pool).await.map_err(|e| {
-            self.log_error(&format!("Failed to get signatures for pool {}", pool), &e);
-            e
-        })?;
-
-        if signatures.is_empty() {
-            self.log_activity("Backfill", Some(&format!("No signatures found for pool {}", pool)));
-            return Ok((0, 0));
+        let on_error = |e: &anyhow::Error| {
+            metrics::record_rpc_error(self.dex_name(), e);
+            self.log_error(&format!("Failed to get signatures for pool {}", pool), e);
+        };
+        let chunk_size = backfill_manager.config().max_signatures_per_request.max(1);
+
+        let mut total_signatures = 0;
+        let mut total_processed = 0;
+        let mut total_success = 0;
+
+        // Catch up on what happened while the indexer was down
+        let stored_progress = backfill_manager.backfill_progress(pool).await.inspect_err(on_error)?;
+        if stored_progress.is_some() {
+            let last_signature = backfill_manager.last_signature(pool).await?;
+            let rolled_back = match &last_signature {
+                Some(signature) => {
+                    let last_signatures = HashMap::from([(*pool, signature.clone())]);
+                    let dropped = backfill_manager
+                        .pools_with_dropped_signatures(&last_signatures).await
+                        .inspect_err(on_error)?;
+                    if dropped.contains(pool) {
+                        self.roll_back_pool(pool, signature).await?
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            };
+
+            match rolled_back {
+                Some((processed, success)) => {
+                    total_signatures += processed;
+                    total_processed += processed;
+                    total_success += success;
+                }
+                None => {
+                    let signatures = backfill_manager
+                        .backfill_since_signature(pool, last_signature).await
+                        .inspect_err(on_error)?;
+
+                    total_signatures += signatures.len();
+                    for chunk in signatures.chunks(chunk_size) {
+                        let (processed, success) = self.backfill_signature_chunk(
+                            pool,
+                            chunk
+                        ).await?;
+                        total_processed += processed;
+                        total_success += success;
+                    }
+                }
+            }
+        }
+
+        match backfill_manager.config().order {
+            BackfillOrder::NewestFirst => {
+                let budget = backfill_manager
+                    .config()
+                    .pool_cap.map(|cap| cap.saturating_sub(total_signatures));
+                let (signatures, processed, success) = self.continue_initial_backfill(
+                    pool,
+                    budget
+                ).await?;
+                total_signatures += signatures;
+                total_processed += processed;
+                total_success += success;
+            }
+            BackfillOrder::OldestFirst => {
+                let signatures = backfill_manager
+                    .initial_backfill_for_pool(pool).await
+                    .inspect_err(on_error)?;
+
+                total_signatures += signatures.len();
+                for chunk in signatures.chunks(chunk_size) {
+                    let (processed, success) = self.backfill_signature_chunk(pool, chunk).await?;
+                    total_processed += processed;
+                    total_success += success;
+                }
+
+                // Signatures are only reversed once all were collected, so progress is all or nothing
+                let stored_progress = stored_progress.unwrap_or_default();
+                if !stored_progress.backfill_complete {
+                    let progress = BackfillProgress {
+                        oldest_backfilled_signature: signatures
+                            .first()
+                            .map(|sig| sig.to_string())
+                            .or(stored_progress.oldest_backfilled_signature),
+                        backfill_complete: true,
+                    };
+                    backfill_manager.record_backfill_progress(pool, progress).await?;
+                }
+            }
+        }
+
+        if total_signatures == 0 {
+            self.log_pool_activity("Backfill", &format!("No signatures found for pool {}", pool));
         }
+
+        Ok((total_processed, total_success))
+    }
+
+    /// Page a pool's initial backfill further back in history, newest first
+    ///
+    /// Stops once `budget` transactions were processed, storing progress so the
+    /// next cycle continues from there. The budget is checked between pages, so
+    /// it can be overshot by less than one page.
+    /// Returns (signatures, processed, successful).
+    async fn continue_initial_backfill(
+        &self,
+        pool: &Pubkey,
+        budget: Option<usize>
+    ) -> Result<(usize, usize, usize)> {
+        let backfill_manager = self.backfill_manager();
+        let on_error = |e: &anyhow::Error| {
+            metrics::record_rpc_error(self.dex_name(), e);
+            self.log_error(&format!("Failed to get signatures for pool {}", pool), e);
+        };
+
+        let mut paginator = backfill_manager
+            .initial_backfill_paginator(pool).await
+            .inspect_err(on_error)?;
+
+        let mut total_signatures = 0;
+        let mut total_processed = 0;
+        let mut total_success = 0;
+        while !paginator.is_done() {
+            if budget.is_some_and(|budget| total_signatures >= budget) {
+                self.log_activity(
+                    "Pool cap reached",
+                    Some(&format!("Deferring the rest of the initial backfill for pool {}", pool))
+                );
+                break;
+            }
+
+            let signatures = backfill_manager
+                .next_signature_page(pool, &mut paginator).await
+                .inspect_err(on_error)?;
+
+            if !signatures.is_empty() {
+                total_signatures += signatures.len();
+                let (processed, success) = self.backfill_signature_chunk(pool, &signatures).await?;
+                total_processed += processed;
+                total_success += success;
+            }
+
+            backfill_manager.record_backfill_progress(pool, paginator.progress()).await?;
+        }
+
+        Ok((total_signatures, total_processed, total_success))
+    }
+
+    /// Re-index a pool's transactions inside a slot or time window, oldest first
+    ///
+    /// A one-shot backfill for a known outage window: the WebSocket subscription
+    /// is not started and the pool's stored checkpoint is not moved.
+    async fn backfill_window(
+        &self,
+        pool: &Pubkey,
+        window: &BackfillWindow
+    ) -> Result<(usize, usize)> {
+        let backfill_manager = self.backfill_manager();
+        let signatures = backfill_manager
+            .signatures_in_window(pool, window).await
+            .inspect_err(|e| {
+                metrics::record_rpc_error(self.dex_name(), e);
+                self.log_error(&format!("Failed to get signatures for pool {}", pool), e);
+            })?;
+
+        let mut total_processed = 0;
+        let mut total_success = 0;
+        let chunk_size = backfill_manager.config().max_signatures_per_request.max(1);
+        for chunk in signatures.chunks(chunk_size) {
+            let (processed, success) = self.backfill_signature_chunk(pool, chunk).await?;
+            total_processed += processed;
+            total_success += success;
+        }
+
+        self.log_processing_stats("Window backfill", total_processed, total_success);
+        Ok((total_processed, total_success))
+    }
+
+    /// Index exactly the given transactions of a pool, e.g. a list from an external source
+    ///
+    /// Duplicates and signatures whose events are already stored are skipped, so
+    /// indexing the same list twice stores each event once. Like a window backfill,
+    /// the pool's stored checkpoint is not moved.
+    async fn index_signatures(
+        &self,
+        pool: &Pubkey,
+        signatures: &[Signature]
+    ) -> Result<(usize, usize)> {
+        let mut seen = HashSet::new();
+        let mut pending: Vec<Signature> = signatures
+            .iter()
+            .filter(|signature| seen.insert(**signature))
+            .copied()
+            .collect();
+
+        // Nothing is stored in dry runs, so there is nothing to skip
+        if !self.is_dry_run() {
+            let indexed = self.indexed_signatures(&pending).await?;
+            pending.retain(|signature| !indexed.contains(signature));
+        }
+
+        self.log_activity(
+            "Signature indexing",
+            Some(
+                &format!(
+                    "Indexing {} of {} signatures for pool {}, skipping duplicates and already indexed ones",
+                    pending.len(),
+                    signatures.len(),
+                    pool
+                )
+            )
+        );
+
+        let mut total_processed = 0;
+        let mut total_success = 0;
+        let chunk_size = self.backfill_manager().config().max_signatures_per_request.max(1);
+        for chunk in pending.chunks(chunk_size) {
+            let (processed, success) = self.backfill_signature_chunk(pool, chunk).await?;
+            total_processed += processed;
+            total_success += success;
+        }
+
+        self.log_processing_stats("Signature indexing", total_processed, total_success);
+        Ok((total_processed, total_success))
+    }
+
+    /// Fetch and process one chunk of a pool's backfill signatures
+    async fn backfill_signature_chunk(
+        &self,
+        pool: &Pubkey,
+        signatures: &[Signature]
+    ) -> Result<(usize, usize)> {
         self.log_activity(
             "Transaction fetch",
             Some(&format!("Fetching {} transactions for pool {}", signatures.len(), pool))
         );
 
-        // Process the transactions and return stats
-        self.process_backfill_signatures(&signatures).await
+        self.process_backfill_signatures(signatures).await
     }
 
     /// Process a batch of signatures during backfill
+    ///
+    /// A signature listed more than once is fetched and handled once, since its
+    /// events would otherwise collide with the ones just stored. Returns the number
+    /// of transactions processed, and of those from which an event was stored.
     async fn process_backfill_signatures(
         &self,
-        signatures: &Vec<Signature>
+        signatures: &[Signature]
     ) -> Result<(usize, usize)> {
+        let mut seen = HashSet::new();
+        let unique: Vec<Signature> = signatures
+            .iter()
+            .filter(|signature| seen.insert(**signature))
+            .copied()
+            .collect();
+        if unique.len() < signatures.len() {
+            log::debug!(
+                "[{}] Skipping {} duplicate signature(s) in backfill batch",
+                self.dex_name(),
+                signatures.len() - unique.len()
+            );
+        }
+        let mut signatures = unique;
+
+        // Live events are stored while a background startup backfills, so skip
+        // the transactions they already stored
+        if
+            self.connection_config().startup_strategy == StartupStrategy::Background &&
+            !self.is_dry_run()
+        {
+            let indexed = self.indexed_signatures(&signatures).await?;
+            signatures.retain(|signature| !indexed.contains(signature));
+        }
         let total = signatures.len();
-        let mut success_count = 0;
+        let mut with_events = 0;
         let mut event_batch = Vec::new();
         let backfill_manager = self.backfill_manager();
 
-        for sig in signatures {
+        // Transactions are fetched concurrently, then handled in slot order
+        for (sig, fetched) in backfill_manager.fetch_transactions(&signatures).await {
             log::debug!("[{}] Processing backfill signature: {}", self.dex_name(), sig);
-            match backfill_manager.fetch_transaction(sig).await {
+            match fetched {
                 Ok(tx) => {
                     log::debug!("[{}] Successfully fetched transaction: {}", self.dex_name(), sig);
+                    metrics::record_backfill_transaction(self.dex_name());
 
-                    if let Some(meta) = tx.transaction.meta.clone() {
-                        log::debug!("[{}] Transaction has metadata", self.dex_name());
-
-                        if
-                            let Some(log_messages) = Into::<Option<Vec<String>>>::into(
-                                meta.log_messages
-                            )
-                        {
-                            log::debug!(
-                                "[{}] Transaction has {} log messages",
-                                self.dex_name(),
-                                log_messages.len()
-                            );
-
-                            // Sample the first few log lines for debugging
-                            if !log_messages.is_empty() {
-                                let sample_size = std::cmp::min(3, log_messages.len());
-                                for i in 0..sample_size {
-                                    log::debug!(
-                                        "[{}] Log sample {}/{}: {}",
-                                        self.dex_name(),
-                                        i + 1,
-                                        sample_size,
-                                        log_messages[i]
-                                    );
-                                }
-                            }
+                    // Old transactions can lack a block time; their events keep a NULL block_time
+                    if tx.block_time.is_none() {
+                        log::debug!("[{}] Transaction {} has no block time", self.dex_name(), sig);
+                        metrics::record_missing_block_time(self.dex_name());
+                    }
 
-                            let logs_response = self.tx_to_logs_response(
-                                &sig.to_string(),
-                                &log_messages
-                            );
+                    let events = self.parse_transaction_events(&tx, &sig.to_string()).await?;
 
-                            // Parse events from this transaction
-                            log::debug!(
-                                "[{}] Parsing events from transaction: {}",
-                                self.dex_name(),
-                                sig
-                            );
-                            let events = self.parse_log_events(&logs_response).await?;
+                    // Failed transactions still emit logs, but nothing they log happened
+                    if let Some((_, context)) = events.first() {
+                        if !context.success && self.skip_failed_transaction(&context.log) {
+                            continue;
+                        }
+                    }
+                    metrics::record_events_parsed(self.dex_name(), events.len());
 
-                            log::debug!(
-                                "[{}] Found {} events in transaction {}",
-                                self.dex_name(),
-                                events.len(),
-                                sig
-                            );
+                    log::debug!(
+                        "[{}] Found {} events in transaction {}",
+                        self.dex_name(),
+                        events.len(),
+                        sig
+                    );
 
-                            if !events.is_empty() {
-                                success_count += 1;
-                                event_batch.extend(events);
-                            } else {
-                                log::debug!(
-                                    "[{}] No events found in transaction {}",
-                                    self.dex_name(),
-                                    sig
-                                );
-                            }
-                        } else {
-                            log::debug!("[{}] Transaction has no log messages", self.dex_name());
-                        }
+                    if !events.is_empty() {
+                        with_events += 1;
+                        event_batch.extend(events);
                     }
                 }
                 Err(e) => {
+                    metrics::record_rpc_error(self.dex_name(), &e);
                     self.handle_tx_parse_error(&sig.to_string(), &e)?;
                     // Continue with next signature
                 }
@@ -595,12 +1930,15 @@ pub trait DexIndexer {
                 &format!(
                     "Processed {} transactions, found events in {} transactions, total events: {}",
                     total,
-                    success_count,
+                    with_events,
                     event_batch_len
                 )
             )
         );
 
+        // Transactions count as successful once one of their events is stored
+        let mut stored_signatures = HashSet::new();
+
         // Process each event individually
         if !event_batch.is_empty() {
             // Log that we're processing events
@@ -611,14 +1949,49 @@ pub trait DexIndexer {
 
             // Process each event individually
             let mut processed_count = 0;
-            for event in event_batch {
+            let mut logged_signatures = HashSet::new();
+            for (event, context) in event_batch {
                 // These events come from backfill, so set is_backfill to true
-                if let Err(e) = self.handle_event(event, true).await {
-                    self.log_error("Failed to process backfill event", &e);
-                    // Continue with next event
-                } else {
-                    processed_count += 1;
+                let persisted = self.persist_event(
+                    event,
+                    &context.log,
+                    context.event_index,
+                    true,
+                    Some(context.slot),
+                    context.block_time,
+                    context.fee_payer
+                ).await;
+                match persisted {
+                    Err(e) => {
+                        self.log_error("Failed to process backfill event", &e);
+                        // Continue with next event
+                        continue;
+                    }
+                    Ok(true) => {
+                        stored_signatures.insert(context.log.signature.clone());
+                    }
+                    // Quarantined, skipped or already stored
+                    Ok(false) => {
+                        continue;
+                    }
+                }
+
+                processed_count += 1;
+                // Several events can come from one transaction; store its logs once
+                if logged_signatures.insert(context.log.signature.clone()) {
+                    self.save_transaction_logs(&context.log, Some(context.slot)).await;
                 }
+                self.health_tracker().record_event();
+                if !self.is_dry_run() {
+                    metrics::record_event_persisted(self.dex_name());
+                }
+            }
+
+            if self.is_dry_run() {
+                self.log_activity(
+                    "Dry run",
+                    Some(&format!("{} backfill events would have been written", processed_count))
+                );
             }
 
             log::debug!(
@@ -631,22 +2004,139 @@ pub trait DexIndexer {
             log::debug!("[{}] No events to process from {} transactions", self.dex_name(), total);
         }
 
-        Ok((total, success_count))
+        Ok((total, stored_signatures.len()))
+    }
+
+    /// Roll back a pool whose last signature was dropped by a reorg
+    ///
+    /// Events from `REORG_ROLLBACK_SLOTS` slots before the dropped signature on are
+    /// deleted, and the pool's history since then is re-indexed, moving its checkpoint
+    /// to the newest signature. Returns (processed, successful), or None when the
+    /// dropped signature was stored without a slot, so there is nothing to roll back to.
+    async fn roll_back_pool(
+        &self,
+        pool: &Pubkey,
+        dropped_signature: &str
+    ) -> Result<Option<(usize, usize)>> {
+        let backfill_manager = self.backfill_manager();
+        let Some(dropped_slot) = backfill_manager.last_signature_slot(pool).await? else {
+            self.log_activity(
+                "Reorg rollback skipped",
+                Some(
+                    &format!(
+                        "Last signature {} of pool {} is no longer on chain, but its slot is unknown",
+                        dropped_signature,
+                        pool
+                    )
+                )
+            );
+            return Ok(None);
+        };
+
+        let last_kept_slot = dropped_slot.saturating_sub(REORG_ROLLBACK_SLOTS);
+        let deleted = if self.is_dry_run() {
+            0
+        } else {
+            self.delete_events_after_slot(pool, last_kept_slot).await?
+        };
+        metrics::record_reorg_rollback(self.dex_name());
+        self.log_activity(
+            "Reorg rollback",
+            Some(
+                &format!(
+                    "Last signature {} of pool {} in slot {} is no longer on chain, deleted {} events after slot {}",
+                    dropped_signature,
+                    pool,
+                    dropped_slot,
+                    deleted,
+                    last_kept_slot
+                )
+            )
+        );
+
+        let signatures = backfill_manager.signatures_after_slot(pool, last_kept_slot).await?;
+        self.index_signatures(pool, &signatures).await.map(Some)
     }
 
     /// Handle periodic/scheduled backfill operations
     async fn perform_scheduled_backfill(&self) -> Result<()> {
+        // Pools seen so far are only part of the program's pools, so none are backfilled
+        if self.indexes_all_pools() {
+            return Ok(());
+        }
         self.log_activity("Running scheduled backfill", None);
 
         let mut total_processed = 0;
         let mut total_success = 0;
         let backfill_manager = self.backfill_manager();
+        backfill_manager.set_pool_logging(self.pool_count() <= MAX_LOGGED_POOLS);
+
+        // Backfill recently active pools first
+        let pools = match backfill_manager.prioritize_pools(&self.pool_pubkeys()).await {
+            Ok(pools) => pools,
+            Err(e) => {
+                self.log_error("Failed to order pools by recent activity", &e);
+                self.pool_pubkeys().into_iter().collect()
+            }
+        };
+
+        // Read every pool's last signature up front instead of one query per pool. Only
+        // the pool's own backfill below moves it, so it can't go stale during the cycle
+        let last_signatures = match backfill_manager.last_signatures(&pools).await {
+            Ok(last_signatures) => Some(last_signatures),
+            Err(e) => {
+                self.log_error("Failed to read last signatures, reading them per pool", &e);
+                None
+            }
+        };
+
+        // Check that every last signature is still on chain before paging back to it
+        let dropped_pools = match &last_signatures {
+            Some(last_signatures) =>
+                match backfill_manager.pools_with_dropped_signatures(last_signatures).await {
+                    Ok(pools) => pools,
+                    Err(e) => {
+                        self.log_error("Failed to check last signatures for reorgs", &e);
+                        HashSet::new()
+                    }
+                }
+            None => HashSet::new(),
+        };
+
+        for pool in &pools {
+            // Roll back pools whose last signature was dropped, re-indexing them up to now
+            let dropped_signature = last_signatures
+                .as_ref()
+                .and_then(|last_signatures| last_signatures.get(pool))
+                .filter(|_| dropped_pools.contains(pool));
+            if let Some(dropped_signature) = dropped_signature {
+                match self.roll_back_pool(pool, dropped_signature).await {
+                    Ok(Some((processed, success))) => {
+                        total_processed += processed;
+                        total_success += success;
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.log_error(&format!("Failed to roll back pool {}", pool), &e);
+                        continue;
+                    }
+                }
+            }
 
-        for pool in self.pool_pubkeys() {
             // Get signatures since last processed
-            let signatures = match backfill_manager.backfill_since_last_signature(pool).await {
+            let backfill = match &last_signatures {
+                Some(last_signatures) =>
+                    backfill_manager.backfill_since_signature(
+                        pool,
+                        last_signatures.get(pool).cloned()
+                    ).await,
+                None => backfill_manager.backfill_since_last_signature(pool).await,
+            };
+            let signatures = match backfill {
                 Ok(sigs) => sigs,
                 Err(e) => {
+                    metrics::record_rpc_error(self.dex_name(), &e);
                     self.log_error(
                         &format!("Failed to get recent signatures for pool {}", pool),
                         &e
@@ -655,22 +2145,47 @@ pub trait DexIndexer {
                 }
             };
 
-            if signatures.is_empty() {
-                continue;
+            // Process these signatures in page-sized chunks to bound the transactions held at once
+            let chunk_size = backfill_manager.config().max_signatures_per_request.max(1);
+            for chunk in signatures.chunks(chunk_size) {
+                match self.process_backfill_signatures(chunk).await {
+                    Ok((processed, success)) => {
+                        total_processed += processed;
+                        total_success += success;
+                    }
+                    Err(e) => {
+                        self.log_error(
+                            &format!("Error processing scheduled backfill for pool {}", pool),
+                            &e
+                        );
+                        // Continue with next pool
+                        break;
+                    }
+                }
             }
 
-            // Process these signatures
-            match self.process_backfill_signatures(&signatures).await {
-                Ok((processed, success)) => {
-                    total_processed += processed;
-                    total_success += success;
+            // Keep paging older history for pools whose initial backfill was deferred
+            match backfill_manager.backfill_progress(pool).await {
+                Ok(Some(progress)) if !progress.backfill_complete => {
+                    let budget = backfill_manager
+                        .config()
+                        .pool_cap.map(|cap| cap.saturating_sub(signatures.len()));
+                    match self.continue_initial_backfill(pool, budget).await {
+                        Ok((_, processed, success)) => {
+                            total_processed += processed;
+                            total_success += success;
+                        }
+                        Err(e) => {
+                            self.log_error(
+                                &format!("Error continuing initial backfill for pool {}", pool),
+                                &e
+                            );
+                        }
+                    }
                 }
+                Ok(_) => {}
                 Err(e) => {
-                    self.log_error(
-                        &format!("Error processing scheduled backfill for pool {}", pool),
-                        &e
-                    );
-                    // Continue with next pool
+                    self.log_error(&format!("Failed to load backfill progress for pool {}", pool), &e);
                 }
             }
         }
@@ -678,14 +2193,77 @@ pub trait DexIndexer {
         if total_processed > 0 {
             self.log_processing_stats("Scheduled backfill", total_processed, total_success);
         }
+        self.health_tracker().record_backfill();
 
         Ok(())
     }
 
+    /// Re-check each pool's transactions from the last `reconciliation_window`
+    ///
+    /// The scheduled backfill only fetches transactions after each pool's last
+    /// signature, so a transaction whose events failed to persist before a later
+    /// one was stored is never retried. This pass lists every transaction in the
+    /// window and indexes those without stored events, skipping the rest, so it
+    /// can run any number of times without duplicating events. Returns the number
+    /// of transactions indexed, and of those whose events were actually stored.
+    async fn perform_reconciliation(&self) -> Result<(usize, usize)> {
+        // Nothing is stored in dry runs, and all-pool indexing has no fixed pool list
+        if self.indexes_all_pools() || self.is_dry_run() {
+            return Ok((0, 0));
+        }
+
+        let window_secs = self.connection_config().reconciliation_window.as_secs() as i64;
+        let now = chrono::Utc::now().timestamp();
+        let window = BackfillWindow::BlockTime { from: now - window_secs, to: now };
+        self.log_activity(
+            "Running reconciliation",
+            Some(&format!("Re-checking the last {}s of transactions", window_secs))
+        );
+
+        let mut total_signatures = 0;
+        let mut total_processed = 0;
+        let mut total_success = 0;
+        for pool in self.pool_pubkeys() {
+            let signatures = match self.backfill_manager().signatures_in_window(&pool, &window).await {
+                Ok(signatures) => signatures,
+                Err(e) => {
+                    metrics::record_rpc_error(self.dex_name(), &e);
+                    self.log_error(&format!("Failed to get signatures for pool {}", pool), &e);
+                    continue;
+                }
+            };
+            total_signatures += signatures.len();
+
+            match self.index_signatures(&pool, &signatures).await {
+                Ok((processed, success)) => {
+                    total_processed += processed;
+                    total_success += success;
+                }
+                Err(e) => {
+                    self.log_error(&format!("Error reconciling pool {}", pool), &e);
+                }
+            }
+        }
+
+        self.log_activity(
+            "Reconciliation complete",
+            Some(
+                &format!(
+                    "{} transactions checked, {} had no stored events, events stored from {}",
+                    total_signatures,
+                    total_processed,
+                    total_success
+                )
+            )
+        );
+        metrics::record_reconciled_transactions(self.dex_name(), total_success);
+        Ok((total_processed, total_success))
+    }
+
     /// Process events that were buffered during backfill
     async fn process_buffered_events(
         &self,
-        event_buffer: Arc<Mutex<Vec<RpcLogsResponse>>>,
+        event_buffer: Arc<Mutex<Vec<Response<RpcLogsResponse>>>>,
         is_backfilling: Arc<AtomicBool>,
         buffer_task: JoinHandle<()>
     ) -> Result<()> {
@@ -701,10 +2279,52 @@ pub trait DexIndexer {
         let buffered_events = event_buffer.lock().await;
         let count = buffered_events.len();
 
-        self.log_activity(&format!("Processing {} buffered events", count), None);
+        // A log can be buffered twice, or its transaction already stored by the backfill
+        let mut seen = HashSet::new();
+        let mut pending: Vec<_> = buffered_events
+            .iter()
+            .filter(|event| seen.insert(event.value.signature.clone()))
+            .collect();
+        if !self.is_dry_run() {
+            // A log is skipped only once every event parsed from it is stored
+            let mut log_keys = Vec::with_capacity(pending.len());
+            for event in &pending {
+                let keys: Vec<(Signature, usize)> = match
+                    (event.value.signature.parse(), self.parse_indexed_log_events(&event.value).await)
+                {
+                    (Ok(signature), Ok(events)) =>
+                        events
+                            .iter()
+                            .map(|(index, _)| (signature, *index))
+                            .collect(),
+                    _ => Vec::new(),
+                };
+                log_keys.push(keys);
+            }
+            let all_keys: Vec<(Signature, usize)> = log_keys.iter().flatten().copied().collect();
+            match self.indexed_events(&all_keys).await {
+                Ok(indexed) => {
+                    let mut log_keys = log_keys.into_iter();
+                    pending.retain(|_| {
+                        let keys = log_keys.next().unwrap_or_default();
+                        keys.is_empty() || !keys.iter().all(|key| indexed.contains(key))
+                    });
+                }
+                Err(e) => self.log_error("Failed to look up indexed buffered events", &e),
+            }
+        }
+
+        self.log_activity(
+            &format!(
+                "Processing {} buffered events ({} duplicate or already indexed)",
+                pending.len(),
+                count - pending.len()
+            ),
+            None
+        );
 
-        for event in buffered_events.iter() {
-            if let Err(e) = self.process_log(event).await {
+        for event in pending {
+            if let Err(e) = self.process_log(&event.value, Some(event.context.slot)).await {
                 self.log_error("Error processing buffered event", &e);
                 // Continue processing instead of returning the error
             }
@@ -713,34 +2333,173 @@ pub trait DexIndexer {
         Ok(())
     }
 
+    /// Handle a live log, either immediately or through the confirmation buffer
+    async fn process_live_log(
+        &self,
+        response: Response<RpcLogsResponse>,
+        confirmation_buffer: &mut ConfirmationBuffer
+    ) -> Result<()> {
+        if confirmation_buffer.is_disabled() {
+            return self.process_log(&response.value, Some(response.context.slot)).await;
+        }
+
+        confirmation_buffer.push(response.context.slot, response.value);
+        self.release_confirmed_events(confirmation_buffer).await
+    }
+
+    /// Persist buffered events that have aged past the confirmation delay
+    ///
+    /// Each ready event's signature is checked against the cluster first. Events
+    /// whose transaction is no longer known (rolled back) or failed are dropped.
+    async fn release_confirmed_events(
+        &self,
+        confirmation_buffer: &mut ConfirmationBuffer
+    ) -> Result<()> {
+        let ready = confirmation_buffer.drain_ready();
+        if ready.is_empty() {
+            return Ok(());
+        }
+
+        let signatures = ready
+            .iter()
+            .map(|event| event.log.signature.parse::<Signature>())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let (landed, vanished) = match
+            self.backfill_manager().get_signature_statuses(&signatures).await
+        {
+            Ok(statuses) => confirmation_buffer::split_vanished(ready, &statuses),
+            Err(e) => {
+                // Without statuses we can't tell, so fall back to persisting everything
+                metrics::record_rpc_error(self.dex_name(), &e);
+                self.log_error("Failed to reconcile buffered events", &e);
+                (ready, Vec::new())
+            }
+        };
+
+        for event in vanished {
+            self.log_activity(
+                "Dropped unconfirmed event",
+                Some(&format!("{} (slot {})", event.log.signature, event.slot))
+            );
+        }
+
+        for event in landed {
+            if let Err(e) = self.process_log(&event.log, Some(event.slot)).await {
+                self.log_error("Error processing confirmed event", &e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Advance the confirmation buffer using the cluster's current slot
+    async fn refresh_confirmation_slot(
+        &self,
+        confirmation_buffer: &mut ConfirmationBuffer
+    ) -> Result<()> {
+        let slot = self
+            .backfill_manager()
+            .get_current_slot().await
+            .inspect_err(|e| metrics::record_rpc_error(self.dex_name(), e))?;
+        confirmation_buffer.observe_slot(slot);
+        self.release_confirmed_events(confirmation_buffer).await
+    }
+
     /// Main event processing loop with periodic backfill
     async fn run_main_event_loop(&self, ws_manager: WebSocketManager) -> Result<()> {
         // We need a new WebSocket subscription for the main processing loop
         self.log_activity("Starting main event processing loop", None);
+        let mut reconnects = ws_manager.reconnects();
         let mut rx_main = ws_manager.start_subscription().await?;
 
-        // Setup backfill interval (every 5 minutes)
-        let mut backfill_interval = interval(Duration::from_secs(300));
+        // Setup the scheduled backfill interval (every 5 minutes by default)
+        let mut backfill_interval = interval(self.connection_config().backfill_interval);
+
+        // Re-check a wider window now and then (disabled unless an interval is set).
+        // The initial backfill just ran, so the first pass waits a full interval
+        let reconciliation_period = self.connection_config().reconciliation_interval;
+        let period = reconciliation_period.unwrap_or(DEFAULT_RECONCILIATION_WINDOW);
+        let mut reconciliation_interval = interval_at(Instant::now() + period, period);
 
         // Track the last time we detected a connection issue
         let mut last_backfill = std::time::Instant::now();
 
+        // Learn the stream's usual gaps so quiet pools don't look stale
+        let mut staleness = StalenessDetector::new(self.connection_config().staleness);
+
+        // Hold live events until they are old enough (disabled when the delay is 0)
+        let mut confirmation_buffer = ConfirmationBuffer::new(
+            self.connection_config().confirmation_delay_slots
+        );
+        if !confirmation_buffer.is_disabled() {
+            self.log_activity(
+                "Confirmation delay enabled",
+                Some(&format!("{} slots", confirmation_buffer.delay_slots()))
+            );
+        }
+
+        // Poll the current slot so buffered events are released when the stream is quiet
+        let mut confirmation_interval = interval(Duration::from_secs(2));
+
+        // Check whether the database is back while events are held for it
+        let mut outage_interval = interval(Duration::from_secs(5));
+
         loop {
             select! {
                 // Process incoming WebSocket messages
                 Some(log_response) = rx_main.recv() => {
-                    if let Err(e) = self.process_log(&log_response).await {
+                    staleness.record_message(std::time::Instant::now());
+                    if let Err(e) = self.process_live_log(log_response, &mut confirmation_buffer).await {
                         self.log_error("Error processing WebSocket log", &e);
                         // Continue processing instead of stopping the indexer
                     }
                 }
+
+                // Release buffered events once they have aged past the delay
+                _ = confirmation_interval.tick(), if !confirmation_buffer.is_empty() => {
+                    if let Err(e) = self.refresh_confirmation_slot(&mut confirmation_buffer).await {
+                        self.log_error("Error releasing buffered events", &e);
+                    }
+                }
                 
+                // Replay events held while the database was unreachable
+                _ = outage_interval.tick(), if !self.outage_buffer().is_empty() => {
+                    self.replay_held_events().await;
+                }
+
+                // Catch up on logs missed while the WebSocket was disconnected
+                Ok(()) = reconnects.changed() => {
+                    reconnects.borrow_and_update();
+                    self.log_activity("WebSocket reconnected, running backfill", None);
+                    metrics::record_reconnect_backfill(self.dex_name());
+                    if let Err(e) = self.perform_scheduled_backfill().await {
+                        self.log_error("Error during reconnect backfill", &e);
+                    }
+                    last_backfill = std::time::Instant::now();
+                }
+
+                // Heal gaps the scheduled backfill can't see
+                _ = reconciliation_interval.tick(), if reconciliation_period.is_some() => {
+                    if let Err(e) = self.perform_reconciliation().await {
+                        self.log_error("Error during reconciliation", &e);
+                    }
+                }
+
                 // Periodically check for missed transactions
                 _ = backfill_interval.tick() => {
                     if let Some(elapsed) = ws_manager.time_since_last_received() {
-                        if elapsed > Duration::from_secs(60) {
-                            self.log_activity("WebSocket connection seems stale, running backfill", 
-                                            Some(&format!("No messages for {}s", elapsed.as_secs())));
+                        if staleness.is_stale(elapsed) {
+                            self.log_activity(
+                                "WebSocket connection seems stale, running backfill",
+                                Some(
+                                    &format!(
+                                        "No messages for {}s (threshold {}s)",
+                                        elapsed.as_secs(),
+                                        staleness.threshold().as_secs()
+                                    )
+                                )
+                            );
                             
                             // If it's been more than 2 minutes since our last backfill, do another one
                             if last_backfill.elapsed() > Duration::from_secs(120) {
@@ -1,14 +1,12 @@
 pub mod dex_indexer;
 pub mod orca;
-///pub mod raydium;
+pub mod pool_set;
+pub mod raydium;
 
 pub use dex_indexer::*;
 pub use orca::*;
-///pub use raydium::*;
-
-// Future protocol indexers will be added here
-// pub mod raydium;
-// pub use raydium::*;
+pub use pool_set::*;
+pub use raydium::*;
 
 use anyhow::Result;
 
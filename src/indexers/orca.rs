@@ -1,19 +1,60 @@
-use anyhow::Result;
+use anyhow::{ Context, Result };
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use borsh::BorshDeserialize;
-use solana_client::rpc_response::RpcLogsResponse;
+use solana_account_decoder::UiAccount;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_response::{ Response, RpcLogsResponse };
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
-use std::collections::HashSet;
+use solana_sdk::signature::Signature;
+use std::collections::{ HashMap, HashSet };
+use std::str::FromStr;
+use std::sync::{ Arc, Mutex };
 use sqlx::PgPool;
+use tokio::sync::broadcast;
 
-use crate::db::repositories::OrcaWhirlpoolRepository;
-use crate::db::DbSignatureStore;
+use crate::backfill_manager::RetryConfig;
+use crate::db::repositories::{ OrcaEventWriter, OrcaWhirlpoolRepository };
+use crate::db::{ DbSignatureStore, InMemorySignatureStore, QuarantinedEvent };
+use crate::enrichment::{ enrich_event, EnrichmentFailurePolicy, EventEnricher, IdentityEnricher };
+use crate::health::HealthTracker;
+use crate::outage_buffer::OutageBuffer;
+use crate::metrics;
+use crate::events::{
+    EventSource,
+    IndexedEvent,
+    OrcaLiquidityChange,
+    OrcaPoolInitialization,
+    OrcaTrade,
+    PoolStateUpdate,
+    RewardEmission,
+};
 use crate::indexers::dex_indexer::DexIndexer;
+use crate::sinks::EventSink;
+use crate::utils::logging;
+use crate::utils::price::{ sqrt_price_to_price, ui_amount };
+use crate::validation::{
+    validate_orca_liquidity,
+    validate_orca_pool_initialized,
+    validate_orca_trade,
+    LiquidityChange,
+    ValidationMode,
+    Violation,
+};
+use crate::models::common::Protocol;
+use crate::models::orca::account_layout::parse_whirlpool_account;
 use crate::models::orca::whirlpool::{
     TRADED_EVENT_DISCRIMINATOR,
     LIQUIDITY_INCREASED_DISCRIMINATOR,
     LIQUIDITY_DECREASED_DISCRIMINATOR,
+    POOL_INITIALIZED_DISCRIMINATOR,
     OrcaWhirlpoolEventType,
     OrcaWhirlpoolEvent,
+    OrcaWhirlpoolPoolInitializedEvent,
+    OrcaWhirlpoolPoolInitializedEventRecord,
+    OrcaWhirlpoolPoolInitializedRecord,
+    OrcaWhirlpoolPoolRecord,
     OrcaWhirlpoolTradedEvent,
     OrcaWhirlpoolLiquidityIncreasedEvent,
     OrcaWhirlpoolLiquidityDecreasedEvent,
@@ -25,30 +66,595 @@ use crate::models::orca::whirlpool::{
 };
 use crate::{ BackfillConfig, BackfillManager, SignatureStore };
 
-use super::ConnectionConfig;
+use super::{ ConnectionConfig, PoolSet };
+
+const PROTOCOL: Protocol = Protocol::OrcaWhirlpool;
+
+// Borsh-encoded sizes of the version 1 event payloads, after the discriminator
+const TRADED_EVENT_LEN: usize = 32 * 5 + 1 + 8 * 6 + 16 * 2;
+const LIQUIDITY_EVENT_LEN: usize = 32 * 2 + 4 * 2 + 16 + 8 * 4;
+const POOL_INITIALIZED_EVENT_LEN: usize = 32 * 4 + 2 + 32 * 2 + 2 + 16;
+
+// Known event layouts: discriminator, event type, layout version and exact payload
+// size. All fields are fixed-size, so a change to an event's fields changes its size
+const EVENT_LAYOUTS: [([u8; 8], OrcaWhirlpoolEventType, i32, usize); 4] = [
+    (TRADED_EVENT_DISCRIMINATOR, OrcaWhirlpoolEventType::Traded, 1, TRADED_EVENT_LEN),
+    (
+        LIQUIDITY_INCREASED_DISCRIMINATOR,
+        OrcaWhirlpoolEventType::LiquidityIncreased,
+        1,
+        LIQUIDITY_EVENT_LEN,
+    ),
+    (
+        LIQUIDITY_DECREASED_DISCRIMINATOR,
+        OrcaWhirlpoolEventType::LiquidityDecreased,
+        1,
+        LIQUIDITY_EVENT_LEN,
+    ),
+    (
+        POOL_INITIALIZED_DISCRIMINATOR,
+        OrcaWhirlpoolEventType::PoolInitialized,
+        1,
+        POOL_INITIALIZED_EVENT_LEN,
+    ),
+];
+
+// Type of the Orca event with the given discriminator, None for other program data
+fn event_type_of(discriminator: &[u8]) -> Option<OrcaWhirlpoolEventType> {
+    EVENT_LAYOUTS.iter()
+        .find(|(layout, ..)| discriminator == layout)
+        .map(|(_, event_type, ..)| event_type.clone())
+}
+
+/// Layout version of an event payload (the bytes after the discriminator)
+///
+/// Returns None for unknown discriminators and for payloads matching none of the
+/// event's known layouts, such as one Orca extended after this indexer was built,
+/// or one cut off by log truncation.
+pub fn parse_version(discriminator: &[u8], payload: &[u8]) -> Option<i32> {
+    EVENT_LAYOUTS.iter()
+        .find(|(layout, _, _, len)| discriminator == layout && payload.len() == *len)
+        .map(|(_, _, version, _)| *version)
+}
+
+/// An Orca event whose payload matches none of the known layouts of its type
+///
+/// Such events can't be decoded safely, so they are quarantined rather than indexed.
+#[derive(Debug, Clone)]
+pub struct UnknownLayoutEvent {
+    pub event_type: OrcaWhirlpoolEventType,
+    /// Read from the start of the payload, where every Orca event has its pool; the
+    /// default address when the payload is too short to hold one
+    pub whirlpool: Pubkey,
+    /// Discriminator and payload, as they appeared in the logs
+    pub data: Vec<u8>,
+}
 
-// Default Orca pool (SOL/USDC)
-const DEFAULT_ORCA_POOL: &str = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
-const DEX: &str = "orca";
+impl UnknownLayoutEvent {
+    fn new(event_type: OrcaWhirlpoolEventType, data: &[u8]) -> Self {
+        let whirlpool = data
+            .get(8..40)
+            .and_then(|key| Pubkey::try_from(key).ok())
+            .unwrap_or_default();
+        Self { event_type, whirlpool, data: data.to_vec() }
+    }
+
+    fn violation(&self) -> Violation {
+        let known_lens: Vec<String> = EVENT_LAYOUTS.iter()
+            .filter(|(layout, ..)| self.data.starts_with(layout))
+            .map(|(_, _, version, len)| format!("{} bytes in version {}", len, version))
+            .collect();
+        Violation::new(
+            "unknown_layout",
+            format!(
+                "{}-byte {} payload matches no known layout ({}); newer layout or truncated log?",
+                self.data.len() - 8,
+                self.event_type.to_string(),
+                known_lens.join(", ")
+            )
+        )
+    }
+}
 
 /// Represents a parsed event from Orca Whirlpool logs
+///
+/// Decoded events carry the signature of their transaction and the layout version
+/// their payload matched.
 #[derive(Debug)]
 pub enum OrcaWhirlpoolParsedEvent {
-    Traded(OrcaWhirlpoolTradedEvent, String), // Event and signature
-    LiquidityIncreased(OrcaWhirlpoolLiquidityIncreasedEvent, String),
-    LiquidityDecreased(OrcaWhirlpoolLiquidityDecreasedEvent, String),
+    Traded(OrcaWhirlpoolTradedEvent, String, i32), // Event, signature and layout version
+    LiquidityIncreased(OrcaWhirlpoolLiquidityIncreasedEvent, String, i32),
+    LiquidityDecreased(OrcaWhirlpoolLiquidityDecreasedEvent, String, i32),
+    PoolInitialized(OrcaWhirlpoolPoolInitializedEvent, String, i32),
+    UnknownLayout(UnknownLayoutEvent, String),
+}
+
+impl OrcaWhirlpoolParsedEvent {
+    /// Pool of an event on an existing pool; None for pool creation
+    fn pool_event_whirlpool(&self) -> Option<Pubkey> {
+        match self {
+            Self::Traded(event, ..) => Some(event.whirlpool),
+            Self::LiquidityIncreased(event, ..) => Some(event.whirlpool),
+            Self::LiquidityDecreased(event, ..) => Some(event.whirlpool),
+            Self::PoolInitialized(..) | Self::UnknownLayout(..) => None,
+        }
+    }
+
+    /// Pool the event belongs to, including the new pool of a pool creation
+    fn whirlpool(&self) -> Pubkey {
+        match self {
+            Self::Traded(event, ..) => event.whirlpool,
+            Self::LiquidityIncreased(event, ..) => event.whirlpool,
+            Self::LiquidityDecreased(event, ..) => event.whirlpool,
+            Self::PoolInitialized(event, ..) => event.whirlpool,
+            Self::UnknownLayout(event, _) => event.whirlpool,
+        }
+    }
+
+    fn signature(&self) -> &str {
+        match self {
+            Self::Traded(_, signature, _) |
+            Self::LiquidityIncreased(_, signature, _) |
+            Self::LiquidityDecreased(_, signature, _) |
+            Self::PoolInitialized(_, signature, _) |
+            Self::UnknownLayout(_, signature) => signature,
+        }
+    }
+
+    fn event_type(&self) -> OrcaWhirlpoolEventType {
+        match self {
+            Self::Traded(..) => OrcaWhirlpoolEventType::Traded,
+            Self::LiquidityIncreased(..) => OrcaWhirlpoolEventType::LiquidityIncreased,
+            Self::LiquidityDecreased(..) => OrcaWhirlpoolEventType::LiquidityDecreased,
+            Self::PoolInitialized(..) => OrcaWhirlpoolEventType::PoolInitialized,
+            Self::UnknownLayout(event, _) => event.event_type.clone(),
+        }
+    }
+
+    /// The event's program data, discriminator included, as it appeared in the logs
+    ///
+    /// Borsh encodes each value one way, so writing the fields back in layout order
+    /// reproduces the logged bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        match self {
+            Self::Traded(event, ..) => {
+                data.extend_from_slice(&TRADED_EVENT_DISCRIMINATOR);
+                for key in [
+                    &event.whirlpool,
+                    &event.token_vault_a,
+                    &event.token_vault_b,
+                    &event.tick_array_lower,
+                    &event.tick_array_upper,
+                ] {
+                    data.extend_from_slice(key.as_ref());
+                }
+                data.push(event.a_to_b as u8);
+                for amount in [
+                    event.input_amount,
+                    event.output_amount,
+                    event.input_transfer_fee,
+                    event.output_transfer_fee,
+                    event.protocol_fee,
+                    event.lp_fee,
+                ] {
+                    data.extend_from_slice(&amount.to_le_bytes());
+                }
+                data.extend_from_slice(&event.pre_sqrt_price.to_le_bytes());
+                data.extend_from_slice(&event.post_sqrt_price.to_le_bytes());
+            }
+            Self::LiquidityIncreased(event, ..) => {
+                data.extend_from_slice(&LIQUIDITY_INCREASED_DISCRIMINATOR);
+                encode_liquidity_change(
+                    &mut data,
+                    [&event.whirlpool, &event.position],
+                    [event.tick_lower_index, event.tick_upper_index],
+                    event.liquidity,
+                    [
+                        event.token_a_amount,
+                        event.token_b_amount,
+                        event.token_a_transfer_fee,
+                        event.token_b_transfer_fee,
+                    ]
+                );
+            }
+            Self::LiquidityDecreased(event, ..) => {
+                data.extend_from_slice(&LIQUIDITY_DECREASED_DISCRIMINATOR);
+                encode_liquidity_change(
+                    &mut data,
+                    [&event.whirlpool, &event.position],
+                    [event.tick_lower_index, event.tick_upper_index],
+                    event.liquidity,
+                    [
+                        event.token_a_amount,
+                        event.token_b_amount,
+                        event.token_a_transfer_fee,
+                        event.token_b_transfer_fee,
+                    ]
+                );
+            }
+            Self::PoolInitialized(event, ..) => {
+                data.extend_from_slice(&POOL_INITIALIZED_DISCRIMINATOR);
+                for key in [
+                    &event.whirlpool,
+                    &event.whirlpools_config,
+                    &event.token_mint_a,
+                    &event.token_mint_b,
+                ] {
+                    data.extend_from_slice(key.as_ref());
+                }
+                data.extend_from_slice(&event.tick_spacing.to_le_bytes());
+                data.extend_from_slice(event.token_program_a.as_ref());
+                data.extend_from_slice(event.token_program_b.as_ref());
+                data.push(event.decimals_a);
+                data.push(event.decimals_b);
+                data.extend_from_slice(&event.initial_sqrt_price.to_le_bytes());
+            }
+            Self::UnknownLayout(event, _) => data.extend_from_slice(&event.data),
+        }
+        data
+    }
+
+    /// Layout version the event was decoded from, stored with the event; None
+    /// for events of unknown layouts, which aren't decoded
+    pub fn version(&self) -> Option<i32> {
+        match self {
+            Self::Traded(_, _, version) |
+            Self::LiquidityIncreased(_, _, version) |
+            Self::LiquidityDecreased(_, _, version) |
+            Self::PoolInitialized(_, _, version) => Some(*version),
+            Self::UnknownLayout(..) => None,
+        }
+    }
+}
+
+// Write the fields shared by LiquidityIncreased and LiquidityDecreased in layout order
+fn encode_liquidity_change(
+    data: &mut Vec<u8>,
+    keys: [&Pubkey; 2],
+    ticks: [i32; 2],
+    liquidity: u128,
+    amounts: [u64; 4]
+) {
+    for key in keys {
+        data.extend_from_slice(key.as_ref());
+    }
+    for tick in ticks {
+        data.extend_from_slice(&tick.to_le_bytes());
+    }
+    data.extend_from_slice(&liquidity.to_le_bytes());
+    for amount in amounts {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
 }
 
 /// Orca Whirlpool event indexer
 pub struct OrcaWhirlpoolIndexer {
     repository: OrcaWhirlpoolRepository,
-    pool_pubkeys: HashSet<Pubkey>,
+    event_writer: Box<dyn OrcaEventWriter>,
+    pool_pubkeys: PoolSet,
     signature_store: SignatureStore,
     backfill_manager: BackfillManager,
     connection_config: ConnectionConfig,
+    event_sender: broadcast::Sender<IndexedEvent>,
+    pool_state_sender: broadcast::Sender<PoolStateUpdate>,
+    sinks: Vec<Box<dyn EventSink>>,
+    enricher: Box<dyn EventEnricher>,
+    enrichment_failure_policy: EnrichmentFailurePolicy,
+    token_decimals: Mutex<HashMap<Pubkey, (u8, u8)>>,
+    auto_subscribe_mints: Option<HashSet<Pubkey>>,
+    all_pools: bool,
+    health: Arc<HealthTracker>,
+    outage_buffer: OutageBuffer,
 }
 
 impl OrcaWhirlpoolIndexer {
+    /// Subscribe to events as they are indexed
+    ///
+    /// A subscriber that falls more than the channel capacity behind skips the
+    /// oldest events and gets `RecvError::Lagged` with the number it missed.
+    pub fn subscribe(&self) -> broadcast::Receiver<IndexedEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Subscribe to pool state updates published by `track_pool_state`
+    pub fn subscribe_pool_state(&self) -> broadcast::Receiver<PoolStateUpdate> {
+        self.pool_state_sender.subscribe()
+    }
+
+    /// Write indexed events to additional sinks (e.g. a webhook) alongside the database
+    pub fn with_sinks(mut self, sinks: Vec<Box<dyn EventSink>>) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// Write parsed events through `event_writer` instead of to the database
+    ///
+    /// Pools, token decimals and signatures are still read from and written to the
+    /// database. Tests pass an `InMemoryOrcaEventWriter` to check what was written.
+    pub fn with_event_writer(mut self, event_writer: Box<dyn OrcaEventWriter>) -> Self {
+        self.event_writer = event_writer;
+        self
+    }
+
+    /// Derive extra fields for each event before it is persisted
+    ///
+    /// `policy` decides whether an event whose enrichment fails is dropped or
+    /// stored without the derived fields. Without this, no fields are added.
+    pub fn with_enricher(
+        mut self,
+        enricher: Box<dyn EventEnricher>,
+        policy: EnrichmentFailurePolicy
+    ) -> Self {
+        self.enricher = enricher;
+        self.enrichment_failure_policy = policy;
+        self
+    }
+
+    /// Automatically monitor newly created pools whose tokens are both in `mints`
+    ///
+    /// Pools are picked up from their PoolInitialized event, saved to the database
+    /// and added to the live pool set. Without this, pool creation is ignored.
+    pub fn with_auto_subscribe(mut self, mints: HashSet<Pubkey>) -> Self {
+        self.auto_subscribe_mints = Some(mints);
+        self
+    }
+
+    /// Send RPC calls through `rpc_client` instead of a client for the configured RPC URL
+    ///
+    /// Lets tests drive backfills against canned responses, with a client built by
+    /// `RpcClient::new_sender`.
+    pub fn with_rpc_client(mut self, rpc_client: RpcClient) -> Self {
+        self.backfill_manager = BackfillManager::with_rpc_client(
+            self.backfill_manager.config().clone(),
+            self.signature_store.clone(),
+            rpc_client
+        );
+        self
+    }
+
+    /// Index events of every Whirlpool instead of only the monitored pools
+    ///
+    /// Each pool is stored, by address only, the first time one of its events is
+    /// seen. Backfills are skipped, since the program's pools aren't known up front.
+    pub fn with_all_pools(mut self) -> Self {
+        self.all_pools = true;
+        self
+    }
+
+    /// Start monitoring a pool seen while indexing all pools, storing it if it is new
+    async fn register_seen_pool(&self, pool: &Pubkey) -> Result<()> {
+        if !self.all_pools || self.pool_pubkeys.contains(pool) {
+            return Ok(());
+        }
+
+        let inserted = if self.is_dry_run() {
+            false
+        } else {
+            self.repository.insert_pool_if_missing(&pool.to_string()).await?
+        };
+        self.add_pool(*pool);
+        if inserted {
+            self.log_pool_activity("Discovered pool", &pool.to_string());
+        }
+        Ok(())
+    }
+
+    /// Track the monitored pools' price and liquidity through account subscriptions
+    ///
+    /// Runs until the subscription stops, decoding every change to a pool account and
+    /// publishing it to `subscribe_pool_state` subscribers. Log parsing only sees
+    /// prices at swaps, while this also catches liquidity moves and every tick change.
+    /// Only the pools monitored when this starts are subscribed.
+    pub async fn track_pool_state(&self) -> Result<()> {
+        let pools: Vec<Pubkey> = self.pool_pubkeys().into_iter().collect();
+        if pools.is_empty() {
+            self.log_activity("No pools to track the state of", None);
+            return Ok(());
+        }
+        let program_id = Pubkey::from_str(&self.connection_config.program_ids.orca_whirlpool)?;
+
+        self.log_activity(
+            "Starting account subscriptions for pool state",
+            Some(&format!("{} pools", pools.len()))
+        );
+        let ws_manager = self.websocket_manager();
+        let mut updates = ws_manager.start_account_subscription(pools).await?;
+
+        while let Some((pool, response)) = updates.recv().await {
+            match pool_state_update(&pool, &response, &program_id) {
+                Ok(update) => {
+                    log::debug!(
+                        "[orca] Pool {} state at slot {}: sqrt price {}, liquidity {}, tick {}",
+                        pool,
+                        update.slot,
+                        update.sqrt_price,
+                        update.liquidity,
+                        update.tick_current_index
+                    );
+                    // Nobody listening is fine, updates are only published
+                    let _ = self.pool_state_sender.send(update);
+                }
+                Err(e) => {
+                    logging::log_error(self.dex_name(), "Failed to decode pool state", &e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a new pool should be auto-subscribed
+    fn is_auto_subscribe_pool(&self, event: &OrcaWhirlpoolPoolInitializedEvent) -> bool {
+        match &self.auto_subscribe_mints {
+            Some(mints) => mints.contains(&event.token_mint_a) && mints.contains(&event.token_mint_b),
+            None => false,
+        }
+    }
+
+    /// Token A and token B decimals of a pool, if known
+    ///
+    /// Decimals are looked up once per pool and cached. Pools without token metadata
+    /// are looked up again on their next trade, so loading metadata later fills in prices.
+    async fn pool_decimals(&self, pool: &Pubkey) -> Option<(u8, u8)> {
+        let cached = self.token_decimals.lock().unwrap().get(pool).copied();
+        if cached.is_some() {
+            return cached;
+        }
+
+        match self.repository.get_token_decimals(&pool.to_string()).await {
+            Ok(Some(decimals)) => {
+                self.token_decimals.lock().unwrap().insert(*pool, decimals);
+                Some(decimals)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                log::debug!("[orca] Failed to look up token decimals for {}: {}", pool, e);
+                None
+            }
+        }
+    }
+
+    /// Warn about a trade whose amounts, scaled by the pool's token decimals, exceed
+    /// the configured plausible amount; the decimals are most likely wrong
+    ///
+    /// Flagged trades are still stored.
+    fn check_trade_amounts(&self, event: &OrcaWhirlpoolTradedEvent, decimals: (u8, u8)) {
+        let Some(max_amount) = self.connection_config.max_plausible_trade_amount else {
+            return;
+        };
+
+        let (input_decimals, output_decimals) = if event.a_to_b {
+            decimals
+        } else {
+            (decimals.1, decimals.0)
+        };
+        let amounts = [
+            ("input", ui_amount(event.input_amount, input_decimals), input_decimals),
+            ("output", ui_amount(event.output_amount, output_decimals), output_decimals),
+        ];
+
+        let mut implausible = false;
+        for (side, amount, decimals) in amounts {
+            if amount > max_amount {
+                log::warn!(
+                    "[orca] Implausible trade {} amount in pool {}: {} tokens with {} decimals exceeds {}, check the pool's token decimals",
+                    side,
+                    event.whirlpool,
+                    amount,
+                    decimals,
+                    max_amount
+                );
+                implausible = true;
+            }
+        }
+
+        if implausible {
+            metrics::record_implausible_trade(PROTOCOL.dex());
+        }
+    }
+
+    /// Check an event against `--event-validation`, returning the check it failed
+    ///
+    /// Events of unknown layouts fail whatever the mode, since they weren't decoded.
+    async fn validate_event(&self, event: &OrcaWhirlpoolParsedEvent) -> Result<(), Violation> {
+        let mode = self.connection_config.event_validation;
+        match event {
+            OrcaWhirlpoolParsedEvent::Traded(event, ..) => {
+                // Only strict validation compares scaled amounts, which needs the token decimals
+                let max_amount = self.connection_config.max_plausible_trade_amount;
+                let scaled_amounts = if
+                    mode == ValidationMode::Strict &&
+                    max_amount.is_some() &&
+                    !self.is_dry_run()
+                {
+                    self.pool_decimals(&event.whirlpool).await.map(|decimals| {
+                        let (input_decimals, output_decimals) = if event.a_to_b {
+                            decimals
+                        } else {
+                            (decimals.1, decimals.0)
+                        };
+                        (
+                            ui_amount(event.input_amount, input_decimals),
+                            ui_amount(event.output_amount, output_decimals),
+                        )
+                    })
+                } else {
+                    None
+                };
+                validate_orca_trade(event, mode, scaled_amounts, max_amount)
+            }
+            OrcaWhirlpoolParsedEvent::LiquidityIncreased(event, ..) => {
+                validate_orca_liquidity(&LiquidityChange::from(event), mode)
+            }
+            OrcaWhirlpoolParsedEvent::LiquidityDecreased(event, ..) => {
+                validate_orca_liquidity(&LiquidityChange::from(event), mode)
+            }
+            OrcaWhirlpoolParsedEvent::PoolInitialized(event, ..) => {
+                validate_orca_pool_initialized(event, mode)
+            }
+            OrcaWhirlpoolParsedEvent::UnknownLayout(event, _) => Err(event.violation()),
+        }
+    }
+
+    /// Store an event that failed validation in the quarantine table instead of indexing it
+    async fn quarantine_event(
+        &self,
+        event: &OrcaWhirlpoolParsedEvent,
+        event_index: usize,
+        violation: &Violation,
+        slot: Option<u64>,
+        block_time: Option<i64>
+    ) -> Result<()> {
+        let quarantined = QuarantinedEvent {
+            dex: PROTOCOL.dex().to_string(),
+            signature: event.signature().to_string(),
+            event_index,
+            event_type: event.event_type().to_string(),
+            pool: event.whirlpool().to_string(),
+            reason: violation.to_string(),
+            raw_data: STANDARD.encode(event.encode()),
+            slot,
+            block_time,
+        };
+        log::warn!(
+            "[orca] Quarantining {} event of pool {} in {}: {}",
+            quarantined.event_type,
+            quarantined.pool,
+            quarantined.signature,
+            violation
+        );
+
+        if self.is_dry_run() {
+            logging::log_dex_activity(
+                "dry-run",
+                self.dex_name(),
+                "Would quarantine",
+                Some(&format!("{:?}", quarantined))
+            );
+        } else if self.event_writer.insert_quarantined_event(quarantined).await?.is_none() {
+            // Already quarantined when the transaction was first seen
+            return Ok(());
+        }
+        metrics::record_quarantined_event(PROTOCOL.dex(), violation.check);
+        Ok(())
+    }
+
+    /// Publish an indexed event to sinks and subscribers
+    ///
+    /// A failing sink is logged and skipped; it never fails event handling.
+    async fn publish_event(&self, event: IndexedEvent) {
+        for sink in &self.sinks {
+            if let Err(e) = sink.write(&event).await {
+                logging::log_error(sink.name(), "Failed to write event", &e);
+            }
+        }
+
+        // Sending only fails when nobody is subscribed
+        let _ = self.event_sender.send(event);
+    }
+
+
     // Utility methods that are not part of the trait
     /// Log details about a traded event
     fn log_traded_event(&self, event: &OrcaWhirlpoolTradedEvent) {
@@ -92,20 +698,74 @@ impl OrcaWhirlpoolIndexer {
         );
     }
 
+    /// Store a newly created pool with its tokens and start monitoring it
+    async fn subscribe_new_pool(
+        &self,
+        event: &OrcaWhirlpoolPoolInitializedEvent,
+        signature: &str,
+        source_label: &str
+    ) -> Result<()> {
+        let pool_record = OrcaWhirlpoolPoolRecord {
+            whirlpool: event.whirlpool.to_string(),
+            token_mint_a: event.token_mint_a.to_string(),
+            token_mint_b: event.token_mint_b.to_string(),
+            token_name_a: None,
+            token_name_b: None,
+            pool_name: None,
+            decimals_a: event.decimals_a as i32,
+            decimals_b: event.decimals_b as i32,
+        };
+
+        if self.is_dry_run() {
+            self.log_dry_run_event(source_label, &pool_record);
+        } else {
+            self.repository.upsert_pool(&pool_record).await?;
+        }
+
+        self.add_pool(event.whirlpool);
+        self.token_decimals
+            .lock()
+            .unwrap()
+            .insert(event.whirlpool, (event.decimals_a, event.decimals_b));
+        self.log_activity(
+            "Auto-subscribed pool",
+            Some(
+                &format!(
+                    "{} ({}/{}) created in {}",
+                    event.whirlpool,
+                    event.token_mint_a,
+                    event.token_mint_b,
+                    signature
+                )
+            )
+        );
+        Ok(())
+    }
+
     /// Create a base event record
-    fn create_base_event(
+    pub fn create_base_event(
         &self,
         signature: &str,
         whirlpool: &Pubkey,
-        event_type: OrcaWhirlpoolEventType
+        event_type: OrcaWhirlpoolEventType,
+        version: i32,
+        slot: Option<u64>,
+        block_time: Option<i64>
     ) -> OrcaWhirlpoolEvent {
         OrcaWhirlpoolEvent {
             id: 0, // Will be set by database
             signature: signature.to_string(),
+            event_index: 0, // Set by the caller, who knows the event's position
             whirlpool: whirlpool.to_string(),
             event_type: event_type.to_string(),
-            version: 1,
+            dex: self.dex_name().to_string(),
+            slot: slot.map(|slot| slot as i64),
+            // Left NULL rather than guessed when the block time is unknown
+            block_time: block_time.and_then(|time| chrono::DateTime::from_timestamp(time, 0)),
+            fee_payer: None, // Set by the caller, when the transaction was fetched
+            version,
             timestamp: chrono::Utc::now(),
+            enrichment: None,
         }
     }
 }
@@ -120,59 +780,115 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
         provided_pools: Option<&Vec<String>>,
         connection_config: ConnectionConfig
     ) -> Result<Self> {
+        connection_config.validate()?;
+
         // Create the repository for database access
         let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
 
         // Resolve pool addresses with priority: CLI args > DB > Default
         let pool_pubkeys = repository.get_pools_with_fallback(
             provided_pools,
-            DEFAULT_ORCA_POOL
+            &connection_config.default_orca_pool,
+            connection_config.strict_pool_addresses,
+            connection_config.pool_token_mint.as_ref()
         ).await?;
 
         // Log the source of pool addresses
         if provided_pools.is_some() && !provided_pools.unwrap().is_empty() {
             crate::utils::logging::log_activity(
-                DEX,
+                PROTOCOL.dex(),
                 "Pool source",
                 Some("from command line arguments")
             );
+        } else if let Some(mint) = &connection_config.pool_token_mint {
+            crate::utils::logging::log_activity(
+                PROTOCOL.dex(),
+                "Pool source",
+                Some(&format!("{} subscribed pools trading {}", pool_pubkeys.len(), mint))
+            );
         } else if pool_pubkeys.len() > 1 {
-            crate::utils::logging::log_activity(DEX, "Pool source", Some("from database"));
+            crate::utils::logging::log_activity(PROTOCOL.dex(), "Pool source", Some("from database"));
         } else {
             crate::utils::logging::log_activity(
-                DEX,
+                PROTOCOL.dex(),
                 "Pool source",
                 Some("using default pool (no pools in CLI or database)")
             );
         }
 
-        // Create the signature store
-        let signature_store = SignatureStore::Database(DbSignatureStore::new(db_pool.clone()));
+        // Create the signature store (kept in memory for dry runs so nothing is written)
+        let signature_store = if connection_config.dry_run {
+            SignatureStore::InMemory(InMemorySignatureStore::new())
+        } else {
+            SignatureStore::Database(DbSignatureStore::new(db_pool.clone()))
+        };
 
         // Create the backfill manager
         let backfill_config = BackfillConfig {
             rpc_url: connection_config.rpc_url.clone(),
+            rpc_headers: connection_config.rpc_headers.clone(),
             max_signatures_per_request: 100,
             initial_backfill_slots: 10_000,
-            dex_type: DEX.to_string(),
+            dex_type: PROTOCOL.dex().to_string(),
+            commitment: connection_config.commitment,
+            rpc_timeout: connection_config.rpc_timeout,
+            retry: RetryConfig::default(),
+            order: connection_config.backfill_order,
+            pool_cap: connection_config.backfill_pool_cap,
+            max_requests_per_second: connection_config.backfill_max_requests_per_second,
+            fetch_concurrency: connection_config.backfill_fetch_concurrency,
+            pool_concurrency: connection_config.backfill_pool_concurrency,
+            signature_read_batch_size: connection_config.signature_read_batch_size,
         };
         let backfill_manager = BackfillManager::new(backfill_config, signature_store.clone());
+        let outage_buffer = OutageBuffer::new(connection_config.outage_buffer_capacity);
+        let (event_sender, _) = broadcast::channel(connection_config.event_channel_capacity.max(1));
+        let (pool_state_sender, _) = broadcast::channel(
+            connection_config.event_channel_capacity.max(1)
+        );
 
         Ok(Self {
             repository,
-            pool_pubkeys,
+            event_writer: Box::new(OrcaWhirlpoolRepository::new(db_pool.clone())),
+            pool_pubkeys: PoolSet::new(pool_pubkeys),
             signature_store,
             backfill_manager,
             connection_config,
+            event_sender,
+            pool_state_sender,
+            sinks: Vec::new(),
+            enricher: Box::new(IdentityEnricher),
+            enrichment_failure_policy: EnrichmentFailurePolicy::default(),
+            token_decimals: Mutex::new(HashMap::new()),
+            auto_subscribe_mints: None,
+            all_pools: false,
+            health: Arc::new(HealthTracker::new()),
+            outage_buffer,
         })
     }
 
     fn program_ids(&self) -> Vec<&str> {
-        vec!["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"]
+        vec![self.connection_config.program_ids.orca_whirlpool.as_str()]
+    }
+
+    fn pool_pubkeys(&self) -> HashSet<Pubkey> {
+        self.pool_pubkeys.snapshot()
+    }
+
+    fn contains_pool(&self, pool: &Pubkey) -> bool {
+        self.pool_pubkeys.contains(pool)
+    }
+
+    fn pool_count(&self) -> usize {
+        self.pool_pubkeys.len()
+    }
+
+    fn add_pool(&self, pool: Pubkey) -> bool {
+        self.pool_pubkeys.insert(pool)
     }
 
-    fn pool_pubkeys(&self) -> &HashSet<Pubkey> {
-        &self.pool_pubkeys
+    fn remove_pool(&self, pool: &Pubkey) -> bool {
+        self.pool_pubkeys.remove(pool)
     }
 
     fn repository(&self) -> &Self::Repository {
@@ -180,13 +896,56 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
     }
 
     fn dex_name(&self) -> &str {
-        DEX
+        PROTOCOL.dex()
+    }
+
+    fn event_discriminators(&self) -> Vec<([u8; 8], &'static str)> {
+        vec![
+            (TRADED_EVENT_DISCRIMINATOR, "Traded"),
+            (LIQUIDITY_INCREASED_DISCRIMINATOR, "LiquidityIncreased"),
+            (LIQUIDITY_DECREASED_DISCRIMINATOR, "LiquidityDecreased"),
+            (POOL_INITIALIZED_DISCRIMINATOR, "PoolInitialized")
+        ]
     }
 
     fn signature_store(&self) -> &SignatureStore {
         &self.signature_store
     }
 
+    async fn indexed_signatures(&self, signatures: &[Signature]) -> Result<HashSet<Signature>> {
+        let signatures: Vec<String> = signatures.iter().map(Signature::to_string).collect();
+        let indexed = self.repository.indexed_signatures(&signatures).await?;
+        Ok(
+            indexed
+                .iter()
+                .filter_map(|signature| Signature::from_str(signature).ok())
+                .collect()
+        )
+    }
+
+    async fn indexed_events(
+        &self,
+        keys: &[(Signature, usize)]
+    ) -> Result<HashSet<(Signature, usize)>> {
+        let keys: Vec<(String, i32)> = keys
+            .iter()
+            .map(|(signature, index)| (signature.to_string(), *index as i32))
+            .collect();
+        let indexed = self.repository.indexed_events(&keys).await?;
+        Ok(
+            indexed
+                .into_iter()
+                .filter_map(|(signature, index)| {
+                    Some((Signature::from_str(&signature).ok()?, index as usize))
+                })
+                .collect()
+        )
+    }
+
+    async fn delete_events_after_slot(&self, pool: &Pubkey, slot: u64) -> Result<u64> {
+        self.repository.delete_events_after_slot(&pool.to_string(), slot as i64).await
+    }
+
     fn backfill_manager(&self) -> &BackfillManager {
         &self.backfill_manager
     }
@@ -195,8 +954,30 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
         &self.connection_config
     }
 
+    fn health_tracker(&self) -> &Arc<HealthTracker> {
+        &self.health
+    }
+
+    fn outage_buffer(&self) -> &OutageBuffer {
+        &self.outage_buffer
+    }
+
+    fn indexes_all_pools(&self) -> bool {
+        self.all_pools
+    }
+
     /// Parse events from a log, returning any found events without persisting them
     async fn parse_log_events(&self, log: &RpcLogsResponse) -> Result<Vec<Self::ParsedEvent>> {
+        let events = self.parse_indexed_log_events(log).await?;
+        Ok(events.into_iter().map(|(_, event)| event).collect())
+    }
+
+    /// Parse events from a log, each with its position among all the Orca events the log
+    /// holds, counted before events of unmonitored pools are dropped
+    async fn parse_indexed_log_events(
+        &self,
+        log: &RpcLogsResponse
+    ) -> Result<Vec<(usize, Self::ParsedEvent)>> {
         // Debug log to see contents of log messages
         log::debug!(
             "[orca] Parsing log with signature: {}, contains {} log lines",
@@ -210,7 +991,8 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
             .any(|line| {
                 line.contains("Swap") ||
                     line.contains("IncreaseLiquidity") ||
-                    line.contains("DecreaseLiquidity")
+                    line.contains("DecreaseLiquidity") ||
+                    line.contains("InitializePool")
             });
 
         if !contains_relevant_events {
@@ -219,6 +1001,7 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
         }
 
         let mut events = Vec::new();
+        let mut event_count = 0;
 
         // Extract and process events
         let log_lines: Vec<&str> = log.logs
@@ -239,6 +1022,50 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                             // Get the discriminator (first 8 bytes)
                             let discriminator = &data[0..8];
 
+                            // Every Orca event takes a position, whether or not it is kept
+                            let event_type = event_type_of(discriminator);
+                            let event_index = event_count;
+                            if event_type.is_some() {
+                                event_count += 1;
+                            }
+
+                            // Payloads are only decoded as a layout they match exactly, so
+                            // newer layouts and truncated payloads are passed on undecoded
+                            let Some(version) = parse_version(discriminator, &data[8..]) else {
+                                let Some(event_type) = event_type else {
+                                    continue;
+                                };
+                                let event = UnknownLayoutEvent::new(event_type, &data);
+                                // A payload too short to name its pool may be of any pool
+                                if
+                                    event.whirlpool == Pubkey::default() ||
+                                    self.is_monitored_pool(&event.whirlpool)
+                                {
+                                    log::warn!(
+                                        "[orca] {} event of pool {} in {} matches no known layout",
+                                        event.event_type.to_string(),
+                                        event.whirlpool,
+                                        log.signature
+                                    );
+                                    events.push(
+                                        (
+                                            event_index,
+                                            OrcaWhirlpoolParsedEvent::UnknownLayout(
+                                                event,
+                                                log.signature.clone()
+                                            ),
+                                        )
+                                    );
+                                } else {
+                                    self.log_unmonitored_pool_event(
+                                        &event.whirlpool,
+                                        &event.event_type.to_string(),
+                                        &log.signature
+                                    );
+                                }
+                                continue;
+                            };
+
                             // Using if-else statements with slice comparisons instead of match
                             if discriminator == &TRADED_EVENT_DISCRIMINATOR[..] {
                                 log::debug!("[orca] Found TRADED_EVENT_DISCRIMINATOR");
@@ -250,20 +1077,27 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                                         );
 
                                         // Check if this pool is in our watch list
-                                        let is_monitored = self.is_monitored_pool(
-                                            &event.whirlpool,
-                                            self.pool_pubkeys()
-                                        );
+                                        let is_monitored = self.is_monitored_pool(&event.whirlpool);
                                         log::debug!("[orca] Is pool monitored: {}", is_monitored);
 
                                         if is_monitored {
                                             self.log_traded_event(&event);
                                             events.push(
-                                                OrcaWhirlpoolParsedEvent::Traded(
-                                                    event,
-                                                    log.signature.clone()
+                                                (
+                                                    event_index,
+                                                    OrcaWhirlpoolParsedEvent::Traded(
+                                                        event,
+                                                        log.signature.clone(),
+                                                        version
+                                                    ),
                                                 )
                                             );
+                                        } else {
+                                            self.log_unmonitored_pool_event(
+                                                &event.whirlpool,
+                                                "Traded",
+                                                &log.signature
+                                            );
                                         }
                                     }
                                     Err(e) => {
@@ -278,19 +1112,24 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                                         )
                                 {
                                     // Check if this pool is in our watch list
-                                    if
-                                        self.is_monitored_pool(
-                                            &event.whirlpool,
-                                            self.pool_pubkeys()
-                                        )
-                                    {
+                                    if self.is_monitored_pool(&event.whirlpool) {
                                         self.log_liquidity_increased_event(&event);
                                         events.push(
-                                            OrcaWhirlpoolParsedEvent::LiquidityIncreased(
-                                                event,
-                                                log.signature.clone()
+                                            (
+                                                event_index,
+                                                OrcaWhirlpoolParsedEvent::LiquidityIncreased(
+                                                    event,
+                                                    log.signature.clone(),
+                                                    version
+                                                ),
                                             )
                                         );
+                                    } else {
+                                        self.log_unmonitored_pool_event(
+                                            &event.whirlpool,
+                                            "LiquidityIncreased",
+                                            &log.signature
+                                        );
                                     }
                                 }
                             } else if discriminator == &LIQUIDITY_DECREASED_DISCRIMINATOR[..] {
@@ -301,26 +1140,71 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                                         )
                                 {
                                     // Check if this pool is in our watch list
-                                    if
-                                        self.is_monitored_pool(
+                                    if self.is_monitored_pool(&event.whirlpool) {
+                                        self.log_liquidity_decreased_event(&event);
+                                        events.push(
+                                            (
+                                                event_index,
+                                                OrcaWhirlpoolParsedEvent::LiquidityDecreased(
+                                                    event,
+                                                    log.signature.clone(),
+                                                    version
+                                                ),
+                                            )
+                                        );
+                                    } else {
+                                        self.log_unmonitored_pool_event(
                                             &event.whirlpool,
-                                            self.pool_pubkeys()
+                                            "LiquidityDecreased",
+                                            &log.signature
+                                        );
+                                    }
+                                }
+                            } else if discriminator == &POOL_INITIALIZED_DISCRIMINATOR[..] {
+                                if
+                                    let Ok(event) =
+                                        OrcaWhirlpoolPoolInitializedEvent::try_from_slice(
+                                            &data[8..]
                                         )
+                                {
+                                    // New pools are picked up when monitored or of a token pair of interest
+                                    if
+                                        self.is_monitored_pool(&event.whirlpool) ||
+                                        self.is_auto_subscribe_pool(&event)
                                     {
-                                        self.log_liquidity_decreased_event(&event);
                                         events.push(
-                                            OrcaWhirlpoolParsedEvent::LiquidityDecreased(
-                                                event,
-                                                log.signature.clone()
+                                            (
+                                                event_index,
+                                                OrcaWhirlpoolParsedEvent::PoolInitialized(
+                                                    event,
+                                                    log.signature.clone(),
+                                                    version
+                                                ),
                                             )
                                         );
+                                    } else {
+                                        log::debug!(
+                                            "[orca] Ignoring new pool {} ({}/{})",
+                                            event.whirlpool,
+                                            event.token_mint_a,
+                                            event.token_mint_b
+                                        );
                                     }
                                 }
                             }
                         }
                     }
                     None => {
-                        log::debug!("[orca] Failed to extract event data from line");
+                        // Valid base64 comes in groups of 4 characters
+                        let base64_data = line.split("Program data: ").nth(1).unwrap_or_default();
+                        if base64_data.trim_end().len() % 4 != 0 {
+                            log::warn!(
+                                "[orca] Program data line in {} looks truncated, skipping it",
+                                log.signature
+                            );
+                        } else {
+                            log::debug!("[orca] Failed to extract event data from line");
+                        }
                     }
                 }
             }
@@ -331,37 +1215,132 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
     }
 
     /// Handle a single event (for both real-time and backfill processing)
-    async fn handle_event(&self, event: Self::ParsedEvent, is_backfill: bool) -> Result<()> {
+    async fn handle_event(
+        &self,
+        event: Self::ParsedEvent,
+        event_index: usize,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+        fee_payer: Option<Pubkey>
+    ) -> Result<bool> {
         // Create a source label for logging
         let source_label = if is_backfill { "BACKFILL" } else { "LIVE" };
+        let event_source = |pool: Pubkey, signature: &str| EventSource {
+            pool,
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            is_backfill,
+        };
 
+        // Events failing validation, or of unknown layouts, are quarantined, and neither
+        // stored nor published
+        if let Err(violation) = self.validate_event(&event).await {
+            self.quarantine_event(&event, event_index, &violation, slot, block_time).await?;
+            return Ok(false);
+        }
+
+        // Pools seen for the first time are stored before their events reference them
+        if let Some(pool) = event.pool_event_whirlpool() {
+            self.register_seen_pool(&pool).await?;
+        }
+
+        let base_event = |signature: &str, whirlpool: &Pubkey, event_type, version| {
+            let mut base_event = self.create_base_event(
+                signature,
+                whirlpool,
+                event_type,
+                version,
+                slot,
+                block_time
+            );
+            base_event.event_index = event_index as i32;
+            base_event.fee_payer = fee_payer.map(|fee_payer| fee_payer.to_string());
+            base_event
+        };
         match event {
-            OrcaWhirlpoolParsedEvent::Traded(event_data, signature) => {
+            OrcaWhirlpoolParsedEvent::Traded(event_data, signature, version) => {
+                // Dust trades are only counted, when a minimum input amount is set
+                let pool = event_data.whirlpool.to_string();
+                if let Some(min_amount) = self.connection_config.min_input_amount_for(&pool) {
+                    if event_data.input_amount < min_amount {
+                        log::debug!(
+                            "[orca] Skipping trade in {} of pool {}: input amount {} is below {}",
+                            signature,
+                            pool,
+                            event_data.input_amount,
+                            min_amount
+                        );
+                        metrics::record_dust_trade_skipped(PROTOCOL.dex());
+                        return Ok(false);
+                    }
+                }
+
                 // Create the base event
-                let base_event = self.create_base_event(
+                let base_event = base_event(
                     &signature,
                     &event_data.whirlpool,
-                    OrcaWhirlpoolEventType::Traded
+                    OrcaWhirlpoolEventType::Traded,
+                    version
                 );
 
+                // Token decimals come from the database, so dry runs skip the price and amount check
+                let decimals = if self.is_dry_run() {
+                    None
+                } else {
+                    self.pool_decimals(&event_data.whirlpool).await
+                };
+                let price = decimals.map(|(decimals_a, decimals_b)| {
+                    sqrt_price_to_price(event_data.post_sqrt_price, decimals_a, decimals_b)
+                });
+                if let Some(decimals) = decimals {
+                    self.check_trade_amounts(&event_data, decimals);
+                }
+
                 // Create the data record
                 let data = OrcaWhirlpoolTradedRecord {
                     event_id: 0, // Will be set after base event is inserted
                     a_to_b: event_data.a_to_b,
-                    pre_sqrt_price: event_data.pre_sqrt_price as i64,
-                    post_sqrt_price: event_data.post_sqrt_price as i64,
-                    input_amount: event_data.input_amount as i64,
-                    output_amount: event_data.output_amount as i64,
-                    input_transfer_fee: event_data.input_transfer_fee as i64,
-                    output_transfer_fee: event_data.output_transfer_fee as i64,
-                    lp_fee: event_data.lp_fee as i64,
-                    protocol_fee: event_data.protocol_fee as i64,
+                    pre_sqrt_price: event_data.pre_sqrt_price,
+                    post_sqrt_price: event_data.post_sqrt_price,
+                    input_amount: event_data.input_amount,
+                    output_amount: event_data.output_amount,
+                    input_transfer_fee: event_data.input_transfer_fee,
+                    output_transfer_fee: event_data.output_transfer_fee,
+                    lp_fee: event_data.lp_fee,
+                    protocol_fee: event_data.protocol_fee,
+                    price,
                 };
 
-                let event_record = OrcaWhirlpoolTradedEventRecord {
+                let mut event_record = OrcaWhirlpoolTradedEventRecord {
                     base: base_event,
                     data,
                 };
+                let indexed_event = IndexedEvent::OrcaTraded(OrcaTrade {
+                    source: event_source(event_data.whirlpool, &signature),
+                    a_to_b: event_data.a_to_b,
+                    input_amount: event_data.input_amount,
+                    output_amount: event_data.output_amount,
+                    input_transfer_fee: event_data.input_transfer_fee,
+                    output_transfer_fee: event_data.output_transfer_fee,
+                    lp_fee: event_data.lp_fee,
+                    protocol_fee: event_data.protocol_fee,
+                    pre_sqrt_price: event_data.pre_sqrt_price,
+                    post_sqrt_price: event_data.post_sqrt_price,
+                });
+
+                // Attach derived fields, or drop the event if enrichment failed under the skip policy
+                let Some(enrichment) = enrich_event(
+                    self.enricher.as_ref(),
+                    self.enrichment_failure_policy,
+                    PROTOCOL.dex(),
+                    &indexed_event
+                ).await else {
+                    return Ok(false);
+                };
+                event_record.base.enrichment = enrichment;
+
                 // Add source to log message
                 log::info!(
                     "[{}][{}] Traded event: pool={}, a_to_b={}, in={}, out={}",
@@ -373,14 +1352,23 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                     event_data.output_amount
                 );
 
-                self.repository.insert_traded_event(event_record).await?;
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                } else if
+                    self.event_writer.insert_traded_event(event_record).await?.is_none()
+                {
+                    // Already stored, and published, when the transaction was first indexed
+                    return Ok(false);
+                }
+                self.publish_event(indexed_event).await;
             }
-            OrcaWhirlpoolParsedEvent::LiquidityIncreased(event_data, signature) => {
+            OrcaWhirlpoolParsedEvent::LiquidityIncreased(event_data, signature, version) => {
                 // Create the base event
-                let base_event = self.create_base_event(
+                let base_event = base_event(
                     &signature,
                     &event_data.whirlpool,
-                    OrcaWhirlpoolEventType::LiquidityIncreased
+                    OrcaWhirlpoolEventType::LiquidityIncreased,
+                    version
                 );
 
                 // Create the data record
@@ -389,17 +1377,39 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                     position: event_data.position.to_string(),
                     tick_lower_index: event_data.tick_lower_index,
                     tick_upper_index: event_data.tick_upper_index,
-                    liquidity: event_data.liquidity as i64,
-                    token_a_amount: event_data.token_a_amount as i64,
-                    token_b_amount: event_data.token_b_amount as i64,
-                    token_a_transfer_fee: event_data.token_a_transfer_fee as i64,
-                    token_b_transfer_fee: event_data.token_b_transfer_fee as i64,
+                    liquidity: event_data.liquidity,
+                    token_a_amount: event_data.token_a_amount,
+                    token_b_amount: event_data.token_b_amount,
+                    token_a_transfer_fee: event_data.token_a_transfer_fee,
+                    token_b_transfer_fee: event_data.token_b_transfer_fee,
                 };
 
-                let event_record = OrcaWhirlpoolLiquidityIncreasedEventRecord {
+                let mut event_record = OrcaWhirlpoolLiquidityIncreasedEventRecord {
                     base: base_event,
                     data,
                 };
+                let indexed_event = IndexedEvent::OrcaLiquidityIncreased(OrcaLiquidityChange {
+                    source: event_source(event_data.whirlpool, &signature),
+                    position: event_data.position,
+                    tick_lower_index: event_data.tick_lower_index,
+                    tick_upper_index: event_data.tick_upper_index,
+                    liquidity: event_data.liquidity,
+                    token_a_amount: event_data.token_a_amount,
+                    token_b_amount: event_data.token_b_amount,
+                    token_a_transfer_fee: event_data.token_a_transfer_fee,
+                    token_b_transfer_fee: event_data.token_b_transfer_fee,
+                });
+
+                // Attach derived fields, or drop the event if enrichment failed under the skip policy
+                let Some(enrichment) = enrich_event(
+                    self.enricher.as_ref(),
+                    self.enrichment_failure_policy,
+                    PROTOCOL.dex(),
+                    &indexed_event
+                ).await else {
+                    return Ok(false);
+                };
+                event_record.base.enrichment = enrichment;
 
                 // Add source to log message
                 log::info!(
@@ -412,14 +1422,23 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                     event_data.token_b_amount
                 );
 
-                self.repository.insert_liquidity_increased_event(event_record).await?;
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                } else if
+                    self.event_writer.insert_liquidity_increased_event(event_record).await?.is_none()
+                {
+                    // Already stored, and published, when the transaction was first indexed
+                    return Ok(false);
+                }
+                self.publish_event(indexed_event).await;
             }
-            OrcaWhirlpoolParsedEvent::LiquidityDecreased(event_data, signature) => {
+            OrcaWhirlpoolParsedEvent::LiquidityDecreased(event_data, signature, version) => {
                 // Create the base event
-                let base_event = self.create_base_event(
+                let base_event = base_event(
                     &signature,
                     &event_data.whirlpool,
-                    OrcaWhirlpoolEventType::LiquidityDecreased
+                    OrcaWhirlpoolEventType::LiquidityDecreased,
+                    version
                 );
 
                 // Create the data record
@@ -428,17 +1447,39 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                     position: event_data.position.to_string(),
                     tick_lower_index: event_data.tick_lower_index,
                     tick_upper_index: event_data.tick_upper_index,
-                    liquidity: event_data.liquidity as i64,
-                    token_a_amount: event_data.token_a_amount as i64,
-                    token_b_amount: event_data.token_b_amount as i64,
-                    token_a_transfer_fee: event_data.token_a_transfer_fee as i64,
-                    token_b_transfer_fee: event_data.token_b_transfer_fee as i64,
+                    liquidity: event_data.liquidity,
+                    token_a_amount: event_data.token_a_amount,
+                    token_b_amount: event_data.token_b_amount,
+                    token_a_transfer_fee: event_data.token_a_transfer_fee,
+                    token_b_transfer_fee: event_data.token_b_transfer_fee,
                 };
 
-                let event_record = OrcaWhirlpoolLiquidityDecreasedEventRecord {
+                let mut event_record = OrcaWhirlpoolLiquidityDecreasedEventRecord {
                     base: base_event,
                     data,
                 };
+                let indexed_event = IndexedEvent::OrcaLiquidityDecreased(OrcaLiquidityChange {
+                    source: event_source(event_data.whirlpool, &signature),
+                    position: event_data.position,
+                    tick_lower_index: event_data.tick_lower_index,
+                    tick_upper_index: event_data.tick_upper_index,
+                    liquidity: event_data.liquidity,
+                    token_a_amount: event_data.token_a_amount,
+                    token_b_amount: event_data.token_b_amount,
+                    token_a_transfer_fee: event_data.token_a_transfer_fee,
+                    token_b_transfer_fee: event_data.token_b_transfer_fee,
+                });
+
+                // Attach derived fields, or drop the event if enrichment failed under the skip policy
+                let Some(enrichment) = enrich_event(
+                    self.enricher.as_ref(),
+                    self.enrichment_failure_policy,
+                    PROTOCOL.dex(),
+                    &indexed_event
+                ).await else {
+                    return Ok(false);
+                };
+                event_record.base.enrichment = enrichment;
 
                 // Add source to log message
                 log::info!(
@@ -451,10 +1492,127 @@ impl DexIndexer for OrcaWhirlpoolIndexer {
                     event_data.token_b_amount
                 );
 
-                self.repository.insert_liquidity_decreased_event(event_record).await?;
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                } else if
+                    self.event_writer.insert_liquidity_decreased_event(event_record).await?.is_none()
+                {
+                    // Already stored, and published, when the transaction was first indexed
+                    return Ok(false);
+                }
+                self.publish_event(indexed_event).await;
+            }
+            OrcaWhirlpoolParsedEvent::PoolInitialized(event_data, signature, version) => {
+                // Pools that are already monitored keep their stored names
+                if !self.pool_pubkeys.contains(&event_data.whirlpool) {
+                    self.subscribe_new_pool(&event_data, &signature, source_label).await?;
+                }
+
+                // Create the base event
+                let base_event = base_event(
+                    &signature,
+                    &event_data.whirlpool,
+                    OrcaWhirlpoolEventType::PoolInitialized,
+                    version
+                );
+
+                // Create the data record
+                let data = OrcaWhirlpoolPoolInitializedRecord {
+                    event_id: 0, // Will be set after base event is inserted
+                    whirlpools_config: event_data.whirlpools_config.to_string(),
+                    token_mint_a: event_data.token_mint_a.to_string(),
+                    token_mint_b: event_data.token_mint_b.to_string(),
+                    tick_spacing: event_data.tick_spacing as i32,
+                    decimals_a: event_data.decimals_a as i32,
+                    decimals_b: event_data.decimals_b as i32,
+                    initial_sqrt_price: event_data.initial_sqrt_price,
+                };
+
+                let mut event_record = OrcaWhirlpoolPoolInitializedEventRecord {
+                    base: base_event,
+                    data,
+                };
+                let indexed_event = IndexedEvent::OrcaPoolInitialized(OrcaPoolInitialization {
+                    source: event_source(event_data.whirlpool, &signature),
+                    whirlpools_config: event_data.whirlpools_config,
+                    token_mint_a: event_data.token_mint_a,
+                    token_mint_b: event_data.token_mint_b,
+                    tick_spacing: event_data.tick_spacing,
+                    decimals_a: event_data.decimals_a,
+                    decimals_b: event_data.decimals_b,
+                    initial_sqrt_price: event_data.initial_sqrt_price,
+                });
+
+                // Attach derived fields, or drop the event if enrichment failed under the skip policy
+                let Some(enrichment) = enrich_event(
+                    self.enricher.as_ref(),
+                    self.enrichment_failure_policy,
+                    PROTOCOL.dex(),
+                    &indexed_event
+                ).await else {
+                    return Ok(false);
+                };
+                event_record.base.enrichment = enrichment;
+
+                // Add source to log message
+                log::info!(
+                    "[{}][{}] PoolInitialized event: pool={}, tokenA={}, tokenB={}, tick_spacing={}",
+                    self.dex_name(),
+                    source_label,
+                    event_data.whirlpool,
+                    event_data.token_mint_a,
+                    event_data.token_mint_b,
+                    event_data.tick_spacing
+                );
+
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                } else if
+                    self.event_writer.insert_pool_initialized_event(event_record).await?.is_none()
+                {
+                    // Already stored, and published, when the transaction was first indexed
+                    return Ok(false);
+                }
+                self.publish_event(indexed_event).await;
+            }
+            // Quarantined by validation above
+            OrcaWhirlpoolParsedEvent::UnknownLayout(..) => {
+                return Ok(false);
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 }
+
+// Decode a pool account change into its price and liquidity
+fn pool_state_update(
+    pool: &Pubkey,
+    response: &Response<UiAccount>,
+    program_id: &Pubkey
+) -> Result<PoolStateUpdate> {
+    let account: Account = response.value
+        .decode()
+        .with_context(|| format!("Failed to decode account data of pool {}", pool))?;
+    let whirlpool = parse_whirlpool_account(pool, &account, program_id)?;
+
+    Ok(PoolStateUpdate {
+        pool: *pool,
+        slot: response.context.slot,
+        sqrt_price: whirlpool.sqrt_price,
+        liquidity: whirlpool.liquidity,
+        tick_current_index: whirlpool.tick_current_index,
+        fee_rate: whirlpool.fee_rate,
+        // Uninitialized reward slots have the default mint
+        reward_emissions: whirlpool.reward_infos
+            .iter()
+            .enumerate()
+            .filter(|(_, reward)| reward.mint != Pubkey::default())
+            .map(|(index, reward)| RewardEmission {
+                reward_index: index as u8,
+                reward_mint: reward.mint,
+                emissions_per_second_x64: reward.emissions_per_second_x64,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,63 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::{ Arc, RwLock, RwLockReadGuard };
+
+/// Set of monitored pools that can be changed while an indexer runs
+///
+/// Clones share the same set, so a handle can be given to whatever adds or
+/// removes pools at runtime while the indexer keeps reading it. Each method takes
+/// the lock once, so every read sees the set as it was between two changes.
+#[derive(Debug, Clone, Default)]
+pub struct PoolSet {
+    pools: Arc<RwLock<HashSet<Pubkey>>>,
+}
+
+impl PoolSet {
+    pub fn new(pools: HashSet<Pubkey>) -> Self {
+        Self {
+            pools: Arc::new(RwLock::new(pools)),
+        }
+    }
+
+    /// Whether the pool is in the set
+    pub fn contains(&self, pool: &Pubkey) -> bool {
+        self.read().contains(pool)
+    }
+
+    /// Add a pool, returning false if it was already in the set
+    pub fn insert(&self, pool: Pubkey) -> bool {
+        self.pools.write().unwrap().insert(pool)
+    }
+
+    /// Remove a pool, returning false if it wasn't in the set
+    pub fn remove(&self, pool: &Pubkey) -> bool {
+        self.pools.write().unwrap().remove(pool)
+    }
+
+    /// Number of pools in the set
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Copy of the current set, unaffected by later changes
+    pub fn snapshot(&self) -> HashSet<Pubkey> {
+        self.read().clone()
+    }
+
+    /// Read guard over the set, for several lookups against the same state
+    ///
+    /// Changes wait until the guard is dropped, so don't hold it across an await.
+    pub fn read(&self) -> RwLockReadGuard<'_, HashSet<Pubkey>> {
+        self.pools.read().unwrap()
+    }
+}
+
+impl From<HashSet<Pubkey>> for PoolSet {
+    fn from(pools: HashSet<Pubkey>) -> Self {
+        Self::new(pools)
+    }
+}
@@ -1,20 +1,21 @@
-use anyhow::{ Context, Result };
+use anyhow::Result;
 use borsh::BorshDeserialize;
 use solana_client::rpc_response::RpcLogsResponse;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use std::collections::HashSet;
 use std::str::FromStr;
+use std::sync::Arc;
 use sqlx::PgPool;
-use chrono::Utc;
+use chrono::{ DateTime, Utc };
 use async_trait::async_trait;
 
 use crate::db::repositories::raydium::RaydiumRepository;
-use crate::db::signature_store::SignatureStore;
+use crate::db::signature_store::{ DbSignatureStore, InMemorySignatureStore, SignatureStore };
 use crate::backfill_manager::BackfillManager;
-use crate::models::raydium::amm::{
-    TRADED_EVENT_DISCRIMINATOR as AMM_TRADED_DISCRIMINATOR,
-    // Add other AMM discriminators as needed
-};
+use crate::health::HealthTracker;
+use crate::outage_buffer::OutageBuffer;
+use crate::models::common::Protocol;
 use crate::models::raydium::clmm::{
     CLMM_CREATE_PERSONAL_POSITION_DISCRIMINATOR,
     CLMM_LIQUIDITY_INCREASED_DISCRIMINATOR,
@@ -30,14 +31,18 @@ use crate::models::raydium::clmm::{
     RaydiumCLMMCreatePostionEventRecord,
     RaydiumCLMMIncreaseLiquidityEventRecord,
     RaydiumCLMMDecreaseLiquidityEventRecord,
+    find_pool_in_logs,
+    find_position_mint_in_logs,
 };
 use crate::utils::logging;
 use crate::indexers::dex_indexer::{ DexIndexer, ConnectionConfig };
+use crate::indexers::pool_set::PoolSet;
 
 // Default pools for fallback
 const DEFAULT_RAYDIUM_AMM_POOL: &str = ""; // Replace with an appropriate default AMM pool
 const DEFAULT_RAYDIUM_CLMM_POOL: &str = ""; // Replace with an appropriate default CLMM pool
-const DEX_NAME: &str = "raydium";
+// AMM and CLMM pools are tracked under the same DEX key, so either protocol gives the same one
+const PROTOCOL: Protocol = Protocol::RaydiumClmm;
 
 /// The pool type for distinguishing between AMM and CLMM pools
 #[derive(Debug, Clone, PartialEq)]
@@ -46,6 +51,25 @@ pub enum RaydiumPoolType {
     CLMM,
 }
 
+/// Check that no pool is classified as both an AMM and a CLMM pool
+///
+/// A pool in both sets would have every event processed twice, once per pool type.
+pub fn validate_pool_sets(
+    amm_pool_pubkeys: &HashSet<Pubkey>,
+    clmm_pool_pubkeys: &HashSet<Pubkey>
+) -> Result<()> {
+    let mut overlapping: Vec<String> = amm_pool_pubkeys
+        .intersection(clmm_pool_pubkeys)
+        .map(|pool| pool.to_string())
+        .collect();
+    if overlapping.is_empty() {
+        return Ok(());
+    }
+
+    overlapping.sort();
+    anyhow::bail!("Pools classified as both AMM and CLMM: {}", overlapping.join(", "))
+}
+
 /// Represents a parsed event from Raydium logs
 #[derive(Debug)]
 pub enum RaydiumParsedEvent {
@@ -54,7 +78,7 @@ pub enum RaydiumParsedEvent {
     // Additional AMM events as needed
 
     // CLMM Events
-    ClmmCreatePosition(RaydiumCLMMCreatePositionEvent, String, Pubkey), // Event, signature, and pool
+    ClmmCreatePosition(RaydiumCLMMCreatePositionEvent, String, Pubkey, Option<Pubkey>), // Event, signature, pool, and position NFT mint if found in the same transaction
     ClmmIncreaseLiquidity(RaydiumCLMMIncreaseLiquidityEvent, String, Pubkey),
     ClmmDecreaseLiquidity(RaydiumCLMMDecreaseLiquidityEvent, String, Pubkey),
 }
@@ -62,11 +86,13 @@ pub enum RaydiumParsedEvent {
 /// Raydium combined indexer for both AMM and CLMM
 pub struct RaydiumIndexer {
     repository: RaydiumRepository,
-    amm_pool_pubkeys: HashSet<Pubkey>,
-    clmm_pool_pubkeys: HashSet<Pubkey>,
+    amm_pool_pubkeys: PoolSet,
+    clmm_pool_pubkeys: PoolSet,
     signature_store: SignatureStore,
     backfill_manager: BackfillManager,
     connection_config: ConnectionConfig,
+    health: Arc<HealthTracker>,
+    outage_buffer: OutageBuffer,
 }
 
 impl RaydiumIndexer {
@@ -127,13 +153,21 @@ impl RaydiumIndexer {
         &self,
         signature: &str,
         pool: &Pubkey,
-        event_type: RaydiumCLMMEventType
+        event_type: RaydiumCLMMEventType,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>
     ) -> RaydiumCLMMEvent {
         RaydiumCLMMEvent {
             id: 0, // Will be set by database
             signature: signature.to_string(),
             pool: pool.to_string(),
             event_type: event_type.to_string(),
+            dex: self.dex_name().to_string(),
+            slot: slot.map(|slot| slot as i64),
+            // Left NULL rather than guessed when the block time is unknown
+            block_time: block_time.and_then(|time| DateTime::from_timestamp(time, 0)),
+            is_backfill,
             version: 1,
             timestamp: Utc::now(),
         }
@@ -143,18 +177,18 @@ impl RaydiumIndexer {
     // Helper method that combines both AMM and CLMM pools
     fn all_pool_pubkeys(&self) -> HashSet<Pubkey> {
         let mut all_pools = HashSet::new();
-        all_pools.extend(self.amm_pool_pubkeys.iter().cloned());
-        all_pools.extend(self.clmm_pool_pubkeys.iter().cloned());
+        all_pools.extend(self.amm_pool_pubkeys.read().iter().cloned());
+        all_pools.extend(self.clmm_pool_pubkeys.read().iter().cloned());
         all_pools
     }
 
     /// Determine if a pool is an AMM pool
-    fn is_amm_pool(&self, pool: &Pubkey) -> bool {
+    pub fn is_amm_pool(&self, pool: &Pubkey) -> bool {
         self.amm_pool_pubkeys.contains(pool)
     }
 
     /// Determine if a pool is a CLMM pool
-    fn is_clmm_pool(&self, pool: &Pubkey) -> bool {
+    pub fn is_clmm_pool(&self, pool: &Pubkey) -> bool {
         self.clmm_pool_pubkeys.contains(pool)
     }
 
@@ -194,13 +228,19 @@ impl RaydiumIndexer {
                             )
                         {
                             // Check if this pool is monitored
-                            if self.is_clmm_pool(&event.pool_state) {
-                                self.log_create_position_event(&event, &event.pool_state);
+                            let pool = event.pool_state;
+                            if self.is_clmm_pool(&pool) {
+                                self.log_create_position_event(&event, &pool);
+
+                                // The create event doesn't carry the position NFT mint, so take it
+                                // from the liquidity event emitted in the same transaction
+                                let position_nft_mint = find_position_mint_in_logs(&log.logs);
                                 events.push(
                                     RaydiumParsedEvent::ClmmCreatePosition(
                                         event,
                                         log.signature.clone(),
-                                        event.pool_state
+                                        pool,
+                                        position_nft_mint
                                     )
                                 );
                             }
@@ -214,13 +254,11 @@ impl RaydiumIndexer {
                                 &data[8..]
                             )
                         {
-                            // We need to determine the pool address for increase liquidity events
-                            // This might require looking up the position in the logs or database
-                            // For now, we'll log a placeholder and implement the lookup later
+                            // Increase liquidity events only carry the position NFT mint
                             let pool = self.lookup_pool_for_position(
                                 &event.position_nft_mint,
                                 log
-                            )?;
+                            ).await?;
 
                             if let Some(pool_pubkey) = pool {
                                 if self.is_clmm_pool(&pool_pubkey) {
@@ -244,12 +282,11 @@ impl RaydiumIndexer {
                                 &data[8..]
                             )
                         {
-                            // We need to determine the pool address for decrease liquidity events
-                            // This might require looking up the position in the logs or database
+                            // Decrease liquidity events only carry the position NFT mint
                             let pool = self.lookup_pool_for_position(
                                 &event.position_nft_mint,
                                 log
-                            )?;
+                            ).await?;
 
                             if let Some(pool_pubkey) = pool {
                                 if self.is_clmm_pool(&pool_pubkey) {
@@ -273,26 +310,52 @@ impl RaydiumIndexer {
     }
 
     /// Parse logs for AMM events
-    async fn parse_amm_events(&self, log: &RpcLogsResponse) -> Result<Vec<RaydiumParsedEvent>> {
+    async fn parse_amm_events(&self, _log: &RpcLogsResponse) -> Result<Vec<RaydiumParsedEvent>> {
         // Implementation to parse AMM events similar to CLMM parsing
         // For now return empty vector as placeholder
         Ok(Vec::new())
     }
 
     /// Helper method to look up the pool address from a position NFT
-    fn lookup_pool_for_position(
+    ///
+    /// The transaction's own logs are scanned first, since the create-position or
+    /// liquidity-change event emitted alongside carries the pool. Otherwise the
+    /// position -> pool mapping recorded by earlier events is used (cached in the repository).
+    async fn lookup_pool_for_position(
         &self,
         position_nft_mint: &Pubkey,
         log: &RpcLogsResponse
     ) -> Result<Option<Pubkey>> {
-        // This is a placeholder implementation
-        // In a real implementation, you would:
-        // 1. Try to find the pool address in the log
-        // 2. If not found, query the database for the position -> pool mapping
-        // 3. If still not found, return None or error depending on requirements
-
-        // For now, return None as a placeholder
-        Ok(None)
+        if let Some(pool) = find_pool_in_logs(&log.logs) {
+            self.repository.cache_position_pool(*position_nft_mint, pool);
+            return Ok(Some(pool));
+        }
+
+        let pool = self.repository.get_position_pool(position_nft_mint).await?;
+        if pool.is_none() {
+            logging::log_activity(
+                PROTOCOL.dex(),
+                "Position lookup",
+                Some(&format!("no pool found for position {}", position_nft_mint))
+            );
+        }
+
+        Ok(pool)
+    }
+
+    /// Record a position -> pool mapping (only cached in memory during dry runs)
+    async fn record_position_pool(
+        &self,
+        position_nft_mint: &Pubkey,
+        pool: &Pubkey,
+        signature: &str
+    ) -> Result<()> {
+        if self.is_dry_run() {
+            self.repository.cache_position_pool(*position_nft_mint, *pool);
+            return Ok(());
+        }
+
+        self.repository.save_position_pool(position_nft_mint, pool, signature).await
     }
 }
 
@@ -306,6 +369,8 @@ impl DexIndexer for RaydiumIndexer {
         provided_pools: Option<&Vec<String>>,
         connection_config: ConnectionConfig
     ) -> Result<Self> {
+        connection_config.validate()?;
+
         // Create the repository for database access
         let repository = RaydiumRepository::new(db_pool.clone());
 
@@ -314,58 +379,97 @@ impl DexIndexer for RaydiumIndexer {
         let (amm_pool_pubkeys, clmm_pool_pubkeys) = repository.get_pools_with_fallback(
             provided_pools,
             DEFAULT_RAYDIUM_AMM_POOL,
-            DEFAULT_RAYDIUM_CLMM_POOL
+            DEFAULT_RAYDIUM_CLMM_POOL,
+            connection_config.strict_pool_addresses
         ).await?;
+        validate_pool_sets(&amm_pool_pubkeys, &clmm_pool_pubkeys)?;
 
         // Log the source of pool addresses
         if provided_pools.is_some() && !provided_pools.unwrap().is_empty() {
-            logging::log_activity(DEX_NAME, "Pool source", Some("from command line arguments"));
+            logging::log_activity(PROTOCOL.dex(), "Pool source", Some("from command line arguments"));
         } else if !amm_pool_pubkeys.is_empty() || !clmm_pool_pubkeys.is_empty() {
-            logging::log_activity(DEX_NAME, "Pool source", Some("from database"));
+            logging::log_activity(PROTOCOL.dex(), "Pool source", Some("from database"));
         } else {
             logging::log_activity(
-                DEX_NAME,
+                PROTOCOL.dex(),
                 "Pool source",
                 Some("using default pools (no pools in CLI or database)")
             );
         }
 
-        // Create the signature store
-        let signature_store = Self::create_signature_store()?;
+        // Create the signature store (kept in memory for dry runs so nothing is written)
+        let signature_store = if connection_config.dry_run {
+            SignatureStore::InMemory(InMemorySignatureStore::new())
+        } else {
+            SignatureStore::Database(DbSignatureStore::new(db_pool.clone()))
+        };
 
         // Create the backfill manager
         let backfill_config = crate::backfill_manager::BackfillConfig {
             rpc_url: connection_config.rpc_url.clone(),
+            rpc_headers: connection_config.rpc_headers.clone(),
             max_signatures_per_request: 100,
             initial_backfill_slots: 10_000,
-            dex_type: DEX_NAME.to_string(),
+            dex_type: PROTOCOL.dex().to_string(),
+            commitment: connection_config.commitment,
+            rpc_timeout: connection_config.rpc_timeout,
+            retry: crate::backfill_manager::RetryConfig::default(),
+            order: connection_config.backfill_order,
+            pool_cap: connection_config.backfill_pool_cap,
+            max_requests_per_second: connection_config.backfill_max_requests_per_second,
+            fetch_concurrency: connection_config.backfill_fetch_concurrency,
+            pool_concurrency: connection_config.backfill_pool_concurrency,
+            signature_read_batch_size: connection_config.signature_read_batch_size,
         };
         let backfill_manager = BackfillManager::new(backfill_config, signature_store.clone());
+        let outage_buffer = OutageBuffer::new(connection_config.outage_buffer_capacity);
 
         Ok(Self {
             repository,
-            amm_pool_pubkeys,
-            clmm_pool_pubkeys,
+            amm_pool_pubkeys: PoolSet::new(amm_pool_pubkeys),
+            clmm_pool_pubkeys: PoolSet::new(clmm_pool_pubkeys),
             signature_store,
             backfill_manager,
             connection_config,
+            health: Arc::new(HealthTracker::new()),
+            outage_buffer,
         })
     }
 
     fn program_ids(&self) -> Vec<&str> {
+        let program_ids = &self.connection_config.program_ids;
         vec![
             // AMM program ID
-            "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+            program_ids.raydium_amm.as_str(),
             // CLMM program ID
-            "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK"
+            program_ids.raydium_clmm.as_str()
         ]
     }
 
-    fn pool_pubkeys(&self) -> &HashSet<Pubkey> {
+    fn pool_pubkeys(&self) -> HashSet<Pubkey> {
         // Return all pools (both AMM and CLMM)
-        // This is a limitation of the current trait design
         // We maintain separate pool sets internally but expose a combined view
-        &self.all_pool_pubkeys()
+        self.all_pool_pubkeys()
+    }
+
+    fn contains_pool(&self, pool: &Pubkey) -> bool {
+        self.amm_pool_pubkeys.contains(pool) || self.clmm_pool_pubkeys.contains(pool)
+    }
+
+    fn pool_count(&self) -> usize {
+        // The sets are disjoint, as checked on startup
+        self.amm_pool_pubkeys.len() + self.clmm_pool_pubkeys.len()
+    }
+
+    fn add_pool(&self, pool: Pubkey) -> bool {
+        // AMM and CLMM pools are kept in separate sets, and the pool type can't be told from its address
+        log::warn!("[raydium] Adding pools at runtime is not supported yet, ignoring {}", pool);
+        false
+    }
+
+    fn remove_pool(&self, pool: &Pubkey) -> bool {
+        // The sets are disjoint, so at most one of them holds the pool
+        self.amm_pool_pubkeys.remove(pool) || self.clmm_pool_pubkeys.remove(pool)
     }
 
     fn repository(&self) -> &Self::Repository {
@@ -373,13 +477,36 @@ impl DexIndexer for RaydiumIndexer {
     }
 
     fn dex_name(&self) -> &str {
-        DEX_NAME
+        PROTOCOL.dex()
+    }
+
+    fn event_discriminators(&self) -> Vec<([u8; 8], &'static str)> {
+        vec![
+            (CLMM_CREATE_PERSONAL_POSITION_DISCRIMINATOR, "CreatePersonalPositionEvent"),
+            (CLMM_LIQUIDITY_INCREASED_DISCRIMINATOR, "IncreaseLiquidityEvent"),
+            (CLMM_LIQUIDITY_DECREASED_DISCRIMINATOR, "DecreaseLiquidityEvent")
+        ]
     }
 
     fn signature_store(&self) -> &SignatureStore {
         &self.signature_store
     }
 
+    async fn indexed_signatures(&self, signatures: &[Signature]) -> Result<HashSet<Signature>> {
+        let signatures: Vec<String> = signatures.iter().map(Signature::to_string).collect();
+        let indexed = self.repository.indexed_signatures(&signatures).await?;
+        Ok(
+            indexed
+                .iter()
+                .filter_map(|signature| Signature::from_str(signature).ok())
+                .collect()
+        )
+    }
+
+    async fn delete_events_after_slot(&self, pool: &Pubkey, slot: u64) -> Result<u64> {
+        self.repository.delete_events_after_slot(&pool.to_string(), slot as i64).await
+    }
+
     fn backfill_manager(&self) -> &BackfillManager {
         &self.backfill_manager
     }
@@ -388,6 +515,14 @@ impl DexIndexer for RaydiumIndexer {
         &self.connection_config
     }
 
+    fn health_tracker(&self) -> &Arc<HealthTracker> {
+        &self.health
+    }
+
+    fn outage_buffer(&self) -> &OutageBuffer {
+        &self.outage_buffer
+    }
+
     /// Parse events from a log, returning any found events without persisting them
     async fn parse_log_events(&self, log: &RpcLogsResponse) -> Result<Vec<Self::ParsedEvent>> {
         // Quick check if the log contains any of our program IDs
@@ -410,22 +545,46 @@ impl DexIndexer for RaydiumIndexer {
     }
 
     /// Handle a single event (for both real-time and backfill processing)
-    async fn handle_event(&self, event: Self::ParsedEvent) -> Result<()> {
+    async fn handle_event(
+        &self,
+        event: Self::ParsedEvent,
+        _event_index: usize,
+        is_backfill: bool,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+        _fee_payer: Option<Pubkey>
+    ) -> Result<bool> {
+        // Create a source label for logging
+        let source_label = if is_backfill { "BACKFILL" } else { "LIVE" };
+
         match event {
             // Handle AMM events
             RaydiumParsedEvent::AmmTraded(signature) => {
                 // Handle AMM traded event (placeholder)
-                log::info!("Processed AMM traded event for transaction: {}", signature);
-                Ok(())
+                log::info!(
+                    "[{}][{}] Processed AMM traded event for transaction: {}",
+                    PROTOCOL.dex(),
+                    source_label,
+                    signature
+                );
+                Ok(false)
             }
 
             // Handle CLMM events
-            RaydiumParsedEvent::ClmmCreatePosition(event_data, signature, pool) => {
+            RaydiumParsedEvent::ClmmCreatePosition(event_data, signature, pool, position_nft_mint) => {
+                // Record the position -> pool mapping for later liquidity events
+                if let Some(position_nft_mint) = position_nft_mint {
+                    self.record_position_pool(&position_nft_mint, &pool, &signature).await?;
+                }
+
                 // Create the base event
                 let base_event = self.create_clmm_base_event(
                     &signature,
                     &pool,
-                    RaydiumCLMMEventType::CreatePosition
+                    RaydiumCLMMEventType::CreatePosition,
+                    is_backfill,
+                    slot,
+                    block_time
                 );
 
                 // Create the data record
@@ -433,7 +592,6 @@ impl DexIndexer for RaydiumIndexer {
                     event_id: 0, // Will be set after base event is inserted
                     minter: event_data.minter.to_string(),
                     nft_owner: event_data.nft_owner.to_string(),
-                    output_amount: 0, // This field is not in the event data
                     tick_lower_index: event_data.tick_lower_index,
                     tick_upper_index: event_data.tick_upper_index,
                     liquidity: event_data.liquidity,
@@ -448,16 +606,27 @@ impl DexIndexer for RaydiumIndexer {
                     data,
                 };
 
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                    return Ok(true);
+                }
+
                 self.repository.insert_clmm_create_position_event(event_record).await?;
-                Ok(())
+                Ok(true)
             }
 
             RaydiumParsedEvent::ClmmIncreaseLiquidity(event_data, signature, pool) => {
+                // Persist the mapping in case the pool was only found in this transaction's logs
+                self.record_position_pool(&event_data.position_nft_mint, &pool, &signature).await?;
+
                 // Create the base event
                 let base_event = self.create_clmm_base_event(
                     &signature,
                     &pool,
-                    RaydiumCLMMEventType::IncreaseLiquidity
+                    RaydiumCLMMEventType::IncreaseLiquidity,
+                    is_backfill,
+                    slot,
+                    block_time
                 );
 
                 // Create the data record
@@ -476,16 +645,27 @@ impl DexIndexer for RaydiumIndexer {
                     data,
                 };
 
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                    return Ok(true);
+                }
+
                 self.repository.insert_clmm_increase_liquidity_event(event_record).await?;
-                Ok(())
+                Ok(true)
             }
 
             RaydiumParsedEvent::ClmmDecreaseLiquidity(event_data, signature, pool) => {
+                // Persist the mapping in case the pool was only found in this transaction's logs
+                self.record_position_pool(&event_data.position_nft_mint, &pool, &signature).await?;
+
                 // Create the base event
                 let base_event = self.create_clmm_base_event(
                     &signature,
                     &pool,
-                    RaydiumCLMMEventType::DecreaseLiquidity
+                    RaydiumCLMMEventType::DecreaseLiquidity,
+                    is_backfill,
+                    slot,
+                    block_time
                 );
 
                 // Create the data record
@@ -507,8 +687,13 @@ impl DexIndexer for RaydiumIndexer {
                     data,
                 };
 
+                if self.is_dry_run() {
+                    self.log_dry_run_event(source_label, &event_record);
+                    return Ok(true);
+                }
+
                 self.repository.insert_clmm_decrease_liquidity_event(event_record).await?;
-                Ok(())
+                Ok(true)
             }
         }
     }
@@ -4,7 +4,25 @@ pub mod db;
 pub mod indexers;
 pub mod websocket_manager;
 pub mod backfill_manager;
+pub mod config;
+pub mod confirmation_buffer;
+pub mod dead_letter;
+pub mod enrichment;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod health;
+pub mod metrics;
+pub mod outage_buffer;
+pub mod pool_loader;
+pub mod program_ids;
+pub mod rate_limiter;
+pub mod rpc;
+pub mod sinks;
+pub mod staleness;
+pub mod subscription_hub;
 pub mod utils;
+pub mod validation;
 
 // Re-export common types and traits
 pub use models::common::Protocol;
@@ -16,15 +34,19 @@ pub use models::orca::whirlpool::{
     TRADED_EVENT_DISCRIMINATOR,
     LIQUIDITY_INCREASED_DISCRIMINATOR,
     LIQUIDITY_DECREASED_DISCRIMINATOR,
+    POOL_INITIALIZED_DISCRIMINATOR,
     OrcaWhirlpoolEventType,
     OrcaWhirlpoolTradedEvent,
     OrcaWhirlpoolLiquidityIncreasedEvent,
     OrcaWhirlpoolLiquidityDecreasedEvent,
+    OrcaWhirlpoolPoolInitializedEvent,
 };
 pub use db::repositories::OrcaWhirlpoolRepository;
 pub use models::orca::whirlpool::OrcaWhirlpoolPoolRecord;
 pub use indexers::OrcaWhirlpoolIndexer;
 
-pub use websocket_manager::{ WebSocketManager, WebSocketConfig };
+pub use websocket_manager::{ ChannelOverflowPolicy, LogSubscription, WebSocketManager, WebSocketConfig };
 pub use backfill_manager::{ BackfillManager, BackfillConfig };
+pub use program_ids::ProgramIds;
+pub use events::{ IndexedEvent, PoolStateUpdate, RewardEmission };
 pub use db::signature_store::SignatureStore;
@@ -6,30 +6,324 @@
  ******************************************************************************/
 
 use anyhow::{ Context, Result };
-use clap::{ Parser, Subcommand };
+use chrono::{ DateTime, Utc };
+use clap::parser::ValueSource;
+use clap::{ ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{ HashMap, HashSet };
+use std::fs::File;
+use std::io::{ BufWriter, Write };
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
 
 use indexer::{
-    db::{ Database, DbConfig },
-    indexers::{ start_indexer, ConnectionConfig, DexIndexer, OrcaWhirlpoolIndexer },
-    utils::logging,
+    backfill_manager::{
+        parse_signature_list,
+        BackfillOrder,
+        BackfillWindow,
+        DEFAULT_FETCH_CONCURRENCY,
+        DEFAULT_POOL_CONCURRENCY,
+        DEFAULT_SIGNATURE_READ_BATCH_SIZE,
+    },
+    config::Config,
+    db::{
+        repositories::{ OrcaWhirlpoolRepository, PoolDeletion },
+        Database,
+        DbConfig,
+        TokenMetadataStore,
+    },
+    dead_letter::DEFAULT_DEAD_LETTER_PATH,
+    outage_buffer::DEFAULT_OUTAGE_BUFFER_CAPACITY,
+    export::{ export_orca_trades_csv, CompressedWriter, ExportCompression, TradeExport },
+    health::{ self, DEFAULT_HEALTH_STALE_THRESHOLD },
+    indexers::{
+        parse_commitment,
+        parse_pool_min_input_amount,
+        parse_program_commitment,
+        start_indexer,
+        ConnectionConfig,
+        DEFAULT_BACKFILL_INTERVAL,
+        DEFAULT_RECONCILIATION_WINDOW,
+        DexIndexer,
+        OrcaWhirlpoolIndexer,
+        RaydiumIndexer,
+        StartupStrategy,
+    },
+    metrics,
+    models::orca::whirlpool::OrcaWhirlpoolPoolRecord,
+    pool_loader::{ orca::OrcaProcessor, DexProcessor },
+    program_ids::{
+        ProgramIds,
+        DEFAULT_ORCA_POOL,
+        METAPLEX_METADATA_PROGRAM_ID,
+        ORCA_WHIRLPOOL_PROGRAM_ID,
+        RAYDIUM_AMM_PROGRAM_ID,
+        RAYDIUM_CLMM_PROGRAM_ID,
+    },
+    rpc::{ build_rpc_client_with_headers, parse_rpc_header, Cluster },
+    sinks::{
+        EventSink,
+        StdoutSink,
+        WebhookConfig,
+        WebhookSink,
+        DEFAULT_WEBHOOK_QUEUE_CAPACITY,
+    },
+    staleness::{ StalenessConfig, DEFAULT_MAX_STALE_THRESHOLD, DEFAULT_STALE_GAP_MULTIPLIER },
+    utils::logging::{ self, LogFormat },
+    validation::ValidationMode,
+    websocket_manager::{ ChannelOverflowPolicy, DEFAULT_WS_CHANNEL_CAPACITY },
 };
 
 // Default values
 const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
 const DEFAULT_WS_URL: &str = "wss://api.mainnet-beta.solana.com";
+// Age after which `pools add` re-fetches stored token metadata, as load_pools does by default
+const DEFAULT_TOKEN_METADATA_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Solana DEX indexer CLI
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// TOML file with settings for the flags below (see docs/cli-usage.md); flags given
+    /// on the command line override it, and it overrides environment variables
+    #[arg(long, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+
+    /// Use the public RPC and WebSocket URLs of a cluster: mainnet, devnet, testnet or
+    /// localnet (http://localhost:8899); --rpc-url and --ws-url still override them
+    #[arg(long, env = "SOLANA_CLUSTER")]
+    cluster: Option<Cluster>,
+
     /// Solana RPC URL
     #[arg(long, default_value = DEFAULT_RPC_URL)]
     rpc_url: String,
 
+    /// Extra HTTP header for RPC requests as 'Name: value' (repeatable, or ';'-separated in the env var)
+    #[arg(
+        long = "rpc-header",
+        env = "RPC_HEADERS",
+        value_delimiter = ';',
+        value_parser = parse_rpc_header
+    )]
+    rpc_headers: Vec<(String, String)>,
+
     /// Solana WebSocket URL
     #[arg(long, default_value = DEFAULT_WS_URL)]
     ws_url: String,
 
+    /// Comma-separated list of WebSocket URLs to fail over to when the primary fails
+    #[arg(long, use_value_delimiter = true, value_delimiter = ',')]
+    ws_fallback_urls: Option<Vec<String>>,
+
+    /// Log notifications buffered between the WebSocket and event processing
+    #[arg(long, env = "WS_CHANNEL_CAPACITY", default_value_t = DEFAULT_WS_CHANNEL_CAPACITY)]
+    ws_channel_capacity: usize,
+
+    /// When the buffer is full: block (stop reading the socket until it drains) or
+    /// drop (discard notifications, recovered by the next backfill)
+    #[arg(long, env = "WS_OVERFLOW_POLICY", default_value = "block")]
+    ws_overflow_policy: ChannelOverflowPolicy,
+
+    /// Subscribe to each program's logs on its own connection, so a failing
+    /// subscription doesn't interrupt the other programs' events
+    #[arg(long, env = "WS_SUBSCRIPTION_PER_PROGRAM")]
+    ws_subscription_per_program: bool,
+
+    /// Commitment level of one program's subscription as 'PROGRAM_ID=LEVEL' (repeatable,
+    /// or ','-separated in the env var; requires --ws-subscription-per-program)
+    #[arg(
+        long = "program-commitment",
+        env = "PROGRAM_COMMITMENTS",
+        value_delimiter = ',',
+        value_parser = parse_program_commitment,
+        requires = "ws_subscription_per_program"
+    )]
+    program_commitments: Vec<(String, CommitmentConfig)>,
+
+    /// Also subscribe to the Orca pools' accounts, tracking their price and liquidity
+    /// as they change (opens one account subscription per pool)
+    #[arg(long, env = "SUBSCRIBE_POOL_ACCOUNTS")]
+    subscribe_pool_accounts: bool,
+
+    /// Number of slots a live event must age before it is persisted (0 disables the delay)
+    #[arg(long, env = "CONFIRMATION_DELAY_SLOTS", default_value_t = 0)]
+    confirmation_delay_slots: u64,
+
+    /// Orca Whirlpool program ID (override for local validators or custom deployments)
+    #[arg(long, env = "ORCA_WHIRLPOOL_PROGRAM_ID", default_value = ORCA_WHIRLPOOL_PROGRAM_ID)]
+    orca_program_id: String,
+
+    /// Orca pool indexed when no pools are given or subscribed (override for other clusters)
+    #[arg(long, env = "ORCA_DEFAULT_POOL", default_value = DEFAULT_ORCA_POOL)]
+    orca_default_pool: String,
+
+    /// Raydium AMM program ID
+    #[arg(long, env = "RAYDIUM_AMM_PROGRAM_ID", default_value = RAYDIUM_AMM_PROGRAM_ID)]
+    raydium_amm_program_id: String,
+
+    /// Raydium CLMM program ID
+    #[arg(long, env = "RAYDIUM_CLMM_PROGRAM_ID", default_value = RAYDIUM_CLMM_PROGRAM_ID)]
+    raydium_clmm_program_id: String,
+
+    /// Commitment level for the subscription and RPC calls: processed, confirmed or finalized
+    #[arg(long, env = "COMMITMENT", default_value = "confirmed", value_parser = parse_commitment)]
+    commitment: CommitmentConfig,
+
+    /// Timeout in seconds for each RPC request
+    #[arg(long, env = "RPC_TIMEOUT_SECS", default_value_t = 30)]
+    rpc_timeout_secs: u64,
+
+    /// File that events are written to when they still fail to persist after retries
+    #[arg(long, env = "DEAD_LETTER_FILE", default_value = DEFAULT_DEAD_LETTER_PATH)]
+    dead_letter_file: String,
+
+    /// Events held in memory while the database is unreachable, replayed once it is back
+    #[arg(long, env = "OUTAGE_BUFFER_SIZE", default_value_t = DEFAULT_OUTAGE_BUFFER_CAPACITY)]
+    outage_buffer_size: usize,
+
+    /// Order in which the initial backfill processes transactions: newest-first or oldest-first
+    #[arg(long, env = "BACKFILL_ORDER", default_value = "newest-first")]
+    backfill_order: BackfillOrder,
+
+    /// Startup order: buffer (subscribe, backfill, then process buffered events),
+    /// background (process live events while backfilling) or backfill-first
+    #[arg(long, env = "STARTUP_STRATEGY", default_value = "buffer")]
+    startup_strategy: StartupStrategy,
+
+    /// Seconds between scheduled backfills catching up on missed transactions
+    #[arg(
+        long,
+        env = "BACKFILL_INTERVAL_SECS",
+        default_value_t = DEFAULT_BACKFILL_INTERVAL.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    backfill_interval_secs: u64,
+
+    /// Seconds between reconciliation passes re-checking recent history for missing
+    /// events, e.g. 86400 for daily (disabled if not set)
+    #[arg(long, env = "RECONCILE_INTERVAL_SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    reconcile_interval_secs: Option<u64>,
+
+    /// Seconds of history each reconciliation pass re-checks
+    #[arg(
+        long,
+        env = "RECONCILE_WINDOW_SECS",
+        default_value_t = DEFAULT_RECONCILIATION_WINDOW.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    reconcile_window_secs: u64,
+
+    /// Maximum transactions backfilled per pool in one cycle; the rest is deferred to the next cycle
+    #[arg(long, env = "BACKFILL_POOL_CAP")]
+    backfill_pool_cap: Option<usize>,
+
+    /// Maximum backfill RPC requests per second (unlimited if not set)
+    #[arg(long, env = "BACKFILL_MAX_RPS", value_parser = clap::value_parser!(u32).range(1..))]
+    backfill_max_rps: Option<u32>,
+
+    /// Backfill transactions fetched at once
+    #[arg(
+        long,
+        env = "BACKFILL_CONCURRENCY",
+        default_value_t = DEFAULT_FETCH_CONCURRENCY as u32,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    backfill_concurrency: u32,
+
+    /// Pools backfilled at once during the initial backfill
+    #[arg(
+        long,
+        env = "BACKFILL_POOL_CONCURRENCY",
+        default_value_t = DEFAULT_POOL_CONCURRENCY as u32,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    backfill_pool_concurrency: u32,
+
+    /// Pools whose last processed signatures are read with one query in a scheduled backfill
+    #[arg(
+        long,
+        env = "SIGNATURE_READ_BATCH_SIZE",
+        default_value_t = DEFAULT_SIGNATURE_READ_BATCH_SIZE as u32,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    signature_read_batch_size: u32,
+
+    /// Warn about trades moving more than this many whole tokens, a sign of wrong token decimals
+    #[arg(long, env = "MAX_PLAUSIBLE_TRADE_AMOUNT")]
+    max_plausible_trade_amount: Option<f64>,
+
+    /// Check parsed events before storing them and quarantine the ones that fail: off, basic or strict
+    #[arg(long, env = "EVENT_VALIDATION", default_value = "off")]
+    event_validation: ValidationMode,
+
+    /// Drop Orca trades whose input amount, in the input token's smallest units, is below
+    /// this; lossy, the dropped trades are only counted
+    #[arg(long, env = "MIN_INPUT_AMOUNT")]
+    min_input_amount: Option<u64>,
+
+    /// Minimum trade input amount of one pool as 'POOL=AMOUNT', overriding --min-input-amount
+    /// (repeatable, or ','-separated in the env var)
+    #[arg(
+        long = "pool-min-input-amount",
+        env = "POOL_MIN_INPUT_AMOUNTS",
+        value_delimiter = ',',
+        value_parser = parse_pool_min_input_amount
+    )]
+    pool_min_input_amounts: Vec<(String, u64)>,
+
+    /// URL that each indexed event is POSTed to as JSON; the webhook is disabled if not set
+    #[arg(long, env = "WEBHOOK_URL")]
+    webhook_url: Option<String>,
+
+    /// Events queued for webhook delivery before new ones are dropped
+    #[arg(long, env = "WEBHOOK_QUEUE_SIZE", default_value_t = DEFAULT_WEBHOOK_QUEUE_CAPACITY)]
+    webhook_queue_size: usize,
+
+    /// Upper bound in seconds on how long a quiet stream may go without messages before a backfill runs
+    #[arg(long, env = "STALE_MAX_SECS", default_value_t = DEFAULT_MAX_STALE_THRESHOLD.as_secs())]
+    stale_max_secs: u64,
+
+    /// Multiple of the stream's median gap between messages after which it is considered stale
+    #[arg(long, env = "STALE_GAP_MULTIPLIER", default_value_t = DEFAULT_STALE_GAP_MULTIPLIER)]
+    stale_gap_multiplier: f64,
+
+    /// Also write each indexed event to stdout as one line of JSON; logs move to stderr
+    #[arg(long, env = "ALSO_STDOUT")]
+    also_stdout: bool,
+
+    /// Store the raw log messages of each transaction that produced an indexed event
+    #[arg(long, env = "STORE_TRANSACTION_LOGS")]
+    store_transaction_logs: bool,
+
+    /// Parse and log events without writing them to the database
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Fail startup when a given pool address is invalid, instead of skipping it with a warning
+    #[arg(long, env = "STRICT_POOL_ADDRESSES")]
+    strict_pool_addresses: bool,
+
+    /// Log output format: text or json
+    #[arg(long, env = "LOG_FORMAT", default_value = "text")]
+    log_format: LogFormat,
+
+    /// Port to serve Prometheus metrics on (/metrics); metrics are disabled if not set
+    #[arg(long, env = "METRICS_PORT")]
+    metrics_port: Option<u16>,
+
+    /// Port to serve the liveness check on (/healthz); disabled if not set
+    #[arg(long, env = "HEALTH_PORT")]
+    health_port: Option<u16>,
+
+    /// Seconds without WebSocket messages before /healthz reports the indexer unhealthy (503)
+    #[arg(long, env = "HEALTH_STALE_SECS", default_value_t = DEFAULT_HEALTH_STALE_THRESHOLD.as_secs())]
+    health_stale_secs: u64,
+
     /// Indexer command to run
     #[command(subcommand)]
     command: Command,
@@ -42,7 +336,48 @@ enum Command {
         /// Comma-separated list of pool addresses to index
         #[arg(long, use_value_delimiter = true, value_delimiter = ',')]
         pools: Option<Vec<String>>,
+
+        /// Automatically index newly created pools whose tokens are both in --auto-subscribe-mints
+        #[arg(long, requires = "auto_subscribe_mints")]
+        auto_subscribe: bool,
+
+        /// Comma-separated token mints of interest for --auto-subscribe
+        #[arg(long, use_value_delimiter = true, value_delimiter = ',', requires = "auto_subscribe")]
+        auto_subscribe_mints: Option<Vec<String>>,
+
+        /// Index the subscribed pools trading this token mint, e.g. every SOL pair, instead of all subscribed pools
+        #[arg(long, conflicts_with_all = ["pools", "all_pools"])]
+        token_mint: Option<String>,
+
+        /// Index every Whirlpool instead of a pool list; backfills are skipped (high volume, see docs)
+        #[arg(long, conflicts_with = "pools")]
+        all_pools: bool,
+
+        /// One-shot Orca command to run instead of the live indexer
+        #[command(subcommand)]
+        action: Option<OrcaCommand>,
     },
+    /// Run the Orca and Raydium indexers concurrently, sharing the database pool
+    All {
+        /// Comma-separated list of Orca pool addresses to index
+        #[arg(long, use_value_delimiter = true, value_delimiter = ',')]
+        orca_pools: Option<Vec<String>>,
+
+        /// Comma-separated list of Raydium pool addresses to index
+        #[arg(long, use_value_delimiter = true, value_delimiter = ',')]
+        raydium_pools: Option<Vec<String>>,
+
+        /// Keep the other indexers running when one fails, instead of stopping them all
+        #[arg(long)]
+        keep_going: bool,
+    },
+    /// Manage the subscribed Orca pools, then exit
+    Pools {
+        #[command(subcommand)]
+        action: PoolsCommand,
+    },
+    /// Print a config file listing every key with its description and default, then exit
+    ConfigTemplate,
     // Future support for additional DEXes
     /*
     /// Run the Raydium indexer (future implementation)
@@ -54,42 +389,864 @@ enum Command {
     */
 }
 
+#[derive(Subcommand, Debug)]
+enum OrcaCommand {
+    /// Re-index one pool's transactions in a slot or time window, then exit
+    Backfill {
+        /// Pool address to backfill
+        #[arg(long)]
+        pool: String,
+
+        /// First slot of the window (inclusive)
+        #[arg(long, requires = "to_slot", conflicts_with_all = ["from", "to"])]
+        from_slot: Option<u64>,
+
+        /// Last slot of the window (inclusive)
+        #[arg(long, requires = "from_slot")]
+        to_slot: Option<u64>,
+
+        /// Start of the window as an RFC 3339 timestamp, e.g. 2024-05-01T12:00:00Z
+        #[arg(long, requires = "to", value_parser = parse_rfc3339)]
+        from: Option<DateTime<Utc>>,
+
+        /// End of the window as an RFC 3339 timestamp
+        #[arg(long, requires = "from", value_parser = parse_rfc3339)]
+        to: Option<DateTime<Utc>>,
+    },
+    /// Index an explicit list of one pool's transactions, then exit
+    IndexSignatures {
+        /// Pool address the transactions belong to
+        #[arg(long)]
+        pool: String,
+
+        /// File with one transaction signature per line; blank lines and # comments are skipped
+        #[arg(long)]
+        sigs: String,
+    },
+    /// Export one pool's indexed trades as CSV, then exit
+    Export {
+        /// Pool address to export
+        #[arg(long)]
+        pool: String,
+
+        /// Only export trades at or after this RFC 3339 timestamp
+        #[arg(long, value_parser = parse_rfc3339)]
+        from: Option<DateTime<Utc>>,
+
+        /// Only export trades before this RFC 3339 timestamp
+        #[arg(long, value_parser = parse_rfc3339)]
+        to: Option<DateTime<Utc>>,
+
+        /// File to write the CSV to; written to stdout if not set
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Compression for the output: gzip, zstd or none
+        #[arg(long, default_value = "none")]
+        compress: ExportCompression,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PoolsCommand {
+    /// List the subscribed pools
+    List,
+    /// Show one subscribed pool and its tokens
+    Show {
+        /// Pool address
+        pool: String,
+    },
+    /// Fetch a pool and its token metadata from chain and subscribe to it
+    Add {
+        /// Pool address
+        pool: String,
+
+        /// Fail instead of storing placeholder names when the token metadata can't be resolved
+        #[arg(long)]
+        require_token_metadata: bool,
+    },
+    /// Unsubscribe from a pool; its indexed events are kept unless --delete-events is given
+    Remove {
+        /// Pool address
+        pool: String,
+
+        /// Also forget the pool's last signature and backfill progress, so re-adding it backfills from scratch
+        #[arg(long)]
+        forget_signatures: bool,
+
+        /// Also delete the pool's indexed events (cannot be undone)
+        #[arg(long)]
+        delete_events: bool,
+    },
+    /// Report the slot range of a pool's indexed events and stretches that look like missed events
+    Gaps {
+        /// Pool address
+        pool: String,
+    },
+}
+
+/// Canonical RPC and WebSocket URLs of a cluster
+fn cluster_urls(cluster: Cluster) -> (&'static str, &'static str) {
+    match cluster {
+        Cluster::Mainnet => (DEFAULT_RPC_URL, DEFAULT_WS_URL),
+        Cluster::Devnet => ("https://api.devnet.solana.com", "wss://api.devnet.solana.com"),
+        Cluster::Testnet => ("https://api.testnet.solana.com", "wss://api.testnet.solana.com"),
+        Cluster::Localnet => ("http://localhost:8899", "ws://localhost:8900"),
+    }
+}
+
+// Use a config file value unless the flag was given on the command line
+fn set_from_config<T>(target: &mut T, value: Option<T>, matches: &ArgMatches, id: &str) {
+    if let Some(value) = value {
+        if matches.value_source(id) != Some(ValueSource::CommandLine) {
+            *target = value;
+        }
+    }
+}
+
+/// Fill in the settings from a config file that weren't given on the command line
+///
+/// Flags set only by their environment variable or default take the file's value.
+fn apply_config(cli: &mut Cli, config: Config, matches: &ArgMatches) {
+    set_from_config(&mut cli.cluster, config.cluster.map(Some), matches, "cluster");
+
+    // A cluster only replaces the default URLs, not ones set on the command line or in the file
+    if let Some(cluster) = cli.cluster {
+        let (rpc_url, ws_url) = cluster_urls(cluster);
+        if matches.value_source("rpc_url") != Some(ValueSource::CommandLine) {
+            cli.rpc_url = rpc_url.to_string();
+        }
+        if matches.value_source("ws_url") != Some(ValueSource::CommandLine) {
+            cli.ws_url = ws_url.to_string();
+        }
+    }
+    set_from_config(&mut cli.rpc_url, config.rpc_url, matches, "rpc_url");
+    set_from_config(
+        &mut cli.rpc_headers,
+        config.rpc_headers.map(|headers| headers.into_iter().collect()),
+        matches,
+        "rpc_headers"
+    );
+    set_from_config(&mut cli.ws_url, config.ws_url, matches, "ws_url");
+    set_from_config(
+        &mut cli.ws_fallback_urls,
+        config.ws_fallback_urls.map(Some),
+        matches,
+        "ws_fallback_urls"
+    );
+    set_from_config(
+        &mut cli.ws_channel_capacity,
+        config.ws_channel_capacity,
+        matches,
+        "ws_channel_capacity"
+    );
+    set_from_config(
+        &mut cli.ws_overflow_policy,
+        config.ws_overflow_policy,
+        matches,
+        "ws_overflow_policy"
+    );
+    set_from_config(
+        &mut cli.subscribe_pool_accounts,
+        config.subscribe_pool_accounts,
+        matches,
+        "subscribe_pool_accounts"
+    );
+    set_from_config(
+        &mut cli.confirmation_delay_slots,
+        config.confirmation_delay_slots,
+        matches,
+        "confirmation_delay_slots"
+    );
+    set_from_config(&mut cli.orca_program_id, config.orca_program_id, matches, "orca_program_id");
+    set_from_config(&mut cli.orca_default_pool, config.orca_default_pool, matches, "orca_default_pool");
+    set_from_config(
+        &mut cli.raydium_amm_program_id,
+        config.raydium_amm_program_id,
+        matches,
+        "raydium_amm_program_id"
+    );
+    set_from_config(
+        &mut cli.raydium_clmm_program_id,
+        config.raydium_clmm_program_id,
+        matches,
+        "raydium_clmm_program_id"
+    );
+    set_from_config(&mut cli.commitment, config.commitment, matches, "commitment");
+    set_from_config(&mut cli.rpc_timeout_secs, config.rpc_timeout_secs, matches, "rpc_timeout_secs");
+    set_from_config(&mut cli.dead_letter_file, config.dead_letter_file, matches, "dead_letter_file");
+    set_from_config(
+        &mut cli.outage_buffer_size,
+        config.outage_buffer_size,
+        matches,
+        "outage_buffer_size"
+    );
+    set_from_config(&mut cli.backfill_order, config.backfill_order, matches, "backfill_order");
+    set_from_config(&mut cli.startup_strategy, config.startup_strategy, matches, "startup_strategy");
+    set_from_config(
+        &mut cli.backfill_interval_secs,
+        config.backfill_interval_secs,
+        matches,
+        "backfill_interval_secs"
+    );
+    set_from_config(
+        &mut cli.reconcile_interval_secs,
+        config.reconcile_interval_secs.map(Some),
+        matches,
+        "reconcile_interval_secs"
+    );
+    set_from_config(
+        &mut cli.reconcile_window_secs,
+        config.reconcile_window_secs,
+        matches,
+        "reconcile_window_secs"
+    );
+    set_from_config(
+        &mut cli.backfill_pool_cap,
+        config.backfill_pool_cap.map(Some),
+        matches,
+        "backfill_pool_cap"
+    );
+    set_from_config(
+        &mut cli.backfill_max_rps,
+        config.backfill_max_rps.map(Some),
+        matches,
+        "backfill_max_rps"
+    );
+    set_from_config(
+        &mut cli.backfill_concurrency,
+        config.backfill_concurrency,
+        matches,
+        "backfill_concurrency"
+    );
+    set_from_config(
+        &mut cli.backfill_pool_concurrency,
+        config.backfill_pool_concurrency,
+        matches,
+        "backfill_pool_concurrency"
+    );
+    set_from_config(
+        &mut cli.signature_read_batch_size,
+        config.signature_read_batch_size,
+        matches,
+        "signature_read_batch_size"
+    );
+    set_from_config(
+        &mut cli.max_plausible_trade_amount,
+        config.max_plausible_trade_amount.map(Some),
+        matches,
+        "max_plausible_trade_amount"
+    );
+    set_from_config(&mut cli.event_validation, config.event_validation, matches, "event_validation");
+    set_from_config(
+        &mut cli.min_input_amount,
+        config.min_input_amount.map(Some),
+        matches,
+        "min_input_amount"
+    );
+    set_from_config(
+        &mut cli.pool_min_input_amounts,
+        config.pool_min_input_amounts.map(|amounts| amounts.into_iter().collect()),
+        matches,
+        "pool_min_input_amounts"
+    );
+    set_from_config(&mut cli.webhook_url, config.webhook_url.map(Some), matches, "webhook_url");
+    set_from_config(
+        &mut cli.webhook_queue_size,
+        config.webhook_queue_size,
+        matches,
+        "webhook_queue_size"
+    );
+    set_from_config(&mut cli.stale_max_secs, config.stale_max_secs, matches, "stale_max_secs");
+    set_from_config(
+        &mut cli.stale_gap_multiplier,
+        config.stale_gap_multiplier,
+        matches,
+        "stale_gap_multiplier"
+    );
+    set_from_config(&mut cli.also_stdout, config.also_stdout, matches, "also_stdout");
+    set_from_config(
+        &mut cli.strict_pool_addresses,
+        config.strict_pool_addresses,
+        matches,
+        "strict_pool_addresses"
+    );
+    set_from_config(
+        &mut cli.store_transaction_logs,
+        config.store_transaction_logs,
+        matches,
+        "store_transaction_logs"
+    );
+    set_from_config(&mut cli.log_format, config.log_format, matches, "log_format");
+    set_from_config(&mut cli.metrics_port, config.metrics_port.map(Some), matches, "metrics_port");
+    set_from_config(&mut cli.health_port, config.health_port.map(Some), matches, "health_port");
+    set_from_config(
+        &mut cli.health_stale_secs,
+        config.health_stale_secs,
+        matches,
+        "health_stale_secs"
+    );
+
+    // Pool lists belong to the subcommand's arguments
+    match (&mut cli.command, matches.subcommand()) {
+        (Command::Orca { pools, token_mint, all_pools: false, .. }, Some((_, orca_matches))) => {
+            set_from_config(pools, config.orca_pools.map(Some), orca_matches, "pools");
+            set_from_config(token_mint, config.token_mint.map(Some), orca_matches, "token_mint");
+        }
+        (Command::All { orca_pools, raydium_pools, .. }, Some((_, all_matches))) => {
+            set_from_config(orca_pools, config.orca_pools.map(Some), all_matches, "orca_pools");
+            set_from_config(
+                raydium_pools,
+                config.raydium_pools.map(Some),
+                all_matches,
+                "raydium_pools"
+            );
+        }
+        _ => {}
+    }
+}
+
+/// Build the `config-template` output from the flags each config key sets
+///
+/// Keys are looked up among the global flags, then the `orca` and `all` flags
+/// the pool list keys belong to.
+fn config_template() -> String {
+    let command = Cli::command();
+    let commands = [Some(&command), command.find_subcommand("orca"), command.find_subcommand("all")];
+    Config::template(|key| {
+        if key == "database_url" {
+            return (Some("Postgres connection string, used instead of DATABASE_URL".to_string()), None);
+        }
+        let arg = commands
+            .iter()
+            .flatten()
+            .find_map(|command| command.get_arguments().find(|arg| arg.get_id() == key));
+        let Some(arg) = arg else {
+            return (None, None);
+        };
+        let description = arg.get_long_help().or(arg.get_help()).map(|help| help.to_string());
+        // Switches are off unless given
+        let default = match arg.get_action() {
+            ArgAction::SetTrue => Some("false".to_string()),
+            _ =>
+                arg
+                    .get_default_values()
+                    .first()
+                    .map(|value| value.to_string_lossy().into_owned()),
+        };
+        (description, default)
+    })
+}
+
+/// Parse an RFC 3339 timestamp
+fn parse_rfc3339(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .with_context(|| format!("Invalid RFC 3339 timestamp '{}'", value))
+}
+
+/// Build the backfill window from either the slot or the time bounds
+fn backfill_window(
+    from_slot: Option<u64>,
+    to_slot: Option<u64>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>
+) -> Result<BackfillWindow> {
+    let window = match (from_slot, to_slot, from, to) {
+        (Some(from), Some(to), None, None) => BackfillWindow::Slots { from, to },
+        (None, None, Some(from), Some(to)) =>
+            BackfillWindow::BlockTime { from: from.timestamp(), to: to.timestamp() },
+        _ => anyhow::bail!("Specify either --from-slot and --to-slot, or --from and --to"),
+    };
+    window.validate()?;
+    Ok(window)
+}
+
+/// Print a subscribed pool as one line of `pools list` or `pools show`
+fn print_pool(pool: &OrcaWhirlpoolPoolRecord) {
+    let name = pool.pool_name.as_deref().unwrap_or("unnamed");
+    // Pools added by --all-pools aren't loaded yet
+    if pool.token_mint_a.is_empty() || pool.token_mint_b.is_empty() {
+        println!("{}  {}  tokens not loaded", pool.whirlpool, name);
+        return;
+    }
+    let token = |mint: &str, name: &Option<String>, decimals: i32| {
+        format!("{} ({}, {} decimals)", mint, name.as_deref().unwrap_or("unknown"), decimals)
+    };
+    println!(
+        "{}  {}  {} / {}",
+        pool.whirlpool,
+        name,
+        token(&pool.token_mint_a, &pool.token_name_a, pool.decimals_a),
+        token(&pool.token_mint_b, &pool.token_name_b, pool.decimals_b)
+    );
+}
+
+/// Run a `pools` command against the subscribed pools
+async fn run_pools_command(
+    action: &PoolsCommand,
+    db: &Database,
+    connection_config: &ConnectionConfig
+) -> Result<()> {
+    let repository = OrcaWhirlpoolRepository::new(db.pool().clone());
+    match action {
+        PoolsCommand::List => {
+            let mut pools = repository.get_all_pools().await?;
+            pools.sort_by(|a, b| a.whirlpool.cmp(&b.whirlpool));
+            for pool in &pools {
+                print_pool(pool);
+            }
+            logging::log_activity(
+                "system",
+                "Pools",
+                Some(&format!("{} subscribed Orca pools", pools.len()))
+            );
+        }
+        PoolsCommand::Show { pool } => {
+            let record = repository
+                .get_pool(pool).await?
+                .with_context(|| format!("Pool {} is not subscribed", pool))?;
+            print_pool(&record);
+        }
+        PoolsCommand::Add { pool, require_token_metadata } => {
+            let pool_pubkey = Pubkey::from_str(pool).with_context(||
+                format!("Invalid pool address {}", pool)
+            )?;
+            let program_id = Pubkey::from_str(&connection_config.program_ids.orca_whirlpool)?;
+            let metadata_program_id = Pubkey::from_str(METAPLEX_METADATA_PROGRAM_ID)?;
+            let rpc_client = Arc::new(
+                build_rpc_client_with_headers(
+                    &connection_config.rpc_url,
+                    connection_config.commitment,
+                    connection_config.rpc_timeout,
+                    &connection_config.rpc_headers
+                )
+            );
+
+            // Resolve the pool the same way load_pools does
+            let processor = OrcaProcessor {
+                program_id,
+                tokens: TokenMetadataStore::new(
+                    db.pool().clone(),
+                    rpc_client.clone(),
+                    metadata_program_id,
+                    *require_token_metadata
+                ),
+                token_metadata_ttl: DEFAULT_TOKEN_METADATA_TTL,
+            };
+            let record = processor
+                .process_pool(&rpc_client, &pool_pubkey, &mut HashMap::new(), true).await
+                .with_context(|| format!("Failed to fetch Orca pool {}", pool))?;
+            repository.upsert_pool(&record.to_orca_record()).await?;
+            logging::log_activity(
+                "system",
+                "Pools",
+                Some(&format!("Subscribed to Orca pool {} ({})", pool, record.pool_name))
+            );
+        }
+        PoolsCommand::Remove { pool, forget_signatures, delete_events } => {
+            let deletion = PoolDeletion {
+                forget_signatures: *forget_signatures,
+                delete_events: *delete_events,
+            };
+            // Signatures and events of an already removed pool can still be cleaned up
+            if !repository.delete_pool(pool, deletion).await? {
+                if deletion == PoolDeletion::default() {
+                    anyhow::bail!("Pool {} is not subscribed", pool);
+                }
+                log::warn!("Pool {} was not subscribed", pool);
+            }
+            let mut removed = vec!["subscription"];
+            if deletion.forget_signatures {
+                removed.push("last signature");
+            }
+            if deletion.delete_events {
+                removed.push("indexed events");
+            }
+            logging::log_activity(
+                "system",
+                "Pools",
+                Some(&format!("Removed Orca pool {} ({})", pool, removed.join(", ")))
+            );
+        }
+        PoolsCommand::Gaps { pool } => {
+            let report = repository.find_slot_gaps(pool).await?;
+            let (Some(oldest_slot), Some(newest_slot)) = (report.oldest_slot, report.newest_slot) else {
+                println!("{}  no events with a recorded slot", pool);
+                return Ok(());
+            };
+            println!(
+                "{}  slots {}..={}, contiguous from {}",
+                pool,
+                oldest_slot,
+                newest_slot,
+                report.contiguous_from().unwrap_or(oldest_slot)
+            );
+            for gap in &report.gaps {
+                println!(
+                    "  gap after slot {} until slot {} ({} slots without events)",
+                    gap.after_slot,
+                    gap.before_slot,
+                    gap.missing_slots()
+                );
+            }
+            if !report.gaps.is_empty() {
+                log::warn!(
+                    "Pool {} has {} suspicious slot gaps; backfill them with `orca backfill --from-slot --to-slot`",
+                    pool,
+                    report.gaps.len()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the Orca indexer, tracking its pools' state alongside it when enabled
+async fn start_orca(indexer: &OrcaWhirlpoolIndexer) -> Result<()> {
+    if !indexer.connection_config().pool_state_updates {
+        return start_indexer(indexer).await;
+    }
+    tokio::try_join!(start_indexer(indexer), indexer.track_pool_state())?;
+    Ok(())
+}
+
+/// Wait for concurrently running indexers to finish
+///
+/// When one fails the others are stopped and its error returned, unless
+/// `keep_going` is set, in which case the rest keep running and an error is
+/// only returned once every indexer has stopped.
+async fn run_all(mut indexers: JoinSet<(&'static str, Result<()>)>, keep_going: bool) -> Result<()> {
+    let mut failed = 0;
+    while let Some(joined) = indexers.join_next().await {
+        let (name, result) = joined.context("Indexer task panicked")?;
+        match result {
+            Ok(()) => logging::log_activity("system", "Indexer stopped", Some(name)),
+            Err(e) if keep_going => {
+                failed += 1;
+                logging::log_error("system", &format!("{} indexer failed, others keep running", name), &e);
+            }
+            Err(e) => {
+                indexers.abort_all();
+                return Err(e.context(format!("{} indexer failed", name)));
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} indexer(s) failed", failed);
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load environment variables from .env file if present
     dotenv::dotenv().ok();
 
-    // Parse command line arguments
-    let cli = Cli::parse();
+    // Parse command line arguments, filling in the rest from the config file if given
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Command::ConfigTemplate = cli.command {
+        print!("{}", config_template());
+        return Ok(());
+    }
+    let config = match &cli.config {
+        Some(path) => Config::from_file(path)?,
+        None => Config::default(),
+    };
+    let database_url = config.database_url.clone();
+    apply_config(&mut cli, config, &matches);
+
+    // Initialize logging (verbosity is controlled with RUST_LOG)
+    logging::set_log_format(cli.log_format);
+    let printing_to_stdout = matches!(
+        &cli.command,
+        Command::Orca { action: Some(OrcaCommand::Export { output: None, .. }), .. } |
+            Command::Pools { .. }
+    );
+    if cli.also_stdout || printing_to_stdout {
+        // Keep stdout for event lines, exported rows or listed pools only
+        logging::init_logger_with_target("info,sqlx=warn", env_logger::Target::Stderr);
+    } else {
+        logging::init_logger("info,sqlx=warn");
+    }
+
+    // Start the metrics server if enabled
+    if let Some(port) = cli.metrics_port {
+        metrics::init()?;
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(addr).await {
+                logging::log_error("metrics", "Metrics server stopped", &e);
+            }
+        });
+    }
 
-    // Get database configuration
-    let db_config = DbConfig::from_env().context("Failed to get database configuration")?;
+    // Get database configuration (the config file's URL takes precedence over DATABASE_URL)
+    let db_config = match database_url {
+        Some(url) => DbConfig::new(url),
+        None => DbConfig::from_env().context("Failed to get database configuration")?,
+    };
 
     // Connect to the database
     let db = Database::connect(db_config).await.context("Failed to connect to database")?;
     logging::log_activity("system", "Database connection", Some("Successfully connected"));
 
+    // Resolve program IDs (mainnet unless overridden)
+    let program_ids = ProgramIds::default()
+        .with_orca_whirlpool(cli.orca_program_id)
+        .with_raydium_amm(cli.raydium_amm_program_id)
+        .with_raydium_clmm(cli.raydium_clmm_program_id);
+    program_ids.validate()?;
+
     // Create connection configuration
-    let connection_config = ConnectionConfig::new(cli.rpc_url, cli.ws_url);
+    let connection_config = ConnectionConfig::new(cli.rpc_url, cli.ws_url)
+        .with_rpc_headers(cli.rpc_headers.into_iter().collect())
+        .with_ws_fallback_urls(cli.ws_fallback_urls.unwrap_or_default())
+        .with_ws_channel(cli.ws_channel_capacity, cli.ws_overflow_policy)
+        .with_ws_subscription_per_program(
+            cli.ws_subscription_per_program,
+            cli.program_commitments.into_iter().collect()
+        )
+        .with_pool_state_updates(cli.subscribe_pool_accounts)
+        .with_confirmation_delay_slots(cli.confirmation_delay_slots)
+        .with_program_ids(program_ids)
+        .with_default_orca_pool(cli.orca_default_pool)
+        .with_dry_run(cli.dry_run)
+        .with_strict_pool_addresses(cli.strict_pool_addresses)
+        .with_commitment(cli.commitment)
+        .with_rpc_timeout(Duration::from_secs(cli.rpc_timeout_secs))
+        .with_dead_letter_path(cli.dead_letter_file)
+        .with_outage_buffer_capacity(cli.outage_buffer_size)
+        .with_backfill_order(cli.backfill_order)
+        .with_startup_strategy(cli.startup_strategy)
+        .with_backfill_pool_cap(cli.backfill_pool_cap)
+        .with_backfill_max_requests_per_second(cli.backfill_max_rps)
+        .with_backfill_fetch_concurrency(cli.backfill_concurrency as usize)
+        .with_backfill_pool_concurrency(cli.backfill_pool_concurrency as usize)
+        .with_signature_read_batch_size(cli.signature_read_batch_size as usize)
+        .with_max_plausible_trade_amount(cli.max_plausible_trade_amount)
+        .with_event_validation(cli.event_validation)
+        .with_min_input_amount(cli.min_input_amount)
+        .with_pool_min_input_amounts(cli.pool_min_input_amounts.into_iter().collect())
+        .with_store_transaction_logs(cli.store_transaction_logs)
+        .with_backfill_interval(Duration::from_secs(cli.backfill_interval_secs))
+        .with_reconciliation_interval(cli.reconcile_interval_secs.map(Duration::from_secs))
+        .with_reconciliation_window(Duration::from_secs(cli.reconcile_window_secs))
+        .with_staleness(StalenessConfig {
+            max_threshold: Duration::from_secs(cli.stale_max_secs),
+            gap_multiplier: cli.stale_gap_multiplier,
+            ..StalenessConfig::default()
+        });
+
+    if cli.dry_run {
+        logging::log_activity("system", "Dry run", Some("events will not be written to the database"));
+    }
+
+    // Additional sinks that indexed events are written to alongside the database
+    let mut sinks: Vec<Box<dyn EventSink>> = Vec::new();
+    if let Some(url) = cli.webhook_url {
+        let mut webhook_config = WebhookConfig::new(url.clone());
+        webhook_config.queue_capacity = cli.webhook_queue_size;
+        sinks.push(Box::new(WebhookSink::new(webhook_config)?));
+        logging::log_activity("system", "Webhook sink", Some(&format!("POSTing events to {}", url)));
+    }
+    if cli.also_stdout {
+        sinks.push(Box::new(StdoutSink::new()));
+        logging::log_activity("system", "Stdout sink", Some("writing events to stdout as NDJSON"));
+    }
 
     match &cli.command {
-        Command::Orca { pools } => {
+        Command::Orca {
+            action: Some(OrcaCommand::Backfill { pool, from_slot, to_slot, from, to }),
+            ..
+        } => {
+            let window = backfill_window(*from_slot, *to_slot, *from, *to)?;
+            let pool_pubkey = Pubkey::from_str(pool).with_context(||
+                format!("Invalid pool address {}", pool)
+            )?;
+            logging::log_activity(
+                "system",
+                "Window backfill",
+                Some(&format!("Backfilling Orca pool {} in {:?}", pool, window))
+            );
+
+            // Only the requested pool is monitored, and the live subscription is never started
+            let pools = vec![pool.clone()];
+            let indexer = OrcaWhirlpoolIndexer::new(
+                db.pool().clone(),
+                Some(&pools),
+                connection_config
+            ).await?.with_sinks(sinks);
+
+            indexer
+                .backfill_window(&pool_pubkey, &window).await
+                .context("Orca window backfill failed")?;
+        }
+        Command::Orca {
+            action: Some(OrcaCommand::IndexSignatures { pool, sigs }),
+            ..
+        } => {
+            let pool_pubkey = Pubkey::from_str(pool).with_context(||
+                format!("Invalid pool address {}", pool)
+            )?;
+            let contents = std::fs
+                ::read_to_string(sigs)
+                .with_context(|| format!("Failed to read signature file {}", sigs))?;
+            let signatures = parse_signature_list(&contents).with_context(||
+                format!("Failed to parse signature file {}", sigs)
+            )?;
+            logging::log_activity(
+                "system",
+                "Signature indexing",
+                Some(&format!("Indexing {} signatures of Orca pool {}", signatures.len(), pool))
+            );
+
+            // Only the requested pool is monitored, and the live subscription is never started
+            let pools = vec![pool.clone()];
+            let indexer = OrcaWhirlpoolIndexer::new(
+                db.pool().clone(),
+                Some(&pools),
+                connection_config
+            ).await?.with_sinks(sinks);
+
+            indexer
+                .index_signatures(&pool_pubkey, &signatures).await
+                .context("Orca signature indexing failed")?;
+        }
+        Command::Orca {
+            action: Some(OrcaCommand::Export { pool, from, to, output, compress }),
+            ..
+        } => {
+            let export = TradeExport::new(pool.clone()).with_range(*from, *to);
+            let output: Box<dyn Write> = match output {
+                Some(path) => {
+                    let file = File::create(path).with_context(||
+                        format!("Failed to create export file {}", path)
+                    )?;
+                    Box::new(BufWriter::new(file))
+                }
+                None => Box::new(std::io::stdout().lock()),
+            };
+
+            // Rows are compressed as they are written, never buffered as a whole
+            let mut writer = CompressedWriter::new(output, *compress)?;
+            let rows = export_orca_trades_csv(db.pool(), &export, &mut writer).await?;
+            writer.finish().context("Failed to finish export output")?;
+            logging::log_activity(
+                "system",
+                "Export",
+                Some(&format!("Exported {} trades of Orca pool {}", rows, pool))
+            );
+        }
+        Command::Orca {
+            pools,
+            token_mint,
+            auto_subscribe,
+            auto_subscribe_mints,
+            all_pools,
+            action: None,
+        } => {
             logging::log_activity(
                 "system",
                 "Indexer initialization",
                 Some("Starting Orca Whirlpool indexer")
             );
 
+            // Pools given by address take priority over the token filter
+            let token_mint = token_mint
+                .as_deref()
+                .map(|mint| {
+                    Pubkey::from_str(mint).with_context(|| format!("Invalid token mint {}", mint))
+                })
+                .transpose()?;
+
             // Create indexer with resolved pool addresses in one operation
             let indexer = OrcaWhirlpoolIndexer::new(
                 db.pool().clone(),
                 pools.as_ref(),
+                connection_config.with_pool_token_mint(token_mint)
+            ).await?.with_sinks(sinks);
+
+            let indexer = if *auto_subscribe {
+                let mints = auto_subscribe_mints
+                    .iter()
+                    .flatten()
+                    .map(|mint| {
+                        Pubkey::from_str(mint).with_context(|| format!("Invalid token mint {}", mint))
+                    })
+                    .collect::<Result<HashSet<Pubkey>>>()?;
+                logging::log_activity(
+                    "system",
+                    "Auto-subscribe",
+                    Some(&format!("indexing new pools between {} token mints", mints.len()))
+                );
+                indexer.with_auto_subscribe(mints)
+            } else {
+                indexer
+            };
+
+            let indexer = if *all_pools {
+                logging::log_activity(
+                    "system",
+                    "All pools",
+                    Some("indexing every Whirlpool's live events, without backfill")
+                );
+                indexer.with_all_pools()
+            } else {
+                indexer
+            };
+
+            // Start the liveness server if enabled
+            if let Some(port) = cli.health_port {
+                let addr = SocketAddr::from(([0, 0, 0, 0], port));
+                let tracker = indexer.health_tracker().clone();
+                let threshold = Duration::from_secs(cli.health_stale_secs);
+                tokio::spawn(async move {
+                    if let Err(e) = health::serve(addr, tracker, threshold).await {
+                        logging::log_error("health", "Health server stopped", &e);
+                    }
+                });
+            }
+
+            // Start the indexer (pools are contained within the indexer)
+            start_orca(&indexer).await.context("Orca indexer failed")?;
+        }
+        Command::All { orca_pools, raydium_pools, keep_going } => {
+            logging::log_activity(
+                "system",
+                "Indexer initialization",
+                Some("Starting Orca Whirlpool and Raydium indexers")
+            );
+            if cli.health_port.is_some() {
+                log::warn!("--health-port only applies to single-DEX commands, not starting the health server");
+            }
+
+            // Sinks are only supported by the Orca indexer so far
+            let orca = OrcaWhirlpoolIndexer::new(
+                db.pool().clone(),
+                orca_pools.as_ref(),
+                connection_config.clone()
+            ).await?.with_sinks(sinks);
+            let raydium = RaydiumIndexer::new(
+                db.pool().clone(),
+                raydium_pools.as_ref(),
                 connection_config
             ).await?;
 
-            // Start the indexer (pools are contained within the indexer)
-            start_indexer(&indexer).await.context("Orca indexer failed")?;
+            // Each indexer runs its own subscription and backfill loop
+            let mut indexers = JoinSet::new();
+            indexers.spawn(async move { ("Orca", start_orca(&orca).await) });
+            indexers.spawn(async move { ("Raydium", start_indexer(&raydium).await) });
+
+            run_all(indexers, *keep_going).await?;
+        }
+        Command::Pools { action } => {
+            run_pools_command(action, &db, &connection_config).await?;
         }
+        // Printed before the config file was read
+        Command::ConfigTemplate => {}
         // For future implementation
         /*
         Command::Raydium { pools } => {
@@ -0,0 +1,480 @@
+use anyhow::{ Context, Result };
+use hyper::service::{ make_service_fn, service_fn };
+use hyper::{ Body, Method, Request, Response, Server, StatusCode };
+use prometheus::{ Encoder, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder };
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+use crate::error::IndexerError;
+use crate::utils::logging;
+
+/// Prometheus metrics exposed by the indexer
+///
+/// Metrics are only recorded once `init` has been called, so the recording helpers
+/// below are no-ops when the metrics server is disabled.
+pub struct Metrics {
+    pub registry: Registry,
+    /// Events decoded from logs, by DEX
+    pub events_parsed: IntCounterVec,
+    /// Events successfully handled (persisted), by DEX
+    pub events_persisted: IntCounterVec,
+    /// Events written to the dead-letter queue after all retries failed, by DEX
+    pub events_dead_lettered: IntCounterVec,
+    /// Transactions fetched during backfill, by DEX
+    pub backfill_transactions_fetched: IntCounterVec,
+    /// WebSocket reconnection attempts
+    pub websocket_reconnects: IntCounter,
+    /// Whether the WebSocket subscription is currently connected (1) or not (0)
+    pub websocket_connected: IntGauge,
+    /// Log notifications dropped because the indexer was a full channel behind
+    pub websocket_messages_dropped: IntCounter,
+    /// RPC errors, by DEX and error kind
+    pub rpc_errors: IntCounterVec,
+    /// Decoded events skipped because their pool isn't monitored, by DEX and event type
+    pub unmonitored_pool_events: IntCounterVec,
+    /// Events delivered by an event sink, by sink
+    pub sink_events_delivered: IntCounterVec,
+    /// Events an event sink gave up on, by sink and reason
+    pub sink_events_dropped: IntCounterVec,
+    /// Events whose enricher failed, by DEX
+    pub enrichment_failures: IntCounterVec,
+    /// Transactions skipped because they failed on-chain, by DEX
+    pub failed_transactions_skipped: IntCounterVec,
+    pub missing_block_times: IntCounterVec,
+    /// Trades whose decimal-scaled amount is implausibly large, by DEX
+    pub implausible_trades: IntCounterVec,
+    /// Events that failed validation and were quarantined, by DEX and failed check
+    pub quarantined_events: IntCounterVec,
+    /// Trades dropped for an input amount below the configured minimum, by DEX
+    pub dust_trades_skipped: IntCounterVec,
+    /// Last-signature reads from the signature store, by DEX and kind (single or bulk)
+    pub signature_store_reads: IntCounterVec,
+    /// Pools rolled back because a reorg dropped their last signature, by DEX
+    pub reorg_rollbacks: IntCounterVec,
+    /// Backfills run right after the WebSocket reconnected, by DEX
+    pub reconnect_backfills: IntCounterVec,
+    /// Transactions whose missing events a reconciliation pass stored, by DEX
+    pub reconciled_transactions: IntCounterVec,
+    /// Transactions a backfill found in slots before the pool's checkpoint, by DEX
+    pub missed_live_events: IntCounterVec,
+    /// Events held in memory while the database is unreachable, by DEX
+    pub outage_buffer_events: IntGaugeVec,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+impl Metrics {
+    fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("indexer".to_string()), None)?;
+
+        let events_parsed = IntCounterVec::new(
+            Opts::new("events_parsed_total", "Events decoded from program logs"),
+            &["dex"]
+        )?;
+        let events_persisted = IntCounterVec::new(
+            Opts::new("events_persisted_total", "Events successfully handled and persisted"),
+            &["dex"]
+        )?;
+        let events_dead_lettered = IntCounterVec::new(
+            Opts::new("events_dead_lettered_total", "Events dead-lettered after failing to persist"),
+            &["dex"]
+        )?;
+        let backfill_transactions_fetched = IntCounterVec::new(
+            Opts::new("backfill_transactions_fetched_total", "Transactions fetched during backfill"),
+            &["dex"]
+        )?;
+        let websocket_reconnects = IntCounter::new(
+            "websocket_reconnects_total",
+            "WebSocket reconnection attempts"
+        )?;
+        let websocket_connected = IntGauge::new(
+            "websocket_connected",
+            "Whether the WebSocket subscription is connected"
+        )?;
+        let websocket_messages_dropped = IntCounter::new(
+            "websocket_messages_dropped_total",
+            "WebSocket log notifications dropped because the processing channel was full"
+        )?;
+        let rpc_errors = IntCounterVec::new(
+            Opts::new("rpc_errors_total", "RPC errors by error kind"),
+            &["dex", "kind"]
+        )?;
+        let unmonitored_pool_events = IntCounterVec::new(
+            Opts::new(
+                "unmonitored_pool_events_total",
+                "Decoded events skipped because their pool is not monitored"
+            ),
+            &["dex", "event_type"]
+        )?;
+        let sink_events_delivered = IntCounterVec::new(
+            Opts::new("sink_events_delivered_total", "Events delivered by event sinks"),
+            &["sink"]
+        )?;
+        let sink_events_dropped = IntCounterVec::new(
+            Opts::new("sink_events_dropped_total", "Events dropped by event sinks"),
+            &["sink", "reason"]
+        )?;
+        let enrichment_failures = IntCounterVec::new(
+            Opts::new("enrichment_failures_total", "Events whose enricher failed"),
+            &["dex"]
+        )?;
+        let failed_transactions_skipped = IntCounterVec::new(
+            Opts::new(
+                "failed_transactions_skipped_total",
+                "Transactions skipped because they failed on-chain"
+            ),
+            &["dex"]
+        )?;
+        let missing_block_times = IntCounterVec::new(
+            Opts::new(
+                "backfill_missing_block_time_total",
+                "Backfilled transactions without a block time"
+            ),
+            &["dex"]
+        )?;
+        let implausible_trades = IntCounterVec::new(
+            Opts::new(
+                "implausible_trades_total",
+                "Trades whose decimal-scaled amount is implausibly large"
+            ),
+            &["dex"]
+        )?;
+        let quarantined_events = IntCounterVec::new(
+            Opts::new(
+                "quarantined_events_total",
+                "Events that failed validation and were quarantined instead of indexed"
+            ),
+            &["dex", "check"]
+        )?;
+        let dust_trades_skipped = IntCounterVec::new(
+            Opts::new(
+                "dust_trades_skipped_total",
+                "Trades not stored because their input amount is below the configured minimum"
+            ),
+            &["dex"]
+        )?;
+        let signature_store_reads = IntCounterVec::new(
+            Opts::new(
+                "signature_store_reads_total",
+                "Last-signature reads from the signature store, per pool or in bulk"
+            ),
+            &["dex", "kind"]
+        )?;
+        let reorg_rollbacks = IntCounterVec::new(
+            Opts::new(
+                "reorg_rollbacks_total",
+                "Pools rolled back because a reorg dropped their last signature"
+            ),
+            &["dex"]
+        )?;
+        let reconnect_backfills = IntCounterVec::new(
+            Opts::new(
+                "reconnect_backfills_total",
+                "Backfills run right after the WebSocket reconnected"
+            ),
+            &["dex"]
+        )?;
+        let reconciled_transactions = IntCounterVec::new(
+            Opts::new(
+                "reconciled_transactions_total",
+                "Transactions whose missing events a reconciliation pass stored"
+            ),
+            &["dex"]
+        )?;
+        let missed_live_events = IntCounterVec::new(
+            Opts::new(
+                "missed_live_events_total",
+                "Transactions a backfill found in slots before the pool's checkpoint"
+            ),
+            &["dex"]
+        )?;
+        let outage_buffer_events = IntGaugeVec::new(
+            Opts::new(
+                "outage_buffer_events",
+                "Events held in memory while the database is unreachable"
+            ),
+            &["dex"]
+        )?;
+
+        registry.register(Box::new(events_parsed.clone()))?;
+        registry.register(Box::new(events_persisted.clone()))?;
+        registry.register(Box::new(events_dead_lettered.clone()))?;
+        registry.register(Box::new(backfill_transactions_fetched.clone()))?;
+        registry.register(Box::new(websocket_reconnects.clone()))?;
+        registry.register(Box::new(websocket_connected.clone()))?;
+        registry.register(Box::new(websocket_messages_dropped.clone()))?;
+        registry.register(Box::new(rpc_errors.clone()))?;
+        registry.register(Box::new(unmonitored_pool_events.clone()))?;
+        registry.register(Box::new(sink_events_delivered.clone()))?;
+        registry.register(Box::new(sink_events_dropped.clone()))?;
+        registry.register(Box::new(enrichment_failures.clone()))?;
+        registry.register(Box::new(failed_transactions_skipped.clone()))?;
+        registry.register(Box::new(missing_block_times.clone()))?;
+        registry.register(Box::new(implausible_trades.clone()))?;
+        registry.register(Box::new(quarantined_events.clone()))?;
+        registry.register(Box::new(dust_trades_skipped.clone()))?;
+        registry.register(Box::new(signature_store_reads.clone()))?;
+        registry.register(Box::new(reorg_rollbacks.clone()))?;
+        registry.register(Box::new(reconnect_backfills.clone()))?;
+        registry.register(Box::new(reconciled_transactions.clone()))?;
+        registry.register(Box::new(missed_live_events.clone()))?;
+        registry.register(Box::new(outage_buffer_events.clone()))?;
+
+        Ok(Self {
+            registry,
+            events_parsed,
+            events_persisted,
+            events_dead_lettered,
+            backfill_transactions_fetched,
+            websocket_reconnects,
+            websocket_connected,
+            websocket_messages_dropped,
+            rpc_errors,
+            unmonitored_pool_events,
+            sink_events_delivered,
+            sink_events_dropped,
+            enrichment_failures,
+            failed_transactions_skipped,
+            missing_block_times,
+            implausible_trades,
+            quarantined_events,
+            dust_trades_skipped,
+            signature_store_reads,
+            reorg_rollbacks,
+            reconnect_backfills,
+            reconciled_transactions,
+            missed_live_events,
+            outage_buffer_events,
+        })
+    }
+}
+
+/// Enable metrics collection (idempotent)
+pub fn init() -> Result<&'static Metrics> {
+    if let Some(metrics) = METRICS.get() {
+        return Ok(metrics);
+    }
+    let metrics = Metrics::new().context("Failed to create metrics registry")?;
+    Ok(METRICS.get_or_init(|| metrics))
+}
+
+/// Get the metrics if collection is enabled
+pub fn get() -> Option<&'static Metrics> {
+    METRICS.get()
+}
+
+/// Render all metrics in the Prometheus text exposition format
+pub fn gather() -> String {
+    let Some(metrics) = get() else {
+        return String::new();
+    };
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer) {
+        logging::log_error("metrics", "Failed to encode metrics", &anyhow::anyhow!("{}", e));
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+//
+// RECORDING HELPERS
+//
+
+/// Record events decoded from a log
+pub fn record_events_parsed(dex: &str, count: usize) {
+    if let Some(metrics) = get() {
+        metrics.events_parsed.with_label_values(&[dex]).inc_by(count as u64);
+    }
+}
+
+/// Record an event successfully handled
+pub fn record_event_persisted(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.events_persisted.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record an event written to the dead-letter queue
+pub fn record_event_dead_lettered(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.events_dead_lettered.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a decoded event skipped because its pool isn't monitored
+pub fn record_unmonitored_pool_event(dex: &str, event_type: &str) {
+    if let Some(metrics) = get() {
+        metrics.unmonitored_pool_events.with_label_values(&[dex, event_type]).inc();
+    }
+}
+
+/// Record an event delivered by an event sink
+pub fn record_sink_event_delivered(sink: &str) {
+    if let Some(metrics) = get() {
+        metrics.sink_events_delivered.with_label_values(&[sink]).inc();
+    }
+}
+
+/// Record an event dropped by an event sink, e.g. because its queue was full
+pub fn record_sink_event_dropped(sink: &str, reason: &str) {
+    if let Some(metrics) = get() {
+        metrics.sink_events_dropped.with_label_values(&[sink, reason]).inc();
+    }
+}
+
+/// Record an event whose enricher failed
+pub fn record_enrichment_failure(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.enrichment_failures.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a transaction skipped because it failed on-chain
+pub fn record_failed_transaction_skipped(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.failed_transactions_skipped.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a backfilled transaction that has no block time
+pub fn record_missing_block_time(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.missing_block_times.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a trade whose decimal-scaled amount is implausibly large
+pub fn record_implausible_trade(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.implausible_trades.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record an event quarantined because it failed the named validation check
+pub fn record_quarantined_event(dex: &str, check: &str) {
+    if let Some(metrics) = get() {
+        metrics.quarantined_events.with_label_values(&[dex, check]).inc();
+    }
+}
+
+/// Record a trade dropped for an input amount below the configured minimum
+pub fn record_dust_trade_skipped(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.dust_trades_skipped.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a read of last signatures from the signature store, `kind` being single or bulk
+pub fn record_signature_store_read(dex: &str, kind: &str) {
+    if let Some(metrics) = get() {
+        metrics.signature_store_reads.with_label_values(&[dex, kind]).inc();
+    }
+}
+
+/// Record a pool rolled back because a reorg dropped its last signature
+pub fn record_reorg_rollback(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.reorg_rollbacks.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a backfill run right after the WebSocket reconnected
+pub fn record_reconnect_backfill(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.reconnect_backfills.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record transactions whose missing events a reconciliation pass stored
+pub fn record_reconciled_transactions(dex: &str, count: usize) {
+    if let Some(metrics) = get() {
+        metrics.reconciled_transactions.with_label_values(&[dex]).inc_by(count as u64);
+    }
+}
+
+/// Record transactions a backfill found in slots before the pool's checkpoint
+pub fn record_missed_live_events(dex: &str, count: usize) {
+    if let Some(metrics) = get() {
+        metrics.missed_live_events.with_label_values(&[dex]).inc_by(count as u64);
+    }
+}
+
+/// Record the number of events held while the database is unreachable
+pub fn set_outage_buffer_events(dex: &str, count: usize) {
+    if let Some(metrics) = get() {
+        metrics.outage_buffer_events.with_label_values(&[dex]).set(count as i64);
+    }
+}
+
+/// Record a transaction fetched during backfill
+pub fn record_backfill_transaction(dex: &str) {
+    if let Some(metrics) = get() {
+        metrics.backfill_transactions_fetched.with_label_values(&[dex]).inc();
+    }
+}
+
+/// Record a WebSocket reconnection attempt
+pub fn record_websocket_reconnect() {
+    if let Some(metrics) = get() {
+        metrics.websocket_reconnects.inc();
+    }
+}
+
+/// Record the WebSocket connection state
+pub fn set_websocket_connected(connected: bool) {
+    if let Some(metrics) = get() {
+        metrics.websocket_connected.set(connected as i64);
+    }
+}
+
+/// Record a WebSocket log notification dropped because the processing channel was full
+pub fn record_websocket_message_dropped() {
+    if let Some(metrics) = get() {
+        metrics.websocket_messages_dropped.inc();
+    }
+}
+
+/// Record an RPC error, classified by kind
+pub fn record_rpc_error(dex: &str, err: &anyhow::Error) {
+    if let Some(metrics) = get() {
+        metrics.rpc_errors.with_label_values(&[dex, rpc_error_kind(err)]).inc();
+    }
+}
+
+/// Classify an RPC error for the `kind` label, `other` for errors that aren't an `IndexerError`
+pub fn rpc_error_kind(err: &anyhow::Error) -> &'static str {
+    IndexerError::find(err).map_or("other", IndexerError::kind)
+}
+
+//
+// HTTP SERVER
+//
+
+async fn handle_request(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") =>
+            Response::builder()
+                .header("Content-Type", TextEncoder::new().format_type())
+                .body(Body::from(gather())),
+        _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::from("Not Found")),
+    };
+
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+/// Serve `/metrics` on the given address until the process exits
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    init()?;
+
+    let make_service = make_service_fn(|_conn| async {
+        Ok::<_, Infallible>(service_fn(handle_request))
+    });
+
+    let server = Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind metrics server to {}", addr))?
+        .serve(make_service);
+
+    logging::log_activity("metrics", "Server started", Some(&format!("listening on {}", addr)));
+    server.await.context("Metrics server failed")
+}
@@ -5,15 +5,31 @@ use std::str::FromStr;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Protocol {
     OrcaWhirlpool,
-    // Future: RaydiumConcentrated,
+    RaydiumAmm,
+    RaydiumClmm,
     // Future: MercurialStable,
     // etc.
 }
 
+impl Protocol {
+    /// DEX the protocol belongs to
+    ///
+    /// This is the key used for signature tracking, metrics and the `dex` column,
+    /// and must match a value of the `apestrong.dex_type` enum.
+    pub const fn dex(&self) -> &'static str {
+        match self {
+            Protocol::OrcaWhirlpool => "orca",
+            Protocol::RaydiumAmm | Protocol::RaydiumClmm => "raydium",
+        }
+    }
+}
+
 impl ToString for Protocol {
     fn to_string(&self) -> String {
         match self {
             Protocol::OrcaWhirlpool => "orca_whirlpool".to_string(),
+            Protocol::RaydiumAmm => "raydium_amm".to_string(),
+            Protocol::RaydiumClmm => "raydium_clmm".to_string(),
         }
     }
 }
@@ -24,6 +40,8 @@ impl FromStr for Protocol {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "orca_whirlpool" => Ok(Protocol::OrcaWhirlpool),
+            "raydium_amm" => Ok(Protocol::RaydiumAmm),
+            "raydium_clmm" => Ok(Protocol::RaydiumClmm),
             _ => Err(format!("Unknown protocol: {}", s)),
         }
     }
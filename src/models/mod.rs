@@ -1,5 +1,6 @@
 pub mod common;
 pub mod orca;
-/// pub mod raydium;
+pub mod raydium;
+pub mod token;
 
 pub use common::*;
@@ -0,0 +1,109 @@
+use anyhow::{ bail, Result };
+use borsh::BorshDeserialize;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::IndexerError;
+
+/// Size of a Whirlpool account: 8-byte anchor discriminator + 645 bytes of pool data
+pub const WHIRLPOOL_ACCOUNT_LEN: usize = 653;
+
+/// Anchor account discriminator of Whirlpool accounts (first 8 bytes of sha256("account:Whirlpool"))
+pub const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+
+/// Offset of `token_mint_a` in a Whirlpool account, counting the discriminator
+pub const WHIRLPOOL_TOKEN_MINT_A_OFFSET: usize = 101;
+
+/// Offset of `token_mint_b` in a Whirlpool account, counting the discriminator
+pub const WHIRLPOOL_TOKEN_MINT_B_OFFSET: usize = 181;
+
+/// Whirlpool account data, following the anchor discriminator
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct WhirlpoolData {
+    pub whirlpools_config: Pubkey, // 32 bytes
+    pub whirlpool_bump: [u8; 1], // 1 byte
+    pub tick_spacing: u16, // 2 bytes
+    pub tick_spacing_seed: [u8; 2], // 2 bytes
+    pub fee_rate: u16, // 2 bytes
+    pub protocol_fee_rate: u16, // 2 bytes
+    pub liquidity: u128, // 16 bytes
+    pub sqrt_price: u128, // 16 bytes
+    pub tick_current_index: i32, // 4 bytes
+    pub protocol_fee_owed_a: u64, // 8 bytes
+    pub protocol_fee_owed_b: u64, // 8 bytes
+    pub token_mint_a: Pubkey, // 32 bytes
+    pub token_vault_a: Pubkey, // 32 bytes
+    pub fee_growth_global_a: u128, // 16 bytes
+    pub token_mint_b: Pubkey, // 32 bytes
+    pub token_vault_b: Pubkey, // 32 bytes
+    pub fee_growth_global_b: u128, // 16 bytes
+    pub reward_last_updated_timestamp: u64, // 8 bytes
+    pub reward_infos: [WhirlpoolRewardInfo; 3], // 3 * 128 = 384 bytes
+}
+
+/// One of a Whirlpool's reward emissions
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct WhirlpoolRewardInfo {
+    pub mint: Pubkey, // 32 bytes
+    pub vault: Pubkey, // 32 bytes
+    pub authority: Pubkey, // 32 bytes
+    pub emissions_per_second_x64: u128, // 16 bytes
+    pub growth_global_x64: u128, // 16 bytes
+}
+
+/// Check that an account really is a Whirlpool before deserializing it
+///
+/// The account must be owned by the Whirlpool program and have exactly the
+/// Whirlpool account size, otherwise an arbitrary account (token mint, another
+/// program's pool, ...) would deserialize into garbage pool data.
+pub fn validate_whirlpool_account(
+    pool: &Pubkey,
+    account: &Account,
+    program_id: &Pubkey
+) -> Result<()> {
+    if account.owner != *program_id {
+        bail!(
+            "Account {} is not an Orca Whirlpool: owned by {}, expected Whirlpool program {}",
+            pool,
+            account.owner,
+            program_id
+        );
+    }
+
+    if account.data.len() != WHIRLPOOL_ACCOUNT_LEN {
+        bail!(
+            "Account {} is not an Orca Whirlpool: data length is {} bytes, expected {}",
+            pool,
+            account.data.len(),
+            WHIRLPOOL_ACCOUNT_LEN
+        );
+    }
+
+    Ok(())
+}
+
+/// Validate a Whirlpool account and deserialize its pool data
+///
+/// Besides the owner and size checks of `validate_whirlpool_account`, the anchor
+/// discriminator must match and the layout must consume the data exactly, so a
+/// change to the account layout fails loudly instead of yielding shifted fields.
+pub fn parse_whirlpool_account(
+    pool: &Pubkey,
+    account: &Account,
+    program_id: &Pubkey
+) -> Result<WhirlpoolData> {
+    validate_whirlpool_account(pool, account, program_id)?;
+
+    if account.data[..8] != WHIRLPOOL_DISCRIMINATOR {
+        bail!(
+            "Account {} is not an Orca Whirlpool: discriminator {:?} doesn't match {:?}",
+            pool,
+            &account.data[..8],
+            WHIRLPOOL_DISCRIMINATOR
+        );
+    }
+
+    WhirlpoolData::try_from_slice(&account.data[8..]).map_err(|e|
+        IndexerError::Deserialize(format!("Whirlpool {}: {}", pool, e)).into()
+    )
+}
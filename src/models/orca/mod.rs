@@ -1,3 +1,5 @@
+pub mod account_layout;
 pub mod whirlpool;
 
+pub use account_layout::*;
 pub use whirlpool::*;
@@ -18,6 +18,7 @@ use std::str::FromStr;
 pub const TRADED_EVENT_DISCRIMINATOR: [u8; 8] = [225, 202, 73, 175, 147, 43, 160, 150];
 pub const LIQUIDITY_INCREASED_DISCRIMINATOR: [u8; 8] = [30, 7, 144, 181, 102, 254, 155, 161];
 pub const LIQUIDITY_DECREASED_DISCRIMINATOR: [u8; 8] = [166, 1, 36, 71, 112, 202, 181, 171];
+pub const POOL_INITIALIZED_DISCRIMINATOR: [u8; 8] = [100, 118, 173, 87, 12, 198, 254, 229];
 
 /// Types of events emitted by Orca Whirlpool
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -25,6 +26,7 @@ pub enum OrcaWhirlpoolEventType {
     Traded,
     LiquidityIncreased,
     LiquidityDecreased,
+    PoolInitialized,
 }
 
 impl ToString for OrcaWhirlpoolEventType {
@@ -33,6 +35,7 @@ impl ToString for OrcaWhirlpoolEventType {
             OrcaWhirlpoolEventType::Traded => "Traded".to_string(),
             OrcaWhirlpoolEventType::LiquidityIncreased => "LiquidityIncreased".to_string(),
             OrcaWhirlpoolEventType::LiquidityDecreased => "LiquidityDecreased".to_string(),
+            OrcaWhirlpoolEventType::PoolInitialized => "PoolInitialized".to_string(),
         }
     }
 }
@@ -45,6 +48,7 @@ impl FromStr for OrcaWhirlpoolEventType {
             "Traded" => Ok(OrcaWhirlpoolEventType::Traded),
             "LiquidityIncreased" => Ok(OrcaWhirlpoolEventType::LiquidityIncreased),
             "LiquidityDecreased" => Ok(OrcaWhirlpoolEventType::LiquidityDecreased),
+            "PoolInitialized" => Ok(OrcaWhirlpoolEventType::PoolInitialized),
             _ => Err(format!("Unknown Orca Whirlpool event type: {}", s)),
         }
     }
@@ -114,10 +118,16 @@ pub struct OrcaWhirlpoolLiquidityDecreasedEvent {
 pub struct OrcaWhirlpoolEvent {
     pub id: i32,
     pub signature: String,
+    pub event_index: i32, // Position of the event among its transaction's events
     pub whirlpool: String,
     pub event_type: String,
+    pub dex: String, // DEX identifier, for cross-DEX queries
+    pub slot: Option<i64>, // Slot the transaction landed in, when known
+    pub block_time: Option<DateTime<Utc>>, // Time of the transaction's block, when known
+    pub fee_payer: Option<String>, // Transaction fee payer, when known
     pub version: i32,
     pub timestamp: DateTime<Utc>,
+    pub enrichment: Option<serde_json::Value>, // Fields added by the configured enricher
 }
 
 // Database event record structures
@@ -125,14 +135,15 @@ pub struct OrcaWhirlpoolEvent {
 pub struct OrcaWhirlpoolTradedRecord {
     pub event_id: i32,
     pub a_to_b: bool,
-    pub pre_sqrt_price: i64,
-    pub post_sqrt_price: i64,
-    pub input_amount: i64,
-    pub output_amount: i64,
-    pub input_transfer_fee: i64,
-    pub output_transfer_fee: i64,
-    pub lp_fee: i64,
-    pub protocol_fee: i64,
+    pub pre_sqrt_price: u128, // Q64.64, stored as NUMERIC(39, 0)
+    pub post_sqrt_price: u128,
+    pub input_amount: u64, // u64 amounts are stored as NUMERIC(20, 0)
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub price: Option<f64>, // Post-trade price of token A in token B, when decimals are known
 }
 
 // IMPORTANT: LiquidityIncreasedRecord and LiquidityDecreasedRecord must remain separate structures
@@ -145,11 +156,11 @@ pub struct OrcaWhirlpoolLiquidityIncreasedRecord {
     pub position: String,
     pub tick_lower_index: i32,
     pub tick_upper_index: i32,
-    pub liquidity: i64,
-    pub token_a_amount: i64,
-    pub token_b_amount: i64,
-    pub token_a_transfer_fee: i64,
-    pub token_b_transfer_fee: i64,
+    pub liquidity: u128,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub token_a_transfer_fee: u64,
+    pub token_b_transfer_fee: u64,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -158,11 +169,23 @@ pub struct OrcaWhirlpoolLiquidityDecreasedRecord {
     pub position: String,
     pub tick_lower_index: i32,
     pub tick_upper_index: i32,
-    pub liquidity: i64,
-    pub token_a_amount: i64,
-    pub token_b_amount: i64,
-    pub token_a_transfer_fee: i64,
-    pub token_b_transfer_fee: i64,
+    pub liquidity: u128,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub token_a_transfer_fee: u64,
+    pub token_b_transfer_fee: u64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct OrcaWhirlpoolPoolInitializedRecord {
+    pub event_id: i32,
+    pub whirlpools_config: String,
+    pub token_mint_a: String,
+    pub token_mint_b: String,
+    pub tick_spacing: i32,
+    pub decimals_a: i32,
+    pub decimals_b: i32,
+    pub initial_sqrt_price: u128, // Q64.64, stored as NUMERIC(39, 0)
 }
 
 // Legacy record structure for backwards compatibility with existing code
@@ -177,11 +200,11 @@ pub struct OrcaWhirlpoolLiquidityRecord {
     pub position: String,
     pub tick_lower_index: i32,
     pub tick_upper_index: i32,
-    pub liquidity: i64,
-    pub token_a_amount: i64,
-    pub token_b_amount: i64,
-    pub token_a_transfer_fee: i64,
-    pub token_b_transfer_fee: i64,
+    pub liquidity: u128,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub token_a_transfer_fee: u64,
+    pub token_b_transfer_fee: u64,
 }
 
 // Combined record structures for each event type
@@ -203,6 +226,12 @@ pub struct OrcaWhirlpoolLiquidityDecreasedEventRecord {
     pub data: OrcaWhirlpoolLiquidityRecord, // Using the legacy record to maintain compatibility
 }
 
+#[derive(Debug, Clone)]
+pub struct OrcaWhirlpoolPoolInitializedEventRecord {
+    pub base: OrcaWhirlpoolEvent,
+    pub data: OrcaWhirlpoolPoolInitializedRecord,
+}
+
 /// Orca Whirlpool Pool record
 #[derive(Debug, Clone)]
 pub struct OrcaWhirlpoolPoolRecord {
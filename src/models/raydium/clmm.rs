@@ -1,3 +1,4 @@
+use base64::{ engine::general_purpose, Engine as _ };
 use borsh::BorshDeserialize;
 use chrono::{ DateTime, Utc };
 use serde::{ Serialize, Deserialize };
@@ -5,6 +6,8 @@ use solana_sdk::pubkey::Pubkey;
 use sqlx::FromRow;
 use std::str::FromStr;
 
+use crate::models::common::Protocol;
+
 // Raydium CLMM event discriminators
 pub const CLMM_CREATE_PERSONAL_POSITION_DISCRIMINATOR: [u8; 8] = [
     226, 245, 162, 196, 229, 232, 248, 211,
@@ -13,6 +16,8 @@ pub const CLMM_LIQUIDITY_INCREASED_DISCRIMINATOR: [u8; 8] = [
     200, 185, 247, 226, 211, 165, 182, 193,
 ];
 pub const CLMM_LIQUIDITY_DECREASED_DISCRIMINATOR: [u8; 8] = [93, 127, 154, 27, 44, 62, 77, 95];
+// Emitted alongside increase/decrease liquidity; carries the pool address
+pub const CLMM_LIQUIDITY_CHANGE_DISCRIMINATOR: [u8; 8] = [126, 240, 175, 206, 158, 88, 153, 107];
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum RaydiumCLMMEventType {
@@ -106,12 +111,114 @@ pub struct RaydiumCLMMDecreaseLiquidityEvent {
     pub transfer_fee_1: u64,
 }
 
+#[derive(BorshDeserialize, Debug)]
+pub struct RaydiumCLMMLiquidityChangeEvent {
+    /// The pool whose liquidity changed
+    pub pool_state: Pubkey,
+    /// The current tick of the pool
+    pub tick: i32,
+    /// The lower tick of the position
+    pub tick_lower: i32,
+    /// The upper tick of the position
+    pub tick_upper: i32,
+    /// The pool liquidity before the change
+    pub liquidity_before: u128,
+    /// The pool liquidity after the change
+    pub liquidity_after: u128,
+}
+
+/// Decode a `Program data:` log line into its raw event bytes
+fn decode_program_data(line: &str) -> Option<Vec<u8>> {
+    let (_, data) = line.split_once("Program data: ")?;
+    general_purpose::STANDARD.decode(data.trim()).ok()
+}
+
+/// Extract the pool address from a decoded event payload, if the event carries one
+pub fn pool_from_program_data(data: &[u8]) -> Option<Pubkey> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let (discriminator, payload) = data.split_at(8);
+    if discriminator == CLMM_CREATE_PERSONAL_POSITION_DISCRIMINATOR {
+        RaydiumCLMMCreatePositionEvent::try_from_slice(payload)
+            .ok()
+            .map(|event| event.pool_state)
+    } else if discriminator == CLMM_LIQUIDITY_CHANGE_DISCRIMINATOR {
+        RaydiumCLMMLiquidityChangeEvent::try_from_slice(payload)
+            .ok()
+            .map(|event| event.pool_state)
+    } else {
+        None
+    }
+}
+
+/// Extract the position NFT mint from a decoded event payload, if the event carries one
+pub fn position_mint_from_program_data(data: &[u8]) -> Option<Pubkey> {
+    if data.len() < 8 {
+        return None;
+    }
+
+    let (discriminator, payload) = data.split_at(8);
+    if discriminator == CLMM_LIQUIDITY_INCREASED_DISCRIMINATOR {
+        RaydiumCLMMIncreaseLiquidityEvent::try_from_slice(payload)
+            .ok()
+            .map(|event| event.position_nft_mint)
+    } else if discriminator == CLMM_LIQUIDITY_DECREASED_DISCRIMINATOR {
+        RaydiumCLMMDecreaseLiquidityEvent::try_from_slice(payload)
+            .ok()
+            .map(|event| event.position_nft_mint)
+    } else {
+        None
+    }
+}
+
+/// Return the single value produced by `extract` across all `Program data:` lines
+///
+/// Returns `None` if no line yields a value or if the lines disagree, since the value
+/// could not then be attributed unambiguously.
+fn find_unique_in_logs(
+    logs: &[String],
+    extract: fn(&[u8]) -> Option<Pubkey>
+) -> Option<Pubkey> {
+    let mut found: Option<Pubkey> = None;
+
+    for data in logs.iter().filter_map(|line| decode_program_data(line)) {
+        if let Some(value) = extract(&data) {
+            match found {
+                Some(existing) if existing != value => {
+                    return None;
+                }
+                _ => {
+                    found = Some(value);
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Scan a transaction's logs for the CLMM pool its events refer to
+pub fn find_pool_in_logs(logs: &[String]) -> Option<Pubkey> {
+    find_unique_in_logs(logs, pool_from_program_data)
+}
+
+/// Scan a transaction's logs for the position NFT mint its liquidity events refer to
+pub fn find_position_mint_in_logs(logs: &[String]) -> Option<Pubkey> {
+    find_unique_in_logs(logs, position_mint_from_program_data)
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct RaydiumCLMMEvent {
     pub id: i32, // Auto-incremented by DB
     pub signature: String, // Transaction signature
     pub pool: String, // Pool address as string
     pub event_type: String, // Event type as string
+    pub dex: String, // DEX identifier, for cross-DEX queries
+    pub slot: Option<i64>, // Slot the transaction landed in, when known
+    pub block_time: Option<DateTime<Utc>>, // Time of the transaction's block, when known
+    pub is_backfill: bool, // Whether the event was stored by a backfill rather than the live stream
     pub version: i32, // For schema versioning
     pub timestamp: DateTime<Utc>, // Event timestamp
 }
@@ -123,6 +230,10 @@ impl RaydiumCLMMEvent {
             signature,
             pool: pool.to_string(),
             event_type: event_type.to_string(),
+            dex: Protocol::RaydiumClmm.dex().to_string(),
+            slot: None,
+            block_time: None,
+            is_backfill: false,
             version: 1,
             timestamp: Utc::now(),
         }
@@ -134,7 +245,6 @@ pub struct RaydiumCLMMCreatePositionRecord {
     pub event_id: i32,
     pub minter: String,
     pub nft_owner: String,
-    pub output_amount: i64,
     pub tick_lower_index: i32,
     pub tick_upper_index: i32,
     pub liquidity: u128,
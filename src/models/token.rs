@@ -0,0 +1,120 @@
+use anyhow::{ bail, Context, Result };
+use borsh::BorshDeserialize;
+use mpl_token_metadata::accounts::Metadata as MplMetadata;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::state::Mint;
+
+/// Token mint details and Metaplex metadata
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub symbol: String,
+    pub name: String,
+}
+
+// Helper function to deserialize metadata
+fn deserialize_metadata(data: &[u8]) -> Result<MplMetadata> {
+    let mut slice = data;
+    MplMetadata::deserialize(&mut slice).map_err(|e|
+        anyhow::anyhow!("Failed to deserialize metadata: {}", e)
+    )
+}
+
+/// Read a token's decimals from its mint account
+///
+/// Token-2022 mints share the SPL Token mint layout and append their extensions
+/// after it, so only the base layout is unpacked. Accounts too short to hold a
+/// mint, or uninitialized mints, are rejected rather than given made-up decimals.
+pub fn parse_mint_decimals(token_mint: &Pubkey, account: &Account) -> Result<u8> {
+    if account.data.len() < Mint::LEN {
+        bail!(
+            "Account {} is not a token mint: data length is {} bytes, expected at least {}",
+            token_mint,
+            account.data.len(),
+            Mint::LEN
+        );
+    }
+
+    let mint = Mint::unpack(&account.data[..Mint::LEN]).with_context(||
+        format!("Failed to unpack token mint {}", token_mint)
+    )?;
+    Ok(mint.decimals)
+}
+
+/// Fetch token information (mint details, metadata, decimals)
+///
+/// When the Metaplex metadata can't be resolved the mint address is used as the
+/// symbol, unless `require_metadata` is set, in which case an error is returned
+/// so the caller doesn't store a half-populated token.
+pub async fn fetch_token_info(
+    rpc_client: &RpcClient,
+    token_mint: &Pubkey,
+    metadata_program_id: &Pubkey,
+    require_metadata: bool
+) -> Result<TokenInfo> {
+    // Fetch token mint account
+    let token_account = rpc_client
+        .get_account_with_commitment(token_mint, CommitmentConfig::confirmed()).await?
+        .value.context(format!("Token mint account not found for {}", token_mint))?;
+
+    let decimals = parse_mint_decimals(token_mint, &token_account)?;
+
+    // Try to fetch metadata
+    let mut symbol = String::new();
+    let mut name = String::new();
+    let (metadata_pda, _bump) = Pubkey::find_program_address(
+        &[b"metadata", &metadata_program_id.to_bytes(), &token_mint.to_bytes()],
+        metadata_program_id
+    );
+
+    let missing_reason = match
+        rpc_client.get_account_with_commitment(&metadata_pda, CommitmentConfig::confirmed()).await
+    {
+        Ok(account_result) =>
+            match account_result.value {
+                Some(metadata_account) =>
+                    match deserialize_metadata(&metadata_account.data) {
+                        Ok(metadata) => {
+                            symbol = metadata.symbol.trim_end_matches('\0').to_string();
+                            name = metadata.name.trim_end_matches('\0').to_string();
+                            "metadata has an empty symbol".to_string()
+                        }
+                        Err(e) => e.to_string(),
+                    }
+                None => format!("metadata account {} not found", metadata_pda),
+            }
+        Err(e) => format!("failed to fetch metadata account {}: {}", metadata_pda, e),
+    };
+
+    if symbol.is_empty() {
+        if require_metadata {
+            bail!("Token metadata is required but could not be resolved for {}: {}", token_mint, missing_reason);
+        }
+
+        // Local validators usually have no Metaplex metadata, so fall back to the mint address
+        log::warn!(
+            "Warning: No metadata found for {} ({}), using mint address as symbol",
+            token_mint,
+            missing_reason
+        );
+        symbol = short_mint(token_mint);
+    }
+
+    Ok(TokenInfo {
+        mint: *token_mint,
+        decimals,
+        symbol,
+        name,
+    })
+}
+
+// Abbreviated mint address used as a placeholder symbol (e.g. "So11..1112")
+fn short_mint(token_mint: &Pubkey) -> String {
+    let mint = token_mint.to_string();
+    format!("{}..{}", &mint[..4], &mint[mint.len() - 4..])
+}
@@ -0,0 +1,75 @@
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Default number of events held while the database is unreachable
+pub const DEFAULT_OUTAGE_BUFFER_CAPACITY: usize = 10_000;
+
+/// An event that couldn't be persisted because the database was unreachable
+///
+/// Like dead-lettered events, it keeps the logs and its index within them so it
+/// can be parsed again when replayed.
+#[derive(Debug, Clone)]
+pub struct HeldEvent {
+    pub log: RpcLogsResponse,
+    /// Position of the event among the events of `log`, see `DexIndexer::parse_indexed_log_events`
+    pub event_index: usize,
+    pub is_backfill: bool,
+    pub slot: Option<u64>,
+    /// Unix time of the transaction's block, when known
+    pub block_time: Option<i64>,
+    /// Account that paid the transaction's fees, when known
+    pub fee_payer: Option<Pubkey>,
+}
+
+/// Holds events in memory, in order, while the database is unreachable
+///
+/// Database restarts and network blips would otherwise turn every event seen
+/// during the outage into a dead letter. Once the buffer is full, further events
+/// are dead-lettered as before, so memory use stays bounded.
+#[derive(Debug)]
+pub struct OutageBuffer {
+    capacity: usize,
+    events: Mutex<VecDeque<HeldEvent>>,
+}
+
+impl OutageBuffer {
+    /// Create a buffer holding at most `capacity` events (0 disables holding)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Maximum number of events held
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Hold an event, returning false if the buffer is full
+    pub fn hold(&self, event: HeldEvent) -> bool {
+        let mut events = self.events.lock().unwrap();
+        if events.len() >= self.capacity {
+            return false;
+        }
+        events.push_back(event);
+        true
+    }
+
+    /// Remove and return every held event, oldest first
+    pub fn take_all(&self) -> Vec<HeldEvent> {
+        self.events.lock().unwrap().drain(..).collect()
+    }
+
+    /// Number of events held
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    /// Whether no events are held, i.e. the database isn't known to be down
+    pub fn is_empty(&self) -> bool {
+        self.events.lock().unwrap().is_empty()
+    }
+}
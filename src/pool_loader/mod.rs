@@ -1,3 +1,6 @@
+// Fetch pools from chain and resolve their tokens, shared by the `load_pools`
+// binary and the indexer's `pools add` command
+
 // Re-export DEX-specific modules
 pub mod orca;
 
@@ -8,13 +11,10 @@ use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 use futures::future::BoxFuture;
 
+use crate::models::orca::whirlpool::OrcaWhirlpoolPoolRecord;
+
 // Common token information structure
-pub struct TokenInfo {
-    pub mint: Pubkey,
-    pub decimals: u8,
-    pub symbol: String,
-    pub name: String,
-}
+pub use crate::models::token::TokenInfo;
 
 // Common pool record structure
 pub struct PoolRecord {
@@ -32,20 +32,23 @@ pub trait DexProcessor {
         &'a self,
         rpc_client: &'a RpcClient,
         pool_pubkey: &'a Pubkey,
-        metadata_program_id: &'a Pubkey,
         token_cache: &'a mut HashMap<Pubkey, TokenInfo>,
         verbose: bool
     ) -> BoxFuture<'a, Result<PoolRecord>>;
 }
 
-// Helper method for token info cloning
-impl Clone for TokenInfo {
-    fn clone(&self) -> Self {
-        TokenInfo {
-            mint: self.mint,
-            decimals: self.decimals,
-            symbol: self.symbol.clone(),
-            name: self.name.clone(),
+impl PoolRecord {
+    // The record stored for an Orca pool by `OrcaWhirlpoolRepository::upsert_pool`
+    pub fn to_orca_record(&self) -> OrcaWhirlpoolPoolRecord {
+        OrcaWhirlpoolPoolRecord {
+            whirlpool: self.pool_address.to_string(),
+            token_mint_a: self.token_a.mint.to_string(),
+            token_mint_b: self.token_b.mint.to_string(),
+            token_name_a: Some(self.token_a.name.clone()),
+            token_name_b: Some(self.token_b.name.clone()),
+            pool_name: Some(self.pool_name.clone()),
+            decimals_a: self.token_a.decimals as i32,
+            decimals_b: self.token_b.decimals as i32,
         }
     }
 }
@@ -0,0 +1,176 @@
+use anyhow::{ Context, Result };
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{ RpcAccountInfoConfig, RpcProgramAccountsConfig };
+use solana_client::rpc_filter::{ Memcmp, RpcFilterType };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Duration;
+use futures::future::BoxFuture;
+
+use crate::db::TokenMetadataStore;
+use crate::models::orca::{
+    parse_whirlpool_account,
+    WhirlpoolData,
+    WHIRLPOOL_ACCOUNT_LEN,
+    WHIRLPOOL_TOKEN_MINT_A_OFFSET,
+    WHIRLPOOL_TOKEN_MINT_B_OFFSET,
+};
+
+use super::{ DexProcessor, PoolRecord, TokenInfo };
+
+// Find the Whirlpools owned by a program with getProgramAccounts, optionally only
+// those trading `token_mint`. Pools are returned sorted by address.
+//
+// The RPC node filters on the account size (and on the mint, which takes one
+// query per side of the pool), so only Whirlpool accounts are downloaded.
+pub async fn discover_whirlpools(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    token_mint: Option<&Pubkey>
+) -> Result<Vec<(Pubkey, WhirlpoolData)>> {
+    let size_filter = RpcFilterType::DataSize(WHIRLPOOL_ACCOUNT_LEN as u64);
+    let filter_sets = match token_mint {
+        Some(mint) =>
+            vec![
+                vec![
+                    size_filter.clone(),
+                    RpcFilterType::Memcmp(
+                        Memcmp::new_base58_encoded(WHIRLPOOL_TOKEN_MINT_A_OFFSET, mint.as_ref())
+                    )
+                ],
+                vec![
+                    size_filter,
+                    RpcFilterType::Memcmp(
+                        Memcmp::new_base58_encoded(WHIRLPOOL_TOKEN_MINT_B_OFFSET, mint.as_ref())
+                    )
+                ]
+            ],
+        None => vec![vec![size_filter]],
+    };
+
+    let mut pools = Vec::new();
+    for filters in filter_sets {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = rpc_client
+            .get_program_accounts_with_config(program_id, config).await
+            .context("Failed to get Whirlpool program accounts")?;
+
+        for (address, account) in accounts {
+            // The RPC node already filtered by size, but not every node honours filters
+            match parse_whirlpool_account(&address, &account, program_id) {
+                Ok(pool_data) => pools.push((address, pool_data)),
+                Err(e) => log::warn!("Skipping discovered account: {:#}", e),
+            }
+        }
+    }
+
+    // A pool trading the mint against itself would be found by both queries
+    pools.sort_by_key(|(address, _)| *address);
+    pools.dedup_by_key(|(address, _)| *address);
+    Ok(pools)
+}
+
+pub struct OrcaProcessor {
+    // Whirlpool program that must own every pool account
+    pub program_id: Pubkey,
+    // Stored token metadata, fetched from chain when missing or stale
+    pub tokens: TokenMetadataStore,
+    // Age after which stored token metadata is fetched again
+    pub token_metadata_ttl: Duration,
+}
+
+impl OrcaProcessor {
+    // Build a pool record from already deserialized pool data, resolving its tokens
+    pub async fn pool_record(
+        &self,
+        pool_pubkey: &Pubkey,
+        pool_data: &WhirlpoolData,
+        token_cache: &mut HashMap<Pubkey, TokenInfo>,
+        verbose: bool
+    ) -> Result<PoolRecord> {
+        // Fetch token information, using cache if available
+        let token_a_info = if let Some(info) = token_cache.get(&pool_data.token_mint_a) {
+            info.clone()
+        } else {
+            let info = self.tokens
+                .refresh_token_metadata(&pool_data.token_mint_a, self.token_metadata_ttl).await
+                .context("Failed to fetch Token A information")?;
+            token_cache.insert(pool_data.token_mint_a, info.clone());
+            info
+        };
+
+        let token_b_info = if let Some(info) = token_cache.get(&pool_data.token_mint_b) {
+            info.clone()
+        } else {
+            let info = self.tokens
+                .refresh_token_metadata(&pool_data.token_mint_b, self.token_metadata_ttl).await
+                .context("Failed to fetch Token B information")?;
+            token_cache.insert(pool_data.token_mint_b, info.clone());
+            info
+        };
+
+        // Create pool record
+        let pool_record = PoolRecord {
+            pool_address: *pool_pubkey,
+            pool_name: format!("{} / {}", token_a_info.symbol, token_b_info.symbol),
+            dex: String::from("orca"),
+            token_a: token_a_info,
+            token_b: token_b_info,
+        };
+
+        // Display token information
+        if verbose {
+            log::debug!("  Token A Symbol: {}", pool_record.token_a.symbol);
+            log::debug!("  Token A Decimals: {}", pool_record.token_a.decimals);
+            log::debug!("  Token B Symbol: {}", pool_record.token_b.symbol);
+            log::debug!("  Token B Decimals: {}", pool_record.token_b.decimals);
+        }
+
+        Ok(pool_record)
+    }
+}
+
+impl DexProcessor for OrcaProcessor {
+    fn process_pool<'a>(
+        &'a self,
+        rpc_client: &'a RpcClient,
+        pool_pubkey: &'a Pubkey,
+        token_cache: &'a mut HashMap<Pubkey, TokenInfo>,
+        verbose: bool
+    ) -> BoxFuture<'a, Result<PoolRecord>> {
+        Box::pin(async move {
+            // Fetch the pool account data
+            let pool_account = rpc_client
+                .get_account_with_commitment(pool_pubkey, CommitmentConfig::confirmed()).await?
+                .value.context("Pool account not found")?;
+
+            // Debug information
+            if verbose {
+                log::debug!("Account data length: {} bytes", pool_account.data.len());
+            }
+
+            // Reject accounts that aren't Whirlpools before reading their data
+            let pool_data = parse_whirlpool_account(pool_pubkey, &pool_account, &self.program_id)?;
+
+            if verbose {
+                log::debug!("Found pool with the following data:");
+                log::debug!("  Token Mint A: {}", pool_data.token_mint_a);
+                log::debug!("  Token Mint B: {}", pool_data.token_mint_b);
+                log::debug!("  Tick Spacing: {}", pool_data.tick_spacing);
+                log::debug!("  Fee Rate: {}", pool_data.fee_rate);
+            }
+
+            self.pool_record(pool_pubkey, &pool_data, token_cache, verbose).await
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{ Context, Result };
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+// Mainnet program deployments
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+pub const RAYDIUM_AMM_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const RAYDIUM_CLMM_PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+pub const METAPLEX_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+/// Mainnet SOL/USDC Whirlpool, indexed when no Orca pools are given or subscribed
+pub const DEFAULT_ORCA_POOL: &str = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
+
+/// Program IDs the indexers subscribe to
+///
+/// Defaults to the mainnet deployments. Override individual IDs to index a
+/// local validator (`solana-test-validator`) or any cluster with its own
+/// deployments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramIds {
+    pub orca_whirlpool: String,
+    pub raydium_amm: String,
+    pub raydium_clmm: String,
+}
+
+impl Default for ProgramIds {
+    fn default() -> Self {
+        Self {
+            orca_whirlpool: ORCA_WHIRLPOOL_PROGRAM_ID.to_string(),
+            raydium_amm: RAYDIUM_AMM_PROGRAM_ID.to_string(),
+            raydium_clmm: RAYDIUM_CLMM_PROGRAM_ID.to_string(),
+        }
+    }
+}
+
+impl ProgramIds {
+    /// Override the Orca Whirlpool program ID
+    pub fn with_orca_whirlpool(mut self, program_id: impl Into<String>) -> Self {
+        self.orca_whirlpool = program_id.into();
+        self
+    }
+
+    /// Override the Raydium AMM program ID
+    pub fn with_raydium_amm(mut self, program_id: impl Into<String>) -> Self {
+        self.raydium_amm = program_id.into();
+        self
+    }
+
+    /// Override the Raydium CLMM program ID
+    pub fn with_raydium_clmm(mut self, program_id: impl Into<String>) -> Self {
+        self.raydium_clmm = program_id.into();
+        self
+    }
+
+    /// Check that every program ID is a valid pubkey
+    pub fn validate(&self) -> Result<()> {
+        for (name, program_id) in [
+            ("Orca Whirlpool", &self.orca_whirlpool),
+            ("Raydium AMM", &self.raydium_amm),
+            ("Raydium CLMM", &self.raydium_clmm),
+        ] {
+            Pubkey::from_str(program_id).with_context(||
+                format!("Invalid {} program ID: {}", name, program_id)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Paces requests to at most a fixed number per second
+///
+/// Each caller is given the next free slot, `1 / requests_per_second` after the
+/// previous one, and waits for it. Slots are never saved up while idle, so there
+/// is no burst after a quiet period; public RPC nodes ban bursts just as readily.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Limit to the given number of requests per second (at least 1)
+    pub fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second.max(1),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Time between consecutive requests
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Wait until the next request may be sent
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use solana_client::client_error::{ ClientError, ClientErrorKind, Result as ClientResult };
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_rpc_client::http_sender::HttpSender;
+use reqwest::header::{ HeaderMap, HeaderName, HeaderValue };
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Request timeout used when none is configured
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+/// Number of times a request is retried after a transient failure
+pub const DEFAULT_RPC_RETRIES: usize = 3;
+/// Delay before the first retry, doubled on each further attempt
+pub const DEFAULT_RPC_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Build an RPC client with a request timeout and bounded retries on transient errors
+///
+/// The default `RpcClient` has no request timeout, so a hung RPC node can
+/// stall a caller forever. Every client in the indexer and the loader
+/// binaries should be created here.
+pub fn build_rpc_client(url: &str, commitment: CommitmentConfig, timeout: Duration) -> RpcClient {
+    build_rpc_client_with_headers(url, commitment, timeout, &HashMap::new())
+}
+
+/// Build an RPC client that sends the given HTTP headers with every request
+///
+/// For RPC providers that take an API key in a header (e.g. `Authorization`)
+/// rather than in the URL. Headers are validated by `parse_rpc_header`; any
+/// that are still invalid here are skipped with a warning.
+pub fn build_rpc_client_with_headers(
+    url: &str,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+    headers: &HashMap<String, String>
+) -> RpcClient {
+    let http_sender = if headers.is_empty() {
+        HttpSender::new_with_timeout(url, timeout)
+    } else {
+        // Same client settings as HttpSender::new_with_timeout, plus the extra headers
+        let client = reqwest::Client
+            ::builder()
+            .default_headers(header_map(headers))
+            .timeout(timeout)
+            .pool_idle_timeout(timeout)
+            .build()
+            .expect("build rpc client");
+        HttpSender::new_with_client(url, client)
+    };
+
+    let sender = RetryingSender::new(http_sender, DEFAULT_RPC_RETRIES, DEFAULT_RPC_RETRY_DELAY);
+    RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment))
+}
+
+/// Solana cluster whose public endpoints `--cluster` selects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+    /// A `solana-test-validator` on this machine
+    Localnet,
+}
+
+impl FromStr for Cluster {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            "localnet" => Ok(Cluster::Localnet),
+            other =>
+                Err(
+                    format!(
+                        "Unknown cluster '{}', expected 'mainnet', 'devnet', 'testnet' or 'localnet'",
+                        other
+                    )
+                ),
+        }
+    }
+}
+
+/// Parse an RPC header given as `Name: value`
+pub fn parse_rpc_header(value: &str) -> Result<(String, String), String> {
+    let (name, header_value) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid RPC header '{}', expected 'Name: value'", value))?;
+    let (name, header_value) = (name.trim(), header_value.trim());
+
+    HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("Invalid RPC header name '{}'", name))?;
+    HeaderValue::from_str(header_value).map_err(|_| format!("Invalid value for RPC header '{}'", name))?;
+
+    Ok((name.to_string(), header_value.to_string()))
+}
+
+// Default solana-client headers plus the configured ones
+fn header_map(headers: &HashMap<String, String>) -> HeaderMap {
+    let mut map = HttpSender::default_headers();
+    for (name, value) in headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                map.insert(name, value);
+            }
+            _ => {
+                // The value is likely a credential, so only the name is logged
+                log::warn!("Ignoring invalid RPC header '{}'", name);
+            }
+        }
+    }
+    map
+}
+
+/// Check whether a client error is worth retrying (timeouts, dropped connections, 5xx)
+///
+/// Rate limiting (429) is already retried by the HTTP sender itself.
+pub fn is_transient_client_error(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) =>
+            e.is_timeout() ||
+                e.is_connect() ||
+                e.status().is_some_and(|status| status.is_server_error()),
+        _ => false,
+    }
+}
+
+/// RPC transport that retries transient failures of an inner transport
+pub struct RetryingSender<S> {
+    inner: S,
+    max_retries: usize,
+    retry_delay: Duration,
+}
+
+impl<S: RpcSender> RetryingSender<S> {
+    pub fn new(inner: S, max_retries: usize, retry_delay: Duration) -> Self {
+        Self {
+            inner,
+            max_retries,
+            retry_delay,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RpcSender + Send + Sync> RpcSender for RetryingSender<S> {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        let mut attempt = 0;
+        loop {
+            match self.inner.send(request, params.clone()).await {
+                Ok(value) => {
+                    return Ok(value);
+                }
+                Err(e) if attempt < self.max_retries && is_transient_client_error(&e) => {
+                    let delay = self.retry_delay * (1 << attempt);
+                    attempt += 1;
+                    log::debug!(
+                        "RPC {} failed ({}), retry {}/{} in {:?}",
+                        request,
+                        e,
+                        attempt,
+                        self.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
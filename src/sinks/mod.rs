@@ -0,0 +1,23 @@
+pub mod stdout;
+pub mod webhook;
+
+pub use stdout::*;
+pub use webhook::*;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::events::IndexedEvent;
+
+/// Destination that indexed events are written to alongside the database
+///
+/// `write` is called from the event handling path, so implementations should
+/// hand the event off quickly (e.g. to a bounded queue) rather than do slow I/O.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Name used in logs and metrics
+    fn name(&self) -> &str;
+
+    /// Write a single event
+    async fn write(&self, event: &IndexedEvent) -> Result<()>;
+}
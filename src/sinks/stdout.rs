@@ -0,0 +1,59 @@
+use anyhow::{ Context, Result };
+use async_trait::async_trait;
+use std::io::Write;
+use std::sync::Mutex;
+
+use crate::events::IndexedEvent;
+use crate::metrics;
+
+use super::EventSink;
+
+/// Event sink that writes each event as one line of JSON (NDJSON)
+///
+/// Writes to stdout by default. Logs go to stdout too unless the logger is
+/// pointed elsewhere, so the CLI moves logging to stderr when this sink is enabled.
+pub struct StdoutSink {
+    name: String,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Default for StdoutSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self::with_writer(Box::new(std::io::stdout()))
+    }
+
+    /// Write events to the given writer instead of stdout
+    pub fn with_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            name: "stdout".to_string(),
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for StdoutSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn write(&self, event: &IndexedEvent) -> Result<()> {
+        let line = serde_json::to_string(event).context("Failed to serialize event")?;
+
+        // Write the line and newline in one call so lines from concurrent writers never interleave
+        let mut writer = self.writer.lock().unwrap();
+        writer
+            .write_all(format!("{}\n", line).as_bytes())
+            .and_then(|_| writer.flush())
+            .context("Failed to write event to stdout")?;
+
+        metrics::record_sink_event_delivered(&self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{ Context, Result };
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc::{ self, error::TrySendError };
+
+use crate::backfill_manager::RetryConfig;
+use crate::events::IndexedEvent;
+use crate::metrics;
+use crate::utils::logging;
+
+use super::EventSink;
+
+/// Default number of events queued for delivery before new ones are dropped
+pub const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 1000;
+
+/// Default timeout for each webhook request
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration for a webhook sink
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// URL each event is POSTed to as JSON
+    pub url: String,
+    /// Events queued for delivery before new ones are dropped
+    pub queue_capacity: usize,
+    /// Timeout for each request
+    pub timeout: Duration,
+    /// Retry settings for failed deliveries
+    pub retry: RetryConfig,
+}
+
+impl WebhookConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            queue_capacity: DEFAULT_WEBHOOK_QUEUE_CAPACITY,
+            timeout: DEFAULT_WEBHOOK_TIMEOUT,
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+/// Event sink that POSTs each event as JSON to an HTTP endpoint
+///
+/// Events are queued and delivered in order by a background task, so a slow
+/// endpoint never blocks the WebSocket loop. When the queue is full new events
+/// are dropped and counted in `sink_events_dropped_total{reason="queue_full"}`;
+/// events that still fail after all retries are counted with `reason="failed"`.
+pub struct WebhookSink {
+    name: String,
+    sender: mpsc::Sender<String>,
+}
+
+impl WebhookSink {
+    /// Create the sink and start its delivery task (requires a Tokio runtime)
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        let client = reqwest::Client
+            ::builder()
+            .timeout(config.timeout)
+            .build()
+            .context("Failed to create webhook HTTP client")?;
+
+        let name = "webhook".to_string();
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        tokio::spawn(deliver_events(name.clone(), client, config, receiver));
+
+        Ok(Self { name, sender })
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn write(&self, event: &IndexedEvent) -> Result<()> {
+        let body = serde_json::to_string(event).context("Failed to serialize event")?;
+
+        match self.sender.try_send(body) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                metrics::record_sink_event_dropped(&self.name, "queue_full");
+                log::warn!(
+                    "[{}] Queue full, dropping {} event for {}",
+                    self.name,
+                    event.event_type(),
+                    event.source().signature
+                );
+                Ok(())
+            }
+            Err(TrySendError::Closed(_)) => {
+                Err(anyhow::anyhow!("Webhook delivery task has stopped"))
+            }
+        }
+    }
+}
+
+// Deliver queued events one at a time until the sink is dropped
+async fn deliver_events(
+    name: String,
+    client: reqwest::Client,
+    config: WebhookConfig,
+    mut receiver: mpsc::Receiver<String>
+) {
+    while let Some(body) = receiver.recv().await {
+        match post_with_retry(&client, &config, body).await {
+            Ok(()) => metrics::record_sink_event_delivered(&name),
+            Err(e) => {
+                metrics::record_sink_event_dropped(&name, "failed");
+                logging::log_error(&name, "Failed to deliver event", &e);
+            }
+        }
+    }
+}
+
+// POST an event, retrying connection errors, timeouts, 429s and 5xx responses
+async fn post_with_retry(client: &reqwest::Client, config: &WebhookConfig, body: String) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        let result = client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send().await;
+
+        let retryable = match &result {
+            Ok(response) if response.status().is_success() => {
+                return Ok(());
+            }
+            Ok(response) => {
+                response.status().is_server_error() ||
+                    response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(_) => true,
+        };
+
+        if !retryable || attempt >= config.retry.max_attempts {
+            return match result {
+                Ok(response) =>
+                    Err(
+                        anyhow::anyhow!(
+                            "Webhook {} returned {} after {} attempts",
+                            config.url,
+                            response.status(),
+                            attempt
+                        )
+                    ),
+                Err(e) =>
+                    Err(e).with_context(||
+                        format!("Webhook {} failed after {} attempts", config.url, attempt)
+                    ),
+            };
+        }
+
+        tokio::time::sleep(config.retry.delay_for_retry(attempt)).await;
+        attempt += 1;
+    }
+}
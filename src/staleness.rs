@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+use std::time::{ Duration, Instant };
+
+/// Shortest time without messages before the stream is considered stale
+pub const DEFAULT_MIN_STALE_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// Longest time without messages before the stream is considered stale
+pub const DEFAULT_MAX_STALE_THRESHOLD: Duration = Duration::from_secs(1800);
+
+/// How many median gaps may pass without messages before the stream is considered stale
+pub const DEFAULT_STALE_GAP_MULTIPLIER: f64 = 5.0;
+
+/// Gaps observed before the threshold adapts to them
+const MIN_GAP_SAMPLES: usize = 5;
+
+/// Number of recent gaps the median is taken over
+const GAP_WINDOW: usize = 100;
+
+/// Bounds for the adaptive staleness threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StalenessConfig {
+    /// Threshold used until enough gaps are observed, and its lower bound after
+    pub min_threshold: Duration,
+    /// Upper bound on the threshold, however quiet the stream is
+    pub max_threshold: Duration,
+    /// Threshold as a multiple of the median gap between messages
+    pub gap_multiplier: f64,
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            min_threshold: DEFAULT_MIN_STALE_THRESHOLD,
+            max_threshold: DEFAULT_MAX_STALE_THRESHOLD,
+            gap_multiplier: DEFAULT_STALE_GAP_MULTIPLIER,
+        }
+    }
+}
+
+/// Decides when a quiet subscription stream is stale enough to warrant a backfill
+///
+/// A fixed threshold either fires constantly for quiet pools or reacts slowly for
+/// busy ones. The threshold here is a multiple of the median gap between recent
+/// messages, kept between the configured minimum and maximum.
+#[derive(Debug)]
+pub struct StalenessDetector {
+    config: StalenessConfig,
+    last_message: Option<Instant>,
+    gaps: VecDeque<Duration>,
+}
+
+impl StalenessDetector {
+    pub fn new(config: StalenessConfig) -> Self {
+        Self {
+            config,
+            last_message: None,
+            gaps: VecDeque::with_capacity(GAP_WINDOW),
+        }
+    }
+
+    /// Record a message received at the given instant
+    pub fn record_message(&mut self, at: Instant) {
+        if let Some(last) = self.last_message {
+            if self.gaps.len() == GAP_WINDOW {
+                self.gaps.pop_front();
+            }
+            self.gaps.push_back(at.saturating_duration_since(last));
+        }
+        self.last_message = Some(at);
+    }
+
+    /// Median gap between recent messages, once enough have been observed
+    pub fn median_gap(&self) -> Option<Duration> {
+        if self.gaps.len() < MIN_GAP_SAMPLES {
+            return None;
+        }
+        let mut gaps: Vec<Duration> = self.gaps.iter().copied().collect();
+        gaps.sort();
+        Some(gaps[gaps.len() / 2])
+    }
+
+    /// Time without messages after which the stream is considered stale
+    pub fn threshold(&self) -> Duration {
+        let min = self.config.min_threshold;
+        let max = self.config.max_threshold.max(min);
+        match self.median_gap() {
+            Some(gap) => gap.mul_f64(self.config.gap_multiplier.max(0.0)).clamp(min, max),
+            None => min,
+        }
+    }
+
+    /// Whether the given time without messages indicates a stalled stream
+    pub fn is_stale(&self, elapsed: Duration) -> bool {
+        elapsed > self.threshold()
+    }
+}
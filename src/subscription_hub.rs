@@ -0,0 +1,149 @@
+use anyhow::Result;
+use solana_client::{
+    rpc_config::RpcTransactionLogsFilter,
+    rpc_response::{ Response, RpcLogsResponse },
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::utils::logging;
+use crate::websocket_manager::{ WebSocketConfig, WebSocketManager };
+
+/// Default number of logs buffered for each registered indexer
+pub const DEFAULT_HUB_CHANNEL_CAPACITY: usize = 1000;
+
+/// An indexer registered with the hub
+struct Subscriber {
+    name: String,
+    sender: mpsc::Sender<Response<RpcLogsResponse>>,
+}
+
+/// Shares log subscriptions between indexers that monitor the same programs
+///
+/// Each program is subscribed to once, however many indexers register for it,
+/// and every log received for a program is fanned out to each indexer registered
+/// for that program. An indexer registered for several programs gets a log once
+/// per matching subscription.
+pub struct SubscriptionHub {
+    subscribers: BTreeMap<String, Vec<Subscriber>>,
+    channel_capacity: usize,
+}
+
+impl Default for SubscriptionHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscriptionHub {
+    pub fn new() -> Self {
+        Self {
+            subscribers: BTreeMap::new(),
+            channel_capacity: DEFAULT_HUB_CHANNEL_CAPACITY,
+        }
+    }
+
+    /// Number of logs buffered for each indexer before the fan-out waits on it
+    pub fn with_channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity.max(1);
+        self
+    }
+
+    /// Register an indexer for its programs, returning the channel its logs arrive on
+    pub fn register(
+        &mut self,
+        indexer: &str,
+        program_ids: &[&str]
+    ) -> mpsc::Receiver<Response<RpcLogsResponse>> {
+        let (sender, receiver) = mpsc::channel(self.channel_capacity);
+
+        for program_id in program_ids {
+            let subscribers = self.subscribers.entry(program_id.to_string()).or_default();
+            if !subscribers.is_empty() {
+                log::debug!(
+                    "[{}] Sharing the {} subscription with {} other indexer(s)",
+                    indexer,
+                    program_id,
+                    subscribers.len()
+                );
+            }
+            subscribers.push(Subscriber {
+                name: indexer.to_string(),
+                sender: sender.clone(),
+            });
+        }
+
+        receiver
+    }
+
+    /// Programs that need a subscription, each listed once
+    pub fn program_ids(&self) -> Vec<&str> {
+        self.subscribers.keys().map(String::as_str).collect()
+    }
+
+    /// Names of the indexers registered for a program
+    pub fn subscribers(&self, program_id: &str) -> Vec<&str> {
+        self.subscribers
+            .get(program_id)
+            .map(|subscribers| subscribers.iter().map(|s| s.name.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Send a log received for a program to every indexer registered for it
+    ///
+    /// Returns the number of indexers the log was delivered to. Indexers that
+    /// have stopped (dropped their receiver) are skipped.
+    pub async fn fan_out(&self, program_id: &str, response: Response<RpcLogsResponse>) -> usize {
+        let Some(subscribers) = self.subscribers.get(program_id) else {
+            return 0;
+        };
+
+        let mut delivered = 0;
+        for subscriber in subscribers {
+            if subscriber.sender.send(response.clone()).await.is_ok() {
+                delivered += 1;
+            } else {
+                log::debug!(
+                    "[{}] Indexer stopped, not forwarding {} log",
+                    subscriber.name,
+                    program_id
+                );
+            }
+        }
+        delivered
+    }
+
+    /// Start one WebSocket subscription per program and fan logs out as they arrive
+    ///
+    /// `config` is used for every subscription, with its filter replaced by the
+    /// program. The returned managers can be used to monitor or stop the subscriptions.
+    pub async fn start(self: Arc<Self>, config: &WebSocketConfig) -> Result<Vec<WebSocketManager>> {
+        let mut managers = Vec::new();
+
+        for program_id in self.program_ids() {
+            let mut program_config = config.clone();
+            program_config.filter = RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]);
+
+            let manager = WebSocketManager::new(program_config);
+            let mut receiver = manager.start_subscription().await?;
+            logging::log_activity(
+                "websocket",
+                "Shared subscription",
+                Some(&format!("{} for {}", program_id, self.subscribers(program_id).join(", ")))
+            );
+
+            let hub = self.clone();
+            let program_id = program_id.to_string();
+            tokio::spawn(async move {
+                while let Some(response) = receiver.recv().await {
+                    hub.fan_out(&program_id, response).await;
+                }
+            });
+
+            managers.push(manager);
+        }
+
+        Ok(managers)
+    }
+}
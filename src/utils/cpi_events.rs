@@ -0,0 +1,118 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use solana_sdk::bs58;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer,
+    EncodedTransaction,
+    EncodedTransactionWithStatusMeta,
+    UiInstruction,
+    UiMessage,
+    UiParsedInstruction,
+};
+
+/// Prefix of the instruction data Anchor programs emit events with through a self-CPI (`emit_cpi!`)
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// Event data emitted through CPI by the given programs, in instruction order
+///
+/// Events emitted with `emit_cpi!` never appear in the logs, and logged events
+/// can be cut off when the log list is truncated (common for aggregator routes).
+/// Both are recovered here from the transaction's inner instructions. Each entry
+/// is the event's discriminator followed by its payload, as in a `Program data:` line.
+pub fn cpi_event_data(tx: &EncodedTransactionWithStatusMeta, program_ids: &[&str]) -> Vec<Vec<u8>> {
+    let Some(meta) = &tx.meta else {
+        return Vec::new();
+    };
+    let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions else {
+        return Vec::new();
+    };
+
+    let account_keys = account_keys(tx);
+    let mut events = Vec::new();
+
+    for instruction in inner_instructions.iter().flat_map(|inner| &inner.instructions) {
+        // Resolve the invoked program and the instruction's base58 data
+        let (program_id, data) = match instruction {
+            UiInstruction::Compiled(compiled) => {
+                match account_keys.get(compiled.program_id_index as usize) {
+                    Some(program_id) => (program_id.as_str(), &compiled.data),
+                    None => {
+                        continue;
+                    }
+                }
+            }
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) =>
+                (decoded.program_id.as_str(), &decoded.data),
+            // Fully parsed instructions belong to programs the RPC node knows (system, token, ...)
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => {
+                continue;
+            }
+        };
+
+        if !program_ids.contains(&program_id) {
+            continue;
+        }
+
+        match bs58::decode(data).into_vec() {
+            Ok(bytes) if bytes.len() > EVENT_IX_TAG.len() && bytes.starts_with(&EVENT_IX_TAG) => {
+                events.push(bytes[EVENT_IX_TAG.len()..].to_vec());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::debug!("Failed to decode inner instruction data for {}: {}", program_id, e);
+            }
+        }
+    }
+
+    events
+}
+
+/// Append a `Program data:` line for each event missing from the logs
+///
+/// Events that were also logged are left alone, so nothing is parsed twice.
+pub fn with_cpi_event_lines(mut logs: Vec<String>, event_data: &[Vec<u8>]) -> Vec<String> {
+    for data in event_data {
+        let line = format!("Program data: {}", general_purpose::STANDARD.encode(data));
+        if !logs.contains(&line) {
+            logs.push(line);
+        }
+    }
+    logs
+}
+
+// Account keys compiled instructions index into, including lookup table addresses
+/// Fee payer of a fetched transaction, the first of its account keys
+///
+/// None for binary-encoded transactions, whose account keys aren't decoded here.
+pub fn fee_payer(tx: &EncodedTransactionWithStatusMeta) -> Option<Pubkey> {
+    account_keys(tx)
+        .first()
+        .and_then(|key| Pubkey::from_str(key).ok())
+}
+
+fn account_keys(tx: &EncodedTransactionWithStatusMeta) -> Vec<String> {
+    let EncodedTransaction::Json(transaction) = &tx.transaction else {
+        return Vec::new();
+    };
+
+    match &transaction.message {
+        UiMessage::Raw(message) => {
+            let mut keys = message.account_keys.clone();
+            if let Some(meta) = &tx.meta {
+                if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+                    keys.extend(loaded.writable.iter().cloned());
+                    keys.extend(loaded.readonly.iter().cloned());
+                }
+            }
+            keys
+        }
+        // Parsed messages already list lookup table addresses
+        UiMessage::Parsed(message) =>
+            message.account_keys
+                .iter()
+                .map(|account| account.pubkey.clone())
+                .collect(),
+    }
+}
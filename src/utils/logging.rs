@@ -1,18 +1,132 @@
 use chrono;
 use std::fmt::Debug;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::atomic::{ AtomicU8, Ordering };
+
+/// Output format for activity and error logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines: [timestamp] component - message: details
+    Text,
+    /// One JSON object per line: {ts, level, component, dex, message, details}
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("Unknown log format '{}', expected 'text' or 'json'", other)),
+        }
+    }
+}
+
+// Target for records that the functions below have already formatted; filter it
+// with e.g. RUST_LOG=indexer::activity=warn
+const ACTIVITY_TARGET: &str = "indexer::activity";
+
+// Current log format, stored as a u8 so it can be switched at startup without locking
+static LOG_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Set the log format used by all logging functions
+pub fn set_log_format(format: LogFormat) {
+    let value = match format {
+        LogFormat::Text => 0,
+        LogFormat::Json => 1,
+    };
+    LOG_FORMAT.store(value, Ordering::Relaxed);
+}
+
+/// Get the current log format (Text unless changed with `set_log_format`)
+pub fn log_format() -> LogFormat {
+    match LOG_FORMAT.load(Ordering::Relaxed) {
+        1 => LogFormat::Json,
+        _ => LogFormat::Text,
+    }
+}
+
+/// Build a JSON log line: {ts, level, component, dex, message, details}
+pub fn format_json(
+    level: &str,
+    component: &str,
+    dex: Option<&str>,
+    message: &str,
+    details: Option<&str>
+) -> String {
+    serde_json::json!({
+        "ts": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "level": level,
+        "component": component,
+        "dex": dex,
+        "message": message,
+        "details": details,
+    }).to_string()
+}
+
+/// Initialize the global logger
+///
+/// Verbosity is controlled at runtime with `RUST_LOG`, falling back to
+/// `default_filter` (e.g. "info"). Records are written to stdout in the
+/// `[timestamp] ...` text format, or as one JSON object per line in Json mode.
+pub fn init_logger(default_filter: &str) {
+    init_logger_with_target(default_filter, env_logger::Target::Stdout);
+}
+
+/// Initialize the global logger, writing records to the given target
+///
+/// Used to move logs to stderr when stdout carries event output.
+pub fn init_logger_with_target(default_filter: &str, target: env_logger::Target) {
+    let env = env_logger::Env::default().default_filter_or(default_filter);
+    // Ignore the error if a logger was already installed
+    let _ = env_logger::Builder::from_env(env).format(format_record).target(target).try_init();
+}
+
+// Activity records are already formatted by the functions below; records from
+// plain `log::` macros are wrapped in the same format here
+fn format_record(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    let preformatted = record.target() == ACTIVITY_TARGET;
+
+    match (log_format(), preformatted) {
+        (LogFormat::Json, true) => writeln!(buf, "{}", record.args()),
+        (LogFormat::Json, false) => {
+            let level = record.level().as_str().to_ascii_lowercase();
+            let message = record.args().to_string();
+            writeln!(buf, "{}", format_json(&level, record.target(), None, &message, None))
+        }
+        (LogFormat::Text, _) => {
+            let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            writeln!(buf, "[{}] {}", timestamp, record.args())
+        }
+    }
+}
 
 /// Standard format for activity logs: [timestamp] component - message: details
 pub fn log_activity(component: &str, message: &str, details: Option<&str>) {
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    if log_format() == LogFormat::Json {
+        let line = format_json("info", component, None, message, details);
+        log::info!(target: ACTIVITY_TARGET, "{}", line);
+        return;
+    }
+
     let details_str = details.unwrap_or("");
-    println!("[{}] {} - {}: {}", timestamp, component, message, details_str);
+    log::info!(target: ACTIVITY_TARGET, "{} - {}: {}", component, message, details_str);
 }
 
 /// Standard format for error logs: [timestamp] component - ERROR: message
 pub fn log_error(component: &str, context: &str, err: &anyhow::Error) {
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    if log_format() == LogFormat::Json {
+        let err_str = err.to_string();
+        let line = format_json("error", component, None, context, Some(&err_str));
+        log::error!(target: ACTIVITY_TARGET, "{}", line);
+        return;
+    }
+
     let error_message = format!("ERROR - {}: {}", context, err);
-    eprintln!("[{}] {} - {}", timestamp, component, error_message);
+    log::error!(target: ACTIVITY_TARGET, "{} - {}", component, error_message);
 }
 
 /// Log statistics with standard format
@@ -29,16 +143,27 @@ pub fn log_debug<T: Debug>(component: &str, context: &str, details: &T) {
 
 /// Enhanced format for activity logs with DEX name: [timestamp] component (dex) - message: details
 pub fn log_dex_activity(component: &str, dex: &str, message: &str, details: Option<&str>) {
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    if log_format() == LogFormat::Json {
+        let line = format_json("info", component, Some(dex), message, details);
+        log::info!(target: ACTIVITY_TARGET, "{}", line);
+        return;
+    }
+
     let details_str = details.unwrap_or("");
-    println!("[{}] {} ({}) - {}: {}", timestamp, component, dex, message, details_str);
+    log::info!(target: ACTIVITY_TARGET, "{} ({}) - {}: {}", component, dex, message, details_str);
 }
 
 /// Enhanced format for error logs with DEX name: [timestamp] component (dex) - ERROR: message
 pub fn log_dex_error(component: &str, dex: &str, context: &str, err: &anyhow::Error) {
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    if log_format() == LogFormat::Json {
+        let err_str = err.to_string();
+        let line = format_json("error", component, Some(dex), context, Some(&err_str));
+        log::error!(target: ACTIVITY_TARGET, "{}", line);
+        return;
+    }
+
     let error_message = format!("ERROR - {}: {}", context, err);
-    eprintln!("[{}] {} ({}) - {}", timestamp, component, dex, error_message);
+    log::error!(target: ACTIVITY_TARGET, "{} ({}) - {}", component, dex, error_message);
 }
 
 /// Enhanced format for statistics logs with DEX name: [timestamp] component (dex) - context: stats
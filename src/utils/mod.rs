@@ -1 +1,3 @@
+pub mod cpi_events;
 pub mod logging;
+pub mod price;
@@ -0,0 +1,43 @@
+use anyhow::{ anyhow, Result };
+use rust_decimal::Decimal;
+
+/// Convert a Whirlpool Q64.64 sqrt price into the price of token A in token B
+///
+/// The raw price is `(sqrt_price / 2^64)^2` in base units, and is scaled by
+/// `10^(decimals_a - decimals_b)` to get a price in whole tokens.
+pub fn sqrt_price_to_price(sqrt_price: u128, decimals_a: u8, decimals_b: u8) -> f64 {
+    let sqrt_price = (sqrt_price as f64) / 2f64.powi(64);
+    sqrt_price * sqrt_price * 10f64.powi((decimals_a as i32) - (decimals_b as i32))
+}
+
+/// Exact counterpart of `sqrt_price_to_price`, returning the price as a `Decimal`
+///
+/// Computes `(sqrt_price / 2^64)^2 * 10^(decimals_a - decimals_b)` in decimal
+/// arithmetic, so prices keep about 28 significant digits instead of an `f64`'s
+/// 15. Fails when the price doesn't fit a `Decimal`, which only happens near the
+/// ends of the Whirlpool price range with very different token decimals.
+pub fn price_from_sqrt_price(sqrt_price: u128, decimals_a: u8, decimals_b: u8) -> Result<Decimal> {
+    let overflow = || anyhow!("Price of sqrt price {} overflows a Decimal", sqrt_price);
+
+    let sqrt_price = i128
+        ::try_from(sqrt_price)
+        .ok()
+        .and_then(|sqrt_price| Decimal::try_from_i128_with_scale(sqrt_price, 0).ok())
+        .ok_or_else(overflow)?;
+    let q64 = Decimal::from_i128_with_scale(1 << 64, 0);
+    let ratio = sqrt_price.checked_div(q64).ok_or_else(overflow)?;
+    let price = ratio.checked_mul(ratio).ok_or_else(overflow)?;
+
+    // Shift by the decimals difference one power of ten at a time, as it can exceed Decimal's scale
+    let shift = (decimals_a as i32) - (decimals_b as i32);
+    (0..shift.unsigned_abs())
+        .try_fold(price, |price, _| {
+            if shift > 0 { price.checked_mul(Decimal::TEN) } else { price.checked_div(Decimal::TEN) }
+        })
+        .ok_or_else(overflow)
+}
+
+/// Convert a raw token amount in base units into whole tokens
+pub fn ui_amount(amount: u64, decimals: u8) -> f64 {
+    (amount as f64) / 10f64.powi(decimals as i32)
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::orca::whirlpool::{
+    OrcaWhirlpoolLiquidityDecreasedEvent,
+    OrcaWhirlpoolLiquidityIncreasedEvent,
+    OrcaWhirlpoolPoolInitializedEvent,
+    OrcaWhirlpoolTradedEvent,
+};
+
+/// Lowest sqrt price a Whirlpool can reach, as Q64.64
+pub const MIN_SQRT_PRICE: u128 = 4295048016;
+/// Highest sqrt price a Whirlpool can reach, as Q64.64
+pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
+/// Highest tick index a Whirlpool position can use; the lowest is its negative
+pub const MAX_TICK_INDEX: i32 = 443636;
+
+/// How strictly parsed events are checked before they are stored
+///
+/// Events failing a check are written to the `quarantine_events` table instead
+/// of the event tables, and aren't published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Store every parsed event
+    #[default]
+    Off,
+    /// Reject events that break the program's invariants, such as fees larger
+    /// than the amount they were taken from or prices out of range
+    Basic,
+    /// Also reject unusual but possible events: zero output trades, prices moving
+    /// against the trade, empty liquidity changes and trades over
+    /// `--max-plausible-trade-amount`
+    Strict,
+}
+
+impl FromStr for ValidationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(ValidationMode::Off),
+            "basic" => Ok(ValidationMode::Basic),
+            "strict" => Ok(ValidationMode::Strict),
+            other =>
+                Err(
+                    format!(
+                        "Unknown event validation mode '{}', expected 'off', 'basic' or 'strict'",
+                        other
+                    )
+                ),
+        }
+    }
+}
+
+/// A check an event failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Name of the check, used as the `check` label of the quarantine metric
+    pub check: &'static str,
+    pub detail: String,
+}
+
+impl Violation {
+    pub fn new(check: &'static str, detail: String) -> Self {
+        Self { check, detail }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.detail)
+    }
+}
+
+/// Check a trade's amounts and prices
+///
+/// `scaled_amounts` are the input and output amounts in whole tokens, when the
+/// pool's decimals are known, and `max_amount` the plausible limit for them.
+pub fn validate_orca_trade(
+    event: &OrcaWhirlpoolTradedEvent,
+    mode: ValidationMode,
+    scaled_amounts: Option<(f64, f64)>,
+    max_amount: Option<f64>
+) -> Result<(), Violation> {
+    if mode == ValidationMode::Off {
+        return Ok(());
+    }
+
+    if event.input_amount == 0 {
+        return Err(Violation::new("zero_amount", "trade has no input amount".to_string()));
+    }
+    check_fee("input transfer fee", event.input_transfer_fee, event.input_amount)?;
+    check_fee("output transfer fee", event.output_transfer_fee, event.output_amount)?;
+    let fees = u128::from(event.lp_fee) + u128::from(event.protocol_fee);
+    if fees > u128::from(event.input_amount) {
+        return Err(
+            Violation::new(
+                "fee_exceeds_amount",
+                format!(
+                    "LP and protocol fees {} exceed input amount {}",
+                    fees,
+                    event.input_amount
+                )
+            )
+        );
+    }
+    check_sqrt_price("pre", event.pre_sqrt_price)?;
+    check_sqrt_price("post", event.post_sqrt_price)?;
+
+    if mode == ValidationMode::Strict {
+        if event.output_amount == 0 {
+            return Err(Violation::new("zero_amount", "trade has no output amount".to_string()));
+        }
+        // Selling token A pushes the price down, selling token B pushes it up
+        let against_trade = if event.a_to_b {
+            event.post_sqrt_price > event.pre_sqrt_price
+        } else {
+            event.post_sqrt_price < event.pre_sqrt_price
+        };
+        if against_trade {
+            return Err(
+                Violation::new(
+                    "price_direction",
+                    format!(
+                        "sqrt price moved from {} to {} on a trade with a_to_b={}",
+                        event.pre_sqrt_price,
+                        event.post_sqrt_price,
+                        event.a_to_b
+                    )
+                )
+            );
+        }
+        if let (Some((input, output)), Some(max_amount)) = (scaled_amounts, max_amount) {
+            if input > max_amount || output > max_amount {
+                return Err(
+                    Violation::new(
+                        "implausible_amount",
+                        format!(
+                            "{} in / {} out tokens exceeds the plausible amount {}",
+                            input,
+                            output,
+                            max_amount
+                        )
+                    )
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a liquidity change's position range, liquidity and amounts
+pub fn validate_orca_liquidity(
+    event: &LiquidityChange,
+    mode: ValidationMode
+) -> Result<(), Violation> {
+    if mode == ValidationMode::Off {
+        return Ok(());
+    }
+
+    let (tick_lower, tick_upper) = event.ticks;
+    if tick_lower >= tick_upper {
+        return Err(
+            Violation::new(
+                "tick_range",
+                format!("lower tick {} isn't below upper tick {}", tick_lower, tick_upper)
+            )
+        );
+    }
+    if tick_lower < -MAX_TICK_INDEX || tick_upper > MAX_TICK_INDEX {
+        return Err(
+            Violation::new(
+                "tick_range",
+                format!(
+                    "ticks {}..{} outside {}..{}",
+                    tick_lower,
+                    tick_upper,
+                    -MAX_TICK_INDEX,
+                    MAX_TICK_INDEX
+                )
+            )
+        );
+    }
+    // Liquidity is signed on chain, so larger values turn negative when stored
+    if event.liquidity > (i128::MAX as u128) {
+        return Err(
+            Violation::new(
+                "liquidity_range",
+                format!("liquidity {} overflows a signed 128-bit value", event.liquidity)
+            )
+        );
+    }
+    check_fee("token A transfer fee", event.transfer_fees.0, event.amounts.0)?;
+    check_fee("token B transfer fee", event.transfer_fees.1, event.amounts.1)?;
+
+    if mode == ValidationMode::Strict && event.liquidity == 0 {
+        return Err(Violation::new("zero_amount", "liquidity change of zero".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Check a new pool's tick spacing and initial price
+pub fn validate_orca_pool_initialized(
+    event: &OrcaWhirlpoolPoolInitializedEvent,
+    mode: ValidationMode
+) -> Result<(), Violation> {
+    if mode == ValidationMode::Off {
+        return Ok(());
+    }
+
+    if event.tick_spacing == 0 {
+        return Err(Violation::new("tick_range", "pool has a tick spacing of zero".to_string()));
+    }
+    check_sqrt_price("initial", event.initial_sqrt_price)
+}
+
+/// The fields checked on both kinds of Orca liquidity change
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidityChange {
+    pub ticks: (i32, i32),
+    pub liquidity: u128,
+    pub amounts: (u64, u64),
+    pub transfer_fees: (u64, u64),
+}
+
+impl From<&OrcaWhirlpoolLiquidityIncreasedEvent> for LiquidityChange {
+    fn from(event: &OrcaWhirlpoolLiquidityIncreasedEvent) -> Self {
+        Self {
+            ticks: (event.tick_lower_index, event.tick_upper_index),
+            liquidity: event.liquidity,
+            amounts: (event.token_a_amount, event.token_b_amount),
+            transfer_fees: (event.token_a_transfer_fee, event.token_b_transfer_fee),
+        }
+    }
+}
+
+impl From<&OrcaWhirlpoolLiquidityDecreasedEvent> for LiquidityChange {
+    fn from(event: &OrcaWhirlpoolLiquidityDecreasedEvent) -> Self {
+        Self {
+            ticks: (event.tick_lower_index, event.tick_upper_index),
+            liquidity: event.liquidity,
+            amounts: (event.token_a_amount, event.token_b_amount),
+            transfer_fees: (event.token_a_transfer_fee, event.token_b_transfer_fee),
+        }
+    }
+}
+
+fn check_fee(name: &str, fee: u64, amount: u64) -> Result<(), Violation> {
+    if fee > amount {
+        return Err(
+            Violation::new("fee_exceeds_amount", format!("{} {} exceeds amount {}", name, fee, amount))
+        );
+    }
+    Ok(())
+}
+
+fn check_sqrt_price(name: &str, sqrt_price: u128) -> Result<(), Violation> {
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(
+            Violation::new(
+                "price_range",
+                format!(
+                    "{} sqrt price {} outside {}..={}",
+                    name,
+                    sqrt_price,
+                    MIN_SQRT_PRICE,
+                    MAX_SQRT_PRICE
+                )
+            )
+        );
+    }
+    Ok(())
+}
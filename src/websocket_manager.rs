@@ -1,53 +1,281 @@
 use anyhow::Result;
-use futures::stream::StreamExt;
+use futures::future::{ BoxFuture, FutureExt };
+use futures::stream::{ BoxStream, StreamExt };
+use rand::Rng;
+use solana_account_decoder::{ UiAccount, UiAccountEncoding };
 use solana_client::{
     nonblocking::pubsub_client::PubsubClient,
-    rpc_config::RpcTransactionLogsConfig,
+    rpc_config::{ RpcAccountInfoConfig, RpcTransactionLogsConfig },
     rpc_config::RpcTransactionLogsFilter,
-    rpc_response::RpcLogsResponse,
+    rpc_response::{ Response, RpcLogsResponse },
 };
 use solana_sdk::commitment_config::CommitmentConfig;
-use std::sync::{ Arc, atomic::{ AtomicBool, Ordering } };
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::collections::{ HashSet, VecDeque };
+use std::sync::{ Arc, atomic::{ AtomicBool, AtomicUsize, Ordering } };
 use std::time::{ Duration, Instant };
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{ mpsc, watch };
 use tokio::time::sleep;
 
+use crate::health::HealthTracker;
+use crate::metrics;
 use crate::utils::logging;
 
+/// Default number of log notifications buffered between the WebSocket and the indexer
+pub const DEFAULT_WS_CHANNEL_CAPACITY: usize = 1000;
+
+/// What the WebSocket reader does when the indexer falls a full channel behind
+///
+/// Blocking loses nothing, but the socket isn't read while the indexer catches
+/// up, and nodes drop subscriptions whose client stops reading. Dropping keeps
+/// the socket drained at the cost of the dropped transactions, which are only
+/// recovered by the next backfill of their pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelOverflowPolicy {
+    /// Wait for room in the channel before reading the next notification
+    #[default]
+    Block,
+    /// Drop the notification and count it in `websocket_messages_dropped_total`
+    Drop,
+}
+
+impl FromStr for ChannelOverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(ChannelOverflowPolicy::Block),
+            "drop" => Ok(ChannelOverflowPolicy::Drop),
+            other => Err(format!("Unknown overflow policy '{}', expected 'block' or 'drop'", other)),
+        }
+    }
+}
+
+/// Number of recently forwarded signatures remembered to skip duplicates
+const RECENT_SIGNATURE_CAPACITY: usize = 10_000;
+
+/// A log subscription: which logs to receive and at what commitment level
+#[derive(Debug, Clone)]
+pub struct LogSubscription {
+    pub filter: RpcTransactionLogsFilter,
+    pub commitment: CommitmentConfig,
+}
+
+impl LogSubscription {
+    /// Short description of the filter for logging
+    pub fn describe(&self) -> String {
+        match &self.filter {
+            RpcTransactionLogsFilter::All => "all transactions".to_string(),
+            RpcTransactionLogsFilter::AllWithVotes => "all transactions and votes".to_string(),
+            RpcTransactionLogsFilter::Mentions(addresses) => addresses.join(", "),
+        }
+    }
+}
+
 /// Configuration for the WebSocket manager
 pub struct WebSocketConfig {
     /// WebSocket URL
     pub ws_url: String,
+    /// Additional WebSocket URLs to rotate through when a connection fails
+    pub fallback_ws_urls: Vec<String>,
     /// Custom filter for logs
     pub filter: RpcTransactionLogsFilter,
+    /// Independent log subscriptions, each on its own connection; when empty, a
+    /// single subscription to `filter` at `commitment`
+    pub subscriptions: Vec<LogSubscription>,
     /// Maximum number of reconnection attempts
     pub max_reconnect_attempts: u32,
     /// Initial reconnection delay in milliseconds
     pub reconnect_base_delay_ms: u64,
     /// Maximum reconnection delay in milliseconds
     pub reconnect_max_delay_ms: u64,
+    /// Fraction of each delay that may be randomly shaved off (0.0 disables jitter)
+    pub reconnect_jitter_ratio: f64,
     /// Log subscription commitment level
     pub commitment: CommitmentConfig,
+    /// Log notifications buffered while the indexer processes earlier ones
+    pub channel_capacity: usize,
+    /// What to do with notifications once the buffer is full
+    pub overflow_policy: ChannelOverflowPolicy,
 }
 
 impl Default for WebSocketConfig {
     fn default() -> Self {
         Self {
             ws_url: "wss://api.mainnet-beta.solana.com".to_string(),
+            fallback_ws_urls: Vec::new(),
             filter: RpcTransactionLogsFilter::All,
+            subscriptions: Vec::new(),
             max_reconnect_attempts: 0, // 0 means unlimited
             reconnect_base_delay_ms: 500,
             reconnect_max_delay_ms: 30000, // 30 seconds
+            reconnect_jitter_ratio: 0.2,
             commitment: CommitmentConfig::confirmed(),
+            channel_capacity: DEFAULT_WS_CHANNEL_CAPACITY,
+            overflow_policy: ChannelOverflowPolicy::default(),
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// The log subscriptions to open
+    pub fn log_subscriptions(&self) -> Vec<LogSubscription> {
+        if !self.subscriptions.is_empty() {
+            return self.subscriptions.clone();
+        }
+        vec![LogSubscription {
+            filter: self.filter.clone(),
+            commitment: self.commitment,
+        }]
+    }
+}
+
+/// Reconnection state for the WebSocket subscription
+///
+/// Tracks the attempt count, the exponential backoff delay (with jitter) and
+/// the rotation through the primary and fallback URLs.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    urls: Vec<String>,
+    next_url_index: usize,
+    last_url_index: usize,
+    base_delay_ms: u64,
+    max_delay_ms: u64,
+    jitter_ratio: f64,
+    max_attempts: u32,
+    attempts: u32,
+    current_delay_ms: u64,
+}
+
+impl ReconnectPolicy {
+    /// Create a policy from the WebSocket configuration
+    pub fn new(config: &WebSocketConfig) -> Self {
+        let mut urls = vec![config.ws_url.clone()];
+        urls.extend(config.fallback_ws_urls.iter().cloned());
+
+        Self {
+            urls,
+            next_url_index: 0,
+            last_url_index: 0,
+            base_delay_ms: config.reconnect_base_delay_ms,
+            max_delay_ms: config.reconnect_max_delay_ms,
+            jitter_ratio: config.reconnect_jitter_ratio.clamp(0.0, 1.0),
+            max_attempts: config.max_reconnect_attempts,
+            attempts: 0,
+            current_delay_ms: config.reconnect_base_delay_ms,
+        }
+    }
+
+    /// Return the URL to use for the next connection attempt and advance the rotation
+    pub fn next_url(&mut self) -> &str {
+        self.last_url_index = self.next_url_index;
+        self.next_url_index = (self.next_url_index + 1) % self.urls.len();
+        &self.urls[self.last_url_index]
+    }
+
+    /// Return the delay before the next attempt and advance the backoff
+    ///
+    /// The returned delay is the current backoff minus up to `jitter_ratio` of it,
+    /// so it never exceeds the configured maximum.
+    pub fn next_delay(&mut self) -> Duration {
+        self.attempts += 1;
+
+        let delay_ms = self.current_delay_ms;
+        let max_jitter_ms = ((delay_ms as f64) * self.jitter_ratio) as u64;
+        let jitter_ms = if max_jitter_ms > 0 {
+            rand::thread_rng().gen_range(0..=max_jitter_ms)
+        } else {
+            0
+        };
+
+        self.current_delay_ms = std::cmp::min(
+            self.current_delay_ms.saturating_mul(2),
+            self.max_delay_ms
+        );
+
+        Duration::from_millis(delay_ms - jitter_ms)
+    }
+
+    /// Reset the backoff after a successful connection
+    ///
+    /// The next call to `next_url` returns the URL that just connected.
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.current_delay_ms = self.base_delay_ms;
+        self.next_url_index = self.last_url_index;
+    }
+
+    /// Number of reconnection attempts since the last successful connection
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Whether the maximum number of attempts has been reached (never, if unlimited)
+    pub fn is_exhausted(&self) -> bool {
+        self.max_attempts > 0 && self.attempts >= self.max_attempts
+    }
+}
+
+/// Number of connected log subscriptions, which count as connected once all are
+struct ConnectionCount {
+    connected: AtomicUsize,
+    total: usize,
+}
+
+impl ConnectionCount {
+    fn new(total: usize) -> Self {
+        Self {
+            connected: AtomicUsize::new(0),
+            total,
+        }
+    }
+
+    /// Record a subscription connecting or disconnecting, returning whether all are connected
+    fn update(&self, connected: bool) -> bool {
+        if connected {
+            self.connected.fetch_add(1, Ordering::SeqCst) + 1 >= self.total
+        } else {
+            self.connected.fetch_sub(1, Ordering::SeqCst);
+            false
         }
     }
 }
 
+/// Signatures forwarded recently, so a transaction matching several log
+/// subscriptions is only forwarded once
+#[derive(Default)]
+struct RecentSignatures {
+    signatures: std::sync::Mutex<(HashSet<String>, VecDeque<String>)>,
+}
+
+impl RecentSignatures {
+    /// Remember a signature, returning false if it was already seen
+    fn insert(&self, signature: &str) -> bool {
+        let mut guard = self.signatures.lock().unwrap();
+        let (seen, order) = &mut *guard;
+        if !seen.insert(signature.to_string()) {
+            return false;
+        }
+        order.push_back(signature.to_string());
+        if order.len() > RECENT_SIGNATURE_CAPACITY {
+            if let Some(oldest) = order.pop_front() {
+                seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
 /// WebSocket connection manager for Solana
 pub struct WebSocketManager {
     config: WebSocketConfig,
     running: Arc<AtomicBool>,
     last_received: Arc<std::sync::Mutex<Option<Instant>>>,
+    connected_at: Arc<std::sync::Mutex<Option<Instant>>>,
+    health: Option<Arc<HealthTracker>>,
+    reconnects: Arc<watch::Sender<u64>>,
 }
 
 impl WebSocketManager {
@@ -57,59 +285,217 @@ impl WebSocketManager {
             config,
             running: Arc::new(AtomicBool::new(true)),
             last_received: Arc::new(std::sync::Mutex::new(None)),
+            connected_at: Arc::new(std::sync::Mutex::new(None)),
+            health: None,
+            reconnects: Arc::new(watch::channel(0).0),
         }
     }
 
+    /// Report the connection state and received messages to an indexer's health tracker
+    pub fn with_health_tracker(mut self, health: Arc<HealthTracker>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
     /// Start the WebSocket subscription with reconnection logic
-    pub async fn start_subscription(&self) -> Result<mpsc::Receiver<RpcLogsResponse>> {
-        // Create a channel for passing log responses
-        let (tx, rx) = mpsc::channel::<RpcLogsResponse>(1000);
+    ///
+    /// Log responses are forwarded with their notification context so consumers
+    /// know the slot each log was observed in. Each of the configured log
+    /// subscriptions runs on its own connection and reconnects on its own, so one
+    /// failing subscription doesn't interrupt the others. A transaction matching
+    /// several subscriptions is only forwarded once.
+    pub async fn start_subscription(
+        &self
+    ) -> Result<mpsc::Receiver<Response<RpcLogsResponse>>> {
+        let subscriptions = self.config.log_subscriptions();
+        let (tx, rx) = mpsc::channel(self.config.channel_capacity.max(1));
+        let connections = Arc::new(ConnectionCount::new(subscriptions.len()));
+        let recent = (subscriptions.len() > 1).then(|| Arc::new(RecentSignatures::default()));
+
+        for subscription in subscriptions {
+            let label = format!("logs mentioning {}", subscription.describe());
+            let recent = recent.clone();
+            let logs_config = RpcTransactionLogsConfig {
+                commitment: Some(subscription.commitment),
+            };
+            let filter = subscription.filter;
+
+            self.spawn_subscription(tx.clone(), label, Some(connections.clone()), move |client| {
+                let filter = filter.clone();
+                let logs_config = logs_config.clone();
+                let recent = recent.clone();
+                (
+                    async move {
+                        let (stream, _unsubscribe) = client.logs_subscribe(filter, logs_config).await?;
+                        Ok(
+                            stream
+                                .filter(move |response| {
+                                    let first = match &recent {
+                                        Some(recent) => recent.insert(&response.value.signature),
+                                        None => true,
+                                    };
+                                    futures::future::ready(first)
+                                })
+                                .boxed()
+                        )
+                    }
+                ).boxed()
+            });
+        }
+
+        Ok(rx)
+    }
+
+    /// Subscribe to changes of the given accounts, with the same reconnection logic
+    ///
+    /// Each change is forwarded with the account it belongs to. The accounts share
+    /// one connection, and don't affect the connection metrics, which track the
+    /// log subscription.
+    pub async fn start_account_subscription(
+        &self,
+        accounts: Vec<Pubkey>
+    ) -> Result<mpsc::Receiver<(Pubkey, Response<UiAccount>)>> {
+        let account_config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.config.commitment),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        let (tx, rx) = mpsc::channel(self.config.channel_capacity.max(1));
+        let label = format!("{} accounts", accounts.len());
+
+        self.spawn_subscription(tx, label, None, move |client| {
+            let accounts = accounts.clone();
+            let account_config = account_config.clone();
+            (
+                async move {
+                    let mut streams = Vec::with_capacity(accounts.len());
+                    for account in accounts {
+                        let (stream, _unsubscribe) = client.account_subscribe(
+                            &account,
+                            Some(account_config.clone())
+                        ).await?;
+                        streams.push(stream.map(move |response| (account, response)).boxed());
+                    }
+                    Ok(futures::stream::select_all(streams).boxed())
+                }
+            ).boxed()
+        });
 
+        Ok(rx)
+    }
+
+    /// Run a subscription in the background, reconnecting whenever it drops
+    ///
+    /// `subscribe` opens the subscription on a fresh connection. Notifications are
+    /// sent to `tx` under the configured overflow policy. Subscriptions counted in
+    /// `connections` report the connection state to the connection metrics, health
+    /// tracker and reconnect watchers.
+    fn spawn_subscription<T, F>(
+        &self,
+        tx: mpsc::Sender<T>,
+        label: String,
+        connections: Option<Arc<ConnectionCount>>,
+        subscribe: F
+    )
+        where
+            T: Send + 'static,
+            F: for<'c> Fn(&'c PubsubClient) -> BoxFuture<'c, Result<BoxStream<'c, T>>> +
+                Send +
+                'static
+    {
         // Clone values for the subscription task
         let running = self.running.clone();
         let config = self.config.clone();
         let last_received = self.last_received.clone();
+        let connected_at = self.connected_at.clone();
+        let health = if connections.is_some() { self.health.clone() } else { None };
+        let reconnects = self.reconnects.clone();
 
         // Start the subscription task
         tokio::spawn(async move {
-            let mut reconnect_attempts = 0;
-            let mut reconnect_delay = config.reconnect_base_delay_ms;
+            let mut policy = ReconnectPolicy::new(&config);
+            let mut connected_before = false;
+            // Notifications dropped since the channel last had room
+            let mut dropped = 0u64;
 
             // Continuously try to maintain the connection
             while running.load(Ordering::SeqCst) {
-                let pubsub_client_result = PubsubClient::new(&config.ws_url).await;
+                let ws_url = policy.next_url().to_string();
+                let pubsub_client_result = PubsubClient::new(&ws_url).await;
 
                 if let Ok(pubsub_client) = pubsub_client_result {
-                    // Subscribe to logs
-                    let subscription_result = pubsub_client.logs_subscribe(
-                        config.filter.clone(),
-                        RpcTransactionLogsConfig {
-                            commitment: Some(config.commitment),
-                        }
-                    ).await;
-
-                    match subscription_result {
-                        Ok((mut log_stream, _subscription_id)) => {
+                    match subscribe(&pubsub_client).await {
+                        Ok(mut stream) => {
                             logging::log_activity(
                                 "websocket",
                                 "Connection",
-                                Some("established successfully")
+                                Some(&format!("established successfully to {} ({})", ws_url, label))
                             );
 
                             // Reset reconnection counters upon successful connection
-                            reconnect_attempts = 0;
-                            reconnect_delay = config.reconnect_base_delay_ms;
+                            policy.reset();
+                            if let Some(connections) = &connections {
+                                let all_connected = connections.update(true);
+                                if all_connected {
+                                    *connected_at.lock().unwrap() = Some(Instant::now());
+                                }
+                                metrics::set_websocket_connected(all_connected);
+                                if let Some(health) = &health {
+                                    health.set_websocket_connected(all_connected);
+                                }
+                            }
+
+                            // Let watchers know notifications may have been missed while disconnected
+                            if connected_before && connections.is_some() {
+                                reconnects.send_modify(|count| {
+                                    *count += 1;
+                                });
+                            }
+                            connected_before = true;
 
-                            // Process incoming logs until disconnection
-                            while let Some(response) = log_stream.next().await {
+                            // Process incoming notifications until disconnection
+                            while let Some(response) = stream.next().await {
                                 // Update last received timestamp
                                 {
                                     let mut guard = last_received.lock().unwrap();
                                     *guard = Some(Instant::now());
                                 }
+                                if let Some(health) = &health {
+                                    health.record_message();
+                                }
 
                                 // Send to channel, break if channel is closed
-                                if tx.send(response.value).await.is_err() {
+                                let delivered = match config.overflow_policy {
+                                    ChannelOverflowPolicy::Block => tx.send(response).await.is_ok(),
+                                    ChannelOverflowPolicy::Drop =>
+                                        match tx.try_send(response) {
+                                            Ok(()) => {
+                                                if dropped > 0 {
+                                                    logging::log_activity(
+                                                        "websocket",
+                                                        "Channel drained",
+                                                        Some(&format!("{} notifications were dropped", dropped))
+                                                    );
+                                                    dropped = 0;
+                                                }
+                                                true
+                                            }
+                                            Err(TrySendError::Full(_)) => {
+                                                if dropped == 0 {
+                                                    log::warn!(
+                                                        "[websocket] Processing channel full ({} notifications), dropping notifications until it drains",
+                                                        config.channel_capacity
+                                                    );
+                                                }
+                                                dropped += 1;
+                                                metrics::record_websocket_message_dropped();
+                                                true
+                                            }
+                                            Err(TrySendError::Closed(_)) => false,
+                                        }
+                                };
+                                if !delivered {
                                     logging::log_activity(
                                         "websocket",
                                         "Channel closed",
@@ -119,6 +505,14 @@ impl WebSocketManager {
                                 }
                             }
 
+                            if let Some(connections) = &connections {
+                                connections.update(false);
+                                *connected_at.lock().unwrap() = None;
+                                metrics::set_websocket_connected(false);
+                                if let Some(health) = &health {
+                                    health.set_websocket_connected(false);
+                                }
+                            }
                             logging::log_activity(
                                 "websocket",
                                 "Connection dropped",
@@ -126,11 +520,7 @@ impl WebSocketManager {
                             );
                         }
                         Err(e) => {
-                            logging::log_error(
-                                "websocket",
-                                "Subscription failure",
-                                &anyhow::anyhow!("{}", e)
-                            );
+                            logging::log_error("websocket", "Subscription failure", &e);
                         }
                     }
                 } else if let Err(e) = pubsub_client_result {
@@ -143,10 +533,7 @@ impl WebSocketManager {
                 }
 
                 // Check if we've hit the maximum reconnection attempts
-                if
-                    config.max_reconnect_attempts > 0 &&
-                    reconnect_attempts >= config.max_reconnect_attempts
-                {
+                if policy.is_exhausted() {
                     let msg = format!(
                         "Maximum reconnection attempts reached ({}), stopping reconnection",
                         config.max_reconnect_attempts
@@ -160,22 +547,47 @@ impl WebSocketManager {
                 }
 
                 // Implement exponential backoff for reconnection
-                reconnect_attempts += 1;
+                let delay = policy.next_delay();
+                if connections.is_some() {
+                    metrics::record_websocket_reconnect();
+                }
                 logging::log_activity(
                     "websocket",
                     "Reconnection",
-                    Some(&format!("attempt {} in {} ms", reconnect_attempts, reconnect_delay))
+                    Some(
+                        &format!(
+                            "attempt {} in {} ms ({})",
+                            policy.attempts(),
+                            delay.as_millis(),
+                            label
+                        )
+                    )
                 );
-                sleep(Duration::from_millis(reconnect_delay)).await;
-
-                // Increase delay for next attempt with exponential backoff
-                reconnect_delay = std::cmp::min(reconnect_delay * 2, config.reconnect_max_delay_ms);
+                sleep(delay).await;
             }
 
-            logging::log_activity("websocket", "Manager stopped", None);
+            logging::log_activity("websocket", "Manager stopped", Some(&label));
         });
+    }
 
-        Ok(rx)
+    /// When the last message was received, if any
+    pub fn last_received_at(&self) -> Option<Instant> {
+        *self.last_received.lock().unwrap()
+    }
+
+    /// When the log subscription connected, or `None` while it is disconnected
+    ///
+    /// With several log subscriptions, this is when the last of them connected.
+    pub fn connection_established_at(&self) -> Option<Instant> {
+        *self.connected_at.lock().unwrap()
+    }
+
+    /// Number of times the log subscription reconnected after losing its connection
+    ///
+    /// Failed attempts aren't counted, only connections that were re-established,
+    /// so a steadily rising count means the connection keeps dropping.
+    pub fn reconnect_count(&self) -> u64 {
+        *self.reconnects.borrow()
     }
 
     /// Get the time since the last received message
@@ -184,6 +596,13 @@ impl WebSocketManager {
         guard.map(|instant| instant.elapsed())
     }
 
+    /// Watch the number of times a subscription reconnected after losing its connection
+    ///
+    /// The receiver only sees reconnections that happen after it was created.
+    pub fn reconnects(&self) -> watch::Receiver<u64> {
+        self.reconnects.subscribe()
+    }
+
     /// Check if the connection is likely dead
     pub fn is_connection_dead(&self, timeout: Duration) -> bool {
         match self.time_since_last_received() {
@@ -202,11 +621,16 @@ impl Clone for WebSocketConfig {
     fn clone(&self) -> Self {
         WebSocketConfig {
             ws_url: self.ws_url.clone(),
+            fallback_ws_urls: self.fallback_ws_urls.clone(),
             filter: self.filter.clone(),
+            subscriptions: self.subscriptions.clone(),
             max_reconnect_attempts: self.max_reconnect_attempts,
             reconnect_base_delay_ms: self.reconnect_base_delay_ms,
             reconnect_max_delay_ms: self.reconnect_max_delay_ms,
+            reconnect_jitter_ratio: self.reconnect_jitter_ratio,
             commitment: self.commitment,
+            channel_capacity: self.channel_capacity,
+            overflow_policy: self.overflow_policy,
         }
     }
 }
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use solana_client::client_error::{ ClientError, ClientErrorKind, Result as ClientResult };
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction,
+    EncodedTransactionWithStatusMeta,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::Arc;
+use std::time::Duration;
+
+use indexer::backfill_manager::{ BackfillConfig, BackfillManager, DEFAULT_FETCH_CONCURRENCY };
+use indexer::db::signature_store::{ InMemorySignatureStore, SignatureStore };
+
+// Mock RPC node answering getTransaction after a delay, tracking how many
+// requests are in flight at once
+struct SlowTransactionSender {
+    slots: HashMap<String, u64>,
+    in_flight: AtomicUsize,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl RpcSender for SlowTransactionSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        if request == RpcRequest::GetVersion {
+            return Ok(serde_json::json!({ "solana-core": "1.18.26" }));
+        }
+
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        // Unknown signatures fail with an error that isn't retried
+        let Some(slot) = self.slots.get(params[0].as_str().unwrap()) else {
+            return Err(ClientError::from(ClientErrorKind::Custom("Invalid param: WrongSize".into())));
+        };
+        let transaction = EncodedConfirmedTransactionWithStatusMeta {
+            slot: *slot,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::LegacyBinary(String::new()),
+                meta: None,
+                version: None,
+            },
+            block_time: None,
+        };
+        Ok(serde_json::to_value(transaction).unwrap())
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+// Test that transactions are fetched concurrently up to the limit, and returned
+// in slot order with failed fetches last
+#[tokio::test]
+async fn test_fetch_transactions_bounded_and_ordered() {
+    // Signatures in no particular slot order, two of them sharing slot 30
+    let slots = [50, 10, 30, 40, 30, 20, 60, 70];
+    let signatures: Vec<Signature> = (0..=slots.len()).map(|_| Signature::new_unique()).collect();
+    let (failing, known) = signatures.split_last().unwrap();
+
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let sender = SlowTransactionSender {
+        slots: known
+            .iter()
+            .zip(slots)
+            .map(|(signature, slot)| (signature.to_string(), slot))
+            .collect(),
+        in_flight: AtomicUsize::new(0),
+        max_in_flight: max_in_flight.clone(),
+    };
+    let rpc_client = RpcClient::new_sender(
+        sender,
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    let config = BackfillConfig {
+        fetch_concurrency: 3,
+        ..BackfillConfig::default()
+    };
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let manager = BackfillManager::with_rpc_client(config, store, rpc_client);
+
+    // The failing signature is passed first but comes back last
+    let mut input = vec![*failing];
+    input.extend_from_slice(known);
+    let fetched = manager.fetch_transactions(&input).await;
+
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    assert_eq!(fetched.len(), input.len());
+
+    let fetched_slots: Vec<u64> = fetched
+        .iter()
+        .filter_map(|(_, result)| result.as_ref().ok().map(|tx| tx.slot))
+        .collect();
+    assert_eq!(fetched_slots, vec![10, 20, 30, 30, 40, 50, 60, 70]);
+
+    // Transactions in the same slot keep the order they were given in
+    assert_eq!(*fetched[2].0, known[2]);
+    assert_eq!(*fetched[3].0, known[4]);
+
+    let (last_signature, last_result) = fetched.last().unwrap();
+    assert_eq!(*last_signature, failing);
+    assert!(last_result.is_err());
+}
+
+// Test that the default keeps only a few requests in flight
+#[test]
+fn test_default_fetch_concurrency() {
+    assert_eq!(BackfillConfig::default().fetch_concurrency, DEFAULT_FETCH_CONCURRENCY);
+    assert_eq!(DEFAULT_FETCH_CONCURRENCY, 5);
+}
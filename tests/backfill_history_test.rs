@@ -0,0 +1,273 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+
+use indexer::backfill_manager::{ BackfillConfig, BackfillManager, BackfillOrder, BackfillWindow };
+use indexer::db::signature_store::{ BackfillProgress, InMemorySignatureStore, SignatureStore };
+
+// Block time of slot 0 in the mock history, with one slot per second
+const BLOCK_TIME_BASE: i64 = 1_700_000_000;
+
+// Mock RPC node holding a pool's signature history, newest first, one transaction per slot
+struct HistorySender {
+    history: Vec<(String, u64)>,
+}
+
+impl HistorySender {
+    fn new(count: u64) -> Self {
+        let history = (1..=count)
+            .rev()
+            .map(|slot| (Signature::new_unique().to_string(), slot))
+            .collect();
+        Self { history }
+    }
+
+    fn position(&self, signature: Option<&serde_json::Value>) -> Option<usize> {
+        let signature = signature?.as_str()?;
+        self.history.iter().position(|(sig, _)| sig == signature)
+    }
+}
+
+#[async_trait]
+impl RpcSender for HistorySender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        match request {
+            RpcRequest::GetVersion => Ok(serde_json::json!({ "solana-core": "1.18.26" })),
+            RpcRequest::GetSlot => Ok(serde_json::json!(self.history[0].1)),
+            RpcRequest::GetSignaturesForAddress => {
+                let config = &params[1];
+                let limit = config["limit"].as_u64().unwrap_or(1000) as usize;
+                let start = self.position(config.get("before")).map_or(0, |i| i + 1);
+                let end = self.position(config.get("until")).unwrap_or(self.history.len());
+
+                let page: Vec<_> = self.history[start..end.max(start)]
+                    .iter()
+                    .take(limit)
+                    .map(|(signature, slot)| {
+                        serde_json::json!({
+                            "signature": signature,
+                            "slot": slot,
+                            "err": null,
+                            "memo": null,
+                            "blockTime": BLOCK_TIME_BASE + (*slot as i64),
+                            "confirmationStatus": "finalized",
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::json!(page))
+            }
+            other => panic!("Unexpected RPC request {:?}", other),
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+// Helper to build a backfill manager over a mock history of `count` transactions
+fn backfill_manager(
+    count: u64,
+    order: BackfillOrder,
+    store: SignatureStore
+) -> (BackfillManager, Vec<(String, u64)>) {
+    let sender = HistorySender::new(count);
+    let history = sender.history.clone();
+    let rpc_client = RpcClient::new_sender(
+        sender,
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    let config = BackfillConfig {
+        max_signatures_per_request: 100,
+        initial_backfill_slots: count,
+        order,
+        ..BackfillConfig::default()
+    };
+
+    (BackfillManager::with_rpc_client(config, store, rpc_client), history)
+}
+
+// Helper to look up the slot of each returned signature
+fn slots(signatures: &[Signature], history: &[(String, u64)]) -> Vec<u64> {
+    signatures
+        .iter()
+        .map(|signature| {
+            history
+                .iter()
+                .find(|(sig, _)| Signature::from_str(sig).unwrap() == *signature)
+                .unwrap().1
+        })
+        .collect()
+}
+
+// Test that the initial backfill returns signatures newest first by default
+#[tokio::test]
+async fn test_initial_backfill_newest_first() {
+    let (manager, history) = backfill_manager(
+        250,
+        BackfillOrder::NewestFirst,
+        SignatureStore::InMemory(InMemorySignatureStore::new())
+    );
+    let pool = Pubkey::new_unique();
+
+    let signatures = manager.initial_backfill_for_pool(&pool).await.unwrap();
+    let slots = slots(&signatures, &history);
+
+    assert_eq!(slots.len(), 250);
+    assert!(slots.windows(2).all(|w| w[0] > w[1]));
+}
+
+// Test that the initial backfill can return signatures in chronological order
+#[tokio::test]
+async fn test_initial_backfill_oldest_first() {
+    let (manager, history) = backfill_manager(
+        250,
+        BackfillOrder::OldestFirst,
+        SignatureStore::InMemory(InMemorySignatureStore::new())
+    );
+    let pool = Pubkey::new_unique();
+
+    let signatures = manager.initial_backfill_for_pool(&pool).await.unwrap();
+    let slots = slots(&signatures, &history);
+
+    assert_eq!(slots.len(), 250);
+    assert!(slots.windows(2).all(|w| w[0] < w[1]));
+}
+
+// Test backfill order parsing
+#[test]
+fn test_backfill_order_from_str() {
+    assert_eq!(BackfillOrder::from_str("newest-first").unwrap(), BackfillOrder::NewestFirst);
+    assert_eq!(BackfillOrder::from_str("Oldest-First").unwrap(), BackfillOrder::OldestFirst);
+    assert!(BackfillOrder::from_str("random").is_err());
+}
+
+// Test that an incremental backfill pages back to the stored signature instead of stopping after one page
+#[tokio::test]
+async fn test_incremental_backfill_pages_until_last_signature() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let (manager, history) = backfill_manager(300, BackfillOrder::NewestFirst, store.clone());
+    let pool = Pubkey::new_unique();
+
+    // 250 transactions arrived since the stored signature, more than two pages of 100
+    store.update_signature(&pool, history[250].0.clone(), "orca").await.unwrap();
+
+    let signatures = manager.backfill_since_last_signature(&pool).await.unwrap();
+    let slots = slots(&signatures, &history);
+
+    assert_eq!(slots.len(), 250);
+    assert_eq!(slots, (51..=300).collect::<Vec<u64>>());
+
+    // The checkpoint moves to the newest signature
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), Some(history[0].0.clone()));
+}
+
+// Test that a window backfill returns only the signatures inside the slot or time range, oldest first
+#[tokio::test]
+async fn test_window_backfill() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let (manager, history) = backfill_manager(300, BackfillOrder::NewestFirst, store.clone());
+    let pool = Pubkey::new_unique();
+
+    // The window spans pages: slots 300..=201 are the first page of 100
+    let window = BackfillWindow::Slots { from: 50, to: 210 };
+    let signatures = manager.signatures_in_window(&pool, &window).await.unwrap();
+    assert_eq!(slots(&signatures, &history), (50..=210).collect::<Vec<u64>>());
+
+    let window = BackfillWindow::BlockTime { from: BLOCK_TIME_BASE + 10, to: BLOCK_TIME_BASE + 20 };
+    let signatures = manager.signatures_in_window(&pool, &window).await.unwrap();
+    assert_eq!(slots(&signatures, &history), (10..=20).collect::<Vec<u64>>());
+
+    // A one-shot window backfill leaves the checkpoint alone
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), None);
+
+    let inverted = BackfillWindow::Slots { from: 20, to: 10 };
+    assert!(manager.signatures_in_window(&pool, &inverted).await.is_err());
+}
+
+// Test that an interrupted initial backfill resumes from its stored progress after a restart
+#[tokio::test]
+async fn test_initial_backfill_resumes_from_progress() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let (manager, history) = backfill_manager(250, BackfillOrder::NewestFirst, store.clone());
+    let pool = Pubkey::new_unique();
+
+    // The first run processes one page, then crashes
+    let mut paginator = manager.initial_backfill_paginator(&pool).await.unwrap();
+    let first_page = manager.next_signature_page(&pool, &mut paginator).await.unwrap();
+    assert_eq!(first_page.len(), 100);
+    manager.record_backfill_progress(&pool, paginator.progress()).await.unwrap();
+
+    // After the restart paging continues where it stopped without moving the checkpoint
+    let mut paginator = manager.initial_backfill_paginator(&pool).await.unwrap();
+    let mut rest = Vec::new();
+    while !paginator.is_done() {
+        rest.extend(manager.next_signature_page(&pool, &mut paginator).await.unwrap());
+        manager.record_backfill_progress(&pool, paginator.progress()).await.unwrap();
+    }
+    assert_eq!(slots(&rest, &history), (1..=150).rev().collect::<Vec<u64>>());
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), Some(history[0].0.clone()));
+
+    // Once complete, the initial backfill has nothing left to page through
+    let progress = manager.backfill_progress(&pool).await.unwrap().unwrap();
+    assert_eq!(progress, BackfillProgress {
+        oldest_backfilled_signature: Some(history[249].0.clone()),
+        backfill_complete: true,
+    });
+    assert!(manager.initial_backfill_paginator(&pool).await.unwrap().is_done());
+}
+
+// Test that a hyperactive pool is capped per cycle while a quiet pool is still fully serviced
+#[tokio::test]
+async fn test_pool_cap_defers_busy_pool() {
+    let sender = HistorySender::new(300);
+    let history = sender.history.clone();
+    let rpc_client = RpcClient::new_sender(
+        sender,
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let config = BackfillConfig {
+        max_signatures_per_request: 100,
+        pool_cap: Some(100),
+        ..BackfillConfig::default()
+    };
+    let manager = BackfillManager::with_rpc_client(config, store.clone(), rpc_client);
+
+    // The busy pool has 250 new transactions since its checkpoint, the quiet one 5
+    let busy_pool = Pubkey::new_unique();
+    let quiet_pool = Pubkey::new_unique();
+    store.update_signature(&busy_pool, history[250].0.clone(), "orca").await.unwrap();
+    store.update_signature(&quiet_pool, history[5].0.clone(), "orca").await.unwrap();
+
+    // First cycle: the busy pool gets its oldest 100, the quiet pool everything
+    let busy = manager.backfill_since_last_signature(&busy_pool).await.unwrap();
+    assert_eq!(slots(&busy, &history), (51..=150).collect::<Vec<u64>>());
+    let quiet = manager.backfill_since_last_signature(&quiet_pool).await.unwrap();
+    assert_eq!(slots(&quiet, &history), (296..=300).collect::<Vec<u64>>());
+
+    // The busy pool's checkpoint only moved as far as what was processed
+    assert_eq!(store.get_signature(&busy_pool, "orca").await.unwrap(), Some(history[150].0.clone()));
+
+    // The next cycles pick up the deferred transactions without gaps
+    let busy = manager.backfill_since_last_signature(&busy_pool).await.unwrap();
+    assert_eq!(slots(&busy, &history), (151..=250).collect::<Vec<u64>>());
+    let busy = manager.backfill_since_last_signature(&busy_pool).await.unwrap();
+    assert_eq!(slots(&busy, &history), (251..=300).collect::<Vec<u64>>());
+    assert_eq!(store.get_signature(&busy_pool, "orca").await.unwrap(), Some(history[0].0.clone()));
+}
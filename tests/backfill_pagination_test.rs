@@ -0,0 +1,79 @@
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::signature::Signature;
+
+use indexer::backfill_manager::SignaturePaginator;
+
+// Helper to build a page of signature infos, newest first, at the given slots
+fn page(slots: &[u64]) -> Vec<RpcConfirmedTransactionStatusWithSignature> {
+    slots
+        .iter()
+        .map(|slot| RpcConfirmedTransactionStatusWithSignature {
+            signature: Signature::new_unique().to_string(),
+            slot: *slot,
+            err: None,
+            memo: None,
+            block_time: None,
+            confirmation_status: None,
+        })
+        .collect()
+}
+
+// Test that the cursor moves to the oldest signature of each full page
+#[test]
+fn test_paginator_advances_before_cursor() {
+    let mut paginator = SignaturePaginator::new(3, None, None);
+    assert!(paginator.before().is_none());
+
+    let first = page(&[100, 99, 98]);
+    assert_eq!(paginator.advance(&first).unwrap().len(), 3);
+    assert!(!paginator.is_done());
+    assert_eq!(paginator.before().unwrap().to_string(), first[2].signature);
+
+    let second = page(&[97, 96, 95]);
+    paginator.advance(&second).unwrap();
+    assert!(!paginator.is_done());
+    assert_eq!(paginator.before().unwrap().to_string(), second[2].signature);
+}
+
+// Test that a short or empty page ends the walk once history is exhausted
+#[test]
+fn test_paginator_stops_on_short_page() {
+    let mut paginator = SignaturePaginator::new(3, None, None);
+    paginator.advance(&page(&[100, 99, 98])).unwrap();
+
+    assert_eq!(paginator.advance(&page(&[97])).unwrap().len(), 1);
+    assert!(paginator.is_done());
+
+    let mut empty = SignaturePaginator::new(3, None, None);
+    assert!(empty.advance(&[]).unwrap().is_empty());
+    assert!(empty.is_done());
+}
+
+// Test that signatures older than the configured depth are dropped and end the walk
+#[test]
+fn test_paginator_stops_at_min_slot() {
+    let mut paginator = SignaturePaginator::new(3, None, Some(96));
+    assert_eq!(paginator.advance(&page(&[100, 99, 98])).unwrap().len(), 3);
+    assert!(!paginator.is_done());
+
+    let signatures = paginator.advance(&page(&[97, 96, 95])).unwrap();
+    assert_eq!(signatures.len(), 2);
+    assert!(paginator.is_done());
+}
+
+// Test that walking many pages collects every signature within the depth exactly once
+#[test]
+fn test_paginator_collects_beyond_page_size() {
+    let history: Vec<u64> = (0..250).rev().collect();
+    let pages: Vec<_> = history.chunks(100).map(page).collect();
+
+    let mut paginator = SignaturePaginator::new(100, None, Some(0));
+    let mut collected = Vec::new();
+    for page in &pages {
+        assert!(!paginator.is_done());
+        collected.extend(paginator.advance(page).unwrap());
+    }
+
+    assert!(paginator.is_done());
+    assert_eq!(collected.len(), 250);
+}
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use std::sync::atomic::{ AtomicU32, Ordering };
+use std::sync::Arc;
+
+use indexer::backfill_manager::{
+    is_transient_error,
+    retry_with_backoff,
+    BackfillConfig,
+    BackfillManager,
+    RetryConfig,
+};
+use indexer::db::signature_store::{ InMemorySignatureStore, SignatureStore };
+use indexer::error::IndexerError;
+
+// Helper to build a retry config without real delays
+fn test_retry_config(max_attempts: u32) -> RetryConfig {
+    RetryConfig {
+        max_attempts,
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        jitter_ratio: 0.5,
+    }
+}
+
+// Test that an operation failing twice with transient errors then succeeding is retried
+#[tokio::test]
+async fn test_retry_transient_then_success() {
+    let calls = AtomicU32::new(0);
+
+    let result = retry_with_backoff(&test_retry_config(5), "mock", || async {
+        match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(IndexerError::RateLimited("429 Too Many Requests".to_string()).into()),
+            1 => Err(IndexerError::Transient("operation timed out".to_string()).into()),
+            _ => Ok(42),
+        }
+    }).await;
+
+    assert_eq!(result.unwrap(), 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+// Test that permanent errors are returned immediately
+#[tokio::test]
+async fn test_retry_permanent_error() {
+    let calls = AtomicU32::new(0);
+
+    let result: anyhow::Result<u32> = retry_with_backoff(&test_retry_config(5), "mock", || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(IndexerError::Permanent("Invalid param: WrongSize".to_string()).into())
+    }).await;
+
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+// Test that retries stop after the configured number of attempts
+#[tokio::test]
+async fn test_retry_max_attempts() {
+    let calls = AtomicU32::new(0);
+
+    let result: anyhow::Result<u32> = retry_with_backoff(&test_retry_config(3), "mock", || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(IndexerError::Transient("connection reset by peer".to_string()).into())
+    }).await;
+
+    assert!(result.unwrap_err().to_string().contains("connection reset"));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+// Test that the transient check looks through added context, and only trusts the error's type
+#[test]
+fn test_is_transient_error_with_context() {
+    let err = anyhow::Error
+        ::from(IndexerError::Transient("error trying to connect: Connection refused".to_string()))
+        .context("Failed to fetch transaction for signature abc");
+    assert!(is_transient_error(&err));
+    assert!(
+        !is_transient_error(
+            &IndexerError::Permanent("Transaction version (1) is not supported".to_string()).into()
+        )
+    );
+    assert!(!is_transient_error(&IndexerError::TransactionNotFound("abc".to_string()).into()));
+
+    // Untyped errors aren't retried, whatever their message says
+    assert!(!is_transient_error(&anyhow::anyhow!("429 Too Many Requests")));
+}
+
+// Mock RPC node that knows no transactions, counting getTransaction requests
+struct EmptyLedgerSender {
+    requests: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl RpcSender for EmptyLedgerSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        _params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        if request == RpcRequest::GetTransaction {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(serde_json::Value::Null)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+// Test that an unknown transaction fails as not found, without being retried
+#[tokio::test]
+async fn test_fetch_unknown_transaction() {
+    let requests = Arc::new(AtomicU32::new(0));
+    let rpc_client = RpcClient::new_sender(
+        EmptyLedgerSender { requests: requests.clone() },
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    let config = BackfillConfig {
+        retry: test_retry_config(3),
+        ..BackfillConfig::default()
+    };
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let manager = BackfillManager::with_rpc_client(config, store, rpc_client);
+
+    let err = manager.fetch_transaction(&Signature::new_unique()).await.unwrap_err();
+    assert!(matches!(IndexerError::find(&err), Some(IndexerError::TransactionNotFound(_))));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction,
+    EncodedTransactionWithStatusMeta,
+    TransactionStatusMeta,
+    UiTransactionStatusMeta,
+};
+use std::collections::{ HashMap, HashSet };
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+
+/// Block time of slot 0 on the mock node, with one slot per second
+pub const BLOCK_TIME_BASE: i64 = 1_700_000_000;
+
+/// A transaction held by the mock node
+#[derive(Debug, Clone)]
+pub struct MockTransaction {
+    pub signature: Signature,
+    pub slot: u64,
+    pub logs: Vec<String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    // Signature history of each address, newest first
+    history: HashMap<Pubkey, Vec<MockTransaction>>,
+    fetched: Vec<Signature>,
+    delay: Duration,
+    in_flight: usize,
+    max_in_flight: usize,
+    panicking: HashSet<Pubkey>,
+}
+
+/// An RPC node answering from canned transactions, for driving indexers end to end
+///
+/// Serves the calls backfills make (signature history, transactions, block times,
+/// signature statuses and the current slot). Clones share the same history, so
+/// transactions can be added after handing a client to an indexer.
+#[derive(Clone, Default)]
+pub struct MockRpc {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a transaction touching `address` that emitted `logs`, returning its signature
+    ///
+    /// Transactions must be added in slot order, oldest first.
+    pub fn add_transaction(&self, address: &Pubkey, slot: u64, logs: Vec<String>) -> Signature {
+        let signature = Signature::new_unique();
+        let transaction = MockTransaction { signature, slot, logs };
+        self.state.lock().unwrap().history.entry(*address).or_default().insert(0, transaction);
+        signature
+    }
+
+    /// Client sending its requests to this node
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_sender(self.clone(), RpcClientConfig::with_commitment(CommitmentConfig::confirmed()))
+    }
+
+    /// Answer each request after `delay`, as a remote node would
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = delay;
+    }
+
+    /// Panic when asked for the signature history of `address`, as a bug would
+    pub fn panic_on(&self, address: &Pubkey) {
+        self.state.lock().unwrap().panicking.insert(*address);
+    }
+
+    /// Most requests that were in flight at once
+    pub fn max_in_flight(&self) -> usize {
+        self.state.lock().unwrap().max_in_flight
+    }
+
+    /// Signatures fetched with getTransaction, in request order
+    pub fn fetched(&self) -> Vec<Signature> {
+        self.state.lock().unwrap().fetched.clone()
+    }
+
+    fn find(&self, signature: &str) -> Option<MockTransaction> {
+        let state = self.state.lock().unwrap();
+        state.history
+            .values()
+            .flatten()
+            .find(|transaction| transaction.signature.to_string() == signature)
+            .cloned()
+    }
+
+    fn current_slot(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.history
+            .values()
+            .flatten()
+            .map(|transaction| transaction.slot)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn signatures_for_address(&self, params: &serde_json::Value) -> serde_json::Value {
+        let address: Pubkey = params[0].as_str().unwrap().parse().unwrap();
+        if self.state.lock().unwrap().panicking.contains(&address) {
+            panic!("Signature history of {} requested", address);
+        }
+        let config = &params[1];
+        let limit = config["limit"].as_u64().unwrap_or(1000) as usize;
+
+        let state = self.state.lock().unwrap();
+        let history = state.history.get(&address).cloned().unwrap_or_default();
+        let position = |key: &str| {
+            let signature = config.get(key)?.as_str()?;
+            history.iter().position(|transaction| transaction.signature.to_string() == signature)
+        };
+        let start = position("before").map_or(0, |i| i + 1);
+        let end = position("until").unwrap_or(history.len());
+
+        let page: Vec<_> = history[start..end.max(start)]
+            .iter()
+            .take(limit)
+            .map(|transaction| {
+                serde_json::json!({
+                    "signature": transaction.signature.to_string(),
+                    "slot": transaction.slot,
+                    "err": null,
+                    "memo": null,
+                    "blockTime": BLOCK_TIME_BASE + (transaction.slot as i64),
+                    "confirmationStatus": "finalized",
+                })
+            })
+            .collect();
+        serde_json::json!(page)
+    }
+
+    fn transaction(&self, params: &serde_json::Value) -> serde_json::Value {
+        let signature = params[0].as_str().unwrap();
+        let Some(transaction) = self.find(signature) else {
+            return serde_json::Value::Null;
+        };
+        self.state.lock().unwrap().fetched.push(transaction.signature);
+
+        let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+            log_messages: Some(transaction.logs),
+            ..TransactionStatusMeta::default()
+        });
+        let encoded = EncodedConfirmedTransactionWithStatusMeta {
+            slot: transaction.slot,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::LegacyBinary(String::new()),
+                meta: Some(meta),
+                version: None,
+            },
+            block_time: Some(BLOCK_TIME_BASE + (transaction.slot as i64)),
+        };
+        serde_json::to_value(&encoded).unwrap()
+    }
+
+    fn signature_statuses(&self, params: &serde_json::Value) -> serde_json::Value {
+        let statuses: Vec<_> = params[0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|signature| {
+                let transaction = self.find(signature.as_str().unwrap())?;
+                Some(
+                    serde_json::json!({
+                        "slot": transaction.slot,
+                        "confirmations": null,
+                        "err": null,
+                        "status": { "Ok": null },
+                        "confirmationStatus": "finalized",
+                    })
+                )
+            })
+            .collect();
+        serde_json::json!({ "context": { "slot": self.current_slot() }, "value": statuses })
+    }
+}
+
+#[async_trait]
+impl RpcSender for MockRpc {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        let delay = {
+            let mut state = self.state.lock().unwrap();
+            state.in_flight += 1;
+            state.max_in_flight = state.max_in_flight.max(state.in_flight);
+            state.delay
+        };
+        tokio::time::sleep(delay).await;
+        self.state.lock().unwrap().in_flight -= 1;
+
+        match request {
+            RpcRequest::GetVersion => Ok(serde_json::json!({ "solana-core": "1.18.26" })),
+            RpcRequest::GetSlot => Ok(serde_json::json!(self.current_slot())),
+            RpcRequest::GetSignaturesForAddress => Ok(self.signatures_for_address(&params)),
+            RpcRequest::GetTransaction => Ok(self.transaction(&params)),
+            RpcRequest::GetBlockTime => {
+                Ok(serde_json::json!(BLOCK_TIME_BASE + (params[0].as_u64().unwrap() as i64)))
+            }
+            RpcRequest::GetSignatureStatuses => Ok(self.signature_statuses(&params)),
+            other => panic!("Unexpected RPC request {:?}", other),
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
@@ -0,0 +1,77 @@
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use indexer::db::run_migrations;
+
+#[cfg(feature = "testcontainers")]
+use testcontainers::{ clients::Cli, core::WaitFor, Container, GenericImage };
+
+#[allow(dead_code)]
+pub mod mock_rpc;
+
+/// A migrated test database, kept alive for as long as the fixture is
+pub struct TestDatabase {
+    pub pool: PgPool,
+    /// URL the pool connects to, for tests that open their own connections
+    #[allow(dead_code)]
+    pub url: String,
+    #[cfg(feature = "testcontainers")]
+    _container: Option<Container<'static, GenericImage>>,
+}
+
+// Helper to get a migrated database for the given DEX schemas.
+// Uses TEST_DATABASE_URL when set, otherwise starts a Postgres container when the
+// testcontainers feature is enabled. Returns None (skipping the test) when neither is available.
+pub async fn test_database(dexes: &[&str]) -> Option<TestDatabase> {
+    let database = match std::env::var("TEST_DATABASE_URL") {
+        Ok(url) =>
+            TestDatabase {
+                pool: PgPoolOptions::new().max_connections(2).connect(&url).await.unwrap(),
+                url,
+                #[cfg(feature = "testcontainers")]
+                _container: None,
+            },
+        Err(_) => start_container().await?,
+    };
+
+    run_migrations(&database.pool, dexes).await.unwrap();
+
+    Some(database)
+}
+
+#[cfg(not(feature = "testcontainers"))]
+async fn start_container() -> Option<TestDatabase> {
+    eprintln!("TEST_DATABASE_URL not set and testcontainers feature disabled, skipping database test");
+    None
+}
+
+// Helper to start a throwaway Postgres container and connect to it
+#[cfg(feature = "testcontainers")]
+async fn start_container() -> Option<TestDatabase> {
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    static DOCKER: OnceLock<Cli> = OnceLock::new();
+
+    let image = GenericImage::new("postgres", "16-alpine")
+        .with_env_var("POSTGRES_HOST_AUTH_METHOD", "trust")
+        .with_wait_for(WaitFor::message_on_stderr("database system is ready to accept connections"));
+    let container = DOCKER.get_or_init(Cli::default).run(image);
+    let url = format!("postgres://postgres@127.0.0.1:{}/postgres", container.get_host_port_ipv4(5432));
+
+    // The image restarts Postgres once after initialising, so retry until it accepts connections
+    let mut attempts = 0;
+    let pool = loop {
+        match PgPoolOptions::new().max_connections(2).connect(&url).await {
+            Ok(pool) => break pool,
+            Err(e) if attempts < 30 => {
+                attempts += 1;
+                log::debug!("Waiting for Postgres container: {}", e);
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            Err(e) => panic!("Postgres container did not become ready: {}", e),
+        }
+    };
+
+    Some(TestDatabase { pool, url, _container: Some(container) })
+}
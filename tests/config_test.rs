@@ -0,0 +1,132 @@
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use indexer::backfill_manager::BackfillOrder;
+use indexer::config::Config;
+use indexer::indexers::StartupStrategy;
+use indexer::rpc::Cluster;
+use indexer::utils::logging::LogFormat;
+
+#[test]
+fn test_parse_config_file() {
+    let config = Config::parse(
+        r#"
+        database_url = "postgres://indexer@localhost/indexer"
+        cluster = "devnet"
+        rpc_url = "https://rpc.example.com"
+        ws_url = "wss://rpc.example.com"
+        commitment = "finalized"
+        backfill_order = "oldest-first"
+        startup_strategy = "background"
+        backfill_interval_secs = 60
+        backfill_concurrency = 8
+        log_format = "json"
+        metrics_port = 9090
+        strict_pool_addresses = true
+        orca_default_pool = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE"
+        orca_pools = ["Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE"]
+
+        [rpc_headers]
+        Authorization = "Bearer secret"
+        "#
+    ).unwrap();
+
+    assert_eq!(config.database_url.as_deref(), Some("postgres://indexer@localhost/indexer"));
+    assert_eq!(config.cluster, Some(Cluster::Devnet));
+    assert_eq!(config.rpc_url.as_deref(), Some("https://rpc.example.com"));
+    assert_eq!(config.commitment, Some(CommitmentConfig::finalized()));
+    assert_eq!(config.backfill_order, Some(BackfillOrder::OldestFirst));
+    assert_eq!(config.startup_strategy, Some(StartupStrategy::Background));
+    assert_eq!(config.backfill_interval_secs, Some(60));
+    assert_eq!(config.backfill_concurrency, Some(8));
+    assert_eq!(config.log_format, Some(LogFormat::Json));
+    assert_eq!(config.metrics_port, Some(9090));
+    assert_eq!(config.strict_pool_addresses, Some(true));
+    assert_eq!(
+        config.orca_default_pool.as_deref(),
+        Some("Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+    );
+    assert_eq!(
+        config.orca_pools,
+        Some(vec!["Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string()])
+    );
+    assert_eq!(
+        config.rpc_headers.unwrap().get("Authorization").map(String::as_str),
+        Some("Bearer secret")
+    );
+
+    // Keys that aren't set stay unset, so the flags keep their env values and defaults
+    assert_eq!(config.ws_fallback_urls, None);
+    assert_eq!(config.webhook_url, None);
+    assert_eq!(config.raydium_pools, None);
+}
+
+#[test]
+fn test_empty_config_file() {
+    assert_eq!(Config::parse("").unwrap(), Config::default());
+}
+
+#[test]
+fn test_unknown_key_rejected() {
+    let err = Config::parse("rpc_ulr = \"https://rpc.example.com\"").unwrap_err();
+    assert!(err.to_string().contains("rpc_ulr"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_invalid_values_rejected() {
+    assert!(Config::parse("commitment = \"eventually\"").is_err());
+    assert!(Config::parse("cluster = \"localhost\"").is_err());
+    assert!(Config::parse("startup_strategy = \"sometime\"").is_err());
+    assert!(Config::parse("event_validation = \"paranoid\"").is_err());
+    assert!(Config::parse("[pool_min_input_amounts]\nnot-a-pool = 1000").is_err());
+    assert!(Config::parse("token_mint = \"not-a-mint\"").is_err());
+    assert!(Config::parse("orca_program_id = \"not-a-program\"").is_err());
+    assert!(Config::parse("orca_default_pool = \"not-a-pool\"").is_err());
+    assert!(Config::parse("backfill_concurrency = 0").is_err());
+    assert!(Config::parse("backfill_interval_secs = 0").is_err());
+    assert!(Config::parse("metrics_port = \"9090\"").is_err());
+}
+
+#[test]
+fn test_from_file() {
+    let path = std::env::temp_dir().join(format!("indexer_config_{}.toml", std::process::id()));
+    std::fs::write(&path, "rpc_url = \"https://rpc.example.com\"\n").unwrap();
+
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(config.rpc_url.as_deref(), Some("https://rpc.example.com"));
+
+    std::fs::remove_file(&path).unwrap();
+    let err = Config::from_file(&path).unwrap_err();
+    assert!(err.to_string().contains("Failed to read config file"));
+}
+
+// Test that the config-template output lists every key, parses to the defaults as is,
+// and keeps parsing with each default uncommented
+#[test]
+fn test_config_template() {
+    let output = std::process::Command
+        ::new(env!("CARGO_BIN_EXE_indexer"))
+        .arg("config-template")
+        .env_remove("CONFIG_FILE")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let template = String::from_utf8(output.stdout).unwrap();
+
+    for key in Config::keys() {
+        assert!(template.contains(&format!("\n# {} =", key)), "{} missing from the template", key);
+    }
+    assert_eq!(Config::parse(&template).unwrap(), Config::default());
+
+    let defaults: String = template
+        .lines()
+        .filter_map(|line| line.strip_prefix("# "))
+        .filter(|line| Config::keys().iter().any(|key| line.starts_with(&format!("{} = ", key))))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let config = Config::parse(&defaults).unwrap();
+    assert_eq!(config.rpc_url.as_deref(), Some("https://api.mainnet-beta.solana.com"));
+    assert_eq!(config.commitment, Some(CommitmentConfig::confirmed()));
+    assert_eq!(config.backfill_interval_secs, Some(300));
+    assert_eq!(config.also_stdout, Some(false));
+    assert_eq!(config.cluster, None);
+}
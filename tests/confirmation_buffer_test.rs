@@ -0,0 +1,103 @@
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_transaction_status::{ TransactionConfirmationStatus, TransactionStatus };
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+use indexer::confirmation_buffer::{ split_vanished, ConfirmationBuffer };
+
+// Helper to build a minimal log notification
+fn test_log(signature: &str) -> RpcLogsResponse {
+    RpcLogsResponse {
+        signature: signature.to_string(),
+        err: None,
+        logs: vec!["Program log: test".to_string()],
+    }
+}
+
+// Helper to build a signature status
+fn test_status(err: Option<TransactionError>) -> TransactionStatus {
+    TransactionStatus {
+        slot: 100,
+        confirmations: Some(5),
+        status: match &err {
+            Some(e) => Err(e.clone()),
+            None => Ok(()),
+        },
+        err,
+        confirmation_status: Some(TransactionConfirmationStatus::Confirmed),
+    }
+}
+
+// Test that an event is held until it is the configured number of slots old
+#[test]
+fn test_event_held_for_delay() {
+    let mut buffer = ConfirmationBuffer::new(3);
+    buffer.push(100, test_log("sig1"));
+
+    // Not old enough yet
+    for slot in 100..103 {
+        buffer.observe_slot(slot);
+        assert!(buffer.drain_ready().is_empty());
+        assert_eq!(buffer.len(), 1);
+    }
+
+    // Released once the chain is 3 slots past the event
+    buffer.observe_slot(103);
+    let ready = buffer.drain_ready();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].log.signature, "sig1");
+    assert_eq!(ready[0].slot, 100);
+    assert!(buffer.is_empty());
+}
+
+// Test that only aged events are released and newer ones keep waiting
+#[test]
+fn test_partial_release() {
+    let mut buffer = ConfirmationBuffer::new(2);
+    buffer.push(100, test_log("old"));
+    buffer.push(101, test_log("newer"));
+    buffer.push(102, test_log("newest"));
+
+    let ready = buffer.drain_ready();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0].log.signature, "old");
+    assert_eq!(buffer.len(), 2);
+
+    // Older slots never move the buffer backwards
+    buffer.observe_slot(90);
+    assert_eq!(buffer.current_slot(), 102);
+    assert!(buffer.drain_ready().is_empty());
+}
+
+// Test that a zero delay disables buffering
+#[test]
+fn test_zero_delay_disabled() {
+    let mut buffer = ConfirmationBuffer::new(0);
+    assert!(buffer.is_disabled());
+
+    buffer.push(100, test_log("sig1"));
+    assert_eq!(buffer.drain_ready().len(), 1);
+}
+
+// Test that events whose transaction vanished or failed are dropped
+#[test]
+fn test_split_vanished() {
+    let mut buffer = ConfirmationBuffer::new(1);
+    buffer.push(100, test_log("landed"));
+    buffer.push(100, test_log("rolled_back"));
+    buffer.push(100, test_log("failed"));
+    buffer.observe_slot(101);
+
+    let failed = TransactionError::InstructionError(0, InstructionError::Custom(1));
+    let statuses = vec![Some(test_status(None)), None, Some(test_status(Some(failed)))];
+
+    let (landed, vanished) = split_vanished(buffer.drain_ready(), &statuses);
+    assert_eq!(landed.len(), 1);
+    assert_eq!(landed[0].log.signature, "landed");
+
+    let vanished: Vec<&str> = vanished
+        .iter()
+        .map(|event| event.log.signature.as_str())
+        .collect();
+    assert_eq!(vanished, vec!["rolled_back", "failed"]);
+}
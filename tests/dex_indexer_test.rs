@@ -0,0 +1,13 @@
+use indexer::indexers::{ start_indexer, DexIndexer, OrcaWhirlpoolIndexer, RaydiumIndexer };
+
+// Helper that only compiles if the indexer satisfies start_indexer's bounds
+fn assert_startable<T: DexIndexer + Send + Sync>() {
+    let _start = start_indexer::<T>;
+}
+
+// Test that every indexer implements the full DexIndexer trait and can be started
+#[test]
+fn test_indexers_usable_via_start_indexer() {
+    assert_startable::<OrcaWhirlpoolIndexer>();
+    assert_startable::<RaydiumIndexer>();
+}
@@ -0,0 +1,172 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use indexer::validation::{
+    validate_orca_liquidity,
+    validate_orca_pool_initialized,
+    validate_orca_trade,
+    LiquidityChange,
+    ValidationMode,
+    MAX_SQRT_PRICE,
+    MAX_TICK_INDEX,
+};
+use indexer::{ OrcaWhirlpoolPoolInitializedEvent, OrcaWhirlpoolTradedEvent };
+
+fn trade() -> OrcaWhirlpoolTradedEvent {
+    OrcaWhirlpoolTradedEvent {
+        whirlpool: Pubkey::new_unique(),
+        token_vault_a: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        tick_array_lower: Pubkey::new_unique(),
+        tick_array_upper: Pubkey::new_unique(),
+        a_to_b: true,
+        input_amount: 1_000_000,
+        output_amount: 990_000,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        protocol_fee: 300,
+        lp_fee: 2_700,
+        pre_sqrt_price: 2 << 64,
+        post_sqrt_price: 1 << 64,
+    }
+}
+
+fn liquidity_change() -> LiquidityChange {
+    LiquidityChange {
+        ticks: (-128, 128),
+        liquidity: 1_000_000,
+        amounts: (5_000, 7_000),
+        transfer_fees: (0, 0),
+    }
+}
+
+#[test]
+fn test_parse_validation_mode() {
+    assert_eq!(ValidationMode::from_str("off").unwrap(), ValidationMode::Off);
+    assert_eq!(ValidationMode::from_str("Basic").unwrap(), ValidationMode::Basic);
+    assert_eq!(ValidationMode::from_str("strict").unwrap(), ValidationMode::Strict);
+    assert!(ValidationMode::from_str("paranoid").is_err());
+    assert_eq!(ValidationMode::default(), ValidationMode::Off);
+}
+
+#[test]
+fn test_valid_trade_accepted() {
+    for mode in [ValidationMode::Off, ValidationMode::Basic, ValidationMode::Strict] {
+        assert_eq!(validate_orca_trade(&trade(), mode, Some((1.0, 0.99)), Some(1_000.0)), Ok(()));
+    }
+}
+
+#[test]
+fn test_invalid_trades_rejected() {
+    let basic = |event: OrcaWhirlpoolTradedEvent| {
+        validate_orca_trade(&event, ValidationMode::Basic, None, None).unwrap_err().check
+    };
+
+    assert_eq!(basic(OrcaWhirlpoolTradedEvent { input_amount: 0, ..trade() }), "zero_amount");
+    assert_eq!(basic(OrcaWhirlpoolTradedEvent { lp_fee: 2_000_000, ..trade() }), "fee_exceeds_amount");
+    assert_eq!(
+        basic(OrcaWhirlpoolTradedEvent { output_transfer_fee: 1_000_000, ..trade() }),
+        "fee_exceeds_amount"
+    );
+    // Fees are added without overflowing
+    assert_eq!(
+        basic(OrcaWhirlpoolTradedEvent { lp_fee: u64::MAX, protocol_fee: u64::MAX, ..trade() }),
+        "fee_exceeds_amount"
+    );
+    assert_eq!(basic(OrcaWhirlpoolTradedEvent { post_sqrt_price: 0, ..trade() }), "price_range");
+    assert_eq!(
+        basic(OrcaWhirlpoolTradedEvent { pre_sqrt_price: MAX_SQRT_PRICE + 1, ..trade() }),
+        "price_range"
+    );
+    // Every event is accepted with validation off
+    assert_eq!(
+        validate_orca_trade(
+            &(OrcaWhirlpoolTradedEvent { input_amount: 0, ..trade() }),
+            ValidationMode::Off,
+            None,
+            None
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_strict_trade_checks() {
+    let check = |event: OrcaWhirlpoolTradedEvent, mode: ValidationMode, scaled: Option<(f64, f64)>| {
+        validate_orca_trade(&event, mode, scaled, Some(1_000.0)).map_err(|violation| violation.check)
+    };
+
+    let no_output = OrcaWhirlpoolTradedEvent { output_amount: 0, ..trade() };
+    assert_eq!(check(no_output, ValidationMode::Basic, None), Ok(()));
+    let no_output = OrcaWhirlpoolTradedEvent { output_amount: 0, ..trade() };
+    assert_eq!(check(no_output, ValidationMode::Strict, None), Err("zero_amount"));
+
+    // Selling token B must not lower the price
+    let wrong_direction = || OrcaWhirlpoolTradedEvent { a_to_b: false, ..trade() };
+    assert_eq!(check(wrong_direction(), ValidationMode::Basic, None), Ok(()));
+    assert_eq!(check(wrong_direction(), ValidationMode::Strict, None), Err("price_direction"));
+
+    // Scaled amounts are only compared when known
+    assert_eq!(check(trade(), ValidationMode::Strict, None), Ok(()));
+    assert_eq!(
+        check(trade(), ValidationMode::Strict, Some((5_000.0, 1.0))),
+        Err("implausible_amount")
+    );
+    assert_eq!(check(trade(), ValidationMode::Basic, Some((5_000.0, 1.0))), Ok(()));
+}
+
+#[test]
+fn test_liquidity_checks() {
+    let check = |event: LiquidityChange, mode: ValidationMode| {
+        validate_orca_liquidity(&event, mode).map_err(|violation| violation.check)
+    };
+
+    assert_eq!(check(liquidity_change(), ValidationMode::Strict), Ok(()));
+    assert_eq!(
+        check(LiquidityChange { ticks: (128, 128), ..liquidity_change() }, ValidationMode::Basic),
+        Err("tick_range")
+    );
+    assert_eq!(
+        check(
+            LiquidityChange { ticks: (0, MAX_TICK_INDEX + 1), ..liquidity_change() },
+            ValidationMode::Basic
+        ),
+        Err("tick_range")
+    );
+    // Liquidity that would be stored as a negative number
+    assert_eq!(
+        check(LiquidityChange { liquidity: u128::MAX, ..liquidity_change() }, ValidationMode::Basic),
+        Err("liquidity_range")
+    );
+    assert_eq!(
+        check(LiquidityChange { transfer_fees: (0, 7_001), ..liquidity_change() }, ValidationMode::Basic),
+        Err("fee_exceeds_amount")
+    );
+
+    let empty = LiquidityChange { liquidity: 0, ..liquidity_change() };
+    assert_eq!(check(empty, ValidationMode::Basic), Ok(()));
+    assert_eq!(check(empty, ValidationMode::Strict), Err("zero_amount"));
+}
+
+#[test]
+fn test_pool_initialized_checks() {
+    let pool = |tick_spacing: u16, initial_sqrt_price: u128| OrcaWhirlpoolPoolInitializedEvent {
+        whirlpool: Pubkey::new_unique(),
+        whirlpools_config: Pubkey::new_unique(),
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        tick_spacing,
+        token_program_a: Pubkey::new_unique(),
+        token_program_b: Pubkey::new_unique(),
+        decimals_a: 9,
+        decimals_b: 6,
+        initial_sqrt_price,
+    };
+    let check = |event: OrcaWhirlpoolPoolInitializedEvent| {
+        validate_orca_pool_initialized(&event, ValidationMode::Basic).map_err(|violation| violation.check)
+    };
+
+    assert_eq!(check(pool(64, 1 << 64)), Ok(()));
+    assert_eq!(check(pool(0, 1 << 64)), Err("tick_range"));
+    assert_eq!(check(pool(64, 1)), Err("price_range"));
+}
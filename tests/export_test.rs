@@ -0,0 +1,197 @@
+use chrono::{ Duration, Utc };
+use flate2::read::GzDecoder;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::io::{ Read, Write };
+
+use indexer::export::{
+    export_orca_trades_csv,
+    CompressedWriter,
+    ExportCompression,
+    TradeExport,
+    TRADE_EXPORT_COLUMNS,
+};
+use indexer::models::orca::whirlpool::{
+    OrcaWhirlpoolEvent,
+    OrcaWhirlpoolTradedEventRecord,
+    OrcaWhirlpoolTradedRecord,
+};
+use indexer::{ OrcaWhirlpoolPoolRecord, OrcaWhirlpoolRepository };
+
+mod common;
+
+// Helper to build a trade of 2 token A for 300 token B, or the reverse
+fn trade(whirlpool: &str, slot: i64, a_to_b: bool) -> OrcaWhirlpoolTradedEventRecord {
+    let (input_amount, output_amount) = if a_to_b {
+        (2_000_000_000, 300_000_000)
+    } else {
+        (300_000_000, 2_000_000_000)
+    };
+    OrcaWhirlpoolTradedEventRecord {
+        base: OrcaWhirlpoolEvent {
+            id: 0,
+            signature: Signature::new_unique().to_string(),
+            event_index: 0,
+            whirlpool: whirlpool.to_string(),
+            event_type: "Traded".to_string(),
+            dex: "orca".to_string(),
+            slot: Some(slot),
+            block_time: None,
+            fee_payer: None,
+            version: 1,
+            timestamp: Utc::now(),
+            enrichment: None,
+        },
+        data: OrcaWhirlpoolTradedRecord {
+            event_id: 0,
+            a_to_b,
+            pre_sqrt_price: 1,
+            post_sqrt_price: 1,
+            input_amount,
+            output_amount,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            lp_fee: 3,
+            protocol_fee: 1,
+            price: Some(150.0),
+        },
+    }
+}
+
+// Helper to run an export into memory, returning the row count and CSV lines
+async fn export_lines(db_pool: &sqlx::PgPool, export: &TradeExport) -> (u64, Vec<String>) {
+    let mut output = Vec::new();
+    let rows = export_orca_trades_csv(db_pool, export, &mut output).await.unwrap();
+    let lines = String::from_utf8(output).unwrap().lines().map(str::to_string).collect();
+    (rows, lines)
+}
+
+// Test that a pool's trades are exported with a header, scaled amounts and the time range applied
+#[tokio::test]
+async fn test_export_orca_trades_csv() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    // A SOL/USDC-like pool with 9 and 6 decimals
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    repository
+        .upsert_pool(
+            &(OrcaWhirlpoolPoolRecord {
+                whirlpool: whirlpool.clone(),
+                token_mint_a: Pubkey::new_from_array(rand::random()).to_string(),
+                token_mint_b: Pubkey::new_from_array(rand::random()).to_string(),
+                token_name_a: Some("SOL".to_string()),
+                token_name_b: Some("USDC".to_string()),
+                pool_name: Some("SOL/USDC".to_string()),
+                decimals_a: 9,
+                decimals_b: 6,
+            })
+        ).await
+        .unwrap();
+
+    // Four recent trades, one in the other direction, and one from two days ago
+    for (slot, a_to_b) in [
+        (100, true),
+        (101, false),
+        (102, true),
+        (103, true),
+    ] {
+        repository.insert_traded_event(trade(&whirlpool, slot, a_to_b)).await.unwrap();
+    }
+    let mut old_trade = trade(&whirlpool, 50, true);
+    old_trade.base.block_time = Some(Utc::now() - Duration::days(2));
+    repository.insert_traded_event(old_trade).await.unwrap();
+
+    // Trades of other pools are not exported
+    let other_pool = Pubkey::new_from_array(rand::random()).to_string();
+    repository.insert_traded_event(trade(&other_pool, 100, true)).await.unwrap();
+
+    // Small batches, so the export pages through the rows
+    let export = TradeExport::new(whirlpool.clone()).with_batch_size(2);
+    let (rows, lines) = export_lines(&db_pool, &export).await;
+    assert_eq!(rows, 5);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], TRADE_EXPORT_COLUMNS.join(","));
+    assert!(lines[0].starts_with("signature,slot,block_time,indexed_at,whirlpool,a_to_b,"));
+
+    // Amounts are scaled by the decimals of the token on each side
+    let column = |line: &str, name: &str| {
+        let index = TRADE_EXPORT_COLUMNS.iter()
+            .position(|column| *column == name)
+            .unwrap();
+        line.split(',').nth(index).unwrap().to_string()
+    };
+    assert_eq!(column(&lines[1], "slot"), "100");
+    assert_eq!(column(&lines[1], "whirlpool"), whirlpool);
+    assert_eq!(column(&lines[1], "input_amount"), "2000000000");
+    assert_eq!(column(&lines[1], "input_amount_ui"), "2");
+    assert_eq!(column(&lines[1], "output_amount_ui"), "300");
+    assert_eq!(column(&lines[2], "a_to_b"), "false");
+    assert_eq!(column(&lines[2], "input_amount_ui"), "300");
+    assert_eq!(column(&lines[2], "output_amount_ui"), "2");
+    assert_eq!(column(&lines[1], "block_time"), "");
+    assert_eq!(column(&lines[1], "price"), "150");
+    assert!(lines[1..].iter().all(|line| line.split(',').count() == TRADE_EXPORT_COLUMNS.len()));
+
+    // The time range leaves out the old trade
+    let export = export.with_range(Some(Utc::now() - Duration::hours(1)), Some(Utc::now()));
+    let (rows, lines) = export_lines(&db_pool, &export).await;
+    assert_eq!(rows, 4);
+    assert_eq!(lines.len(), 5);
+
+    // Without token metadata, the scaled amounts are left empty
+    let (rows, lines) = export_lines(&db_pool, &TradeExport::new(other_pool)).await;
+    assert_eq!(rows, 1);
+    assert_eq!(column(&lines[1], "input_amount_ui"), "");
+}
+
+// Test parsing compression names from CLI values
+#[test]
+fn test_export_compression_from_str() {
+    assert_eq!("gzip".parse::<ExportCompression>().unwrap(), ExportCompression::Gzip);
+    assert_eq!("ZSTD".parse::<ExportCompression>().unwrap(), ExportCompression::Zstd);
+    assert_eq!("none".parse::<ExportCompression>().unwrap(), ExportCompression::None);
+    assert!("bzip2".parse::<ExportCompression>().is_err());
+}
+
+// Test that zstd output decompresses back to what was written
+#[test]
+fn test_zstd_writer_round_trip() {
+    let mut writer = CompressedWriter::new(Vec::new(), ExportCompression::Zstd).unwrap();
+    writer.write_all(b"signature,slot\nabc,1\n").unwrap();
+    let compressed = writer.finish().unwrap();
+
+    assert_eq!(zstd::decode_all(compressed.as_slice()).unwrap(), b"signature,slot\nabc,1\n");
+}
+
+// Test that a gzip export decompresses to the header and the pool's rows
+#[tokio::test]
+async fn test_export_gzip() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    for slot in 100..103 {
+        repository.insert_traded_event(trade(&whirlpool, slot, true)).await.unwrap();
+    }
+
+    let mut writer = CompressedWriter::new(Vec::new(), ExportCompression::Gzip).unwrap();
+    let export = TradeExport::new(whirlpool.clone());
+    let rows = export_orca_trades_csv(&db_pool, &export, &mut writer).await.unwrap();
+    let compressed = writer.finish().unwrap();
+    assert_eq!(rows, 3);
+    assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+
+    let mut csv = String::new();
+    GzDecoder::new(compressed.as_slice()).read_to_string(&mut csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], TRADE_EXPORT_COLUMNS.join(","));
+    assert!(lines[1..].iter().all(|line| line.contains(&whirlpool)));
+}
@@ -0,0 +1,88 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+use tokio::net::TcpStream;
+
+use indexer::health::{ self, HealthTracker };
+
+// Helper to issue a plain HTTP GET and return the full response
+async fn http_get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+// Test that the health snapshot reflects recorded events, backfills and connection state
+#[test]
+fn test_health_snapshot() {
+    let tracker = HealthTracker::new();
+    let health = tracker.snapshot();
+    assert_eq!(health.last_event_at, None);
+    assert_eq!(health.last_backfill_at, None);
+    assert!(!health.websocket_connected);
+    assert_eq!(health.seconds_since_last_message, None);
+    assert_eq!(health.events_processed_total, 0);
+
+    // Before the first message the connection is not considered dead
+    assert!(!tracker.is_connection_dead(Duration::ZERO));
+
+    tracker.set_websocket_connected(true);
+    tracker.record_message();
+    tracker.record_event();
+    tracker.record_event();
+    tracker.record_backfill();
+
+    let health = tracker.snapshot();
+    assert!(health.last_event_at.is_some());
+    assert!(health.last_backfill_at.is_some());
+    assert!(health.websocket_connected);
+    assert_eq!(health.seconds_since_last_message, Some(0));
+    assert_eq!(health.events_processed_total, 2);
+    assert!(!tracker.is_connection_dead(Duration::from_secs(60)));
+}
+
+// Test that /healthz returns the snapshot, and 503 once the connection is dead
+#[tokio::test]
+async fn test_health_server() {
+    // Reserve a free port, then hand it to the server
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let tracker = Arc::new(HealthTracker::new());
+    tracker.set_websocket_connected(true);
+    tracker.record_message();
+    tokio::spawn(health::serve(addr, tracker.clone(), Duration::from_millis(200)));
+
+    // Wait for the server to come up
+    let mut response = String::new();
+    for _ in 0..50 {
+        if TcpStream::connect(addr).await.is_ok() {
+            response = http_get(addr, "/healthz").await;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("\"websocket_connected\":true"));
+    assert!(response.contains("\"events_processed_total\":0"));
+
+    // No messages for longer than the threshold
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let response = http_get(addr, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 503"));
+
+    // A new message makes it healthy again
+    tracker.record_message();
+    let response = http_get(addr, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 200"));
+
+    let response = http_get(addr, "/unknown").await;
+    assert!(response.starts_with("HTTP/1.1 404"));
+}
@@ -0,0 +1,86 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+use tokio::net::TcpStream;
+
+use indexer::error::IndexerError;
+use indexer::metrics;
+
+// Helper to issue a plain HTTP GET and return the full response
+async fn http_get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+// Test that recorded values show up in the exported metrics
+#[test]
+fn test_metrics_recording() {
+    metrics::init().unwrap();
+
+    metrics::record_events_parsed("test_dex", 3);
+    metrics::record_event_persisted("test_dex");
+    metrics::record_backfill_transaction("test_dex");
+    metrics::record_websocket_reconnect();
+    metrics::set_websocket_connected(true);
+    metrics::record_rpc_error(
+        "test_dex",
+        &IndexerError::RateLimited("HTTP status 429 Too Many Requests".to_string()).into()
+    );
+    metrics::record_quarantined_event("test_dex", "price_range");
+
+    let output = metrics::gather();
+    assert!(output.contains("indexer_events_parsed_total{dex=\"test_dex\"}"));
+    assert!(output.contains("indexer_events_persisted_total{dex=\"test_dex\"}"));
+    assert!(output.contains("indexer_backfill_transactions_fetched_total{dex=\"test_dex\"}"));
+    assert!(output.contains("indexer_websocket_reconnects_total"));
+    assert!(output.contains("indexer_websocket_connected 1"));
+    assert!(output.contains("indexer_rpc_errors_total{dex=\"test_dex\",kind=\"rate_limit\"}"));
+    assert!(
+        output.contains("indexer_quarantined_events_total{check=\"price_range\",dex=\"test_dex\"} 1")
+    );
+}
+
+// Test that RPC errors are classified by their IndexerError variant, through added context
+#[test]
+fn test_rpc_error_kind() {
+    let kind = |err: IndexerError| {
+        metrics::rpc_error_kind(&anyhow::Error::from(err).context("Failed to fetch transaction"))
+    };
+    assert_eq!(kind(IndexerError::RateLimited("429 Too Many Requests".to_string())), "rate_limit");
+    assert_eq!(kind(IndexerError::Transient("request timed out".to_string())), "transient");
+    assert_eq!(kind(IndexerError::TransactionNotFound("abc".to_string())), "not_found");
+    assert_eq!(kind(IndexerError::Permanent("invalid params".to_string())), "permanent");
+    assert_eq!(metrics::rpc_error_kind(&anyhow::anyhow!("429 Too Many Requests")), "other");
+}
+
+// Test that the HTTP server serves /metrics and rejects other paths
+#[tokio::test]
+async fn test_metrics_server() {
+    // Reserve a free port, then hand it to the server
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    tokio::spawn(metrics::serve(addr));
+
+    // Wait for the server to come up
+    let mut response = String::new();
+    for _ in 0..50 {
+        if TcpStream::connect(addr).await.is_ok() {
+            response = http_get(addr, "/metrics").await;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("indexer_websocket_connected"));
+
+    let response = http_get(addr, "/unknown").await;
+    assert!(response.starts_with("HTTP/1.1 404"));
+}
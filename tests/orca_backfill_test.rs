@@ -0,0 +1,216 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::time::Duration;
+
+use indexer::indexers::{ ConnectionConfig, DexIndexer, OrcaWhirlpoolIndexer };
+use indexer::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+use indexer::TRADED_EVENT_DISCRIMINATOR;
+
+mod common;
+
+use common::mock_rpc::{ MockRpc, BLOCK_TIME_BASE };
+
+// Helper to build the logs of a swap on a pool, emitting its Traded event
+fn swap_logs(whirlpool: &Pubkey) -> Vec<String> {
+    let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(whirlpool.as_ref());
+    for _ in 0..4 {
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+    }
+    data.push(1);
+    for amount in [1_000u64, 990, 0, 0, 1, 3] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    for sqrt_price in [1u128 << 64, 1u128 << 64] {
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+    }
+    vec![
+        format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+        "Program log: Instruction: Swap".to_string(),
+        format!("Program data: {}", STANDARD.encode(&data)),
+        format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID)
+    ]
+}
+
+// Helper to read a pool's stored events as (signature, slot, block time), oldest first
+async fn stored_events(db_pool: &sqlx::PgPool, whirlpool: &Pubkey) -> Vec<(String, i64, i64)> {
+    sqlx
+        ::query_as(
+            "SELECT signature, slot, EXTRACT(EPOCH FROM block_time)::BIGINT
+             FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 ORDER BY slot"
+        )
+        .bind(whirlpool.to_string())
+        .fetch_all(db_pool).await
+        .unwrap()
+}
+
+// Helper to list the expected stored events of the given transactions
+fn expected_events(transactions: &[(Signature, u64)]) -> Vec<(String, i64, i64)> {
+    transactions
+        .iter()
+        .map(|(signature, slot)| (signature.to_string(), *slot as i64, BLOCK_TIME_BASE + (*slot as i64)))
+        .collect()
+}
+
+// Test that the initial backfill stores a pool's history from the RPC node, and that
+// the next scheduled backfill only fetches what happened since
+#[tokio::test]
+async fn test_backfill_through_mock_rpc() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let other_pool = Pubkey::new_from_array(rand::random());
+
+    let rpc = MockRpc::new();
+    let mut transactions: Vec<(Signature, u64)> = (100..103)
+        .map(|slot| (rpc.add_transaction(&whirlpool, slot, swap_logs(&whirlpool)), slot))
+        .collect();
+    rpc.add_transaction(&other_pool, 103, swap_logs(&other_pool));
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(database.pool.clone(), Some(&pools), connection_config).await
+        .unwrap()
+        .with_rpc_client(rpc.rpc_client());
+
+    indexer.perform_backfill().await.unwrap();
+    assert_eq!(stored_events(&database.pool, &whirlpool).await, expected_events(&transactions));
+    assert_eq!(rpc.fetched().len(), 3);
+    let last_signature = indexer.signature_store().get_signature(&whirlpool, "orca").await.unwrap();
+    assert_eq!(last_signature, Some(transactions[2].0.to_string()));
+
+    // A new swap lands while the indexer runs
+    transactions.push((rpc.add_transaction(&whirlpool, 104, swap_logs(&whirlpool)), 104));
+    indexer.perform_scheduled_backfill().await.unwrap();
+    assert_eq!(stored_events(&database.pool, &whirlpool).await, expected_events(&transactions));
+    assert_eq!(rpc.fetched()[3..], [transactions[3].0]);
+}
+
+// Test that pools are backfilled several at a time, never more than the pool concurrency
+#[tokio::test]
+async fn test_backfill_pools_concurrently() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let whirlpools: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_from_array(rand::random())).collect();
+
+    let rpc = MockRpc::new();
+    rpc.set_delay(Duration::from_millis(20));
+    let transactions: Vec<(Pubkey, Signature, u64)> = whirlpools
+        .iter()
+        .zip(100..)
+        .map(|(whirlpool, slot)| {
+            (*whirlpool, rpc.add_transaction(whirlpool, slot, swap_logs(whirlpool)), slot)
+        })
+        .collect();
+
+    // One request per pool at a time, so requests in flight are pools in flight
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    )
+        .with_backfill_fetch_concurrency(1)
+        .with_backfill_pool_concurrency(3);
+    let pools: Vec<String> = whirlpools.iter().map(|whirlpool| whirlpool.to_string()).collect();
+    let indexer = OrcaWhirlpoolIndexer::new(database.pool.clone(), Some(&pools), connection_config).await
+        .unwrap()
+        .with_rpc_client(rpc.rpc_client());
+
+    indexer.perform_backfill().await.unwrap();
+    assert_eq!(rpc.max_in_flight(), 3);
+    for (whirlpool, signature, slot) in transactions {
+        assert_eq!(
+            stored_events(&database.pool, &whirlpool).await,
+            expected_events(&[(signature, slot)])
+        );
+    }
+}
+
+// Test that a pool whose backfill panics doesn't stop the pools backfilled alongside it
+#[tokio::test]
+async fn test_backfill_pool_panic_isolated() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let whirlpools: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_from_array(rand::random())).collect();
+
+    let rpc = MockRpc::new();
+    rpc.set_delay(Duration::from_millis(20));
+    let transactions: Vec<(Pubkey, Signature, u64)> = whirlpools
+        .iter()
+        .zip(100..)
+        .map(|(whirlpool, slot)| {
+            (*whirlpool, rpc.add_transaction(whirlpool, slot, swap_logs(whirlpool)), slot)
+        })
+        .collect();
+    rpc.panic_on(&whirlpools[1]);
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    ).with_backfill_pool_concurrency(3);
+    let pools: Vec<String> = whirlpools.iter().map(|whirlpool| whirlpool.to_string()).collect();
+    let indexer = OrcaWhirlpoolIndexer::new(database.pool.clone(), Some(&pools), connection_config).await
+        .unwrap()
+        .with_rpc_client(rpc.rpc_client());
+
+    indexer.perform_backfill().await.unwrap();
+    for (whirlpool, signature, slot) in transactions {
+        let expected = if whirlpool == whirlpools[1] {
+            Vec::new()
+        } else {
+            expected_events(&[(signature, slot)])
+        };
+        assert_eq!(stored_events(&database.pool, &whirlpool).await, expected);
+    }
+}
+
+// Test that a reconciliation pass re-indexes a transaction whose events went missing
+// behind the last signature, which the scheduled backfill never looks at again
+#[tokio::test]
+async fn test_reconciliation_recovers_missing_events() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let whirlpool = Pubkey::new_from_array(rand::random());
+
+    let rpc = MockRpc::new();
+    let transactions: Vec<(Signature, u64)> = (100..103)
+        .map(|slot| (rpc.add_transaction(&whirlpool, slot, swap_logs(&whirlpool)), slot))
+        .collect();
+
+    // The mock node's block times are years old, so look back far enough to cover them
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    ).with_reconciliation_window(Duration::from_secs(100 * 365 * 24 * 60 * 60));
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(database.pool.clone(), Some(&pools), connection_config).await
+        .unwrap()
+        .with_rpc_client(rpc.rpc_client());
+    indexer.perform_backfill().await.unwrap();
+
+    // The middle transaction's events are lost, as if they had failed to persist
+    sqlx
+        ::query("DELETE FROM apestrong.orca_whirlpool_events WHERE signature = $1")
+        .bind(transactions[1].0.to_string())
+        .execute(&database.pool).await
+        .unwrap();
+
+    indexer.perform_scheduled_backfill().await.unwrap();
+    assert_eq!(stored_events(&database.pool, &whirlpool).await.len(), 2);
+
+    let fetched_before = rpc.fetched().len();
+    assert_eq!(indexer.perform_reconciliation().await.unwrap(), (1, 1));
+    assert_eq!(stored_events(&database.pool, &whirlpool).await, expected_events(&transactions));
+    assert_eq!(rpc.fetched()[fetched_before..], [transactions[1].0]);
+
+    // Nothing is missing any more, so a second pass indexes nothing
+    assert_eq!(indexer.perform_reconciliation().await.unwrap(), (0, 0));
+}
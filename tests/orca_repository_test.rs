@@ -0,0 +1,1755 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rust_decimal::Decimal;
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::message::MessageHeader;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+use std::collections::HashSet;
+use std::sync::{ Arc, Mutex };
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction,
+    EncodedTransactionWithStatusMeta,
+    TransactionStatusMeta,
+    UiMessage,
+    UiRawMessage,
+    UiTransaction,
+    UiTransactionStatusMeta,
+};
+
+use indexer::enrichment::{ Enrichment, EnrichmentFailurePolicy, EventEnricher };
+use indexer::events::IndexedEvent;
+use indexer::indexers::{ ConnectionConfig, DexIndexer, OrcaWhirlpoolIndexer, OrcaWhirlpoolParsedEvent };
+use indexer::models::orca::whirlpool::{
+    OrcaWhirlpoolEvent,
+    OrcaWhirlpoolLiquidityDecreasedEventRecord,
+    OrcaWhirlpoolLiquidityIncreasedEventRecord,
+    OrcaWhirlpoolLiquidityRecord,
+    OrcaWhirlpoolPoolInitializedEventRecord,
+    OrcaWhirlpoolPoolInitializedRecord,
+    OrcaWhirlpoolTradedEvent,
+    OrcaWhirlpoolTradedEventRecord,
+    OrcaWhirlpoolTradedRecord,
+};
+use indexer::pool_loader::{ PoolRecord, TokenInfo };
+use indexer::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+use indexer::sinks::StdoutSink;
+use indexer::utils::price::sqrt_price_to_price;
+use indexer::validation::ValidationMode;
+use indexer::backfill_manager::parse_signature_list;
+use indexer::db::repositories::{
+    decode_liquidity_amounts,
+    PoolDeletion,
+    PoolStats,
+    SlotGap,
+    SlotGapReport,
+    TradedEventDecoded,
+};
+use indexer::db::common::from_numeric;
+use indexer::db::{ QuarantineStore, TransactionLogStore };
+use indexer::db::signature_store::{ DbSignatureStore, SignatureStore };
+use indexer::{ OrcaWhirlpoolPoolRecord, OrcaWhirlpoolRepository, TRADED_EVENT_DISCRIMINATOR };
+
+mod common;
+
+// Helper to build a traded event record for a pool at a slot
+fn traded_event(whirlpool: &str, signature: &str, slot: i64) -> OrcaWhirlpoolTradedEventRecord {
+    OrcaWhirlpoolTradedEventRecord {
+        base: OrcaWhirlpoolEvent {
+            id: 0,
+            signature: signature.to_string(),
+            event_index: 0,
+            whirlpool: whirlpool.to_string(),
+            event_type: "traded".to_string(),
+            dex: "orca".to_string(),
+            slot: Some(slot),
+            block_time: None,
+            fee_payer: None,
+            version: 1,
+            timestamp: chrono::Utc::now(),
+            enrichment: None,
+        },
+        data: OrcaWhirlpoolTradedRecord {
+            event_id: 0,
+            a_to_b: true,
+            pre_sqrt_price: 1,
+            post_sqrt_price: 1,
+            input_amount: 1_000,
+            output_amount: 990,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            lp_fee: 3,
+            protocol_fee: 1,
+            price: None,
+        },
+    }
+}
+
+// Helper to build a liquidity change record for a position
+fn liquidity_record(position: &str, liquidity: u128) -> OrcaWhirlpoolLiquidityRecord {
+    OrcaWhirlpoolLiquidityRecord {
+        event_id: 0,
+        position: position.to_string(),
+        tick_lower_index: -100,
+        tick_upper_index: 100,
+        liquidity,
+        token_a_amount: 10,
+        token_b_amount: 20,
+        token_a_transfer_fee: 0,
+        token_b_transfer_fee: 0,
+    }
+}
+
+// Test that events are grouped and counted per slot within the requested range
+#[tokio::test]
+async fn test_events_by_slot() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    // Use a fresh pool address so earlier runs don't affect the counts
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let slots = [100, 100, 100, 101, 103, 110];
+    for slot in slots.iter() {
+        let signature = Signature::new_unique().to_string();
+        repository.insert_traded_event(traded_event(&whirlpool, &signature, *slot)).await.unwrap();
+    }
+
+    let counts = repository.events_by_slot(&whirlpool, 100, 105).await.unwrap();
+    assert_eq!(counts, vec![(100, 3), (101, 1), (103, 1)]);
+
+    // The range is inclusive on both ends
+    let counts = repository.events_by_slot(&whirlpool, 103, 110).await.unwrap();
+    assert_eq!(counts, vec![(103, 1), (110, 1)]);
+
+    // Other pools are not counted
+    let other_pool = Pubkey::new_from_array(rand::random()).to_string();
+    assert!(repository.events_by_slot(&other_pool, 0, 1_000).await.unwrap().is_empty());
+}
+
+// Test that a long stretch without events in a busy pool is reported as a gap
+#[tokio::test]
+async fn test_find_slot_gaps() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    // Events every other slot, then nothing for 800 slots, then events again
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let slots = (100..=200).step_by(2).chain((1_000..=1_010).step_by(2));
+    for slot in slots {
+        let signature = Signature::new_unique().to_string();
+        repository.insert_traded_event(traded_event(&whirlpool, &signature, slot)).await.unwrap();
+    }
+
+    let report = repository.find_slot_gaps(&whirlpool).await.unwrap();
+    assert_eq!(report.oldest_slot, Some(100));
+    assert_eq!(report.newest_slot, Some(1_010));
+    assert_eq!(report.gaps, vec![SlotGap { after_slot: 200, before_slot: 1_000 }]);
+    assert_eq!(report.gaps[0].missing_slots(), 799);
+    assert_eq!(report.contiguous_from(), Some(1_000));
+
+    // A pool without events has no range and no gaps
+    let other_pool = Pubkey::new_from_array(rand::random()).to_string();
+    let report = repository.find_slot_gaps(&other_pool).await.unwrap();
+    assert_eq!(report, SlotGapReport::default());
+    assert_eq!(report.contiguous_from(), None);
+}
+
+// Test that a pool's events can be filtered by the layout version they were stored from
+#[tokio::test]
+async fn test_get_events_by_version() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let mut signatures = Vec::new();
+    for (slot, version) in [(100, 1), (101, 2), (102, 1)] {
+        let signature = Signature::new_unique().to_string();
+        let mut event = traded_event(&whirlpool, &signature, slot);
+        event.base.version = version;
+        repository.insert_traded_event(event).await.unwrap();
+        signatures.push(signature);
+    }
+
+    let version_1 = repository.get_events_by_version(&whirlpool, 1).await.unwrap();
+    let found: Vec<_> = version_1
+        .iter()
+        .map(|event| event.signature.clone())
+        .collect();
+    assert_eq!(found, vec![signatures[0].clone(), signatures[2].clone()]);
+    assert!(version_1.iter().all(|event| event.version == 1 && event.whirlpool == whirlpool));
+
+    let version_2 = repository.get_events_by_version(&whirlpool, 2).await.unwrap();
+    assert_eq!(version_2.len(), 1);
+    assert_eq!(version_2[0].signature, signatures[1]);
+    assert_eq!(version_2[0].slot, Some(101));
+    assert!(repository.get_events_by_version(&whirlpool, 3).await.unwrap().is_empty());
+}
+
+// Test that rolling back a pool deletes its events after the slot, with their details
+#[tokio::test]
+async fn test_delete_events_after_slot() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let other_pool = Pubkey::new_from_array(rand::random()).to_string();
+    let mut event_ids = Vec::new();
+    for slot in [100, 101, 105] {
+        let signature = Signature::new_unique().to_string();
+        event_ids.push(
+            repository
+                .insert_traded_event(traded_event(&whirlpool, &signature, slot)).await
+                .unwrap()
+                .unwrap()
+        );
+    }
+    let signature = Signature::new_unique().to_string();
+    repository.insert_traded_event(traded_event(&other_pool, &signature, 105)).await.unwrap();
+
+    // Events without a recorded slot can't be placed relative to the reorg, so they stay
+    let mut unknown_slot = traded_event(&whirlpool, &Signature::new_unique().to_string(), 0);
+    unknown_slot.base.slot = None;
+    repository.insert_traded_event(unknown_slot).await.unwrap();
+
+    assert_eq!(repository.delete_events_after_slot(&whirlpool, 100).await.unwrap(), 2);
+    assert_eq!(repository.events_by_slot(&whirlpool, 0, 1_000).await.unwrap(), vec![(100, 1)]);
+    assert_eq!(repository.events_by_slot(&other_pool, 0, 1_000).await.unwrap(), vec![(105, 1)]);
+
+    let (remaining,): (i64,) = sqlx
+        ::query_as(
+            "SELECT COUNT(*) FROM apestrong.orca_traded_events WHERE event_id = ANY($1)"
+        )
+        .bind(&event_ids)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(remaining, 1);
+
+    let (unknown_slot_events,): (i64,) = sqlx
+        ::query_as(
+            "SELECT COUNT(*) FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 AND slot IS NULL"
+        )
+        .bind(&whirlpool)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(unknown_slot_events, 1);
+
+    // Nothing is left to delete the second time
+    assert_eq!(repository.delete_events_after_slot(&whirlpool, 100).await.unwrap(), 0);
+}
+
+// Test that pool stats aggregate trades and liquidity changes since the given time
+#[tokio::test]
+async fn test_get_pool_stats() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    // Two recent trades in opposite directions, and one from before `since`
+    let mut trade = traded_event(&whirlpool, &Signature::new_unique().to_string(), 100);
+    repository.insert_traded_event(trade.clone()).await.unwrap();
+    trade.base.signature = Signature::new_unique().to_string();
+    trade.data.a_to_b = false;
+    trade.data.input_amount = 500;
+    trade.data.output_amount = 480;
+    repository.insert_traded_event(trade.clone()).await.unwrap();
+    trade.base.signature = Signature::new_unique().to_string();
+    trade.base.block_time = Some(since - chrono::Duration::days(1));
+    repository.insert_traded_event(trade.clone()).await.unwrap();
+
+    // Two positions add liquidity and one of them removes part of it
+    let base_event = |event_type: &str| OrcaWhirlpoolEvent {
+        signature: Signature::new_unique().to_string(),
+        event_index: 0,
+        event_type: event_type.to_string(),
+        ..trade.base.clone()
+    };
+    let position_a = Pubkey::new_unique().to_string();
+    let position_b = Pubkey::new_unique().to_string();
+    for (position, liquidity) in [
+        (&position_a, 5_000),
+        (&position_b, 3_000),
+    ] {
+        repository
+            .insert_liquidity_increased_event(OrcaWhirlpoolLiquidityIncreasedEventRecord {
+                base: OrcaWhirlpoolEvent {
+                    block_time: None,
+                    ..base_event("LiquidityIncreased")
+                },
+                data: liquidity_record(position, liquidity),
+            }).await
+            .unwrap();
+    }
+    repository
+        .insert_liquidity_decreased_event(OrcaWhirlpoolLiquidityDecreasedEventRecord {
+            base: OrcaWhirlpoolEvent {
+                block_time: None,
+                ..base_event("LiquidityDecreased")
+            },
+            data: liquidity_record(&position_a, 2_000),
+        }).await
+        .unwrap();
+
+    // Events of other pools are not counted
+    let other_pool = Pubkey::new_from_array(rand::random()).to_string();
+    let other_trade = traded_event(&other_pool, &Signature::new_unique().to_string(), 100);
+    repository.insert_traded_event(other_trade).await.unwrap();
+
+    let stats = repository.get_pool_stats(&whirlpool, since).await.unwrap();
+    assert_eq!(stats, PoolStats {
+        total_trades: 2,
+        input_volume: 1_500,
+        output_volume: 1_470,
+        net_liquidity_change: 6_000,
+        positions_touched: 2,
+    });
+
+    // Moving `since` back includes the older trade
+    let stats = repository.get_pool_stats(&whirlpool, since - chrono::Duration::days(2)).await.unwrap();
+    assert_eq!(stats.total_trades, 3);
+    assert_eq!(stats.input_volume, 2_000);
+
+    // A pool without events has all-zero stats
+    let empty_pool = Pubkey::new_from_array(rand::random()).to_string();
+    assert_eq!(repository.get_pool_stats(&empty_pool, since).await.unwrap(), PoolStats::default());
+}
+
+// Test that a position's history merges its increases and decreases in time order
+#[tokio::test]
+async fn test_get_position_history() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let position = Pubkey::new_from_array(rand::random()).to_string();
+    let other_position = Pubkey::new_from_array(rand::random()).to_string();
+    let opened = chrono::Utc::now() - chrono::Duration::hours(3);
+    let base_event = |event_type: &str, hours: i64| OrcaWhirlpoolEvent {
+        signature: Signature::new_unique().to_string(),
+        event_index: 0,
+        event_type: event_type.to_string(),
+        block_time: Some(opened + chrono::Duration::hours(hours)),
+        ..traded_event(&whirlpool, "", 100).base
+    };
+
+    // Stored out of order: the position is opened last, and a large increase
+    // checks that liquidity beyond 64 bits survives the round trip
+    let increase = |hours: i64, liquidity: u128, position: &str| {
+        OrcaWhirlpoolLiquidityIncreasedEventRecord {
+            base: base_event("LiquidityIncreased", hours),
+            data: liquidity_record(position, liquidity),
+        }
+    };
+    repository.insert_liquidity_increased_event(increase(1, 1 << 100, &position)).await.unwrap();
+    repository
+        .insert_liquidity_decreased_event(OrcaWhirlpoolLiquidityDecreasedEventRecord {
+            base: base_event("LiquidityDecreased", 2),
+            data: liquidity_record(&position, 2_000),
+        }).await
+        .unwrap();
+    repository.insert_liquidity_increased_event(increase(0, 5_000, &position)).await.unwrap();
+    repository.insert_liquidity_increased_event(increase(1, 7_000, &other_position)).await.unwrap();
+
+    let history = repository.get_position_history(&position).await.unwrap();
+    let changes: Vec<(&str, i128, i128)> = history
+        .iter()
+        .map(|event| (event.event_type.as_str(), event.delta_liquidity, event.balance))
+        .collect();
+    assert_eq!(changes, vec![
+        ("LiquidityIncreased", 5_000, 5_000),
+        ("LiquidityIncreased", 1 << 100, (1 << 100) + 5_000),
+        ("LiquidityDecreased", -2_000, (1 << 100) + 3_000)
+    ]);
+    assert!(history.iter().all(|event| event.whirlpool == whirlpool));
+    assert_eq!(history[0].block_time.map(|time| time.timestamp()), Some(opened.timestamp()));
+    assert_eq!((history[0].tick_lower_index, history[0].tick_upper_index), (-100, 100));
+    assert_eq!((history[2].token_a_amount, history[2].token_b_amount), (10, 20));
+
+    // A position without indexed changes has no history
+    let unknown = Pubkey::new_from_array(rand::random()).to_string();
+    assert!(repository.get_position_history(&unknown).await.unwrap().is_empty());
+}
+
+// Test that unique traders counts the distinct fee payers of a pool's trades in the range
+#[tokio::test]
+async fn test_unique_traders() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let now = chrono::Utc::now();
+    let (payer_a, payer_b) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+
+    // Payer A trades twice, payer B once, and one trade has no recorded payer
+    for (fee_payer, block_time) in [
+        (Some(&payer_a), now),
+        (Some(&payer_a), now),
+        (Some(&payer_b), now),
+        (None, now),
+        (Some(&Pubkey::new_unique().to_string()), now - chrono::Duration::days(2)),
+    ] {
+        let mut trade = traded_event(&whirlpool, &Signature::new_unique().to_string(), 100);
+        trade.base.fee_payer = fee_payer.cloned();
+        trade.base.block_time = Some(block_time);
+        repository.insert_traded_event(trade).await.unwrap();
+    }
+
+    // Another pool's traders are not counted
+    let mut other_trade = traded_event(
+        &Pubkey::new_from_array(rand::random()).to_string(),
+        &Signature::new_unique().to_string(),
+        100
+    );
+    other_trade.base.fee_payer = Some(Pubkey::new_unique().to_string());
+    repository.insert_traded_event(other_trade).await.unwrap();
+
+    let from = now - chrono::Duration::hours(1);
+    let to = now + chrono::Duration::hours(1);
+    assert_eq!(repository.unique_traders(&whirlpool, from, to).await.unwrap(), 2);
+
+    // The older trade's payer is counted once the range includes it, but not at the end bound
+    let from = now - chrono::Duration::days(3);
+    assert_eq!(repository.unique_traders(&whirlpool, from, to).await.unwrap(), 3);
+    assert_eq!(repository.unique_traders(&whirlpool, from, now).await.unwrap(), 1);
+}
+
+// Test that an inserted traded event can be queried back with all its fields
+#[tokio::test]
+async fn test_traded_event_round_trip() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let signature = Signature::new_unique().to_string();
+    let mut event = traded_event(&whirlpool, &signature, 42);
+    event.data.price = Some(150.25);
+    let event_id = repository.insert_traded_event(event).await.unwrap();
+
+    let row: (String, String, Option<i64>, bool, i64, i64, i64, i64, Option<f64>) = sqlx
+        ::query_as(
+            "SELECT e.whirlpool, e.event_type, e.slot, t.a_to_b, t.input_amount::BIGINT, t.output_amount::BIGINT, t.lp_fee::BIGINT, t.protocol_fee::BIGINT, t.price FROM apestrong.orca_whirlpool_events e JOIN apestrong.orca_traded_events t ON e.id = t.event_id WHERE e.signature = $1"
+        )
+        .bind(&signature)
+        .fetch_one(&database.pool).await
+        .unwrap();
+    assert_eq!(row, (whirlpool.clone(), "traded".to_string(), Some(42), true, 1_000, 990, 3, 1, Some(150.25)));
+
+    let (stored_id,): (i32,) = sqlx
+        ::query_as("SELECT id FROM apestrong.orca_whirlpool_events WHERE signature = $1")
+        .bind(&signature)
+        .fetch_one(&database.pool).await
+        .unwrap();
+    assert_eq!(Some(stored_id), event_id);
+
+    // The trade shows up in the repository's aggregate queries
+    assert_eq!(repository.get_recent_trade_volume(&whirlpool, 1).await.unwrap(), 1_000);
+    assert_eq!(repository.events_by_slot(&whirlpool, 0, 100).await.unwrap(), vec![(42, 1)]);
+}
+
+// Test that events are keyed by signature and event index, so one transaction can store several
+#[tokio::test]
+async fn test_two_events_from_one_transaction() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    // A two-hop swap through two pools emits two trades in one transaction
+    let signature = Signature::new_unique().to_string();
+    let first_hop = traded_event(&Pubkey::new_from_array(rand::random()).to_string(), &signature, 42);
+    let mut second_hop = traded_event(&Pubkey::new_from_array(rand::random()).to_string(), &signature, 42);
+    second_hop.base.event_index = 1;
+
+    assert!(repository.insert_traded_event(first_hop.clone()).await.unwrap().is_some());
+    assert!(repository.insert_traded_event(second_hop.clone()).await.unwrap().is_some());
+
+    // Re-indexing the transaction stores nothing new
+    assert_eq!(repository.insert_traded_event(first_hop).await.unwrap(), None);
+    assert_eq!(repository.insert_traded_event(second_hop).await.unwrap(), None);
+
+    let (stored, traded): (i64, i64) = sqlx
+        ::query_as(
+            "SELECT COUNT(*), COUNT(t.event_id) FROM apestrong.orca_whirlpool_events e LEFT JOIN apestrong.orca_traded_events t ON e.id = t.event_id WHERE e.signature = $1"
+        )
+        .bind(&signature)
+        .fetch_one(&database.pool).await
+        .unwrap();
+    assert_eq!((stored, traded), (2, 2));
+
+    let keys = vec![(signature.clone(), 0), (signature.clone(), 1), (signature.clone(), 2)];
+    let indexed = repository.indexed_events(&keys).await.unwrap();
+    assert_eq!(indexed, HashSet::from([(signature.clone(), 0), (signature.clone(), 1)]));
+}
+
+// Test that traded events store the post-trade price, or null when token decimals are unknown
+#[tokio::test]
+async fn test_traded_event_price() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    // A pool with token metadata (9 and 6 decimals, like SOL/USDC) and one without
+    let known_pool = Pubkey::new_from_array(rand::random());
+    let unknown_pool = Pubkey::new_from_array(rand::random());
+    repository
+        .upsert_pool(
+            &(OrcaWhirlpoolPoolRecord {
+                whirlpool: known_pool.to_string(),
+                token_mint_a: Pubkey::new_from_array(rand::random()).to_string(),
+                token_mint_b: Pubkey::new_from_array(rand::random()).to_string(),
+                token_name_a: Some("SOL".to_string()),
+                token_name_b: Some("USDC".to_string()),
+                pool_name: Some("SOL/USDC".to_string()),
+                decimals_a: 9,
+                decimals_b: 6,
+            })
+        ).await
+        .unwrap();
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let pools = vec![known_pool.to_string(), unknown_pool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let sqrt_price: u128 = 7_144_424_502_126_617_408;
+    let mut signatures = Vec::new();
+    for whirlpool in [known_pool, unknown_pool] {
+        let signature = Signature::new_unique().to_string();
+        let event = OrcaWhirlpoolTradedEvent {
+            whirlpool,
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_array_lower: Pubkey::new_unique(),
+            tick_array_upper: Pubkey::new_unique(),
+            a_to_b: true,
+            input_amount: 1_000_000_000,
+            output_amount: 150_000_000,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            protocol_fee: 1,
+            lp_fee: 3,
+            pre_sqrt_price: sqrt_price,
+            post_sqrt_price: sqrt_price,
+        };
+        let event = OrcaWhirlpoolParsedEvent::Traded(event, signature.clone(), 1);
+        indexer.handle_event(event, 0, false, Some(100), None, None).await.unwrap();
+        signatures.push(signature);
+    }
+
+    let stored_price = |signature: String| {
+        let db_pool = db_pool.clone();
+        async move {
+            let (price,): (Option<f64>,) = sqlx
+                ::query_as(
+                    "SELECT t.price FROM apestrong.orca_traded_events t JOIN apestrong.orca_whirlpool_events e ON e.id = t.event_id WHERE e.signature = $1"
+                )
+                .bind(signature)
+                .fetch_one(&db_pool).await
+                .unwrap();
+            price
+        }
+    };
+
+    assert_eq!(stored_price(signatures[0].clone()).await, Some(sqrt_price_to_price(sqrt_price, 9, 6)));
+    assert_eq!(stored_price(signatures[1].clone()).await, None);
+}
+
+// Test that a pool added with `pools add` can be listed, shown and removed
+#[tokio::test]
+async fn test_subscribed_pool_lifecycle() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let token = |symbol: &str, decimals: u8| TokenInfo {
+        mint: Pubkey::new_from_array(rand::random()),
+        decimals,
+        symbol: symbol.to_string(),
+        name: format!("{} token", symbol),
+    };
+    let record = PoolRecord {
+        pool_address: Pubkey::new_from_array(rand::random()),
+        pool_name: "SOL / USDC".to_string(),
+        dex: "orca".to_string(),
+        token_a: token("SOL", 9),
+        token_b: token("USDC", 6),
+    };
+    let whirlpool = record.pool_address.to_string();
+    repository.upsert_pool(&record.to_orca_record()).await.unwrap();
+
+    let stored = repository.get_pool(&whirlpool).await.unwrap().unwrap();
+    assert_eq!(stored.pool_name.as_deref(), Some("SOL / USDC"));
+    assert_eq!(stored.token_mint_a, record.token_a.mint.to_string());
+    assert_eq!(stored.token_name_b.as_deref(), Some("USDC token"));
+    assert_eq!((stored.decimals_a, stored.decimals_b), (9, 6));
+    assert!(
+        repository
+            .get_all_pools().await
+            .unwrap()
+            .iter()
+            .any(|pool| pool.whirlpool == whirlpool)
+    );
+
+    // Removing the subscription keeps the pool's events and last signature
+    let signature = Signature::new_unique().to_string();
+    repository.insert_traded_event(traded_event(&whirlpool, &signature, 100)).await.unwrap();
+    let signatures = SignatureStore::Database(DbSignatureStore::new(database.pool.clone()));
+    signatures.update_signature(&record.pool_address, signature, "orca").await.unwrap();
+
+    assert!(repository.delete_pool(&whirlpool, PoolDeletion::default()).await.unwrap());
+    assert!(repository.get_pool(&whirlpool).await.unwrap().is_none());
+    assert_eq!(repository.events_by_slot(&whirlpool, 0, 1_000).await.unwrap(), vec![(100, 1)]);
+    let fresh_signatures = || SignatureStore::Database(DbSignatureStore::new(database.pool.clone()));
+    assert!(fresh_signatures().has_signature(&record.pool_address, "orca").await.unwrap());
+    assert!(!repository.delete_pool(&whirlpool, PoolDeletion::default()).await.unwrap());
+
+    // Both can be removed with it, even once the subscription is gone
+    let deletion = PoolDeletion { forget_signatures: true, delete_events: true };
+    assert!(!repository.delete_pool(&whirlpool, deletion).await.unwrap());
+    assert!(repository.events_by_slot(&whirlpool, 0, 1_000).await.unwrap().is_empty());
+    assert!(!fresh_signatures().has_signature(&record.pool_address, "orca").await.unwrap());
+}
+
+const DEFAULT_POOL: &str = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
+
+// Test that a token filter selects the subscribed pools trading the token on either side
+#[tokio::test]
+async fn test_get_pools_by_token() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let repository = OrcaWhirlpoolRepository::new(database.pool.clone());
+
+    let sol = Pubkey::new_from_array(rand::random());
+    let usdc = Pubkey::new_from_array(rand::random());
+    let bonk = Pubkey::new_from_array(rand::random());
+    let mut pools = Vec::new();
+    for (token_a, token_b) in [(sol, usdc), (bonk, sol), (bonk, usdc)] {
+        let whirlpool = Pubkey::new_from_array(rand::random());
+        repository
+            .upsert_pool(
+                &(OrcaWhirlpoolPoolRecord {
+                    whirlpool: whirlpool.to_string(),
+                    token_mint_a: token_a.to_string(),
+                    token_mint_b: token_b.to_string(),
+                    token_name_a: None,
+                    token_name_b: None,
+                    pool_name: None,
+                    decimals_a: 6,
+                    decimals_b: 6,
+                })
+            ).await
+            .unwrap();
+        pools.push(whirlpool);
+    }
+
+    let sol_pools = HashSet::from([pools[0], pools[1]]);
+    assert_eq!(repository.get_pools_by_token(&sol.to_string()).await.unwrap(), sol_pools);
+    assert_eq!(
+        repository.get_pools_with_fallback(None, DEFAULT_POOL, false, Some(&sol)).await.unwrap(),
+        sol_pools
+    );
+
+    // Pools given by address take priority over the filter
+    let provided = vec![pools[2].to_string()];
+    assert_eq!(
+        repository.get_pools_with_fallback(Some(&provided), DEFAULT_POOL, false, Some(&sol)).await.unwrap(),
+        HashSet::from([pools[2]])
+    );
+
+    // A mint no subscribed pool trades is an error, not a fallback to every pool
+    let unknown = Pubkey::new_from_array(rand::random());
+    assert!(repository.get_pools_by_token(&unknown.to_string()).await.unwrap().is_empty());
+    assert!(repository.get_pools_with_fallback(None, DEFAULT_POOL, false, Some(&unknown)).await.is_err());
+}
+
+// Test that sqrt prices beyond the i64 range are stored without truncation
+#[tokio::test]
+async fn test_sqrt_prices_stored_in_full() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    // Just above i64::MAX, and at the Whirlpool maximum near 2^96
+    let pre_sqrt_price: u128 = (i64::MAX as u128) + 1;
+    let post_sqrt_price: u128 = 79_226_673_515_401_279_992_447_579_055;
+    let signature = Signature::new_unique().to_string();
+    let mut event = traded_event(&Pubkey::new_from_array(rand::random()).to_string(), &signature, 100);
+    event.base.event_type = "Traded".to_string();
+    event.data.pre_sqrt_price = pre_sqrt_price;
+    event.data.post_sqrt_price = post_sqrt_price;
+    repository.insert_traded_event(event).await.unwrap();
+
+    let (pre, post): (String, String) = sqlx
+        ::query_as(
+            "SELECT pre_sqrt_price::TEXT, post_sqrt_price::TEXT FROM apestrong.v_orca_whirlpool_traded WHERE signature = $1"
+        )
+        .bind(&signature)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(from_numeric::<u128>(&pre).unwrap(), pre_sqrt_price);
+    assert_eq!(from_numeric::<u128>(&post).unwrap(), post_sqrt_price);
+}
+
+// Test that u128 liquidity and u64 amounts beyond the i64 range are stored unchanged
+#[tokio::test]
+async fn test_liquidity_stored_in_full() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let mut trade = traded_event(&whirlpool, &Signature::new_unique().to_string(), 100);
+    let base_event = |event_type: &str| OrcaWhirlpoolEvent {
+        signature: Signature::new_unique().to_string(),
+        event_index: 0,
+        event_type: event_type.to_string(),
+        ..trade.base.clone()
+    };
+    let record = OrcaWhirlpoolLiquidityRecord {
+        token_a_amount: u64::MAX,
+        token_b_amount: (i64::MAX as u64) + 1,
+        token_a_transfer_fee: u64::MAX,
+        ..liquidity_record(&Pubkey::new_unique().to_string(), u128::MAX)
+    };
+    let increased_id = repository
+        .insert_liquidity_increased_event(OrcaWhirlpoolLiquidityIncreasedEventRecord {
+            base: base_event("LiquidityIncreased"),
+            data: record.clone(),
+        }).await
+        .unwrap();
+    let decreased_id = repository
+        .insert_liquidity_decreased_event(OrcaWhirlpoolLiquidityDecreasedEventRecord {
+            base: base_event("LiquidityDecreased"),
+            data: record.clone(),
+        }).await
+        .unwrap();
+
+    for (table, event_id) in [
+        ("orca_liquidity_increased_events", increased_id),
+        ("orca_liquidity_decreased_events", decreased_id),
+    ] {
+        let (liquidity, token_a_amount, token_b_amount, token_a_transfer_fee): (
+            String,
+            String,
+            String,
+            String,
+        ) = sqlx
+            ::query_as(
+                &format!(
+                    "SELECT liquidity::TEXT, token_a_amount::TEXT, token_b_amount::TEXT, token_a_transfer_fee::TEXT
+                     FROM apestrong.{} WHERE event_id = $1",
+                    table
+                )
+            )
+            .bind(event_id)
+            .fetch_one(&db_pool).await
+            .unwrap();
+        assert_eq!(from_numeric::<u128>(&liquidity).unwrap(), u128::MAX);
+        assert_eq!(from_numeric::<u64>(&token_a_amount).unwrap(), u64::MAX);
+        assert_eq!(from_numeric::<u64>(&token_b_amount).unwrap(), record.token_b_amount);
+        assert_eq!(from_numeric::<u64>(&token_a_transfer_fee).unwrap(), u64::MAX);
+    }
+
+    // Aggregates over the full-range values don't overflow
+    trade.data.input_amount = u64::MAX;
+    repository.insert_traded_event(trade.clone()).await.unwrap();
+    trade.base.signature = Signature::new_unique().to_string();
+    repository.insert_traded_event(trade).await.unwrap();
+
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+    let stats = repository.get_pool_stats(&whirlpool, since).await.unwrap();
+    assert_eq!(stats.input_volume, 2 * (u64::MAX as u128));
+    assert_eq!(stats.net_liquidity_change, 0);
+    assert_eq!(stats.positions_touched, 1);
+    assert_eq!(
+        repository.get_recent_trade_volume(&whirlpool, 1).await.unwrap(),
+        2 * (u64::MAX as u128)
+    );
+}
+
+// Test that pool creation events are stored with their u128 values in full
+#[tokio::test]
+async fn test_pool_initialized_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let whirlpool = Pubkey::new_from_array(rand::random()).to_string();
+    let trade = traded_event(&whirlpool, &Signature::new_unique().to_string(), 100);
+    let base_event = OrcaWhirlpoolEvent {
+        event_type: "PoolInitialized".to_string(),
+        ..trade.base
+    };
+
+    let mint_a = Pubkey::new_unique().to_string();
+    let initialized_id = repository
+        .insert_pool_initialized_event(OrcaWhirlpoolPoolInitializedEventRecord {
+            base: base_event,
+            data: OrcaWhirlpoolPoolInitializedRecord {
+                event_id: 0,
+                whirlpools_config: Pubkey::new_unique().to_string(),
+                token_mint_a: mint_a.clone(),
+                token_mint_b: Pubkey::new_unique().to_string(),
+                tick_spacing: 64,
+                decimals_a: 9,
+                decimals_b: 6,
+                initial_sqrt_price: u128::MAX,
+            },
+        }).await
+        .unwrap();
+    let (token_mint_a, tick_spacing, initial_sqrt_price): (String, i32, String) = sqlx
+        ::query_as(
+            "SELECT token_mint_a, tick_spacing, initial_sqrt_price::TEXT FROM apestrong.orca_pool_initialized_events WHERE event_id = $1"
+        )
+        .bind(initialized_id)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!((token_mint_a, tick_spacing), (mint_a, 64));
+    assert_eq!(from_numeric::<u128>(&initial_sqrt_price).unwrap(), u128::MAX);
+
+    // Detail rows are rolled back with their base events
+    assert_eq!(repository.delete_events_after_slot(&whirlpool, 99).await.unwrap(), 1);
+    let (remaining,): (i64,) = sqlx
+        ::query_as("SELECT COUNT(*) FROM apestrong.orca_pool_initialized_events WHERE event_id = $1")
+        .bind(initialized_id)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+// Test that events for a pool added while the indexer runs start getting persisted
+#[tokio::test]
+async fn test_added_pool_events_persisted() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let pools = vec![Pubkey::new_from_array(rand::random()).to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool, Some(&pools), connection_config).await.unwrap();
+
+    // Logs of a swap in a pool that isn't monitored yet
+    let new_pool = Pubkey::new_from_array(rand::random());
+    let swap_log = |signature: String| {
+        let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(new_pool.as_ref());
+        for _ in 0..4 {
+            data.extend_from_slice(Pubkey::new_unique().as_ref());
+        }
+        data.push(1);
+        for amount in [1_000u64, 990, 0, 0, 1, 3] {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        for sqrt_price in [1u128 << 64, 1u128 << 64] {
+            data.extend_from_slice(&sqrt_price.to_le_bytes());
+        }
+        RpcLogsResponse {
+            signature,
+            err: None,
+            logs: vec![
+                format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+                "Program log: Instruction: Swap".to_string(),
+                format!("Program data: {}", STANDARD.encode(&data)),
+                format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID)
+            ],
+        }
+    };
+    let new_pool_address = new_pool.to_string();
+    let events = || repository.events_by_slot(&new_pool_address, 0, 1_000);
+
+    indexer.process_log(&swap_log(Signature::new_unique().to_string()), Some(100)).await.unwrap();
+    assert!(events().await.unwrap().is_empty());
+
+    // Once added, the pool's events are persisted
+    assert!(indexer.add_pool(new_pool));
+    assert!(!indexer.add_pool(new_pool));
+    assert!(indexer.pool_pubkeys().contains(&new_pool));
+    indexer.process_log(&swap_log(Signature::new_unique().to_string()), Some(101)).await.unwrap();
+    assert_eq!(events().await.unwrap(), vec![(101, 1)]);
+
+    // And after removing it they are ignored again
+    assert!(indexer.remove_pool(&new_pool));
+    assert!(!indexer.remove_pool(&new_pool));
+    indexer.process_log(&swap_log(Signature::new_unique().to_string()), Some(102)).await.unwrap();
+    assert_eq!(events().await.unwrap(), vec![(101, 1)]);
+}
+
+// Enricher that adds a USD value to trades, or fails when asked to
+struct UsdValueEnricher {
+    fail: bool,
+}
+
+#[async_trait::async_trait]
+impl EventEnricher for UsdValueEnricher {
+    fn name(&self) -> &str {
+        "usd_value"
+    }
+
+    async fn enrich(&self, event: &IndexedEvent) -> anyhow::Result<Enrichment> {
+        if self.fail {
+            anyhow::bail!("price source unavailable");
+        }
+        let mut fields = Enrichment::new();
+        if let IndexedEvent::OrcaTraded(trade) = event {
+            fields.insert("usd_value".to_string(), serde_json::json!((trade.input_amount as f64) * 2.0));
+        }
+        Ok(fields)
+    }
+}
+
+// Test that enriched fields are persisted, and failures follow the configured policy
+#[tokio::test]
+async fn test_enriched_event_persisted() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+
+    // Handle a trade with the given enricher, returning its stored enrichment (None if not stored)
+    let handle_trade = |enricher: UsdValueEnricher, policy: EnrichmentFailurePolicy| {
+        let db_pool = db_pool.clone();
+        let pools = pools.clone();
+        let connection_config = connection_config.clone();
+        async move {
+            let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await
+                .unwrap()
+                .with_enricher(Box::new(enricher), policy);
+            let signature = Signature::new_unique().to_string();
+            let event = OrcaWhirlpoolTradedEvent {
+                whirlpool,
+                token_vault_a: Pubkey::new_unique(),
+                token_vault_b: Pubkey::new_unique(),
+                tick_array_lower: Pubkey::new_unique(),
+                tick_array_upper: Pubkey::new_unique(),
+                a_to_b: true,
+                input_amount: 1_000,
+                output_amount: 990,
+                input_transfer_fee: 0,
+                output_transfer_fee: 0,
+                protocol_fee: 1,
+                lp_fee: 3,
+                pre_sqrt_price: 1 << 64,
+                post_sqrt_price: 1 << 64,
+            };
+            let event = OrcaWhirlpoolParsedEvent::Traded(event, signature.clone(), 1);
+            indexer.handle_event(event, 0, false, Some(100), None, None).await.unwrap();
+
+            let row: Option<(Option<serde_json::Value>,)> = sqlx
+                ::query_as("SELECT enrichment FROM apestrong.orca_whirlpool_events WHERE signature = $1")
+                .bind(signature)
+                .fetch_optional(&db_pool).await
+                .unwrap();
+            row.map(|(enrichment,)| enrichment)
+        }
+    };
+
+    let stored = handle_trade(UsdValueEnricher { fail: false }, EnrichmentFailurePolicy::Skip).await;
+    assert_eq!(stored, Some(Some(serde_json::json!({ "usd_value": 2000.0 }))));
+
+    // A failed enrichment either drops the event or stores it without the fields
+    let stored = handle_trade(UsdValueEnricher { fail: true }, EnrichmentFailurePolicy::Skip).await;
+    assert_eq!(stored, None);
+    let stored = handle_trade(UsdValueEnricher { fail: true }, EnrichmentFailurePolicy::StoreRaw).await;
+    assert_eq!(stored, Some(None));
+}
+
+// Test that a transaction's logs are stored once, however many events it produced
+#[tokio::test]
+async fn test_transaction_logs_stored_once() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    )
+        .with_store_transaction_logs(true);
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    // A transaction with two swaps through the same pool
+    let swap_data = |input_amount: u64| {
+        let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(whirlpool.as_ref());
+        for _ in 0..4 {
+            data.extend_from_slice(Pubkey::new_unique().as_ref());
+        }
+        data.push(1);
+        for amount in [input_amount, 990, 0, 0, 1, 3] {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        for sqrt_price in [1u128 << 64, 1u128 << 64] {
+            data.extend_from_slice(&sqrt_price.to_le_bytes());
+        }
+        format!("Program data: {}", STANDARD.encode(&data))
+    };
+    let signature = Signature::new_unique().to_string();
+    let log = RpcLogsResponse {
+        signature: signature.clone(),
+        err: None,
+        logs: vec![
+            format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+            "Program log: Instruction: Swap".to_string(),
+            swap_data(1_000),
+            "Program log: Instruction: Swap".to_string(),
+            swap_data(2_000),
+            format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID)
+        ],
+    };
+
+    // Both swaps are kept, while the logs are stored once for the transaction
+    indexer.process_log(&log, Some(100)).await.unwrap();
+    assert_eq!(repository.events_by_slot(&whirlpool.to_string(), 0, 1_000).await.unwrap(), vec![(100, 2)]);
+
+    let rows: Vec<(serde_json::Value,)> = sqlx
+        ::query_as("SELECT logs FROM apestrong.transaction_logs WHERE signature = $1")
+        .bind(&signature)
+        .fetch_all(&db_pool).await
+        .unwrap();
+    assert_eq!(rows, vec![(serde_json::json!(log.logs),)]);
+
+    // Storing the logs again leaves the existing row alone
+    let store = TransactionLogStore::new(db_pool);
+    assert!(!store.save_logs(&signature, "orca", Some(100), &log.logs).await.unwrap());
+    assert_eq!(store.get_logs(&signature).await.unwrap(), Some(log.logs));
+}
+
+// Helper to build the logs of a swap on a pool, emitting its Traded event
+fn swap_logs(whirlpool: &Pubkey, result: &str) -> Vec<String> {
+    let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(whirlpool.as_ref());
+    for _ in 0..4 {
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+    }
+    data.push(1);
+    for amount in [1_000u64, 990, 0, 0, 1, 3] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    for sqrt_price in [1u128 << 64, 1u128 << 64] {
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+    }
+    vec![
+        format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+        "Program log: Instruction: Swap".to_string(),
+        format!("Program data: {}", STANDARD.encode(&data)),
+        format!("Program {} {}", ORCA_WHIRLPOOL_PROGRAM_ID, result)
+    ]
+}
+
+// Test that events from a transaction that failed on-chain aren't persisted
+#[tokio::test]
+async fn test_failed_transaction_not_persisted() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool, Some(&pools), connection_config).await.unwrap();
+
+    // Metadata of a swap that emitted its event but then failed (e.g. slippage exceeded)
+    let logs = swap_logs(&whirlpool, "failed: custom program error: 0x1794");
+    let failed_meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        status: Err(TransactionError::InstructionError(0, InstructionError::Custom(0x1794))),
+        log_messages: Some(logs.clone()),
+        ..TransactionStatusMeta::default()
+    });
+    assert!(failed_meta.err.is_some());
+
+    let failed = indexer.tx_to_logs_response(
+        &Signature::new_unique().to_string(),
+        &logs,
+        failed_meta.err
+    );
+    indexer.process_log(&failed, Some(100)).await.unwrap();
+    assert!(repository.events_by_slot(&whirlpool.to_string(), 0, 1_000).await.unwrap().is_empty());
+
+    // The same logs from a successful transaction are persisted
+    let succeeded = indexer.tx_to_logs_response(&Signature::new_unique().to_string(), &logs, None);
+    indexer.process_log(&succeeded, Some(101)).await.unwrap();
+    assert_eq!(repository.events_by_slot(&whirlpool.to_string(), 0, 1_000).await.unwrap(), vec![(101, 1)]);
+}
+
+// Writer that keeps everything written to it, shared with the test
+#[derive(Clone, Default)]
+struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Test that the stdout sink emits a JSON line for an event that is also stored in the database
+#[tokio::test]
+async fn test_stdout_sink_alongside_database() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+    let output = SharedBuffer::default();
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool, Some(&pools), connection_config).await
+        .unwrap()
+        .with_sinks(vec![Box::new(StdoutSink::with_writer(Box::new(output.clone())))]);
+
+    let signature = Signature::new_unique().to_string();
+    let event = OrcaWhirlpoolTradedEvent {
+        whirlpool,
+        token_vault_a: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        tick_array_lower: Pubkey::new_unique(),
+        tick_array_upper: Pubkey::new_unique(),
+        a_to_b: true,
+        input_amount: 1_000,
+        output_amount: 990,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        protocol_fee: 1,
+        lp_fee: 3,
+        pre_sqrt_price: 1 << 64,
+        post_sqrt_price: 1 << 64,
+    };
+    let event = OrcaWhirlpoolParsedEvent::Traded(event, signature.clone(), 1);
+    indexer.handle_event(event, 0, false, Some(100), None, None).await.unwrap();
+
+    assert_eq!(repository.events_by_slot(&whirlpool.to_string(), 0, 1_000).await.unwrap(), vec![(100, 1)]);
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(line["event"], "orca_traded");
+    assert_eq!(line["signature"], signature);
+    assert_eq!(line["pool"], whirlpool.to_string());
+    assert_eq!(line["input_amount"], 1_000);
+}
+
+// Helper to serve a JSON-RPC node that returns the given transaction for every getTransaction call,
+// recording the requested signatures, and the transaction's block time for every getBlockTime call
+async fn start_transaction_rpc_node(transaction: serde_json::Value) -> (String, Arc<Mutex<Vec<String>>>) {
+    use hyper::service::{ make_service_fn, service_fn };
+    use hyper::{ Body, Request, Response, Server };
+    use std::convert::Infallible;
+
+    let fetched = Arc::new(Mutex::new(Vec::new()));
+    let server_fetched = fetched.clone();
+    let make_service = make_service_fn(move |_conn| {
+        let transaction = transaction.clone();
+        let fetched = server_fetched.clone();
+        async move {
+            Ok::<_, Infallible>(
+                service_fn(move |req: Request<Body>| {
+                    let transaction = transaction.clone();
+                    let fetched = fetched.clone();
+                    async move {
+                        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        let result = match request["method"].as_str() {
+                            Some("getVersion") => serde_json::json!({ "solana-core": "1.18.26" }),
+                            Some("getTransaction") => {
+                                let signature = request["params"][0].as_str().unwrap_or_default();
+                                fetched.lock().unwrap().push(signature.to_string());
+                                transaction
+                            }
+                            Some("getBlockTime") => transaction["blockTime"].clone(),
+                            _ => serde_json::Value::Null,
+                        };
+                        let response =
+                            serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "result": result,
+                        });
+                        Ok::<_, Infallible>(Response::new(Body::from(response.to_string())))
+                    }
+                })
+            )
+        }
+    });
+
+    let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    (url, fetched)
+}
+
+// Helper to read the slot and block time stored for a signature's event
+async fn stored_slot_and_block_time(
+    db_pool: &sqlx::PgPool,
+    signature: &str
+) -> (Option<i64>, Option<chrono::DateTime<chrono::Utc>>) {
+    sqlx
+        ::query_as("SELECT slot, block_time FROM apestrong.orca_whirlpool_events WHERE signature = $1")
+        .bind(signature)
+        .fetch_one(db_pool).await
+        .unwrap()
+}
+
+// Test that a backfilled event carries its transaction's slot and block time
+#[tokio::test]
+async fn test_backfilled_block_time_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 240,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, _) = start_transaction_rpc_node(serde_json::to_value(&transaction).unwrap()).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let signature = Signature::new_unique();
+    assert_eq!(indexer.process_backfill_signatures(&[signature]).await.unwrap(), (1, 1));
+
+    let (slot, block_time) = stored_slot_and_block_time(&db_pool, &signature.to_string()).await;
+    assert_eq!(slot, Some(240));
+    assert_eq!(block_time, chrono::DateTime::from_timestamp(1_700_000_000, 0));
+}
+
+// Test that events already stored are neither counted as successful nor as processed
+#[tokio::test]
+async fn test_backfill_counts_stored_transactions() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 245,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, _) = start_transaction_rpc_node(serde_json::to_value(&transaction).unwrap()).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    // The second pass still parses the event, but stores nothing
+    let signature = Signature::new_unique();
+    assert_eq!(indexer.process_backfill_signatures(&[signature]).await.unwrap(), (1, 1));
+    assert_eq!(indexer.process_backfill_signatures(&[signature]).await.unwrap(), (1, 0));
+    assert_eq!(indexer.health_tracker().snapshot().events_processed_total, 1);
+
+    // Nor does the same event arriving live
+    let log = RpcLogsResponse {
+        signature: signature.to_string(),
+        err: None,
+        logs: swap_logs(&whirlpool, "success"),
+    };
+    indexer.process_log(&log, Some(245)).await.unwrap();
+    assert_eq!(indexer.health_tracker().snapshot().events_processed_total, 1);
+}
+
+// Test that a backfilled event records its transaction's fee payer and counts as a trader
+#[tokio::test]
+async fn test_backfilled_fee_payer_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let fee_payer = Pubkey::new_from_array(rand::random());
+    let signature = Signature::new_unique();
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 250,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Json(UiTransaction {
+                signatures: vec![signature.to_string()],
+                message: UiMessage::Raw(UiRawMessage {
+                    header: MessageHeader::default(),
+                    account_keys: vec![fee_payer.to_string(), whirlpool.to_string()],
+                    recent_blockhash: Pubkey::default().to_string(),
+                    instructions: vec![],
+                    address_table_lookups: None,
+                }),
+            }),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, _) = start_transaction_rpc_node(serde_json::to_value(&transaction).unwrap()).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+    assert_eq!(indexer.process_backfill_signatures(&[signature]).await.unwrap(), (1, 1));
+
+    let stored: Option<String> = sqlx
+        ::query_scalar("SELECT fee_payer FROM apestrong.orca_whirlpool_events WHERE signature = $1")
+        .bind(signature.to_string())
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(stored, Some(fee_payer.to_string()));
+
+    let repository = OrcaWhirlpoolRepository::new(db_pool);
+    let from = chrono::DateTime::from_timestamp(1_699_999_999, 0).unwrap();
+    let to = chrono::DateTime::from_timestamp(1_700_000_001, 0).unwrap();
+    assert_eq!(repository.unique_traders(&whirlpool.to_string(), from, to).await.unwrap(), 1);
+}
+
+// Test that a live event gets the block time of its slot, and none when the slot is unknown
+#[tokio::test]
+async fn test_live_block_time_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    // The node reports this block time for every slot
+    let transaction = serde_json::json!({ "blockTime": 1_700_000_100 });
+    let (rpc_url, _) = start_transaction_rpc_node(transaction).await;
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let live_log = || RpcLogsResponse {
+        signature: Signature::new_unique().to_string(),
+        err: None,
+        logs: swap_logs(&whirlpool, "success"),
+    };
+    let (with_slot, without_slot) = (live_log(), live_log());
+    indexer.process_log(&with_slot, Some(260)).await.unwrap();
+    indexer.process_log(&without_slot, None).await.unwrap();
+
+    assert_eq!(
+        stored_slot_and_block_time(&db_pool, &with_slot.signature).await,
+        (Some(260), chrono::DateTime::from_timestamp(1_700_000_100, 0))
+    );
+    assert_eq!(stored_slot_and_block_time(&db_pool, &without_slot.signature).await, (None, None));
+}
+
+// Test that a backfilled transaction without a block time keeps its slot and a NULL block_time
+#[tokio::test]
+async fn test_missing_block_time_stored_as_null() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    // An old transaction the RPC node no longer has a block time for
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 250,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: None,
+    };
+    let (rpc_url, _) = start_transaction_rpc_node(serde_json::to_value(&transaction).unwrap()).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let signature = Signature::new_unique();
+    assert_eq!(indexer.process_backfill_signatures(&[signature]).await.unwrap(), (1, 1));
+
+    let (slot, block_time) = stored_slot_and_block_time(&db_pool, &signature.to_string()).await;
+    assert_eq!(slot, Some(250));
+    assert_eq!(block_time, None);
+}
+
+// Test that a signature repeated within one backfill batch is fetched and stored once,
+// with its trade details attached to the single event row
+#[tokio::test]
+async fn test_duplicate_signature_in_batch_stored_once() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 270,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, fetched) = start_transaction_rpc_node(
+        serde_json::to_value(&transaction).unwrap()
+    ).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let signature = Signature::new_unique();
+    assert_eq!(indexer.process_backfill_signatures(&[signature, signature]).await.unwrap(), (1, 1));
+    assert_eq!(fetched.lock().unwrap().len(), 1);
+
+    let rows: Vec<(i32, String)> = sqlx
+        ::query_as(
+            "SELECT e.id, t.input_amount::TEXT FROM apestrong.orca_whirlpool_events e
+             JOIN apestrong.orca_traded_events t ON t.event_id = e.id
+             WHERE e.signature = $1"
+        )
+        .bind(signature.to_string())
+        .fetch_all(&db_pool).await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(from_numeric::<u64>(&rows[0].1).unwrap(), 1_000);
+}
+
+// Test that a two-hop swap through two monitored pools stores both trades, once
+#[tokio::test]
+async fn test_two_hop_swap_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let first_pool = Pubkey::new_from_array(rand::random());
+    let second_pool = Pubkey::new_from_array(rand::random());
+    let mut logs = swap_logs(&first_pool, "success");
+    logs.extend(swap_logs(&second_pool, "success"));
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(logs),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 280,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, _fetched) = start_transaction_rpc_node(
+        serde_json::to_value(&transaction).unwrap()
+    ).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![first_pool.to_string(), second_pool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    // Backfilling the transaction again adds nothing
+    let signature = Signature::new_unique();
+    for _ in 0..2 {
+        indexer.process_backfill_signatures(&[signature]).await.unwrap();
+    }
+
+    let rows: Vec<(i32, String)> = sqlx
+        ::query_as(
+            "SELECT event_index, whirlpool FROM apestrong.orca_whirlpool_events WHERE signature = $1 ORDER BY event_index"
+        )
+        .bind(signature.to_string())
+        .fetch_all(&db_pool).await
+        .unwrap();
+    assert_eq!(rows, vec![(0, first_pool.to_string()), (1, second_pool.to_string())]);
+}
+
+// Test that an explicit signature list is fetched and stored once, and that
+// indexing it again fetches nothing
+#[tokio::test]
+async fn test_index_signatures() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let meta = UiTransactionStatusMeta::from(TransactionStatusMeta {
+        log_messages: Some(swap_logs(&whirlpool, "success")),
+        ..TransactionStatusMeta::default()
+    });
+    let transaction = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 300,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::LegacyBinary(String::new()),
+            meta: Some(meta),
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    };
+    let (rpc_url, fetched) = start_transaction_rpc_node(
+        serde_json::to_value(&transaction).unwrap()
+    ).await;
+
+    let connection_config = ConnectionConfig::new(rpc_url, "ws://127.0.0.1:8900".to_string());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    // The list repeats a signature, which is only indexed once
+    let contents = signature_file(&[Signature::new_unique(), Signature::new_unique(), Signature::new_unique()]);
+    let signatures = parse_signature_list(&contents).unwrap();
+    let mut list = signatures.clone();
+    list.push(signatures[0]);
+    assert_eq!(indexer.index_signatures(&whirlpool, &list).await.unwrap(), (3, 3));
+
+    let mut requested = fetched.lock().unwrap().clone();
+    requested.sort();
+    let mut expected: Vec<String> = signatures.iter().map(Signature::to_string).collect();
+    expected.sort();
+    assert_eq!(requested, expected);
+
+    let stored: Vec<(String,)> = sqlx
+        ::query_as("SELECT signature FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 ORDER BY signature")
+        .bind(whirlpool.to_string())
+        .fetch_all(&db_pool).await
+        .unwrap();
+    let stored: Vec<String> = stored.into_iter().map(|(signature,)| signature).collect();
+    assert_eq!(stored, expected);
+
+    // Running the same list again skips every signature
+    assert_eq!(indexer.index_signatures(&whirlpool, &list).await.unwrap(), (0, 0));
+    assert_eq!(fetched.lock().unwrap().len(), 3);
+}
+
+// Test that signature files skip comments and blank lines and name the line of a bad entry
+#[test]
+fn test_parse_signature_list() {
+    let signature = Signature::new_unique();
+    let contents = format!("# missed during the outage\n\n  {}  \n", signature);
+    assert_eq!(parse_signature_list(&contents).unwrap(), vec![signature]);
+
+    let error = parse_signature_list(&format!("{}\nnot-a-signature\n", signature)).unwrap_err();
+    assert!(error.to_string().contains("line 2"));
+}
+
+// Helper to write signatures as a signature file, one per line
+fn signature_file(signatures: &[Signature]) -> String {
+    signatures
+        .iter()
+        .map(|signature| format!("{}\n", signature))
+        .collect()
+}
+
+// Test that trade amounts are scaled by the decimals of the side they are on,
+// with transfer fees kept separate
+#[test]
+fn test_traded_event_decoded() {
+    // 2 SOL (9 decimals) sold for 300 USDC (6 decimals), with a 0.5 USDC transfer fee
+    let mut record = traded_event("pool", "signature", 100).data;
+    record.a_to_b = true;
+    record.input_amount = 2_000_000_000;
+    record.output_amount = 300_000_000;
+    record.output_transfer_fee = 500_000;
+    record.lp_fee = 6_000_000;
+
+    let decoded = TradedEventDecoded::from_record(&record, 9, 6).unwrap();
+    assert_eq!(decoded.input_amount, Decimal::new(2, 0));
+    assert_eq!(decoded.output_amount, Decimal::new(300, 0));
+    assert_eq!(decoded.output_transfer_fee, Decimal::new(5, 1));
+    assert_eq!(decoded.net_output_amount(), Decimal::new(2995, 1));
+    assert_eq!(decoded.net_input_amount(), Decimal::new(2, 0));
+    assert_eq!(decoded.lp_fee, Decimal::new(6, 3));
+
+    // The reverse direction sells token B, so the decimals swap sides
+    record.a_to_b = false;
+    record.input_amount = 300_000_000;
+    record.output_amount = 2_000_000_000;
+    record.output_transfer_fee = 0;
+    let decoded = TradedEventDecoded::from_record(&record, 9, 6).unwrap();
+    assert_eq!(decoded.input_amount, Decimal::new(300, 0));
+    assert_eq!(decoded.output_amount, Decimal::new(2, 0));
+    assert_eq!(decoded.lp_fee, Decimal::new(6, 0));
+
+    // Amounts use the full u64 range
+    record.input_amount = u64::MAX;
+    let decoded = TradedEventDecoded::from_record(&record, 0, 0).unwrap();
+    assert_eq!(decoded.input_amount, Decimal::from(u64::MAX));
+
+    assert!(TradedEventDecoded::from_record(&record, 29, 6).is_err());
+}
+
+// Test that liquidity amounts use the token A and token B decimals
+#[test]
+fn test_decode_liquidity_amounts() {
+    let record = OrcaWhirlpoolLiquidityRecord {
+        event_id: 0,
+        position: Pubkey::new_unique().to_string(),
+        tick_lower_index: -100,
+        tick_upper_index: 100,
+        liquidity: 1_000,
+        token_a_amount: 1_500_000_000,
+        token_b_amount: 25_000_000,
+        token_a_transfer_fee: 0,
+        token_b_transfer_fee: 0,
+    };
+    assert_eq!(
+        decode_liquidity_amounts(&record, 9, 6).unwrap(),
+        (Decimal::new(15, 1), Decimal::new(25, 0))
+    );
+}
+
+// Test that quarantined events land in quarantine_events once each and not in the event tables
+#[tokio::test]
+async fn test_quarantined_event_stored() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    ).with_event_validation(ValidationMode::Basic);
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    // A swap whose transfer fee is larger than its input amount
+    let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(whirlpool.as_ref());
+    for _ in 0..4 {
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+    }
+    data.push(1);
+    for amount in [1_000u64, 990, 5_000, 0, 1, 3] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    for sqrt_price in [1u128 << 64, 1u128 << 64] {
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+    }
+    // Routed through the pool twice, so the transaction holds two such swaps
+    let signature = Signature::new_unique().to_string();
+    let log = RpcLogsResponse {
+        signature: signature.clone(),
+        err: None,
+        logs: vec![
+            format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program data: {}", STANDARD.encode(&data)),
+            format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID),
+            format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program data: {}", STANDARD.encode(&data)),
+            format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID)
+        ],
+    };
+    // Replaying the transaction doesn't quarantine its events again
+    indexer.process_log(&log, Some(100)).await.unwrap();
+    indexer.process_log(&log, Some(100)).await.unwrap();
+
+    assert!(repository.events_by_slot(&whirlpool.to_string(), 0, 1_000).await.unwrap().is_empty());
+    let quarantined: Vec<_> = QuarantineStore::new(db_pool)
+        .recent("orca", 100).await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.signature == signature)
+        .collect();
+    assert_eq!(quarantined.len(), 2);
+    let mut indices: Vec<_> = quarantined.iter().map(|event| event.event_index).collect();
+    indices.sort();
+    assert_eq!(indices, vec![Some(0), Some(1)]);
+    assert_eq!(quarantined[0].pool, whirlpool.to_string());
+    assert_eq!(quarantined[0].event_type, "Traded");
+    assert_eq!(quarantined[0].slot, Some(100));
+    assert_eq!(quarantined[0].raw_data, STANDARD.encode(&data));
+    assert!(quarantined[0].reason.starts_with("fee_exceeds_amount:"));
+}
@@ -0,0 +1,169 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use sqlx::postgres::PgPoolOptions;
+use std::time::Duration;
+use tokio::net::{ TcpListener, TcpStream };
+
+use indexer::backfill_manager::RetryConfig;
+use indexer::dead_letter::DeadLetterQueue;
+use indexer::indexers::{ ConnectionConfig, DexIndexer, OrcaWhirlpoolIndexer };
+use indexer::metrics;
+use indexer::outage_buffer::{ HeldEvent, OutageBuffer };
+use indexer::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+use indexer::TRADED_EVENT_DISCRIMINATOR;
+
+mod common;
+
+// Helper to build the logs of a swap on a pool, emitting its Traded event
+fn swap_log(whirlpool: &Pubkey, signature: &str) -> RpcLogsResponse {
+    let mut data = TRADED_EVENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(whirlpool.as_ref());
+    for _ in 0..4 {
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+    }
+    data.push(1);
+    for amount in [1_000u64, 990, 0, 0, 1, 3] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    for sqrt_price in [1u128 << 64, 1u128 << 64] {
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+    }
+
+    RpcLogsResponse {
+        signature: signature.to_string(),
+        err: None,
+        logs: vec![
+            format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program data: {}", STANDARD.encode(&data)),
+            format!("Program {} success", ORCA_WHIRLPOOL_PROGRAM_ID)
+        ],
+    }
+}
+
+// Helper to forward every connection accepted by `listener` to `target`
+fn forward_connections(listener: TcpListener, target: String) {
+    tokio::spawn(async move {
+        while let Ok((mut inbound, _)) = listener.accept().await {
+            let target = target.clone();
+            tokio::spawn(async move {
+                let Ok(mut outbound) = TcpStream::connect(&target).await else {
+                    return;
+                };
+                let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+            });
+        }
+    });
+}
+
+// Test that the buffer keeps events in order and refuses events once full
+#[test]
+fn test_outage_buffer_bounded() {
+    let held_event = |signature: &str| HeldEvent {
+        log: RpcLogsResponse {
+            signature: signature.to_string(),
+            err: None,
+            logs: Vec::new(),
+        },
+        event_index: 0,
+        is_backfill: false,
+        slot: None,
+        block_time: None,
+        fee_payer: None,
+    };
+
+    let buffer = OutageBuffer::new(2);
+    assert!(buffer.is_empty());
+    assert!(buffer.hold(held_event("a")));
+    assert!(buffer.hold(held_event("b")));
+    assert!(!buffer.hold(held_event("c")));
+    assert_eq!(buffer.len(), 2);
+
+    let held: Vec<String> = buffer
+        .take_all()
+        .into_iter()
+        .map(|event| event.log.signature)
+        .collect();
+    assert_eq!(held, vec!["a", "b"]);
+    assert!(buffer.is_empty());
+}
+
+// Test that events are held while the database is unreachable, rather than
+// dead-lettered, and replayed in order once it accepts connections again
+#[tokio::test]
+async fn test_events_held_and_replayed_after_outage() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let metrics = metrics::init().unwrap();
+    let held_gauge = || metrics.outage_buffer_events.with_label_values(&["orca"]).get();
+
+    // Reserve a port for the database proxy, which doesn't listen until the database "recovers"
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let url = reqwest::Url::parse(&database.url).unwrap();
+    let target = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap_or(5432));
+    let mut proxied_url = url.clone();
+    proxied_url.set_host(Some("127.0.0.1")).unwrap();
+    proxied_url.set_port(Some(port)).unwrap();
+
+    let dead_letter_path = std::env::temp_dir().join(
+        format!("indexer_outage_buffer_{}.jsonl", std::process::id())
+    );
+    let _ = std::fs::remove_file(&dead_letter_path);
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    )
+        .with_persist_retry(RetryConfig {
+            max_attempts: 2,
+            base_delay_ms: 1,
+            max_delay_ms: 5,
+            jitter_ratio: 0.0,
+        })
+        .with_dead_letter_path(&dead_letter_path);
+    let db_pool = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(200))
+        .connect_lazy(proxied_url.as_str())
+        .unwrap();
+    let whirlpool = Pubkey::new_from_array(rand::random());
+    let pools = vec![whirlpool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool, Some(&pools), connection_config).await.unwrap();
+
+    let signatures = [Signature::new_unique().to_string(), Signature::new_unique().to_string()];
+    for (slot, signature) in (500..).zip(&signatures) {
+        let log = swap_log(&whirlpool, signature);
+        let event = indexer.parse_log_events(&log).await.unwrap().into_iter().next().unwrap();
+        let err = indexer.persist_event(event, &log, 0, false, Some(slot), None, None).await.unwrap_err();
+        assert!(err.to_string().contains("held"));
+    }
+    assert_eq!(indexer.outage_buffer().len(), 2);
+    assert_eq!(held_gauge(), 2);
+    assert!(DeadLetterQueue::new(&dead_letter_path).read_entries().await.unwrap().is_empty());
+
+    // Nothing is replayed while the database is still down
+    assert_eq!(indexer.replay_held_events().await, 0);
+    assert_eq!(indexer.outage_buffer().len(), 2);
+
+    // The database comes back
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    forward_connections(listener, target);
+
+    assert_eq!(indexer.replay_held_events().await, 2);
+    assert!(indexer.outage_buffer().is_empty());
+    assert_eq!(held_gauge(), 0);
+
+    let stored: Vec<(String, Option<i64>)> = sqlx
+        ::query_as(
+            "SELECT signature, slot FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1 ORDER BY id"
+        )
+        .bind(whirlpool.to_string())
+        .fetch_all(&database.pool).await
+        .unwrap();
+    assert_eq!(
+        stored,
+        vec![(signatures[0].clone(), Some(500)), (signatures[1].clone(), Some(501))]
+    );
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+use indexer::backfill_manager::{ BackfillConfig, BackfillManager };
+use indexer::db::signature_store::{ InMemorySignatureStore, SignatureStore };
+use indexer::rate_limiter::RateLimiter;
+
+// Mock RPC node answering every getSlot call immediately
+struct SlotSender {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl RpcSender for SlotSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        _params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        match request {
+            RpcRequest::GetVersion => Ok(serde_json::json!({ "solana-core": "1.18.26" })),
+            _ => {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                Ok(serde_json::json!(42))
+            }
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+// Test that requests are spaced by the limiter's interval, with no initial burst
+#[tokio::test]
+async fn test_rate_limiter_caps_throughput() {
+    let limiter = RateLimiter::new(50);
+    assert_eq!(limiter.interval(), Duration::from_millis(20));
+
+    let start = Instant::now();
+    for _ in 0..11 {
+        limiter.acquire().await;
+    }
+
+    // The first request goes out immediately, each of the other 10 waits 20ms
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+// Test that concurrent callers share the limit
+#[tokio::test]
+async fn test_rate_limiter_shared_between_tasks() {
+    let limiter = Arc::new(RateLimiter::new(100));
+
+    let start = Instant::now();
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                for _ in 0..5 {
+                    limiter.acquire().await;
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // 20 requests at 100 per second take at least 190ms in total, whichever task sends them
+    assert!(start.elapsed() >= Duration::from_millis(190));
+}
+
+// Test that a backfill manager with a request limit paces its RPC calls
+#[tokio::test]
+async fn test_backfill_manager_rpc_calls_paced() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let rpc_client = RpcClient::new_sender(
+        SlotSender { calls: calls.clone() },
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    let config = BackfillConfig {
+        max_requests_per_second: Some(40),
+        ..BackfillConfig::default()
+    };
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let manager = BackfillManager::with_rpc_client(config, store, rpc_client);
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        assert_eq!(manager.get_current_slot().await.unwrap(), 42);
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
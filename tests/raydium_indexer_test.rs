@@ -0,0 +1,118 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashSet;
+
+use indexer::indexers::raydium::validate_pool_sets;
+use indexer::indexers::{ ConnectionConfig, DexIndexer, RaydiumIndexer, RaydiumParsedEvent };
+use indexer::models::raydium::clmm::RaydiumCLMMCreatePositionEvent;
+
+mod common;
+
+// Test that a pool classified as both AMM and CLMM is rejected
+#[test]
+fn test_overlapping_pool_sets_rejected() {
+    let amm_pool = Pubkey::new_unique();
+    let clmm_pool = Pubkey::new_unique();
+    let shared_pool = Pubkey::new_unique();
+
+    let amm_pools = HashSet::from([amm_pool]);
+    let clmm_pools = HashSet::from([clmm_pool]);
+    assert!(validate_pool_sets(&amm_pools, &clmm_pools).is_ok());
+    assert!(validate_pool_sets(&HashSet::new(), &HashSet::new()).is_ok());
+
+    let amm_pools = HashSet::from([amm_pool, shared_pool]);
+    let clmm_pools = HashSet::from([clmm_pool, shared_pool]);
+    let err = validate_pool_sets(&amm_pools, &clmm_pools).unwrap_err();
+    assert_eq!(err.to_string(), format!("Pools classified as both AMM and CLMM: {}", shared_pool));
+}
+
+// Test that an indexer built from subscribed AMM and CLMM pools tracks both, each under its type
+#[tokio::test]
+async fn test_indexer_with_both_pool_types() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let amm_pool = Pubkey::new_from_array(rand::random());
+    let clmm_pool = Pubkey::new_from_array(rand::random());
+    for (pool, pool_type) in [(amm_pool, "amm"), (clmm_pool, "clmm")] {
+        sqlx
+            ::query(
+                "INSERT INTO apestrong.subscribed_pools (pool_mint, dex, pool_type) VALUES ($1, 'raydium', $2)"
+            )
+            .bind(pool.to_string())
+            .bind(pool_type)
+            .execute(&db_pool).await
+            .unwrap();
+    }
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let indexer = RaydiumIndexer::new(db_pool.clone(), None, connection_config).await;
+
+    // Other Raydium indexers on this database shouldn't pick the pools up
+    sqlx
+        ::query("DELETE FROM apestrong.subscribed_pools WHERE pool_mint = ANY($1)")
+        .bind(vec![amm_pool.to_string(), clmm_pool.to_string()])
+        .execute(&db_pool).await
+        .unwrap();
+    let indexer = indexer.unwrap();
+
+    let pools = indexer.pool_pubkeys();
+    assert!(pools.contains(&amm_pool));
+    assert!(pools.contains(&clmm_pool));
+    assert!(indexer.is_amm_pool(&amm_pool));
+    assert!(!indexer.is_clmm_pool(&amm_pool));
+    assert!(indexer.is_clmm_pool(&clmm_pool));
+    assert!(!indexer.is_amm_pool(&clmm_pool));
+}
+
+// Test that stored events record whether a backfill or the live stream found them
+#[tokio::test]
+async fn test_backfill_flag_stored() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+
+    let pool = Pubkey::new_from_array(rand::random());
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    );
+    let pools = vec![pool.to_string()];
+    let indexer = RaydiumIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+
+    let create_position = |signature: &str| {
+        let event = RaydiumCLMMCreatePositionEvent {
+            pool_state: pool,
+            minter: Pubkey::new_unique(),
+            nft_owner: Pubkey::new_unique(),
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity: 1_000,
+            deposit_amount_0: 10,
+            deposit_amount_1: 20,
+            deposit_amount_0_transfer_fee: 0,
+            deposit_amount_1_transfer_fee: 0,
+        };
+        RaydiumParsedEvent::ClmmCreatePosition(event, signature.to_string(), pool, None)
+    };
+    for is_backfill in [true, false] {
+        let signature = Signature::new_unique().to_string();
+        let stored = indexer
+            .handle_event(create_position(&signature), 0, is_backfill, Some(100), None, None).await
+            .unwrap();
+        assert!(stored);
+
+        let stored_flag: bool = sqlx
+            ::query_scalar("SELECT is_backfill FROM apestrong.raydium_clmm_events WHERE signature = $1")
+            .bind(&signature)
+            .fetch_one(&db_pool).await
+            .unwrap();
+        assert_eq!(stored_flag, is_backfill);
+    }
+}
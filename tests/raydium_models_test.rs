@@ -0,0 +1,145 @@
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+
+use indexer::Protocol;
+use indexer::db::common::{ from_numeric, from_numeric_array, to_numeric, to_numeric_array };
+use indexer::models::raydium::clmm::{
+    RaydiumCLMMEvent,
+    RaydiumCLMMEventType,
+    RaydiumCLMMCreatePositionRecord,
+    RaydiumCLMMDecreaseLiquidityRecord,
+    RaydiumCLMMIncreaseLiquidityRecord,
+};
+
+// Test that u128 values survive the NUMERIC encoding used for liquidity columns
+#[test]
+fn test_u128_numeric_round_trip() {
+    for value in [0u128, 1, u64::MAX as u128 + 1, u128::MAX] {
+        let encoded = to_numeric(value);
+        let decoded: u128 = from_numeric(&encoded).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    // The maximum u128 must be written out in full, not in scientific notation
+    assert_eq!(to_numeric(u128::MAX), "340282366920938463463374607431768211455");
+}
+
+// Test that u64 amounts survive the NUMERIC encoding
+#[test]
+fn test_u64_numeric_round_trip() {
+    for value in [0u64, 42, i64::MAX as u64 + 1, u64::MAX] {
+        let decoded: u64 = from_numeric(&to_numeric(value)).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
+
+// Test that reward amounts survive the NUMERIC[] encoding
+#[test]
+fn test_reward_amounts_numeric_array_round_trip() {
+    let rewards: [u64; 3] = [0, 12345, u64::MAX];
+
+    let encoded = to_numeric_array(&rewards);
+    assert_eq!(encoded.len(), 3);
+
+    let decoded: [u64; 3] = from_numeric_array(&encoded).unwrap();
+    assert_eq!(decoded, rewards);
+
+    // Arrays of the wrong length are rejected
+    let short = vec!["1".to_string(), "2".to_string()];
+    assert!(from_numeric_array::<u64, 3>(&short).is_err());
+}
+
+// Test that invalid NUMERIC text is rejected with an error instead of wrapping
+#[test]
+fn test_numeric_decoding_errors() {
+    assert!(from_numeric::<u64>("-1").is_err());
+    assert!(from_numeric::<u64>("18446744073709551616").is_err()); // u64::MAX + 1
+    assert!(from_numeric::<u128>("not a number").is_err());
+}
+
+// Test that a full set of CLMM records encodes and decodes without loss
+#[test]
+fn test_clmm_records_round_trip() {
+    let pool = Pubkey::from_str("3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv").unwrap();
+    let position_nft_mint = Pubkey::new_unique();
+
+    let base = RaydiumCLMMEvent::new(
+        "test_signature".to_string(),
+        pool,
+        RaydiumCLMMEventType::IncreaseLiquidity
+    );
+    assert_eq!(base.pool, pool.to_string());
+    assert_eq!(base.event_type, "IncreaseLiquidity");
+
+    let create = RaydiumCLMMCreatePositionRecord {
+        event_id: 1,
+        minter: Pubkey::new_unique().to_string(),
+        nft_owner: Pubkey::new_unique().to_string(),
+        tick_lower_index: -100,
+        tick_upper_index: 100,
+        liquidity: u128::MAX,
+        deposit_amount_0: u64::MAX,
+        deposit_amount_1: 1,
+        deposit_amount_0_transfer_fee: 0,
+        deposit_amount_1_transfer_fee: 7,
+    };
+    assert_eq!(from_numeric::<u128>(&to_numeric(create.liquidity)).unwrap(), create.liquidity);
+    assert_eq!(
+        from_numeric::<u64>(&to_numeric(create.deposit_amount_0)).unwrap(),
+        create.deposit_amount_0
+    );
+
+    let increase = RaydiumCLMMIncreaseLiquidityRecord {
+        event_id: 2,
+        position_nft_mint,
+        liquidity: u128::MAX - 1,
+        amount_0: u64::MAX,
+        amount_1: 2,
+        amount_0_transfer_fee: 3,
+        amount_1_transfer_fee: 4,
+    };
+    assert_eq!(
+        Pubkey::from_str(&increase.position_nft_mint.to_string()).unwrap(),
+        position_nft_mint
+    );
+    assert_eq!(
+        from_numeric::<u128>(&to_numeric(increase.liquidity)).unwrap(),
+        increase.liquidity
+    );
+
+    let decrease = RaydiumCLMMDecreaseLiquidityRecord {
+        event_id: 3,
+        position_nft_mint,
+        liquidity: 1 << 100,
+        decrease_amount_0: 10,
+        decrease_amount_1: u64::MAX,
+        fee_amount_0: 1,
+        fee_amount_1: 2,
+        reward_amounts: [u64::MAX, 0, 5],
+        transfer_fee_0: 0,
+        transfer_fee_1: 0,
+    };
+    assert_eq!(
+        from_numeric::<u128>(&to_numeric(decrease.liquidity)).unwrap(),
+        decrease.liquidity
+    );
+    assert_eq!(
+        from_numeric_array::<u64, 3>(&to_numeric_array(&decrease.reward_amounts)).unwrap(),
+        decrease.reward_amounts
+    );
+}
+
+// Test that protocols round-trip through their string names and share DEX keys per DEX
+#[test]
+fn test_protocol_names() {
+    for (protocol, name, dex) in [
+        (Protocol::OrcaWhirlpool, "orca_whirlpool", "orca"),
+        (Protocol::RaydiumAmm, "raydium_amm", "raydium"),
+        (Protocol::RaydiumClmm, "raydium_clmm", "raydium"),
+    ] {
+        assert_eq!(protocol.to_string(), name);
+        assert_eq!(Protocol::from_str(name).unwrap(), protocol);
+        assert_eq!(protocol.dex(), dex);
+    }
+    assert!(Protocol::from_str("raydium").is_err());
+}
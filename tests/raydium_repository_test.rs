@@ -0,0 +1,178 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use indexer::db::common::from_numeric;
+use indexer::db::repositories::RaydiumRepository;
+use indexer::models::raydium::clmm::{
+    RaydiumCLMMCreatePositionRecord,
+    RaydiumCLMMCreatePostionEventRecord,
+    RaydiumCLMMEvent,
+    RaydiumCLMMEventType,
+};
+
+mod common;
+
+// Helper to build a create-position event for a pool at a slot
+fn create_position_event(pool: Pubkey, slot: Option<i64>) -> RaydiumCLMMCreatePostionEventRecord {
+    RaydiumCLMMCreatePostionEventRecord {
+        base: RaydiumCLMMEvent {
+            slot,
+            ..RaydiumCLMMEvent::new(
+                Signature::new_unique().to_string(),
+                pool,
+                RaydiumCLMMEventType::CreatePosition
+            )
+        },
+        data: RaydiumCLMMCreatePositionRecord {
+            event_id: 0,
+            minter: Pubkey::new_unique().to_string(),
+            nft_owner: Pubkey::new_unique().to_string(),
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity: 1_000,
+            deposit_amount_0: 10,
+            deposit_amount_1: 20,
+            deposit_amount_0_transfer_fee: 0,
+            deposit_amount_1_transfer_fee: 0,
+        },
+    }
+}
+
+// Test that a stored create-position event keeps the event's fields and has no
+// output amount column, which the event doesn't carry
+#[tokio::test]
+async fn test_create_position_stored_without_output_amount() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = RaydiumRepository::new(db_pool.clone());
+
+    let pool = Pubkey::new_unique();
+    let minter = Pubkey::new_unique().to_string();
+    let event = RaydiumCLMMCreatePostionEventRecord {
+        base: RaydiumCLMMEvent::new(
+            Signature::new_unique().to_string(),
+            pool,
+            RaydiumCLMMEventType::CreatePosition
+        ),
+        data: RaydiumCLMMCreatePositionRecord {
+            event_id: 0,
+            minter: minter.clone(),
+            nft_owner: Pubkey::new_unique().to_string(),
+            tick_lower_index: -100,
+            tick_upper_index: 100,
+            liquidity: u128::MAX,
+            deposit_amount_0: 1_500,
+            deposit_amount_1: 2_500,
+            deposit_amount_0_transfer_fee: 0,
+            deposit_amount_1_transfer_fee: 7,
+        },
+    };
+    let event_id = repository.insert_clmm_create_position_event(event).await.unwrap();
+
+    let columns: Vec<(String,)> = sqlx
+        ::query_as(
+            "SELECT column_name::TEXT FROM information_schema.columns
+             WHERE table_schema = 'apestrong' AND table_name = 'raydium_clmm_create_position_events'"
+        )
+        .fetch_all(&db_pool).await
+        .unwrap();
+    let columns: Vec<String> = columns.into_iter().map(|(column,)| column).collect();
+    assert!(columns.contains(&"deposit_amount_0".to_string()));
+    assert!(!columns.contains(&"output_amount".to_string()));
+
+    let (stored_minter, liquidity, deposit_amount_1): (String, String, String) = sqlx
+        ::query_as(
+            "SELECT minter, liquidity::TEXT, deposit_amount_1::TEXT
+             FROM apestrong.raydium_clmm_create_position_events WHERE event_id = $1"
+        )
+        .bind(event_id)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(stored_minter, minter);
+    assert_eq!(from_numeric::<u128>(&liquidity).unwrap(), u128::MAX);
+    assert_eq!(from_numeric::<u64>(&deposit_amount_1).unwrap(), 2_500);
+}
+
+// Test that events store their slot and a rollback deletes a pool's events after a slot
+#[tokio::test]
+async fn test_delete_events_after_slot() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = RaydiumRepository::new(db_pool.clone());
+
+    let pool = Pubkey::new_from_array(rand::random());
+    let other_pool = Pubkey::new_from_array(rand::random());
+    for slot in [Some(200), Some(201), Some(250), None] {
+        repository.insert_clmm_create_position_event(create_position_event(pool, slot)).await.unwrap();
+    }
+    repository
+        .insert_clmm_create_position_event(create_position_event(other_pool, Some(250))).await
+        .unwrap();
+
+    assert_eq!(repository.delete_events_after_slot(&pool.to_string(), 200).await.unwrap(), 2);
+
+    // Helper to list a pool's remaining event slots, whose details must still exist
+    let remaining_slots = |pool: Pubkey| {
+        let db_pool = db_pool.clone();
+        async move {
+            let slots: Vec<(Option<i64>,)> = sqlx
+                ::query_as(
+                    "SELECT e.slot FROM apestrong.raydium_clmm_events e
+                     JOIN apestrong.raydium_clmm_create_position_events c ON e.id = c.event_id
+                     WHERE e.pool = $1 ORDER BY e.slot"
+                )
+                .bind(pool.to_string())
+                .fetch_all(&db_pool).await
+                .unwrap();
+            slots
+                .into_iter()
+                .map(|(slot,)| slot)
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(remaining_slots(pool).await, vec![Some(200), None]);
+    assert_eq!(remaining_slots(other_pool).await, vec![Some(250)]);
+}
+
+// Test that an event's block time is stored with it
+#[tokio::test]
+async fn test_block_time_stored() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let repository = RaydiumRepository::new(db_pool.clone());
+
+    let block_time = chrono::DateTime::from_timestamp(1_700_000_000, 0);
+    let mut event = create_position_event(Pubkey::new_unique(), Some(300));
+    event.base.block_time = block_time;
+    let event_id = repository.insert_clmm_create_position_event(event).await.unwrap();
+
+    let (stored,): (Option<chrono::DateTime<chrono::Utc>>,) = sqlx
+        ::query_as("SELECT block_time FROM apestrong.raydium_clmm_events WHERE id = $1")
+        .bind(event_id)
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(stored, block_time);
+}
+
+// Test that only signatures with a stored event are reported as indexed
+#[tokio::test]
+async fn test_indexed_signatures() {
+    let Some(database) = common::test_database(&["raydium"]).await else {
+        return;
+    };
+    let repository = RaydiumRepository::new(database.pool.clone());
+
+    let event = create_position_event(Pubkey::new_from_array(rand::random()), Some(300));
+    let stored = event.base.signature.clone();
+    repository.insert_clmm_create_position_event(event).await.unwrap();
+    let missing = Signature::new_unique().to_string();
+
+    let indexed = repository.indexed_signatures(&[stored.clone(), missing]).await.unwrap();
+    assert_eq!(indexed, [stored].into_iter().collect());
+}
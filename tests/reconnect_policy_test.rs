@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use indexer::websocket_manager::{ ReconnectPolicy, WebSocketConfig };
+
+// Helper to build a config with deterministic (jitter-free) backoff
+fn test_config() -> WebSocketConfig {
+    WebSocketConfig {
+        ws_url: "wss://primary.example.com".to_string(),
+        fallback_ws_urls: vec![
+            "wss://fallback-1.example.com".to_string(),
+            "wss://fallback-2.example.com".to_string()
+        ],
+        max_reconnect_attempts: 0,
+        reconnect_base_delay_ms: 500,
+        reconnect_max_delay_ms: 30_000,
+        reconnect_jitter_ratio: 0.0,
+        ..WebSocketConfig::default()
+    }
+}
+
+// Test that delays double on each attempt starting from the base delay
+#[test]
+fn test_backoff_sequence() {
+    let mut policy = ReconnectPolicy::new(&test_config());
+
+    let delays: Vec<u64> = (0..6).map(|_| policy.next_delay().as_millis() as u64).collect();
+    assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 16000]);
+    assert_eq!(policy.attempts(), 6);
+}
+
+// Test that the delay never exceeds the configured maximum
+#[test]
+fn test_backoff_cap() {
+    let mut policy = ReconnectPolicy::new(&test_config());
+
+    for _ in 0..6 {
+        policy.next_delay();
+    }
+    for _ in 0..10 {
+        assert_eq!(policy.next_delay(), Duration::from_millis(30_000));
+    }
+}
+
+// Test that jittered delays stay within [delay * (1 - ratio), delay]
+#[test]
+fn test_jitter_bounds() {
+    let config = WebSocketConfig {
+        reconnect_jitter_ratio: 0.5,
+        ..test_config()
+    };
+
+    for _ in 0..100 {
+        let mut policy = ReconnectPolicy::new(&config);
+        let expected = [500u64, 1000, 2000, 4000, 8000, 16000, 30000, 30000];
+
+        for base in expected {
+            let delay = policy.next_delay().as_millis() as u64;
+            assert!(
+                delay >= base / 2 && delay <= base,
+                "delay {} outside of [{}, {}]",
+                delay,
+                base / 2,
+                base
+            );
+        }
+    }
+}
+
+// Test that failed attempts rotate through the primary and fallback URLs
+#[test]
+fn test_url_rotation() {
+    let mut policy = ReconnectPolicy::new(&test_config());
+
+    assert_eq!(policy.next_url(), "wss://primary.example.com");
+    assert_eq!(policy.next_url(), "wss://fallback-1.example.com");
+    assert_eq!(policy.next_url(), "wss://fallback-2.example.com");
+    assert_eq!(policy.next_url(), "wss://primary.example.com");
+
+    // Without fallbacks the primary URL is always returned
+    let config = WebSocketConfig {
+        fallback_ws_urls: Vec::new(),
+        ..test_config()
+    };
+    let mut policy = ReconnectPolicy::new(&config);
+    for _ in 0..3 {
+        assert_eq!(policy.next_url(), "wss://primary.example.com");
+    }
+}
+
+// Test that a reset restores the base delay and keeps the URL that connected
+#[test]
+fn test_reset_after_success() {
+    let mut policy = ReconnectPolicy::new(&test_config());
+
+    policy.next_url();
+    policy.next_delay();
+    assert_eq!(policy.next_url(), "wss://fallback-1.example.com");
+    policy.next_delay();
+    policy.next_delay();
+
+    // Connection to fallback-1 succeeded
+    policy.reset();
+
+    assert_eq!(policy.attempts(), 0);
+    assert_eq!(policy.next_delay(), Duration::from_millis(500));
+    assert_eq!(policy.next_url(), "wss://fallback-1.example.com");
+}
+
+// Test that the policy reports exhaustion only when a limit is configured
+#[test]
+fn test_max_attempts() {
+    let config = WebSocketConfig {
+        max_reconnect_attempts: 3,
+        ..test_config()
+    };
+    let mut policy = ReconnectPolicy::new(&config);
+
+    for _ in 0..3 {
+        assert!(!policy.is_exhausted());
+        policy.next_delay();
+    }
+    assert!(policy.is_exhausted());
+
+    // 0 means unlimited
+    let mut unlimited = ReconnectPolicy::new(&test_config());
+    for _ in 0..100 {
+        unlimited.next_delay();
+    }
+    assert!(!unlimited.is_exhausted());
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use solana_client::client_error::{ ClientError, Result as ClientResult };
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::{ RpcError, RpcRequest };
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+
+use indexer::error::IndexerError;
+use indexer::rpc::{
+    build_rpc_client,
+    build_rpc_client_with_headers,
+    is_transient_client_error,
+    parse_rpc_header,
+    Cluster,
+    RetryingSender,
+};
+
+// Mock transport that fails a number of times before returning a slot
+struct FlakySender {
+    failures: usize,
+    permanent: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl RpcSender for FlakySender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        _params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        // RpcClient checks the node version before some calls
+        if request == RpcRequest::GetVersion {
+            return Ok(serde_json::json!({ "solana-core": "1.18.26" }));
+        }
+
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if call < self.failures {
+            return Err(test_error(self.permanent));
+        }
+        Ok(serde_json::json!(42))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+fn test_error(permanent: bool) -> ClientError {
+    if permanent {
+        RpcError::RpcRequestError("invalid params".to_string()).into()
+    } else {
+        std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection reset").into()
+    }
+}
+
+fn flaky_client(failures: usize, permanent: bool, max_retries: usize) -> (RpcClient, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let sender = FlakySender { failures, permanent, calls: calls.clone() };
+    let retrying = RetryingSender::new(sender, max_retries, Duration::from_millis(1));
+    let client = RpcClient::new_sender(
+        retrying,
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+    (client, calls)
+}
+
+// Test that transient errors are retried until the call succeeds
+#[tokio::test]
+async fn test_transient_errors_retried() {
+    let (client, calls) = flaky_client(2, false, 3);
+
+    assert_eq!(client.get_slot().await.unwrap(), 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+// Test that retries are bounded
+#[tokio::test]
+async fn test_retries_bounded() {
+    let (client, calls) = flaky_client(10, false, 3);
+
+    assert!(client.get_slot().await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+// Test that permanent errors are returned without retrying
+#[tokio::test]
+async fn test_permanent_errors_not_retried() {
+    let (client, calls) = flaky_client(1, true, 3);
+
+    assert!(client.get_slot().await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+// Test error classification
+#[test]
+fn test_is_transient_client_error() {
+    assert!(is_transient_client_error(&test_error(false)));
+    assert!(!is_transient_client_error(&test_error(true)));
+}
+
+// Test that client errors are classified by kind rather than by message
+#[test]
+fn test_client_errors_classified() {
+    assert!(matches!(IndexerError::from(test_error(false)), IndexerError::Transient(_)));
+    assert!(matches!(IndexerError::from(test_error(true)), IndexerError::Permanent(_)));
+
+    // Some providers answer with a JSON-RPC error instead of an HTTP 429
+    let rate_limited: ClientError = RpcError::RpcResponseError {
+        code: 429,
+        message: "Too many requests".to_string(),
+        data: solana_client::rpc_request::RpcResponseErrorData::Empty,
+    }.into();
+    let rate_limited = IndexerError::from(rate_limited);
+    assert!(matches!(rate_limited, IndexerError::RateLimited(_)));
+    assert!(rate_limited.is_transient());
+
+    let malformed: ClientError = serde_json::from_str::<u64>("null").unwrap_err().into();
+    assert!(matches!(IndexerError::from(malformed), IndexerError::Deserialize(_)));
+}
+
+// Test that a hung RPC node fails with a timeout instead of stalling
+#[tokio::test]
+async fn test_request_timeout() {
+    // Accept connections but never respond
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+
+    let client = build_rpc_client(&url, CommitmentConfig::confirmed(), Duration::from_millis(100));
+    let result = tokio::time::timeout(Duration::from_secs(10), client.get_slot()).await;
+
+    assert!(result.expect("RPC call should time out on its own").is_err());
+
+    // Sent directly, the timeout keeps its kind instead of being wrapped by the version check
+    let err = client.send::<u64>(RpcRequest::GetSlot, serde_json::Value::Null).await.unwrap_err();
+    assert!(matches!(IndexerError::from(err), IndexerError::Transient(_)));
+}
+
+// Test parsing RPC headers given as 'Name: value'
+#[test]
+fn test_parse_rpc_header() {
+    assert_eq!(
+        parse_rpc_header("Authorization: Bearer abc:def").unwrap(),
+        ("Authorization".to_string(), "Bearer abc:def".to_string())
+    );
+    assert_eq!(
+        parse_rpc_header("x-api-key:123").unwrap(),
+        ("x-api-key".to_string(), "123".to_string())
+    );
+    assert!(parse_rpc_header("Authorization").is_err());
+    assert!(parse_rpc_header("Bad Name: value").is_err());
+    assert!(parse_rpc_header("x-api-key: line\nbreak").is_err());
+}
+
+// Test that cluster names are parsed case-insensitively, including mainnet's full name
+#[test]
+fn test_parse_cluster() {
+    assert_eq!("devnet".parse(), Ok(Cluster::Devnet));
+    assert_eq!("Testnet".parse(), Ok(Cluster::Testnet));
+    assert_eq!("localnet".parse(), Ok(Cluster::Localnet));
+    assert_eq!("mainnet".parse(), Ok(Cluster::Mainnet));
+    assert_eq!("mainnet-beta".parse(), Ok(Cluster::Mainnet));
+    assert!("localhost".parse::<Cluster>().is_err());
+}
+
+// Test that configured headers are sent with every RPC request
+#[tokio::test]
+async fn test_rpc_headers_sent() {
+    use hyper::service::{ make_service_fn, service_fn };
+    use hyper::{ Body, Request, Response, Server };
+    use std::convert::Infallible;
+
+    // Record the Authorization header of each request and answer with a slot
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = seen.clone();
+    let make_service = make_service_fn(move |_conn| {
+        let recorder = recorder.clone();
+        async move {
+            Ok::<_, Infallible>(
+                service_fn(move |req: Request<Body>| {
+                    let recorder = recorder.clone();
+                    async move {
+                        let auth = req
+                            .headers()
+                            .get("authorization")
+                            .map(|value| value.to_str().unwrap().to_string());
+                        recorder.lock().unwrap().push(auth);
+
+                        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        let result = match request["method"].as_str() {
+                            Some("getVersion") => serde_json::json!({ "solana-core": "1.18.26" }),
+                            _ => serde_json::json!(42),
+                        };
+                        let response =
+                            serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "result": result,
+                        });
+                        Ok::<_, Infallible>(Response::new(Body::from(response.to_string())))
+                    }
+                })
+            )
+        }
+    });
+    let server = Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+
+    let headers = HashMap::from([("Authorization".to_string(), "Bearer secret".to_string())]);
+    let client = build_rpc_client_with_headers(
+        &url,
+        CommitmentConfig::confirmed(),
+        Duration::from_secs(5),
+        &headers
+    );
+
+    assert_eq!(client.get_slot().await.unwrap(), 42);
+    let seen = seen.lock().unwrap();
+    assert!(!seen.is_empty());
+    assert!(seen.iter().all(|auth| auth.as_deref() == Some("Bearer secret")));
+}
@@ -0,0 +1,209 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+
+use indexer::db::common::normalize_signature;
+use indexer::db::signature_store::{
+    BackfillProgress,
+    DbSignatureStore,
+    InMemorySignatureStore,
+    SignatureStore,
+    TrackedPoolOrder,
+};
+
+mod common;
+
+// Helper to generate a valid, unique signature
+fn test_signature() -> String {
+    Signature::new_unique().to_string()
+}
+
+// Test that in-memory tracked pools are ordered by most recent update and limited
+#[tokio::test]
+async fn test_in_memory_tracked_pools_order() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let pools: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+
+    for pool in &pools {
+        store.update_signature(pool, test_signature(), "orca").await.unwrap();
+    }
+    // Touching the first pool again makes it the most recent
+    store.update_signature(&pools[0], test_signature(), "orca").await.unwrap();
+    store.update_signature(&Pubkey::new_unique(), test_signature(), "raydium").await.unwrap();
+
+    let recent = store.get_tracked_pools("orca", TrackedPoolOrder::RecentFirst, None).await.unwrap();
+    assert_eq!(recent, vec![pools[0], pools[3], pools[2], pools[1]]);
+
+    let limited = store
+        .get_tracked_pools("orca", TrackedPoolOrder::RecentFirst, Some(2)).await
+        .unwrap();
+    assert_eq!(limited, vec![pools[0], pools[3]]);
+
+    let all = store.get_tracked_pools("orca", TrackedPoolOrder::Unordered, None).await.unwrap();
+    assert_eq!(all.len(), 4);
+}
+
+// Test that database tracked pools are ordered by last_updated descending
+#[tokio::test]
+async fn test_db_tracked_pools_order() {
+    let Some(database) = common::test_database(&[]).await else {
+        return;
+    };
+    let db_pool = database.pool.clone();
+    let store = SignatureStore::Database(DbSignatureStore::new(db_pool.clone()));
+
+    // Push the new pools ahead of anything left over from earlier runs
+    let pools: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    for (i, offset) in [10, 30, 20].iter().enumerate() {
+        store.update_signature(&pools[i], test_signature(), "orca").await.unwrap();
+        sqlx
+            ::query(
+                "UPDATE apestrong.last_signatures SET last_updated = NOW() + make_interval(days => 1, secs => $2) WHERE pool_address = $1"
+            )
+            .bind(pools[i].to_string())
+            .bind(*offset as f64)
+            .execute(&db_pool).await
+            .unwrap();
+    }
+
+    let recent = store
+        .get_tracked_pools("orca", TrackedPoolOrder::RecentFirst, Some(3)).await
+        .unwrap();
+    assert_eq!(recent, vec![pools[1], pools[2], pools[0]]);
+
+    let limited = store
+        .get_tracked_pools("orca", TrackedPoolOrder::RecentFirst, Some(1)).await
+        .unwrap();
+    assert_eq!(limited, vec![pools[1]]);
+}
+
+// Test that differently formatted inputs for the same signature are stored under one key
+#[tokio::test]
+async fn test_signature_normalized_before_storage() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    let pool = Pubkey::new_unique();
+    let signature = Signature::new_unique().to_string();
+
+    // Signatures copied out of logs can carry surrounding whitespace
+    store.update_signature(&pool, format!("  {}\n", signature), "orca").await.unwrap();
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), Some(signature.clone()));
+
+    assert_eq!(normalize_signature(&format!(" {} ", signature)).unwrap(), signature);
+    assert_eq!(normalize_signature(&signature).unwrap(), signature);
+
+    // Malformed signatures are rejected rather than stored
+    assert!(store.update_signature(&pool, "not-a-signature".to_string(), "orca").await.is_err());
+    assert!(normalize_signature("0OIl").is_err());
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), Some(signature));
+}
+
+// Helper to check that backfill progress round-trips through a store
+async fn assert_backfill_progress_round_trip(store: &SignatureStore) {
+    // Random rather than new_unique, which repeats across runs against the same database
+    let pool = Pubkey::new_from_array(rand::random());
+    assert_eq!(store.get_backfill_progress(&pool, "orca").await.unwrap(), None);
+
+    let partial = BackfillProgress {
+        oldest_backfilled_signature: Some(test_signature()),
+        backfill_complete: false,
+    };
+    store.update_backfill_progress(&pool, partial.clone(), "orca").await.unwrap();
+    assert_eq!(store.get_backfill_progress(&pool, "orca").await.unwrap(), Some(partial.clone()));
+
+    let complete = BackfillProgress { backfill_complete: true, ..partial };
+    store.update_backfill_progress(&pool, complete.clone(), "orca").await.unwrap();
+    assert_eq!(store.get_backfill_progress(&pool, "orca").await.unwrap(), Some(complete));
+
+    let invalid = BackfillProgress {
+        oldest_backfilled_signature: Some("not-a-signature".to_string()),
+        backfill_complete: false,
+    };
+    assert!(store.update_backfill_progress(&pool, invalid, "orca").await.is_err());
+}
+
+// Test that in-memory backfill progress is stored and updated per pool
+#[tokio::test]
+async fn test_in_memory_backfill_progress() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    assert_backfill_progress_round_trip(&store).await;
+}
+
+// Test that database backfill progress survives across store instances
+#[tokio::test]
+async fn test_db_backfill_progress() {
+    let Some(database) = common::test_database(&[]).await else {
+        return;
+    };
+    let store = SignatureStore::Database(DbSignatureStore::new(database.pool.clone()));
+    assert_backfill_progress_round_trip(&store).await;
+}
+
+// Helper to check that a bulk read returns the signatures of the stored pools only
+async fn assert_bulk_signature_read(store: &SignatureStore) {
+    let stored: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_from_array(rand::random())).collect();
+    let missing = Pubkey::new_from_array(rand::random());
+    let mut expected = HashMap::new();
+    for pool in &stored {
+        let signature = test_signature();
+        store.update_signature(pool, signature.clone(), "orca").await.unwrap();
+        expected.insert(*pool, signature);
+    }
+
+    let mut pools = stored.clone();
+    pools.push(missing);
+    assert_eq!(store.get_signatures_for_pools(&pools, "orca").await.unwrap(), expected);
+
+    // Signatures of another DEX aren't returned
+    assert!(store.get_signatures_for_pools(&pools, "raydium").await.unwrap().is_empty());
+    assert!(store.get_signatures_for_pools(&[], "orca").await.unwrap().is_empty());
+}
+
+// Test reading the signatures of several pools at once from memory
+#[tokio::test]
+async fn test_in_memory_bulk_signature_read() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    assert_bulk_signature_read(&store).await;
+}
+
+// Test reading the signatures of several pools with one database query
+#[tokio::test]
+async fn test_db_bulk_signature_read() {
+    let Some(database) = common::test_database(&[]).await else {
+        return;
+    };
+    let store = SignatureStore::Database(DbSignatureStore::new(database.pool.clone()));
+    assert_bulk_signature_read(&store).await;
+}
+
+// Helper to check that a signature's slot is stored with it and cleared by a slotless update
+async fn assert_signature_slot_round_trip(store: &SignatureStore) {
+    let pool = Pubkey::new_from_array(rand::random());
+    assert_eq!(store.get_signature_slot(&pool, "orca").await.unwrap(), None);
+
+    let signature = test_signature();
+    store.update_signature_at_slot(&pool, signature.clone(), Some(250_000_000), "orca").await.unwrap();
+    assert_eq!(store.get_signature(&pool, "orca").await.unwrap(), Some(signature));
+    assert_eq!(store.get_signature_slot(&pool, "orca").await.unwrap(), Some(250_000_000));
+    assert_eq!(store.get_signature_slot(&pool, "raydium").await.unwrap(), None);
+
+    // A newer signature of unknown slot must not keep the old signature's slot
+    store.update_signature(&pool, test_signature(), "orca").await.unwrap();
+    assert_eq!(store.get_signature_slot(&pool, "orca").await.unwrap(), None);
+}
+
+// Test that the in-memory store keeps the slot of the last signature
+#[tokio::test]
+async fn test_in_memory_signature_slot() {
+    let store = SignatureStore::InMemory(InMemorySignatureStore::new());
+    assert_signature_slot_round_trip(&store).await;
+}
+
+// Test that the database store keeps the slot of the last signature
+#[tokio::test]
+async fn test_db_signature_slot() {
+    let Some(database) = common::test_database(&[]).await else {
+        return;
+    };
+    let store = SignatureStore::Database(DbSignatureStore::new(database.pool.clone()));
+    assert_signature_slot_round_trip(&store).await;
+}
@@ -0,0 +1,60 @@
+use std::time::{ Duration, Instant };
+
+use indexer::staleness::{ StalenessConfig, StalenessDetector, DEFAULT_MIN_STALE_THRESHOLD };
+
+// Helper to build a detector that has seen messages at a regular interval
+fn detector_with_gap(gap: Duration, messages: u32) -> StalenessDetector {
+    let mut detector = StalenessDetector::new(StalenessConfig::default());
+    let start = Instant::now();
+    for i in 0..messages {
+        detector.record_message(start + gap * i);
+    }
+    detector
+}
+
+// Test that the minimum threshold is used until enough gaps have been observed
+#[test]
+fn test_threshold_before_samples() {
+    let detector = detector_with_gap(Duration::from_secs(600), 3);
+    assert_eq!(detector.median_gap(), None);
+    assert_eq!(detector.threshold(), DEFAULT_MIN_STALE_THRESHOLD);
+    assert!(detector.is_stale(Duration::from_secs(61)));
+}
+
+// Test that a quiet stream doesn't trigger a backfill for its usual gaps, but a stall does
+#[test]
+fn test_quiet_stream_adapts_threshold() {
+    let detector = detector_with_gap(Duration::from_secs(300), 20);
+    assert_eq!(detector.median_gap(), Some(Duration::from_secs(300)));
+    assert_eq!(detector.threshold(), Duration::from_secs(1500));
+
+    // Several minutes of silence is normal for this stream
+    assert!(!detector.is_stale(Duration::from_secs(600)));
+    // But five times the usual gap is a genuine stall
+    assert!(detector.is_stale(Duration::from_secs(1501)));
+}
+
+// Test that a busy stream keeps the minimum threshold
+#[test]
+fn test_busy_stream_keeps_minimum() {
+    let detector = detector_with_gap(Duration::from_secs(1), 50);
+    assert_eq!(detector.threshold(), DEFAULT_MIN_STALE_THRESHOLD);
+    assert!(detector.is_stale(Duration::from_secs(90)));
+}
+
+// Test that the threshold never exceeds the configured maximum
+#[test]
+fn test_threshold_capped() {
+    let config = StalenessConfig {
+        max_threshold: Duration::from_secs(900),
+        ..StalenessConfig::default()
+    };
+    let mut detector = StalenessDetector::new(config);
+    let start = Instant::now();
+    for i in 0..10 {
+        detector.record_message(start + Duration::from_secs(3600) * i);
+    }
+
+    assert_eq!(detector.threshold(), Duration::from_secs(900));
+    assert!(detector.is_stale(Duration::from_secs(901)));
+}
@@ -0,0 +1,48 @@
+use solana_client::rpc_response::{ Response, RpcLogsResponse, RpcResponseContext };
+
+use indexer::program_ids::{ ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID };
+use indexer::subscription_hub::SubscriptionHub;
+
+// Helper to build a log notification for a transaction
+fn log_response(signature: &str, slot: u64) -> Response<RpcLogsResponse> {
+    Response {
+        context: RpcResponseContext::new(slot),
+        value: RpcLogsResponse {
+            signature: signature.to_string(),
+            err: None,
+            logs: vec![format!("Program {} invoke [1]", ORCA_WHIRLPOOL_PROGRAM_ID)],
+        },
+    }
+}
+
+// Test that two indexers sharing a program get one subscription that fans out to both
+#[tokio::test]
+async fn test_shared_program_fans_out() {
+    let mut hub = SubscriptionHub::new();
+    let mut orca = hub.register("orca", &[ORCA_WHIRLPOOL_PROGRAM_ID]);
+    let mut other = hub.register("other", &[ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID]);
+
+    // One subscription per program, however many indexers want it
+    let mut expected = vec![ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID];
+    expected.sort();
+    assert_eq!(hub.program_ids(), expected);
+    assert_eq!(hub.subscribers(ORCA_WHIRLPOOL_PROGRAM_ID), vec!["orca", "other"]);
+
+    // A log for the shared program reaches both indexers
+    assert_eq!(hub.fan_out(ORCA_WHIRLPOOL_PROGRAM_ID, log_response("sig1", 100)).await, 2);
+    assert_eq!(orca.recv().await.unwrap().value.signature, "sig1");
+    let received = other.recv().await.unwrap();
+    assert_eq!(received.value.signature, "sig1");
+    assert_eq!(received.context.slot, 100);
+
+    // A log for a program only one indexer monitors reaches just that one
+    assert_eq!(hub.fan_out(RAYDIUM_CLMM_PROGRAM_ID, log_response("sig2", 101)).await, 1);
+    assert_eq!(other.recv().await.unwrap().value.signature, "sig2");
+    assert!(orca.try_recv().is_err());
+
+    // Unknown programs and stopped indexers are skipped
+    assert_eq!(hub.fan_out("unknown", log_response("sig3", 102)).await, 0);
+    drop(orca);
+    assert_eq!(hub.fan_out(ORCA_WHIRLPOOL_PROGRAM_ID, log_response("sig4", 103)).await, 1);
+    assert_eq!(other.recv().await.unwrap().value.signature, "sig4");
+}
@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_client::client_error::Result as ClientResult;
+use solana_account_decoder::UiAccount;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{ RpcSender, RpcTransportStats };
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::Arc;
+use std::time::Duration;
+
+use indexer::db::TokenMetadataStore;
+use indexer::models::token::{ fetch_token_info, parse_mint_decimals };
+
+mod common;
+
+// Mock RPC node holding a single token mint with 9 decimals and no Metaplex metadata.
+// Counts the account lookups made for the mint
+struct MintSender {
+    mint: Pubkey,
+    fetches: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl RpcSender for MintSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value
+    ) -> ClientResult<serde_json::Value> {
+        match request {
+            RpcRequest::GetVersion => Ok(serde_json::json!({ "solana-core": "1.18.26" })),
+            RpcRequest::GetAccountInfo => {
+                if params[0] != self.mint.to_string() {
+                    // The metadata account doesn't exist
+                    return Ok(serde_json::json!({ "context": { "slot": 1 }, "value": null }));
+                }
+
+                self.fetches.fetch_add(1, Ordering::SeqCst);
+                let mut data = vec![0u8; 82];
+                data[44] = 9;
+                data[45] = 1;
+                Ok(
+                    serde_json::json!({
+                        "context": { "slot": 1 },
+                        "value": {
+                            "data": [STANDARD.encode(&data), "base64"],
+                            "executable": false,
+                            "lamports": 1_461_600,
+                            "owner": "TokenkegQfeZyiNwAXLyjxC4XM4YveSe2NThhFEJU3b",
+                            "rentEpoch": 0,
+                            "space": 82,
+                        },
+                    })
+                )
+            }
+            other => panic!("Unexpected RPC request {:?}", other),
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+// Test that missing metadata falls back to the mint address unless metadata is required
+#[tokio::test]
+async fn test_missing_token_metadata() {
+    let mint = Pubkey::new_unique();
+    let metadata_program_id = Pubkey::new_unique();
+    let rpc_client = RpcClient::new_sender(
+        MintSender { mint, fetches: Arc::default() },
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+    );
+
+    // Lenient mode stores a placeholder symbol
+    let info = fetch_token_info(&rpc_client, &mint, &metadata_program_id, false).await.unwrap();
+    assert_eq!(info.mint, mint);
+    assert_eq!(info.decimals, 9);
+    assert!(info.name.is_empty());
+    let address = mint.to_string();
+    assert_eq!(info.symbol, format!("{}..{}", &address[..4], &address[address.len() - 4..]));
+
+    // Strict mode fails instead of storing blanks
+    let err = fetch_token_info(&rpc_client, &mint, &metadata_program_id, true).await.unwrap_err();
+    assert!(err.to_string().contains("Token metadata is required"));
+    assert!(err.to_string().contains("not found"));
+}
+
+// Helper to store a token fetched the given number of hours ago, with 6 decimals
+async fn store_token(db_pool: &sqlx::PgPool, mint: &Pubkey, hours_ago: i32) {
+    sqlx
+        ::query(
+            "INSERT INTO apestrong.token_metadata (mint, token_name, symbol, decimals, last_updated)
+             VALUES ($1, 'Stored', 'STORED', 6, NOW() - make_interval(hours => $2))"
+        )
+        .bind(mint.to_string())
+        .bind(hours_ago)
+        .execute(db_pool).await
+        .unwrap();
+}
+
+// Test that stored token metadata is reused while fresh and fetched from chain again once stale
+#[tokio::test]
+async fn test_refresh_token_metadata() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    let ttl = Duration::from_secs(24 * 60 * 60);
+
+    // Helper to build a store whose RPC node only knows the given mint
+    let store_for = |mint: Pubkey| {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let rpc_client = RpcClient::new_sender(
+            MintSender { mint, fetches: fetches.clone() },
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed())
+        );
+        let store = TokenMetadataStore::new(
+            database.pool.clone(),
+            Arc::new(rpc_client),
+            Pubkey::new_unique(),
+            false
+        );
+        (store, fetches)
+    };
+
+    // A token fetched an hour ago is served from the database
+    let fresh = Pubkey::new_from_array(rand::random());
+    store_token(&database.pool, &fresh, 1).await;
+    let (store, fetches) = store_for(fresh);
+    let info = store.refresh_token_metadata(&fresh, ttl).await.unwrap();
+    assert_eq!((info.symbol.as_str(), info.decimals), ("STORED", 6));
+    assert_eq!(fetches.load(Ordering::SeqCst), 0);
+
+    // A token fetched two days ago is fetched again and stored as fresh
+    let stale = Pubkey::new_from_array(rand::random());
+    store_token(&database.pool, &stale, 48).await;
+    let (store, fetches) = store_for(stale);
+    let info = store.refresh_token_metadata(&stale, ttl).await.unwrap();
+    assert_eq!(info.decimals, 9);
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    let (stored, last_updated) = store.get_token(&stale).await.unwrap().unwrap();
+    assert_eq!(stored.decimals, 9);
+    assert!(chrono::Utc::now() - last_updated < chrono::Duration::minutes(1));
+
+    // Now fresh, it isn't fetched again
+    store.refresh_token_metadata(&stale, ttl).await.unwrap();
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+// Helper to load the USDC mint account fixture, in the getAccountInfo format
+fn usdc_mint() -> (Pubkey, Account) {
+    let fixture: serde_json::Value = serde_json
+        ::from_str(include_str!("fixtures/usdc_mint_account.json"))
+        .unwrap();
+    let mint = Pubkey::from_str(fixture["pubkey"].as_str().unwrap()).unwrap();
+    let account: UiAccount = serde_json::from_value(fixture["account"].clone()).unwrap();
+    (mint, account.decode().unwrap())
+}
+
+// Test that decimals are read from SPL Token and Token-2022 mints and that
+// accounts that aren't initialized mints are rejected
+#[test]
+fn test_parse_mint_decimals() {
+    let (mint, account) = usdc_mint();
+    assert_eq!(parse_mint_decimals(&mint, &account).unwrap(), 6);
+
+    // Token-2022 mints append their extensions after the base mint layout
+    let mut extended = account.clone();
+    extended.data.resize(170, 0);
+    extended.data[165] = 1;
+    assert_eq!(parse_mint_decimals(&mint, &extended).unwrap(), 6);
+
+    let mut short = account.clone();
+    short.data.truncate(45);
+    let err = parse_mint_decimals(&mint, &short).unwrap_err();
+    assert!(err.to_string().contains("data length is 45 bytes, expected at least 82"));
+
+    let mut uninitialized = account;
+    uninitialized.data[45] = 0;
+    assert!(parse_mint_decimals(&mint, &uninitialized).is_err());
+}
@@ -0,0 +1,138 @@
+use log::{ Level, LevelFilter, Log, Metadata, Record };
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::sync::Mutex;
+
+use indexer::indexers::{ ConnectionConfig, DexIndexer, OrcaWhirlpoolIndexer, OrcaWhirlpoolParsedEvent };
+use indexer::models::orca::whirlpool::OrcaWhirlpoolTradedEvent;
+use indexer::utils::price::ui_amount;
+use indexer::{ metrics, OrcaWhirlpoolPoolRecord, OrcaWhirlpoolRepository };
+
+mod common;
+
+// Logger keeping warnings so tests can check them
+struct CapturingLogger {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.warnings.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger { warnings: Mutex::new(Vec::new()) };
+
+// Helper to build a SOL -> USDC sized trade: 2 SOL in, 300 USDC out in base units
+fn sol_usdc_trade(whirlpool: Pubkey) -> OrcaWhirlpoolTradedEvent {
+    OrcaWhirlpoolTradedEvent {
+        whirlpool,
+        token_vault_a: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        tick_array_lower: Pubkey::new_unique(),
+        tick_array_upper: Pubkey::new_unique(),
+        a_to_b: true,
+        input_amount: 2_000_000_000,
+        output_amount: 300_000_000,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        protocol_fee: 1,
+        lp_fee: 3,
+        pre_sqrt_price: 1 << 64,
+        post_sqrt_price: 1 << 64,
+    }
+}
+
+// Test scaling raw amounts into whole tokens
+#[test]
+fn test_ui_amount() {
+    assert_eq!(ui_amount(2_000_000_000, 9), 2.0);
+    assert_eq!(ui_amount(300_000_000, 6), 300.0);
+    assert_eq!(ui_amount(5, 0), 5.0);
+}
+
+// Test that a trade in a pool with wrong token decimals is flagged with a warning
+// naming the pool and the scaled amount, while a correctly configured pool isn't
+#[tokio::test]
+async fn test_wrong_decimals_trade_flagged() {
+    let Some(database) = common::test_database(&["orca"]).await else {
+        return;
+    };
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+    let metrics = metrics::init().unwrap();
+
+    let db_pool = database.pool.clone();
+    let repository = OrcaWhirlpoolRepository::new(db_pool.clone());
+
+    // SOL has 9 decimals; the misconfigured pool records it with 0
+    let correct_pool = Pubkey::new_from_array(rand::random());
+    let wrong_pool = Pubkey::new_from_array(rand::random());
+    for (whirlpool, decimals_a) in [
+        (correct_pool, 9),
+        (wrong_pool, 0),
+    ] {
+        repository
+            .upsert_pool(
+                &(OrcaWhirlpoolPoolRecord {
+                    whirlpool: whirlpool.to_string(),
+                    token_mint_a: Pubkey::new_from_array(rand::random()).to_string(),
+                    token_mint_b: Pubkey::new_from_array(rand::random()).to_string(),
+                    token_name_a: Some("SOL".to_string()),
+                    token_name_b: Some("USDC".to_string()),
+                    pool_name: Some("SOL/USDC".to_string()),
+                    decimals_a,
+                    decimals_b: 6,
+                })
+            ).await
+            .unwrap();
+    }
+
+    let connection_config = ConnectionConfig::new(
+        "http://127.0.0.1:8899".to_string(),
+        "ws://127.0.0.1:8900".to_string()
+    ).with_max_plausible_trade_amount(Some(1_000_000.0));
+    let pools = vec![correct_pool.to_string(), wrong_pool.to_string()];
+    let indexer = OrcaWhirlpoolIndexer::new(db_pool.clone(), Some(&pools), connection_config).await.unwrap();
+    let flagged = || metrics.implausible_trades.with_label_values(&["orca"]).get();
+
+    let event = OrcaWhirlpoolParsedEvent::Traded(
+        sol_usdc_trade(correct_pool),
+        Signature::new_unique().to_string(),
+        1
+    );
+    indexer.handle_event(event, 0, false, Some(100), None, None).await.unwrap();
+    assert!(LOGGER.warnings.lock().unwrap().is_empty());
+    assert_eq!(flagged(), 0);
+
+    // 2 SOL read with 0 decimals is 2 billion tokens
+    let event = OrcaWhirlpoolParsedEvent::Traded(
+        sol_usdc_trade(wrong_pool),
+        Signature::new_unique().to_string(),
+        1
+    );
+    indexer.handle_event(event, 0, false, Some(101), None, None).await.unwrap();
+
+    let warnings = LOGGER.warnings.lock().unwrap().clone();
+    assert_eq!(warnings.len(), 1, "unexpected warnings: {:?}", warnings);
+    assert!(warnings[0].contains("Implausible trade input amount"));
+    assert!(warnings[0].contains(&wrong_pool.to_string()));
+    assert!(warnings[0].contains("2000000000 tokens with 0 decimals"));
+    assert_eq!(flagged(), 1);
+
+    // The trade is still stored
+    let (count,): (i64,) = sqlx
+        ::query_as("SELECT COUNT(*) FROM apestrong.orca_whirlpool_events WHERE whirlpool = $1")
+        .bind(wrong_pool.to_string())
+        .fetch_one(&db_pool).await
+        .unwrap();
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,140 @@
+use hyper::service::{ make_service_fn, service_fn };
+use hyper::{ Body, Request, Response, Server, StatusCode };
+use solana_sdk::pubkey::Pubkey;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use indexer::backfill_manager::RetryConfig;
+use indexer::events::{ EventSource, IndexedEvent, OrcaTrade };
+use indexer::sinks::{ EventSink, WebhookConfig, WebhookSink };
+
+// Helper to start an HTTP server that fails the first `failures` requests with a 500,
+// then forwards each request body and answers 200
+async fn webhook_server(failures: usize, delay: Duration) -> (SocketAddr, mpsc::UnboundedReceiver<String>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let make_service = make_service_fn(move |_| {
+        let sender = sender.clone();
+        let requests = requests.clone();
+        async move {
+            Ok::<_, Infallible>(
+                service_fn(move |request: Request<Body>| {
+                    let sender = sender.clone();
+                    let requests = requests.clone();
+                    async move {
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        tokio::time::sleep(delay).await;
+                        if requests.fetch_add(1, Ordering::SeqCst) < failures {
+                            let mut response = Response::new(Body::empty());
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            return Ok::<_, Infallible>(response);
+                        }
+                        let _ = sender.send(String::from_utf8(body.to_vec()).unwrap());
+                        Ok(Response::new(Body::empty()))
+                    }
+                })
+            )
+        }
+    });
+
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    (addr, receiver)
+}
+
+// Helper to build a webhook config with fast retries
+fn webhook_config(addr: SocketAddr) -> WebhookConfig {
+    let mut config = WebhookConfig::new(format!("http://{}/events", addr));
+    config.retry = RetryConfig {
+        max_attempts: 3,
+        base_delay_ms: 1,
+        max_delay_ms: 10,
+        jitter_ratio: 0.0,
+    };
+    config
+}
+
+// Helper to create a trade event
+fn trade_event(pool: Pubkey, signature: &str) -> IndexedEvent {
+    IndexedEvent::OrcaTraded(OrcaTrade {
+        source: EventSource {
+            pool,
+            signature: signature.to_string(),
+            slot: Some(42),
+            block_time: None,
+            is_backfill: false,
+        },
+        a_to_b: true,
+        input_amount: 1_000,
+        output_amount: 990,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        lp_fee: 3,
+        protocol_fee: 1,
+        pre_sqrt_price: u128::MAX,
+        post_sqrt_price: 1,
+    })
+}
+
+// Test that events are POSTed as JSON
+#[tokio::test]
+async fn test_webhook_delivers_json() {
+    let (addr, mut bodies) = webhook_server(0, Duration::ZERO).await;
+    let sink = WebhookSink::new(webhook_config(addr)).unwrap();
+    let pool = Pubkey::new_unique();
+
+    sink.write(&trade_event(pool, "sig1")).await.unwrap();
+
+    let body = tokio::time::timeout(Duration::from_secs(5), bodies.recv()).await.unwrap().unwrap();
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["event"], "orca_traded");
+    assert_eq!(json["pool"], pool.to_string());
+    assert_eq!(json["signature"], "sig1");
+    assert_eq!(json["input_amount"], 1_000);
+    assert_eq!(json["pre_sqrt_price"], u128::MAX.to_string());
+}
+
+// Test that a failed delivery is retried
+#[tokio::test]
+async fn test_webhook_retries_server_errors() {
+    let (addr, mut bodies) = webhook_server(2, Duration::ZERO).await;
+    let sink = WebhookSink::new(webhook_config(addr)).unwrap();
+
+    sink.write(&trade_event(Pubkey::new_unique(), "sig1")).await.unwrap();
+
+    let body = tokio::time::timeout(Duration::from_secs(5), bodies.recv()).await.unwrap().unwrap();
+    assert!(body.contains("\"signature\":\"sig1\""));
+}
+
+// Test that a slow endpoint drops events once the queue is full instead of blocking the writer
+#[tokio::test]
+async fn test_webhook_drops_when_queue_full() {
+    let (addr, mut bodies) = webhook_server(0, Duration::from_millis(200)).await;
+    let mut config = webhook_config(addr);
+    config.queue_capacity = 2;
+    let sink = WebhookSink::new(config).unwrap();
+    let pool = Pubkey::new_unique();
+
+    let started = std::time::Instant::now();
+    for i in 0..20 {
+        sink.write(&trade_event(pool, &format!("sig{}", i))).await.unwrap();
+    }
+    assert!(started.elapsed() < Duration::from_millis(200));
+
+    // Only the events that fit in the queue (plus one in flight) are delivered, in order
+    let mut delivered = Vec::new();
+    while
+        let Ok(Some(body)) = tokio::time::timeout(Duration::from_secs(1), bodies.recv()).await
+    {
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        delivered.push(json["signature"].as_str().unwrap().to_string());
+    }
+    assert!(!delivered.is_empty() && delivered.len() <= 3);
+    assert_eq!(delivered[0], "sig0");
+}
@@ -0,0 +1,117 @@
+use solana_client::rpc_config::RpcTransactionLogsFilter;
+use std::time::Duration;
+
+use indexer::metrics;
+use indexer::websocket_manager::{ ChannelOverflowPolicy, WebSocketConfig, WebSocketManager };
+
+// Helper to serve a WebSocket node that answers the log subscription with a burst
+// of `notifications` notifications
+async fn start_ws_node(notifications: usize) -> String {
+    use futures::{ SinkExt, StreamExt };
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(message)) = ws.next().await {
+                    let Message::Text(text) = message else {
+                        continue;
+                    };
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": 1,
+                    });
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+
+                    for index in 0..notifications {
+                        let notification = serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "method": "logsNotification",
+                            "params": {
+                                "result": {
+                                    "context": { "slot": 100 + index },
+                                    "value": {
+                                        "signature": format!("signature_{}", index),
+                                        "err": null,
+                                        "logs": [],
+                                    },
+                                },
+                                "subscription": 1,
+                            },
+                        });
+                        ws.send(Message::Text(notification.to_string())).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+    url
+}
+
+// Helper to build a WebSocket config with a small processing channel
+fn small_channel_config(ws_url: String, overflow_policy: ChannelOverflowPolicy) -> WebSocketConfig {
+    WebSocketConfig {
+        ws_url,
+        filter: RpcTransactionLogsFilter::All,
+        channel_capacity: 2,
+        overflow_policy,
+        ..WebSocketConfig::default()
+    }
+}
+
+// Test that the drop policy keeps reading the socket, dropping and counting what
+// doesn't fit in the channel while nothing is processed
+#[tokio::test]
+async fn test_drop_policy_counts_dropped_notifications() {
+    let metrics = metrics::init().unwrap();
+    let ws_url = start_ws_node(10).await;
+    let manager = WebSocketManager::new(small_channel_config(ws_url, ChannelOverflowPolicy::Drop));
+    let mut receiver = manager.start_subscription().await.unwrap();
+
+    for _ in 0..50 {
+        if metrics.websocket_messages_dropped.get() >= 8 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(metrics.websocket_messages_dropped.get(), 8);
+
+    // The oldest notifications were kept
+    for expected in ["signature_0", "signature_1"] {
+        assert_eq!(receiver.recv().await.unwrap().value.signature, expected);
+    }
+    assert!(receiver.try_recv().is_err());
+    manager.stop();
+}
+
+// Test that the block policy delivers every notification once processing catches up
+#[tokio::test]
+async fn test_block_policy_delivers_every_notification() {
+    let ws_url = start_ws_node(10).await;
+    let manager = WebSocketManager::new(small_channel_config(ws_url, ChannelOverflowPolicy::Block));
+    let mut receiver = manager.start_subscription().await.unwrap();
+
+    // Let the burst back up behind the full channel before processing it
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    for index in 0..10 {
+        let response = tokio::time
+            ::timeout(Duration::from_secs(5), receiver.recv()).await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.value.signature, format!("signature_{}", index));
+    }
+    manager.stop();
+}
+
+// Test that overflow policies parse from their command-line names
+#[test]
+fn test_overflow_policy_from_str() {
+    assert_eq!("block".parse::<ChannelOverflowPolicy>().unwrap(), ChannelOverflowPolicy::Block);
+    assert_eq!("Drop".parse::<ChannelOverflowPolicy>().unwrap(), ChannelOverflowPolicy::Drop);
+    assert!("spill".parse::<ChannelOverflowPolicy>().is_err());
+}
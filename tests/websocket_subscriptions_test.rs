@@ -0,0 +1,179 @@
+use solana_client::rpc_config::RpcTransactionLogsFilter;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{ HashMap, HashSet };
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+use indexer::indexers::parse_program_commitment;
+use indexer::websocket_manager::{ LogSubscription, WebSocketConfig, WebSocketManager };
+
+// Subscriptions opened per program, with the commitment each asked for
+type Subscribed = Arc<Mutex<HashMap<String, Vec<String>>>>;
+
+// Helper to serve a WebSocket node that answers each program's log subscription with
+// a transaction of its own and one mentioning both programs. The first subscription
+// to `flaky` is disconnected after sending its notifications
+async fn start_ws_node(subscribed: Subscribed, flaky: String) -> String {
+    use futures::{ SinkExt, StreamExt };
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let subscribed = subscribed.clone();
+            let flaky = flaky.clone();
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(message)) = ws.next().await {
+                    let Message::Text(text) = message else {
+                        continue;
+                    };
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let program = request["params"][0]["mentions"][0].as_str().unwrap().to_string();
+                    let commitment = request["params"][1]["commitment"].as_str().unwrap().to_string();
+                    let first = {
+                        let mut subscribed = subscribed.lock().unwrap();
+                        let commitments = subscribed.entry(program.clone()).or_default();
+                        commitments.push(commitment);
+                        commitments.len() == 1
+                    };
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": 1,
+                    });
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+
+                    for signature in [format!("only_{}", program), "both".to_string()] {
+                        let notification = serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "method": "logsNotification",
+                            "params": {
+                                "result": {
+                                    "context": { "slot": 100 },
+                                    "value": { "signature": signature, "err": null, "logs": [] },
+                                },
+                                "subscription": 1,
+                            },
+                        });
+                        ws.send(Message::Text(notification.to_string())).await.unwrap();
+                    }
+
+                    if first && program == flaky {
+                        let _ = ws.close(None).await;
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    url
+}
+
+// Test that each program gets its own subscription at its own commitment level, that
+// one dropping reconnects without the other, and that shared transactions arrive once
+#[tokio::test]
+async fn test_independent_program_subscriptions() {
+    let amm = Pubkey::new_unique().to_string();
+    let clmm = Pubkey::new_unique().to_string();
+    let subscribed: Subscribed = Arc::new(Mutex::new(HashMap::new()));
+    let ws_url = start_ws_node(subscribed.clone(), amm.clone()).await;
+
+    let subscription = |program: &str, commitment: CommitmentConfig| LogSubscription {
+        filter: RpcTransactionLogsFilter::Mentions(vec![program.to_string()]),
+        commitment,
+    };
+    let manager = WebSocketManager::new(WebSocketConfig {
+        ws_url,
+        subscriptions: vec![
+            subscription(&amm, CommitmentConfig::confirmed()),
+            subscription(&clmm, CommitmentConfig::processed())
+        ],
+        reconnect_base_delay_ms: 10,
+        reconnect_jitter_ratio: 0.0,
+        ..WebSocketConfig::default()
+    });
+    let mut receiver = manager.start_subscription().await.unwrap();
+
+    let mut signatures = HashSet::new();
+    while signatures.len() < 3 {
+        let response = tokio::time
+            ::timeout(Duration::from_secs(5), receiver.recv()).await
+            .unwrap()
+            .unwrap();
+        assert!(signatures.insert(response.value.signature), "duplicate notification");
+    }
+    let expected: HashSet<String> = [format!("only_{}", amm), format!("only_{}", clmm), "both".to_string()]
+        .into_iter()
+        .collect();
+    assert_eq!(signatures, expected);
+
+    // The dropped AMM subscription reconnects, and what it resends was already forwarded
+    for _ in 0..50 {
+        if subscribed.lock().unwrap().get(&amm).map_or(0, Vec::len) >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(receiver.try_recv().is_err());
+    manager.stop();
+
+    let subscribed = subscribed.lock().unwrap();
+    assert_eq!(subscribed[&amm], vec!["confirmed", "confirmed"]);
+    assert_eq!(subscribed[&clmm], vec!["processed"]);
+}
+
+// Test that the manager exposes when it connected, when it last received a message
+// and how often it reconnected
+#[tokio::test]
+async fn test_connection_accessors() {
+    let program = Pubkey::new_unique().to_string();
+    let subscribed: Subscribed = Arc::new(Mutex::new(HashMap::new()));
+    let ws_url = start_ws_node(subscribed.clone(), program.clone()).await;
+
+    let manager = WebSocketManager::new(WebSocketConfig {
+        ws_url,
+        filter: RpcTransactionLogsFilter::Mentions(vec![program.clone()]),
+        reconnect_base_delay_ms: 10,
+        reconnect_jitter_ratio: 0.0,
+        ..WebSocketConfig::default()
+    });
+    assert_eq!(manager.reconnect_count(), 0);
+    assert!(manager.connection_established_at().is_none());
+    assert!(manager.last_received_at().is_none());
+
+    let started = Instant::now();
+    let mut receiver = manager.start_subscription().await.unwrap();
+    for _ in 0..50 {
+        if manager.reconnect_count() >= 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(manager.reconnect_count(), 1);
+    assert!(receiver.recv().await.is_some());
+
+    // The second connection is the one that stays up
+    let connected_at = manager.connection_established_at().unwrap();
+    assert!(connected_at > started);
+    assert!(manager.last_received_at().unwrap() >= started);
+    assert_eq!(subscribed.lock().unwrap()[&program].len(), 2);
+    manager.stop();
+}
+
+// Test that program commitment overrides parse from 'PROGRAM_ID=LEVEL'
+#[test]
+fn test_parse_program_commitment() {
+    let program = Pubkey::new_unique().to_string();
+
+    let (program_id, commitment) = parse_program_commitment(&format!("{} = finalized", program)).unwrap();
+    assert_eq!(program_id, program);
+    assert_eq!(commitment, CommitmentConfig::finalized());
+
+    assert!(parse_program_commitment(&program).is_err());
+    assert!(parse_program_commitment("not-a-program=confirmed").is_err());
+    assert!(parse_program_commitment(&format!("{}=eventually", program)).is_err());
+}
@@ -0,0 +1,116 @@
+use std::str::FromStr;
+use solana_account_decoder::UiAccount;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use indexer::models::orca::{
+    parse_whirlpool_account,
+    validate_whirlpool_account,
+    WHIRLPOOL_ACCOUNT_LEN,
+    WHIRLPOOL_TOKEN_MINT_A_OFFSET,
+    WHIRLPOOL_TOKEN_MINT_B_OFFSET,
+};
+use indexer::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+
+// Helper to build an account with the given owner and data length
+fn account(owner: Pubkey, len: usize) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data: vec![0; len],
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+// Helper to load the SOL/USDC Whirlpool account fixture, in the getAccountInfo format
+fn sol_usdc_whirlpool() -> (Pubkey, Account) {
+    let fixture: serde_json::Value = serde_json
+        ::from_str(include_str!("fixtures/whirlpool_sol_usdc_account.json"))
+        .unwrap();
+    let pool = Pubkey::from_str(fixture["pubkey"].as_str().unwrap()).unwrap();
+    let account: UiAccount = serde_json::from_value(fixture["account"].clone()).unwrap();
+    (pool, account.decode().unwrap())
+}
+
+fn whirlpool_program() -> Pubkey {
+    Pubkey::from_str(ORCA_WHIRLPOOL_PROGRAM_ID).unwrap()
+}
+
+fn spl_token_program() -> Pubkey {
+    Pubkey::from_str("TokenkegQfeZyiNwAXLyjxC4XM4YveSe2NThhFEJU3b").unwrap()
+}
+
+// Test that a correctly sized account owned by the Whirlpool program passes
+#[test]
+fn test_valid_whirlpool_account() {
+    let pool = Pubkey::new_unique();
+    let account = account(whirlpool_program(), WHIRLPOOL_ACCOUNT_LEN);
+
+    assert!(validate_whirlpool_account(&pool, &account, &whirlpool_program()).is_ok());
+}
+
+// Test that an account owned by another program is rejected with a clear error
+#[test]
+fn test_wrong_owner_rejected() {
+    let pool = Pubkey::new_unique();
+    let token_program = spl_token_program();
+    let account = account(token_program, WHIRLPOOL_ACCOUNT_LEN);
+
+    let err = validate_whirlpool_account(&pool, &account, &whirlpool_program()).unwrap_err();
+    let message = err.to_string();
+
+    assert!(message.contains(&format!("Account {} is not an Orca Whirlpool", pool)));
+    assert!(message.contains(&format!("owned by {}", token_program)));
+    assert!(message.contains(ORCA_WHIRLPOOL_PROGRAM_ID));
+}
+
+// Test that an account with the wrong data length is rejected
+#[test]
+fn test_wrong_length_rejected() {
+    let pool = Pubkey::new_unique();
+    let account = account(whirlpool_program(), 82);
+
+    let err = validate_whirlpool_account(&pool, &account, &whirlpool_program()).unwrap_err();
+
+    assert!(err.to_string().contains("data length is 82 bytes, expected 653"));
+}
+
+// Test that a Whirlpool account fixture deserializes into the expected pool fields
+#[test]
+fn test_parse_whirlpool_account_fixture() {
+    let (pool, account) = sol_usdc_whirlpool();
+
+    let pool_data = parse_whirlpool_account(&pool, &account, &whirlpool_program()).unwrap();
+
+    assert_eq!(
+        pool_data.whirlpools_config.to_string(),
+        "2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ"
+    );
+    assert_eq!(pool_data.tick_spacing, 64);
+    assert_eq!(pool_data.fee_rate, 3000);
+    assert_eq!(pool_data.tick_current_index, -19_402);
+    assert_eq!(pool_data.token_mint_a.to_string(), "So11111111111111111111111111111111111111112");
+    assert_eq!(pool_data.token_mint_b.to_string(), "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+    assert_eq!(pool_data.reward_last_updated_timestamp, 1_736_000_000);
+    assert_eq!(
+        pool_data.reward_infos[0].mint.to_string(),
+        "DjDsi34mSB66p2nhBL6YvhbcLtZbkGfNybFeLDjJqxJW"
+    );
+
+    // The offsets used to filter discovery queries point at the same mints
+    let mint_at = |offset: usize| Pubkey::try_from(&account.data[offset..offset + 32]).unwrap();
+    assert_eq!(mint_at(WHIRLPOOL_TOKEN_MINT_A_OFFSET), pool_data.token_mint_a);
+    assert_eq!(mint_at(WHIRLPOOL_TOKEN_MINT_B_OFFSET), pool_data.token_mint_b);
+}
+
+// Test that a correctly sized account without the Whirlpool discriminator is rejected
+#[test]
+fn test_wrong_discriminator_rejected() {
+    let (pool, mut account) = sol_usdc_whirlpool();
+    account.data[..8].copy_from_slice(&[0; 8]);
+
+    let err = parse_whirlpool_account(&pool, &account, &whirlpool_program()).unwrap_err();
+
+    assert!(err.to_string().contains("discriminator"));
+}
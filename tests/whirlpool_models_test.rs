@@ -4,6 +4,8 @@ use chrono::Utc;
 
 // Import the event type and pool from the public API
 use indexer::{ OrcaWhirlpoolEventType, OrcaWhirlpoolPoolRecord };
+use indexer::utils::price::{ price_from_sqrt_price, sqrt_price_to_price };
+use rust_decimal::Decimal;
 
 // Import the database models directly from the modules
 use indexer::models::orca::whirlpool::{
@@ -13,6 +15,8 @@ use indexer::models::orca::whirlpool::{
     OrcaWhirlpoolTradedEventRecord,
     OrcaWhirlpoolLiquidityIncreasedEventRecord,
     OrcaWhirlpoolLiquidityDecreasedEventRecord,
+    OrcaWhirlpoolPoolInitializedRecord,
+    OrcaWhirlpoolPoolInitializedEventRecord,
 };
 
 // Test the creation and properties of base event records
@@ -22,10 +26,16 @@ fn test_base_event_creation() {
     let base_event = OrcaWhirlpoolEvent {
         id: 0, // Will be set by database
         signature: "test_signature".to_string(),
+        event_index: 0,
         whirlpool: "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string(),
         event_type: OrcaWhirlpoolEventType::Traded.to_string(),
+        dex: "orca".to_string(),
+        slot: None,
+        block_time: None,
+        fee_payer: None,
         version: 1,
         timestamp: Utc::now(),
+        enrichment: None,
     };
 
     // Verify the properties
@@ -43,10 +53,16 @@ fn test_traded_event_record() {
     let base_event = OrcaWhirlpoolEvent {
         id: 1, // Simulating database ID
         signature: "test_traded_signature".to_string(),
+        event_index: 0,
         whirlpool: "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string(),
         event_type: OrcaWhirlpoolEventType::Traded.to_string(),
+        dex: "orca".to_string(),
+        slot: None,
+        block_time: None,
+        fee_payer: None,
         version: 1,
         timestamp: Utc::now(),
+        enrichment: None,
     };
 
     // Create the traded record data
@@ -61,6 +77,7 @@ fn test_traded_event_record() {
         output_transfer_fee: 1,
         lp_fee: 3,
         protocol_fee: 1,
+        price: None,
     };
 
     // Create the combined record
@@ -85,10 +102,16 @@ fn test_liquidity_increased_event_record() {
     let base_event = OrcaWhirlpoolEvent {
         id: 2, // Simulating database ID
         signature: "test_liq_inc_signature".to_string(),
+        event_index: 0,
         whirlpool: "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string(),
         event_type: OrcaWhirlpoolEventType::LiquidityIncreased.to_string(),
+        dex: "orca".to_string(),
+        slot: None,
+        block_time: None,
+        fee_payer: None,
         version: 1,
         timestamp: Utc::now(),
+        enrichment: None,
     };
 
     // Create the liquidity record data
@@ -130,10 +153,16 @@ fn test_liquidity_decreased_event_record() {
     let base_event = OrcaWhirlpoolEvent {
         id: 3, // Simulating database ID
         signature: "test_liq_dec_signature".to_string(),
+        event_index: 0,
         whirlpool: "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string(),
         event_type: OrcaWhirlpoolEventType::LiquidityDecreased.to_string(),
+        dex: "orca".to_string(),
+        slot: None,
+        block_time: None,
+        fee_payer: None,
         version: 1,
         timestamp: Utc::now(),
+        enrichment: None,
     };
 
     // Create the liquidity record data
@@ -166,6 +195,57 @@ fn test_liquidity_decreased_event_record() {
     assert_eq!(event_record.data.token_b_amount, 180);
 }
 
+// Test the creation of a pool initialized event record
+#[test]
+fn test_pool_initialized_event_record() {
+    // Create a base event
+    let base_event = OrcaWhirlpoolEvent {
+        id: 4, // Simulating database ID
+        signature: "test_pool_init_signature".to_string(),
+        event_index: 0,
+        whirlpool: "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE".to_string(),
+        event_type: OrcaWhirlpoolEventType::PoolInitialized.to_string(),
+        dex: "orca".to_string(),
+        slot: None,
+        block_time: None,
+        fee_payer: None,
+        version: 1,
+        timestamp: Utc::now(),
+        enrichment: None,
+    };
+
+    // Create the pool initialized record data
+    let data = OrcaWhirlpoolPoolInitializedRecord {
+        event_id: 4, // Matching the base event ID
+        whirlpools_config: "2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ".to_string(),
+        token_mint_a: "So11111111111111111111111111111111111111112".to_string(),
+        token_mint_b: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+        tick_spacing: 64,
+        decimals_a: 9,
+        decimals_b: 6,
+        initial_sqrt_price: 1 << 64,
+    };
+
+    // Create the combined record
+    let event_record = OrcaWhirlpoolPoolInitializedEventRecord {
+        base: base_event.clone(),
+        data: data.clone(),
+    };
+
+    // Verify the properties
+    assert_eq!(event_record.base.id, base_event.id);
+    assert_eq!(event_record.base.event_type, "PoolInitialized");
+    assert_eq!(event_record.data.event_id, 4);
+    assert_eq!(event_record.data.token_mint_a, "So11111111111111111111111111111111111111112");
+    assert_eq!(event_record.data.tick_spacing, 64);
+    assert_eq!((event_record.data.decimals_a, event_record.data.decimals_b), (9, 6));
+    assert_eq!(event_record.data.initial_sqrt_price, 1 << 64);
+
+    // The new event type round-trips through its stored name
+    let event_type = OrcaWhirlpoolEventType::PoolInitialized;
+    assert_eq!(OrcaWhirlpoolEventType::from_str(&event_type.to_string()), Ok(event_type));
+}
+
 // Test the OrcaWhirlpoolPool model
 #[test]
 fn test_orca_whirlpool_pool() {
@@ -235,3 +315,40 @@ fn test_pubkey_string_conversions() {
     let invalid_pubkey = Pubkey::from_str("invalid");
     assert!(invalid_pubkey.is_err());
 }
+
+// Test converting Q64.64 sqrt prices into token prices
+#[test]
+fn test_sqrt_price_to_price() {
+    // A sqrt price of 1.0 is a raw price of 1, scaled by the decimal difference
+    assert_eq!(sqrt_price_to_price(1 << 64, 6, 6), 1.0);
+    assert_eq!(sqrt_price_to_price(1 << 64, 9, 6), 1000.0);
+    assert_eq!(sqrt_price_to_price(1 << 64, 6, 9), 0.001);
+
+    // A sqrt price of 2.0 squares to a raw price of 4
+    assert_eq!(sqrt_price_to_price(2 << 64, 6, 6), 4.0);
+
+    // SOL (9 decimals) priced in USDC (6 decimals) at about $150
+    let sqrt_price = ((0.15f64).sqrt() * 2f64.powi(64)) as u128;
+    assert!((sqrt_price_to_price(sqrt_price, 9, 6) - 150.0).abs() < 1e-6);
+}
+
+// Test the exact decimal price of Q64.64 sqrt prices
+#[test]
+fn test_price_from_sqrt_price() {
+    assert_eq!(price_from_sqrt_price(1 << 64, 6, 6).unwrap(), Decimal::ONE);
+    assert_eq!(price_from_sqrt_price(1 << 64, 9, 6).unwrap(), Decimal::new(1000, 0));
+    assert_eq!(price_from_sqrt_price(1 << 64, 6, 9).unwrap(), Decimal::new(1, 3));
+    assert_eq!(price_from_sqrt_price(2 << 64, 6, 6).unwrap(), Decimal::new(4, 0));
+    assert_eq!(price_from_sqrt_price(3 << 63, 0, 0).unwrap(), Decimal::new(225, 2));
+
+    // Agrees with the floating point version on a realistic SOL/USDC price
+    let sqrt_price: u128 = 7_144_424_502_126_617_408;
+    let exact: f64 = price_from_sqrt_price(sqrt_price, 9, 6).unwrap().to_string().parse().unwrap();
+    assert!((exact - sqrt_price_to_price(sqrt_price, 9, 6)).abs() < 1e-9);
+
+    // The largest Whirlpool sqrt price still fits, but not with a large decimals gap
+    let max_sqrt_price: u128 = 79_226_673_515_401_279_992_447_579_055;
+    assert!(price_from_sqrt_price(max_sqrt_price, 6, 6).is_ok());
+    assert!(price_from_sqrt_price(max_sqrt_price, 18, 0).is_err());
+    assert!(price_from_sqrt_price(u128::MAX, 6, 6).is_err());
+}